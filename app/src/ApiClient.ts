@@ -30,10 +30,13 @@ export interface UpdateAccount {
   intends_to_use_shared_aggregators?: boolean;
 }
 
+export type MembershipRole = "viewer" | "member" | "admin" | "owner";
+
 export interface Membership {
   user_email: string;
   account_id: string;
   id: string;
+  role: MembershipRole;
   created_at: string;
 }
 
@@ -92,6 +95,84 @@ export interface Task {
   aggregation_job_counter_helper_task_expired: number;
   aggregation_job_counter_helper_invalid_message: number;
   aggregation_job_counter_helper_report_too_early: number;
+  report_count: number;
+  aggregate_collection_count: number;
+  collection_job_counter_pending: number;
+  collection_job_counter_abandoned: number;
+  collection_job_counter_deleted: number;
+  helper_metrics: HelperMetrics | null;
+}
+
+export interface MetricsSummary {
+  task_count: number;
+  totals: Record<TaskCounter, number>;
+  tasks: {
+    task_id: string;
+    name: string;
+    counters: Record<TaskCounter, number>;
+    upload_error_ratio: number;
+    aggregation_error_ratio: number;
+    updated_at: string;
+  }[];
+  highest_error_ratios: {
+    task_id: string;
+    name: string;
+    error_ratio: number;
+  }[];
+  expiring_soon: { task_id: string; name: string; expiration: string }[];
+}
+
+export interface CollectionReadiness {
+  task_id: string;
+  query_type: "TimeInterval" | "FixedSize";
+  min_batch_size: number;
+  report_count: number;
+  reports_per_hour: number;
+  rate_measured_since: string;
+  late_report_count: number;
+  batch_interval_seconds: number | null;
+  ready_intervals: { start: string; duration_seconds: number }[] | null;
+  ready_batches: number | null;
+  next_ready_at: string | null;
+}
+
+export interface DpEstimateParams {
+  vdaf: VdafDefinition;
+  min_batch_size: number;
+}
+
+export interface DpEstimate {
+  epsilon: number | null;
+  sensitivity: number;
+  noise_scale: number;
+  standard_deviation: number;
+  relative_error_at_min_batch_size: number;
+}
+
+export interface HelperMetrics {
+  uploads: {
+    interval_collected: number;
+    report_decode_failure: number;
+    report_decrypt_failure: number;
+    report_expired: number;
+    report_outdated_key: number;
+    report_success: number;
+    report_too_early: number;
+    task_expired: number;
+  } | null;
+  aggregation_jobs: {
+    success: number;
+    helper_batch_collected: number;
+    helper_report_replayed: number;
+    helper_report_dropped: number;
+    helper_hpke_unknown_config_id: number;
+    helper_hpke_decrypt_failure: number;
+    helper_vdaf_prep_error: number;
+    helper_task_expired: number;
+    helper_invalid_message: number;
+    helper_report_too_early: number;
+  } | null;
+  refreshed_at: string;
 }
 
 export interface CollectorAuthToken {
@@ -116,6 +197,12 @@ export type NewTask = Omit<
   | "report_counter_too_early"
   | "report_counter_task_expired"
   | "report_counter_task_expired"
+  | "report_count"
+  | "aggregate_collection_count"
+  | "collection_job_counter_pending"
+  | "collection_job_counter_abandoned"
+  | "collection_job_counter_deleted"
+  | "helper_metrics"
 > & {
   vdaf: {
     type: "sum" | "count" | "histogram";
@@ -131,11 +218,18 @@ export interface UpdateTask {
 
 export interface CreateMembership {
   user_email: string;
+  role?: MembershipRole;
+}
+
+export interface UpdateMembership {
+  role: MembershipRole;
 }
 
 export type Role =
   "Leader" | "Helper" | "Either" | "leader" | "helper" | "either";
 
+export type Visibility = "public" | "allowlist" | "request_access";
+
 export interface Aggregator {
   id: string;
   account_id: string | null;
@@ -151,6 +245,24 @@ export interface Aggregator {
   query_types: string[];
   features: string[];
   protocol: string;
+  has_secondary_bearer_token: boolean;
+  has_client_identity: boolean;
+  ca_certificate: string | null;
+  sunset_at: string | null;
+  visibility: Visibility;
+}
+
+export interface AggregatorAccess {
+  id: string;
+  aggregator_id: string;
+  account_id: string;
+  created_at: string;
+  updated_at: string;
+  granted_at: string | null;
+}
+
+export interface RequestAggregatorAccess {
+  aggregator_id: string;
 }
 
 export interface NewAggregator {
@@ -158,13 +270,52 @@ export interface NewAggregator {
   api_url: string;
   bearer_token: string;
   is_first_party?: boolean;
+  client_certificate?: string;
+  client_key?: string;
+  ca_certificate?: string;
+  visibility?: Visibility;
+}
+
+export interface CreatableVdaf {
+  type: "count" | "histogram" | "sum" | "count_vec" | "sum_vec";
+  dp_strategies: ("NoDifferentialPrivacy" | "PureDpDiscreteLaplace")[];
+}
+
+export interface AggregatorPairCompatibility {
+  compatible: boolean;
+  errors: ValidationErrors;
+  protocol: string | null;
+  vdafs: string[];
+  query_types: string[];
+  features: string[];
+  time_bucketed_fixed_size: boolean;
+  creatable_vdafs: CreatableVdaf[];
+}
+
+export interface TaskExpirationProgress {
+  aggregator_id: string;
+  status: "Pending" | "Success" | "Failed";
+  expired_task_ids: string[];
+  failed_task_ids: string[];
+  remaining_tasks: number;
+  updated_at: string;
 }
 
 export interface UpdateAggregator {
   name?: string;
   bearer_token?: string;
+  client_certificate?: string;
+  client_key?: string;
+  ca_certificate?: string;
+  visibility?: Visibility;
 }
 
+export type ApiTokenScope =
+  | "read"
+  | "tasks:write"
+  | "aggregators:write"
+  | "collector_credentials:write";
+
 export interface ApiToken {
   id: string;
   account_id: string;
@@ -173,6 +324,178 @@ export interface ApiToken {
   deleted_at?: string;
   name?: string;
   last_used_at?: string;
+  scopes: ApiTokenScope[] | null;
+  task_ids: string[] | null;
+  expires_at: string | null;
+}
+
+export interface ApiTokenUsage {
+  id: string;
+  api_token_id: string;
+  hour: string;
+  request_count: number;
+  routes: { [route: string]: number };
+  source_ips: { [sourceIp: string]: number };
+  last_used_at: string;
+}
+
+export interface NewApiToken {
+  name?: string;
+  scopes?: ApiTokenScope[];
+  task_ids?: string[];
+  expires_at?: string;
+}
+
+export type AuditAction =
+  | "create"
+  | "update"
+  | "delete"
+  | "decommission"
+  | "request_access"
+  | "grant_access"
+  | "revoke_access"
+  | "stage_secondary_bearer_token"
+  | "discard_secondary_bearer_token"
+  | "promote_secondary_bearer_token";
+
+export type AuditTargetType =
+  | "account"
+  | "membership"
+  | "api_token"
+  | "collector_credential"
+  | "task"
+  | "aggregator"
+  | "aggregator_access"
+  | "queue_job"
+  | "webhook"
+  | "alert_rule";
+
+export interface AuditEvent {
+  id: string;
+  account_id: string | null;
+  actor_email: string | null;
+  actor_api_token_id: string | null;
+  action: AuditAction;
+  target_type: AuditTargetType;
+  target_id: string;
+  changes: { [field: string]: { before: unknown; after: unknown } };
+  created_at: string;
+}
+
+export type WebhookEventType =
+  | "task.created"
+  | "task.expired"
+  | "task.deleted"
+  | "membership.created"
+  | "membership.updated"
+  | "membership.deleted"
+  | "aggregator.decommissioned"
+  | "aggregator.capabilities_removed"
+  | "aggregator.deleted"
+  | "alert.fired"
+  | "alert.resolved";
+
+export interface Webhook {
+  id: string;
+  account_id: string;
+  name: string | null;
+  url: string;
+  event_types: WebhookEventType[] | null;
+  created_at: string;
+  updated_at: string;
+  deleted_at: string | null;
+}
+
+export interface NewWebhook {
+  name?: string;
+  url: string;
+  event_types?: WebhookEventType[];
+}
+
+export interface UpdateWebhook {
+  name?: string;
+  url?: string;
+  event_types?: WebhookEventType[];
+  all_event_types?: boolean;
+}
+
+export interface WebhookDelivery {
+  id: string;
+  webhook_id: string;
+  event_id: string;
+  event_type: WebhookEventType;
+  attempt: number;
+  status_code: number | null;
+  error: string | null;
+  created_at: string;
+}
+
+export type TaskCounter =
+  | "report_counter_interval_collected"
+  | "report_counter_decode_failure"
+  | "report_counter_decrypt_failure"
+  | "report_counter_expired"
+  | "report_counter_outdated_key"
+  | "report_counter_success"
+  | "report_counter_too_early"
+  | "report_counter_task_expired"
+  | "aggregation_job_counter_success"
+  | "aggregation_job_counter_helper_batch_collected"
+  | "aggregation_job_counter_helper_report_replayed"
+  | "aggregation_job_counter_helper_report_dropped"
+  | "aggregation_job_counter_helper_hpke_unknown_config_id"
+  | "aggregation_job_counter_helper_hpke_decrypt_failure"
+  | "aggregation_job_counter_helper_vdaf_prep_error"
+  | "aggregation_job_counter_helper_task_expired"
+  | "aggregation_job_counter_helper_invalid_message"
+  | "aggregation_job_counter_helper_report_too_early";
+
+export type AlertChannel = "email" | "webhook";
+
+export interface AlertRule {
+  id: string;
+  account_id: string;
+  task_id: string | null;
+  name: string | null;
+  counter: TaskCounter;
+  relative_to: TaskCounter | null;
+  threshold: number;
+  window_seconds: number;
+  channels: AlertChannel[];
+  created_at: string;
+  updated_at: string;
+  deleted_at: string | null;
+}
+
+export interface NewAlertRule {
+  name?: string;
+  task_id?: string;
+  counter: TaskCounter;
+  relative_to?: TaskCounter;
+  threshold: number;
+  window_seconds?: number;
+  channels?: AlertChannel[];
+}
+
+export interface UpdateAlertRule {
+  name?: string;
+  counter?: TaskCounter;
+  relative_to?: TaskCounter;
+  absolute?: boolean;
+  threshold?: number;
+  window_seconds?: number;
+  channels?: AlertChannel[];
+}
+
+export interface Alert {
+  id: string;
+  alert_rule_id: string;
+  account_id: string;
+  task_id: string;
+  value: number;
+  fired_at: string;
+  updated_at: string;
+  resolved_at: string | null;
 }
 
 export interface CollectorCredential {
@@ -300,6 +623,11 @@ export class ApiClient {
     return res.data as Membership;
   }
 
+  async accountMetricsSummary(accountId: string): Promise<MetricsSummary> {
+    const res = await this.get(`/api/accounts/${accountId}/metrics_summary`);
+    return res.data as MetricsSummary;
+  }
+
   async accountTasks(accountId: string): Promise<Task[]> {
     const res = await this.get(`/api/accounts/${accountId}/tasks`);
     return res.data as Task[];
@@ -310,6 +638,23 @@ export class ApiClient {
     return res.data as Task;
   }
 
+  async updateMembership(
+    membershipId: string,
+    membership: UpdateMembership,
+  ): Promise<Membership | { error: ValidationErrorsFor<UpdateMembership> }> {
+    const res = await this.patch(`/api/memberships/${membershipId}`, membership);
+    switch (res.status) {
+      case 200:
+        return res.data as Membership;
+      case 400:
+        return { error: res.data } as {
+          error: ValidationErrorsFor<UpdateMembership>;
+        };
+      default:
+        throw res;
+    }
+  }
+
   async deleteMembership(membershipId: string): Promise<null> {
     await this.delete(`/api/memberships/${membershipId}`);
     return null;
@@ -370,6 +715,58 @@ export class ApiClient {
     return res.data as Aggregator;
   }
 
+  async aggregatorTaskExpiration(
+    aggregatorId: string,
+  ): Promise<TaskExpirationProgress> {
+    const res = await this.get(
+      `/api/aggregators/${aggregatorId}/task_expiration`,
+    );
+    return res.data as TaskExpirationProgress;
+  }
+
+  async aggregatorPairCompatibility(
+    accountId: string,
+    leaderAggregatorId: string,
+    helperAggregatorId: string,
+  ): Promise<AggregatorPairCompatibility> {
+    const params = new URLSearchParams({
+      leader: leaderAggregatorId,
+      helper: helperAggregatorId,
+    });
+    const res = await this.get(
+      `/api/accounts/${accountId}/aggregator_pairs/compatibility?${params}`,
+    );
+    return res.data as AggregatorPairCompatibility;
+  }
+
+  async aggregatorAccess(accountId: string): Promise<AggregatorAccess[]> {
+    const res = await this.get(`/api/accounts/${accountId}/aggregator_access`);
+    return res.data as AggregatorAccess[];
+  }
+
+  async requestAggregatorAccess(
+    accountId: string,
+    request: RequestAggregatorAccess,
+  ): Promise<
+    AggregatorAccess | { error: ValidationErrorsFor<RequestAggregatorAccess> }
+  > {
+    const res = await this.post(
+      `/api/accounts/${accountId}/aggregator_access`,
+      request,
+    );
+    switch (res.status) {
+      case 200:
+      case 201:
+        return res.data as AggregatorAccess;
+      case 400:
+        return { error: res.data } as {
+          error: ValidationErrorsFor<RequestAggregatorAccess>;
+        };
+      default:
+        throw res;
+    }
+  }
+
   async sharedAggregators(): Promise<Aggregator[]> {
     const res = await this.get("/api/aggregators");
     return res.data as Aggregator[];
@@ -423,6 +820,27 @@ export class ApiClient {
     }
   }
 
+  async taskCollectionReadiness(taskId: string): Promise<CollectionReadiness> {
+    const res = await this.get(`/api/tasks/${taskId}/collection_readiness`);
+    return res.data as CollectionReadiness;
+  }
+
+  async dpEstimate(
+    params: DpEstimateParams,
+  ): Promise<DpEstimate | { error: ValidationErrorsFor<DpEstimateParams> }> {
+    const res = await this.post("/api/dp_estimate", params);
+    switch (res.status) {
+      case 200:
+        return res.data as DpEstimate;
+      case 400:
+        return { error: res.data } as {
+          error: ValidationErrorsFor<DpEstimateParams>;
+        };
+      default:
+        throw res;
+    }
+  }
+
   async deleteTask(taskId: string): Promise<null> {
     await this.delete(`/api/tasks/${taskId}`);
     return null;
@@ -435,8 +853,12 @@ export class ApiClient {
 
   async createApiToken(
     accountId: string,
+    apiToken?: NewApiToken,
   ): Promise<ApiToken & { token: string }> {
-    const res = await this.post(`/api/accounts/${accountId}/api_tokens`);
+    const res = await this.post(
+      `/api/accounts/${accountId}/api_tokens`,
+      apiToken,
+    );
     return res.data as ApiToken & { token: string };
   }
 
@@ -445,6 +867,11 @@ export class ApiClient {
     return null;
   }
 
+  async apiTokenUsage(tokenId: string): Promise<ApiTokenUsage[]> {
+    const res = await this.get(`/api/api_tokens/${tokenId}/usage`);
+    return res.data as ApiTokenUsage[];
+  }
+
   async updateApiToken(
     tokenId: string,
     token: { name: string },
@@ -453,6 +880,86 @@ export class ApiClient {
     return null;
   }
 
+  async accountWebhooks(accountId: string): Promise<Webhook[]> {
+    const res = await this.get(`/api/accounts/${accountId}/webhooks`);
+    return res.data as Webhook[];
+  }
+
+  async createWebhook(
+    accountId: string,
+    webhook: NewWebhook,
+  ): Promise<Webhook & { secret: string }> {
+    const res = await this.post(`/api/accounts/${accountId}/webhooks`, webhook);
+    return res.data as Webhook & { secret: string };
+  }
+
+  async updateWebhook(
+    webhookId: string,
+    webhook: UpdateWebhook,
+  ): Promise<Webhook> {
+    const res = await this.patch(`/api/webhooks/${webhookId}`, webhook);
+    return res.data as Webhook;
+  }
+
+  async deleteWebhook(webhookId: string): Promise<null> {
+    await this.delete(`/api/webhooks/${webhookId}`);
+    return null;
+  }
+
+  async webhookDeliveries(webhookId: string): Promise<WebhookDelivery[]> {
+    const res = await this.get(`/api/webhooks/${webhookId}/deliveries`);
+    return res.data as WebhookDelivery[];
+  }
+
+  async accountAlertRules(accountId: string): Promise<AlertRule[]> {
+    const res = await this.get(`/api/accounts/${accountId}/alert_rules`);
+    return res.data as AlertRule[];
+  }
+
+  async createAlertRule(
+    accountId: string,
+    alertRule: NewAlertRule,
+  ): Promise<AlertRule> {
+    const res = await this.post(
+      `/api/accounts/${accountId}/alert_rules`,
+      alertRule,
+    );
+    return res.data as AlertRule;
+  }
+
+  async updateAlertRule(
+    alertRuleId: string,
+    alertRule: UpdateAlertRule,
+  ): Promise<AlertRule> {
+    const res = await this.patch(`/api/alert_rules/${alertRuleId}`, alertRule);
+    return res.data as AlertRule;
+  }
+
+  async deleteAlertRule(alertRuleId: string): Promise<null> {
+    await this.delete(`/api/alert_rules/${alertRuleId}`);
+    return null;
+  }
+
+  async accountFiringAlerts(accountId: string): Promise<Alert[]> {
+    const res = await this.get(`/api/accounts/${accountId}/alerts`);
+    return res.data as Alert[];
+  }
+
+  async accountAuditEvents(
+    accountId: string,
+    searchParams?: URLSearchParams,
+  ): Promise<AuditEvent[]> {
+    const res = await this.get(
+      `/api/accounts/${accountId}/audit_events?${searchParams ?? ""}`,
+    );
+    return res.data as AuditEvent[];
+  }
+
+  async auditEvents(searchParams?: URLSearchParams): Promise<AuditEvent[]> {
+    const res = await this.get(`/api/admin/audit_events?${searchParams ?? ""}`);
+    return res.data as AuditEvent[];
+  }
+
   async queue(searchParams: URLSearchParams): Promise<QueueJob[]> {
     const res = await this.get(`/api/admin/queue?${searchParams}`);
     return res.data as QueueJob[];
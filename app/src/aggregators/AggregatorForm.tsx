@@ -182,6 +182,9 @@ function BearerToken(props: FormikProps<NewAggregator>) {
 }
 
 function ApiUrl(props: FormikProps<NewAggregator>) {
+  // the dap url is read from the aggregator api, so problems with it are shown here
+  const { dap_url: dapUrlError } = props.errors as { dap_url?: string };
+  const error = props.errors.api_url ?? dapUrlError;
   return (
     <FormGroup>
       <FormLabel>API Url</FormLabel>
@@ -191,16 +194,14 @@ function ApiUrl(props: FormikProps<NewAggregator>) {
         onChange={props.handleChange}
         onBlur={props.handleBlur}
         value={props.values.api_url}
-        isInvalid={!!props.errors.api_url}
+        isInvalid={!!error}
         autoComplete="off"
         name="api_url"
         placeholder="https://example.com"
         pattern="https://.*"
         required
       />
-      <FormControl.Feedback type="invalid">
-        {props.errors.api_url}
-      </FormControl.Feedback>
+      <FormControl.Feedback type="invalid">{error}</FormControl.Feedback>
     </FormGroup>
   );
 }
@@ -175,6 +175,59 @@ function AggregationJobMetrics({ task }: { task: Promise<Task> }) {
   );
 }
 
+function CollectionJobMetrics({ task }: { task: Promise<Task> }) {
+  return (
+    <Col md="6">
+      <Card className="my-3">
+        <Card.Body>
+          <Card.Title>Collection Job Metrics</Card.Title>
+        </Card.Body>
+        <Suspense fallback={<Placeholder animation="glow" xs={2} />}>
+          <Await resolve={task}>
+            {(task: Task) => (
+              <ListGroup variant="flush">
+                <ListGroup.Item>
+                  Finished Collections:{" "}
+                  {numberFormat.format(task.aggregate_collection_count)}
+                </ListGroup.Item>
+                <ListGroup.Item>
+                  Reports Collected: {numberFormat.format(task.report_count)}
+                </ListGroup.Item>
+                <ListGroup.Item>
+                  Pending Collections:{" "}
+                  {numberFormat.format(task.collection_job_counter_pending)}
+                </ListGroup.Item>
+                <FailedMetric
+                  name="Abandoned Collections"
+                  counter={task.collection_job_counter_abandoned}
+                />
+                <FailedMetric
+                  name="Deleted Collections"
+                  counter={task.collection_job_counter_deleted}
+                />
+              </ListGroup>
+            )}
+          </Await>
+        </Suspense>
+        <Card.Footer className="text-muted">
+          Last updated{" "}
+          <Suspense fallback={<Placeholder animation="glow" xs={1} />}>
+            <Await resolve={task}>
+              {(task) => (
+                <relative-time datetime={task.updated_at} format="relative">
+                  {DateTime.fromISO(task.updated_at)
+                    .toLocal()
+                    .toLocaleString(DateTime.DATETIME_SHORT)}
+                </relative-time>
+              )}
+            </Await>
+          </Suspense>
+        </Card.Footer>
+      </Card>
+    </Col>
+  );
+}
+
 export default function Metrics() {
   const { task, leaderAggregator } = useLoaderData() as {
     task: Promise<Task>;
@@ -193,6 +246,9 @@ export default function Metrics() {
               {leaderAggregator.features.includes("AggregationJobMetrics") ? (
                 <AggregationJobMetrics task={task} />
               ) : null}
+              {leaderAggregator.features.includes("CollectionJobMetrics") ? (
+                <CollectionJobMetrics task={task} />
+              ) : null}
             </Row>
           );
         }}
@@ -1,6 +1,29 @@
 use crate::{CliResult, DetermineAccountId, Output};
 use clap::Subcommand;
-use divviup_client::{DivviupClient, NewAggregator, Url, Uuid};
+use divviup_client::{DivviupClient, NewAggregator, Url, Uuid, Visibility};
+#[cfg(feature = "admin")]
+use humantime::Timestamp;
+#[cfg(feature = "admin")]
+use std::time::SystemTime;
+#[cfg(feature = "admin")]
+use time::OffsetDateTime;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum VisibilityName {
+    Public,
+    Allowlist,
+    RequestAccess,
+}
+
+impl From<VisibilityName> for Visibility {
+    fn from(value: VisibilityName) -> Self {
+        match value {
+            VisibilityName::Public => Self::Public,
+            VisibilityName::Allowlist => Self::Allowlist,
+            VisibilityName::RequestAccess => Self::RequestAccess,
+        }
+    }
+}
 
 #[derive(Subcommand, Debug)]
 pub enum AggregatorAction {
@@ -41,6 +64,11 @@ pub enum AggregatorAction {
         #[cfg(feature = "admin")]
         /// create an aggregator that is considered first party (ADMIN)
         first_party: bool,
+
+        #[arg(long, value_enum, requires = "shared")]
+        #[cfg(feature = "admin")]
+        /// which accounts can see and use this shared aggregator (ADMIN)
+        visibility: Option<VisibilityName>,
     },
 
     /// Change the display name of an aggregator
@@ -61,11 +89,137 @@ pub enum AggregatorAction {
         bearer_token: String,
     },
 
+    /// Stage a secondary bearer token for an aggregator
+    ///
+    /// The secondary token is used when the aggregator rejects the primary
+    /// bearer token, and becomes the primary once it has been confirmed
+    StageBearerToken {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+
+        /// new bearer token for this aggregator
+        bearer_token: String,
+    },
+
+    /// Check that the aggregator accepts the staged secondary bearer token
+    VerifyBearerToken {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+    },
+
+    /// Retire the primary bearer token in favor of the staged secondary bearer token
+    PromoteBearerToken {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+    },
+
+    /// Discard the staged secondary bearer token
+    DiscardBearerToken {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+    },
+
     /// Update the aggregator's configuration
     UpdateConfig {
         /// uuid for this aggregator
         aggregator_id: Uuid,
     },
+
+    /// Delete an aggregator
+    Delete {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+
+        /// expire all unexpired tasks that use this aggregator
+        ///
+        /// without this, an aggregator with unexpired tasks cannot be deleted
+        #[arg(long, action)]
+        expire_tasks: bool,
+    },
+
+    /// Show how far expiring the tasks of an aggregator deleted with --expire-tasks has
+    /// progressed
+    TaskExpiration {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+    },
+
+    /// Show the tasks, across all accounts, that would be affected by decommissioning a shared
+    /// aggregator (ADMIN)
+    #[cfg(feature = "admin")]
+    ShowDecommission {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+    },
+
+    /// Decommission a shared aggregator (ADMIN)
+    ///
+    /// Members of affected accounts are emailed, new tasks can no longer use the aggregator, and
+    /// its remaining tasks are expired at the sunset date
+    #[cfg(feature = "admin")]
+    Decommission {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+
+        /// when to expire the remaining tasks. the format is RFC 3339
+        sunset_at: Timestamp,
+    },
+
+    /// Request access to a shared aggregator that is only usable by approved accounts
+    RequestAccess {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+    },
+
+    /// List the target account's requests for and grants of access to shared aggregators
+    ListAccess,
+
+    /// Change which accounts can see and use a shared aggregator (ADMIN)
+    #[cfg(feature = "admin")]
+    SetVisibility {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+
+        #[arg(value_enum)]
+        visibility: VisibilityName,
+    },
+
+    /// List the accounts that have requested or been granted access to a shared aggregator
+    /// (ADMIN)
+    #[cfg(feature = "admin")]
+    ShowAccess {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+    },
+
+    /// Grant an account access to a shared aggregator (ADMIN)
+    #[cfg(feature = "admin")]
+    GrantAccess {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+
+        /// uuid for the account
+        account_id: Uuid,
+    },
+
+    /// Revoke an account's access to a shared aggregator, or deny its request (ADMIN)
+    #[cfg(feature = "admin")]
+    RevokeAccess {
+        /// uuid for this aggregator
+        aggregator_id: Uuid,
+
+        /// uuid for the account
+        account_id: Uuid,
+    },
+
+    /// Show which tasks can be created with a leader and helper aggregator
+    Compatibility {
+        /// uuid for the leader aggregator
+        leader_aggregator_id: Uuid,
+
+        /// uuid for the helper aggregator
+        helper_aggregator_id: Uuid,
+    },
 }
 
 impl AggregatorAction {
@@ -92,6 +246,7 @@ impl AggregatorAction {
                 bearer_token,
                 first_party,
                 shared: true,
+                visibility,
             } => output.display(
                 client
                     .create_shared_aggregator(divviup_client::NewSharedAggregator {
@@ -99,6 +254,7 @@ impl AggregatorAction {
                         api_url,
                         bearer_token,
                         is_first_party: first_party,
+                        visibility: visibility.map(Into::into),
                     })
                     .await?,
             ),
@@ -137,11 +293,133 @@ impl AggregatorAction {
                     .await?,
             ),
 
+            Self::StageBearerToken {
+                aggregator_id,
+                bearer_token,
+            } => output.display(
+                client
+                    .stage_aggregator_secondary_bearer_token(aggregator_id, &bearer_token)
+                    .await?,
+            ),
+
+            Self::VerifyBearerToken { aggregator_id } => output.display(
+                client
+                    .verify_aggregator_secondary_bearer_token(aggregator_id)
+                    .await?,
+            ),
+
+            Self::PromoteBearerToken { aggregator_id } => output.display(
+                client
+                    .promote_aggregator_secondary_bearer_token(aggregator_id)
+                    .await?,
+            ),
+
+            Self::DiscardBearerToken { aggregator_id } => {
+                client
+                    .discard_aggregator_secondary_bearer_token(aggregator_id)
+                    .await?
+            }
+
             Self::UpdateConfig { aggregator_id } => output.display(
                 client
                     .update_aggregator_configuration(aggregator_id)
                     .await?,
             ),
+
+            Self::Delete {
+                aggregator_id,
+                expire_tasks,
+            } => {
+                if expire_tasks {
+                    client
+                        .delete_aggregator_and_expire_tasks(aggregator_id)
+                        .await?
+                } else {
+                    client.delete_aggregator(aggregator_id).await?
+                }
+            }
+
+            Self::TaskExpiration { aggregator_id } => {
+                output.display(client.aggregator_task_expiration(aggregator_id).await?)
+            }
+
+            #[cfg(feature = "admin")]
+            Self::ShowDecommission { aggregator_id } => {
+                output.display(client.aggregator_decommission(aggregator_id).await?)
+            }
+
+            #[cfg(feature = "admin")]
+            Self::Decommission {
+                aggregator_id,
+                sunset_at,
+            } => output.display(
+                client
+                    .decommission_aggregator(
+                        aggregator_id,
+                        &OffsetDateTime::from(SystemTime::from(sunset_at)),
+                    )
+                    .await?,
+            ),
+
+            Self::RequestAccess { aggregator_id } => output.display(
+                client
+                    .request_aggregator_access(account_id.await?, aggregator_id)
+                    .await?,
+            ),
+
+            Self::ListAccess => output.display(
+                client
+                    .aggregator_access_for_account(account_id.await?)
+                    .await?,
+            ),
+
+            #[cfg(feature = "admin")]
+            Self::SetVisibility {
+                aggregator_id,
+                visibility,
+            } => output.display(
+                client
+                    .set_aggregator_visibility(aggregator_id, visibility.into())
+                    .await?,
+            ),
+
+            #[cfg(feature = "admin")]
+            Self::ShowAccess { aggregator_id } => {
+                output.display(client.aggregator_access(aggregator_id).await?)
+            }
+
+            #[cfg(feature = "admin")]
+            Self::GrantAccess {
+                aggregator_id,
+                account_id,
+            } => output.display(
+                client
+                    .grant_aggregator_access(aggregator_id, account_id)
+                    .await?,
+            ),
+
+            #[cfg(feature = "admin")]
+            Self::RevokeAccess {
+                aggregator_id,
+                account_id,
+            } => {
+                client
+                    .revoke_aggregator_access(aggregator_id, account_id)
+                    .await?
+            }
+
+            Self::Compatibility {
+                leader_aggregator_id,
+                helper_aggregator_id,
+            } => output.display(
+                client
+                    .aggregator_pair_compatibility(
+                        account_id.await?,
+                        leader_aggregator_id,
+                        helper_aggregator_id,
+                    )
+                    .await?,
+            ),
         }
         Ok(())
     }
@@ -0,0 +1,234 @@
+use crate::{CliResult, DetermineAccountId, Output};
+use clap::Subcommand;
+use divviup_client::{
+    AlertChannel, DivviupClient, NewAlertRule, TaskCounter, UpdateAlertRule, Uuid,
+};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum CounterName {
+    ReportCounterIntervalCollected,
+    ReportCounterDecodeFailure,
+    ReportCounterDecryptFailure,
+    ReportCounterExpired,
+    ReportCounterOutdatedKey,
+    ReportCounterSuccess,
+    ReportCounterTooEarly,
+    ReportCounterTaskExpired,
+    AggregationJobCounterSuccess,
+    AggregationJobCounterHelperBatchCollected,
+    AggregationJobCounterHelperReportReplayed,
+    AggregationJobCounterHelperReportDropped,
+    AggregationJobCounterHelperHpkeUnknownConfigId,
+    AggregationJobCounterHelperHpkeDecryptFailure,
+    AggregationJobCounterHelperVdafPrepError,
+    AggregationJobCounterHelperTaskExpired,
+    AggregationJobCounterHelperInvalidMessage,
+    AggregationJobCounterHelperReportTooEarly,
+}
+
+impl From<CounterName> for TaskCounter {
+    fn from(value: CounterName) -> Self {
+        match value {
+            CounterName::ReportCounterIntervalCollected => Self::ReportCounterIntervalCollected,
+            CounterName::ReportCounterDecodeFailure => Self::ReportCounterDecodeFailure,
+            CounterName::ReportCounterDecryptFailure => Self::ReportCounterDecryptFailure,
+            CounterName::ReportCounterExpired => Self::ReportCounterExpired,
+            CounterName::ReportCounterOutdatedKey => Self::ReportCounterOutdatedKey,
+            CounterName::ReportCounterSuccess => Self::ReportCounterSuccess,
+            CounterName::ReportCounterTooEarly => Self::ReportCounterTooEarly,
+            CounterName::ReportCounterTaskExpired => Self::ReportCounterTaskExpired,
+            CounterName::AggregationJobCounterSuccess => Self::AggregationJobCounterSuccess,
+            CounterName::AggregationJobCounterHelperBatchCollected => {
+                Self::AggregationJobCounterHelperBatchCollected
+            }
+            CounterName::AggregationJobCounterHelperReportReplayed => {
+                Self::AggregationJobCounterHelperReportReplayed
+            }
+            CounterName::AggregationJobCounterHelperReportDropped => {
+                Self::AggregationJobCounterHelperReportDropped
+            }
+            CounterName::AggregationJobCounterHelperHpkeUnknownConfigId => {
+                Self::AggregationJobCounterHelperHpkeUnknownConfigId
+            }
+            CounterName::AggregationJobCounterHelperHpkeDecryptFailure => {
+                Self::AggregationJobCounterHelperHpkeDecryptFailure
+            }
+            CounterName::AggregationJobCounterHelperVdafPrepError => {
+                Self::AggregationJobCounterHelperVdafPrepError
+            }
+            CounterName::AggregationJobCounterHelperTaskExpired => {
+                Self::AggregationJobCounterHelperTaskExpired
+            }
+            CounterName::AggregationJobCounterHelperInvalidMessage => {
+                Self::AggregationJobCounterHelperInvalidMessage
+            }
+            CounterName::AggregationJobCounterHelperReportTooEarly => {
+                Self::AggregationJobCounterHelperReportTooEarly
+            }
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ChannelName {
+    Email,
+    Webhook,
+}
+
+impl From<ChannelName> for AlertChannel {
+    fn from(value: ChannelName) -> Self {
+        match value {
+            ChannelName::Email => Self::Email,
+            ChannelName::Webhook => Self::Webhook,
+        }
+    }
+}
+
+fn channels(channels: Vec<ChannelName>) -> Option<Vec<AlertChannel>> {
+    (!channels.is_empty()).then(|| channels.into_iter().map(Into::into).collect())
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AlertAction {
+    /// list all alert rules for the target account
+    List,
+
+    /// list alerts that are currently firing in the target account
+    Firing,
+
+    /// create a rule that fires when a task counter increases by more than a threshold
+    Create {
+        /// the counter to watch
+        #[arg(value_enum)]
+        counter: CounterName,
+
+        /// how much the counter may increase within the window, or with --relative-to, the
+        /// largest allowed ratio between the two counters' increases (0.05 for 5%)
+        threshold: f64,
+
+        /// compare the counter's increase to this counter's increase
+        #[arg(long, value_enum)]
+        relative_to: Option<CounterName>,
+
+        /// only watch this task. rules without a task apply to every task in the account
+        #[arg(long)]
+        task_id: Option<String>,
+
+        /// how far back to measure increases, in seconds. defaults to an hour
+        #[arg(long)]
+        window_seconds: Option<u64>,
+
+        /// how to notify the account. defaults to email
+        #[arg(long = "channel", value_enum)]
+        channels: Vec<ChannelName>,
+
+        /// a name to identify the rule by
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// change an alert rule
+    Update {
+        alert_rule_id: Uuid,
+
+        #[arg(long)]
+        name: Option<String>,
+
+        #[arg(long, value_enum)]
+        counter: Option<CounterName>,
+
+        #[arg(long)]
+        threshold: Option<f64>,
+
+        #[arg(long, value_enum, conflicts_with = "absolute")]
+        relative_to: Option<CounterName>,
+
+        /// compare absolute increases instead of comparing to another counter
+        #[arg(long)]
+        absolute: bool,
+
+        #[arg(long)]
+        window_seconds: Option<u64>,
+
+        #[arg(long = "channel", value_enum)]
+        channels: Vec<ChannelName>,
+    },
+
+    /// deletes an alert rule by id
+    Delete { alert_rule_id: Uuid },
+}
+
+impl AlertAction {
+    pub(crate) async fn run(
+        self,
+        account_id: DetermineAccountId,
+        client: DivviupClient,
+        output: Output,
+    ) -> CliResult {
+        match self {
+            AlertAction::List => {
+                output.display(client.alert_rules(account_id.await?).await?);
+            }
+
+            AlertAction::Firing => {
+                output.display(client.firing_alerts(account_id.await?).await?);
+            }
+
+            AlertAction::Create {
+                counter,
+                threshold,
+                relative_to,
+                task_id,
+                window_seconds,
+                channels: channel_names,
+                name,
+            } => {
+                let new_alert_rule = NewAlertRule {
+                    name,
+                    task_id,
+                    counter: Some(counter.into()),
+                    relative_to: relative_to.map(Into::into),
+                    threshold: Some(threshold),
+                    window_seconds,
+                    channels: channels(channel_names),
+                };
+                output.display(
+                    client
+                        .create_alert_rule(account_id.await?, new_alert_rule)
+                        .await?,
+                );
+            }
+
+            AlertAction::Update {
+                alert_rule_id,
+                name,
+                counter,
+                threshold,
+                relative_to,
+                absolute,
+                window_seconds,
+                channels: channel_names,
+            } => {
+                let update_alert_rule = UpdateAlertRule {
+                    name,
+                    counter: counter.map(Into::into),
+                    relative_to: relative_to.map(Into::into),
+                    absolute,
+                    threshold,
+                    window_seconds,
+                    channels: channels(channel_names),
+                };
+                output.display(
+                    client
+                        .update_alert_rule(alert_rule_id, update_alert_rule)
+                        .await?,
+                );
+            }
+
+            AlertAction::Delete { alert_rule_id } => {
+                client.delete_alert_rule(alert_rule_id).await?;
+            }
+        }
+        Ok(())
+    }
+}
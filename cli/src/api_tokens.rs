@@ -1,6 +1,27 @@
 use crate::{CliResult, DetermineAccountId, Output};
 use clap::Subcommand;
-use divviup_client::{DivviupClient, Uuid};
+use divviup_client::{ApiTokenScope, DivviupClient, NewApiToken, Uuid};
+use humantime::Duration;
+use time::OffsetDateTime;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ScopeName {
+    Read,
+    TasksWrite,
+    AggregatorsWrite,
+    CollectorCredentialsWrite,
+}
+
+impl From<ScopeName> for ApiTokenScope {
+    fn from(value: ScopeName) -> Self {
+        match value {
+            ScopeName::Read => Self::Read,
+            ScopeName::TasksWrite => Self::TasksWrite,
+            ScopeName::AggregatorsWrite => Self::AggregatorsWrite,
+            ScopeName::CollectorCredentialsWrite => Self::CollectorCredentialsWrite,
+        }
+    }
+}
 
 #[derive(Subcommand, Debug)]
 pub enum ApiTokenAction {
@@ -8,10 +29,29 @@ pub enum ApiTokenAction {
     List,
 
     /// create a new api token attached to the target account
-    Create,
+    Create {
+        /// a name to identify the token by
+        #[arg(long)]
+        name: Option<String>,
+
+        /// limit the token to these scopes. tokens without scopes have full access to the account
+        #[arg(long = "scope", value_enum)]
+        scopes: Vec<ScopeName>,
+
+        /// limit the token to these task ids. requires at least one scope
+        #[arg(long = "task-id", requires = "scopes")]
+        task_ids: Vec<String>,
+
+        /// revoke the token automatically after this long, for example 90d
+        #[arg(long)]
+        expires_in: Option<Duration>,
+    },
 
     /// deletes an api token by id
     Delete { api_token_id: Uuid },
+
+    /// show hourly usage of an api token over the past 30 days
+    Usage { api_token_id: Uuid },
 }
 
 impl ApiTokenAction {
@@ -26,13 +66,44 @@ impl ApiTokenAction {
                 output.display(client.api_tokens(account_id.await?).await?);
             }
 
-            ApiTokenAction::Create => {
+            ApiTokenAction::Create {
+                name: None,
+                scopes,
+                expires_in: None,
+                ..
+            } if scopes.is_empty() => {
                 output.display(client.create_api_token(account_id.await?).await?);
             }
 
+            ApiTokenAction::Create {
+                name,
+                scopes,
+                task_ids,
+                expires_in,
+            } => {
+                let new_api_token = NewApiToken {
+                    name,
+                    scopes: (!scopes.is_empty())
+                        .then(|| scopes.into_iter().map(Into::into).collect()),
+                    task_ids: (!task_ids.is_empty()).then_some(task_ids),
+                    expires_at: expires_in.map(|expires_in| {
+                        OffsetDateTime::now_utc() + std::time::Duration::from(expires_in)
+                    }),
+                };
+                output.display(
+                    client
+                        .create_api_token_with_attributes(account_id.await?, new_api_token)
+                        .await?,
+                );
+            }
+
             ApiTokenAction::Delete { api_token_id } => {
                 client.delete_api_token(api_token_id).await?;
             }
+
+            ApiTokenAction::Usage { api_token_id } => {
+                output.display(client.api_token_usage(api_token_id).await?);
+            }
         }
         Ok(())
     }
@@ -0,0 +1,140 @@
+use crate::{CliResult, DetermineAccountId, Output};
+use clap::Subcommand;
+use divviup_client::{AuditAction, AuditEventFilter, AuditTargetType, DivviupClient};
+use humantime::Duration;
+use time::OffsetDateTime;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum ActionName {
+    Create,
+    Update,
+    Delete,
+    Decommission,
+    RequestAccess,
+    GrantAccess,
+    RevokeAccess,
+    StageSecondaryBearerToken,
+    DiscardSecondaryBearerToken,
+    PromoteSecondaryBearerToken,
+}
+
+impl From<ActionName> for AuditAction {
+    fn from(value: ActionName) -> Self {
+        match value {
+            ActionName::Create => Self::Create,
+            ActionName::Update => Self::Update,
+            ActionName::Delete => Self::Delete,
+            ActionName::Decommission => Self::Decommission,
+            ActionName::RequestAccess => Self::RequestAccess,
+            ActionName::GrantAccess => Self::GrantAccess,
+            ActionName::RevokeAccess => Self::RevokeAccess,
+            ActionName::StageSecondaryBearerToken => Self::StageSecondaryBearerToken,
+            ActionName::DiscardSecondaryBearerToken => Self::DiscardSecondaryBearerToken,
+            ActionName::PromoteSecondaryBearerToken => Self::PromoteSecondaryBearerToken,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum TargetTypeName {
+    Account,
+    Membership,
+    ApiToken,
+    CollectorCredential,
+    Task,
+    Aggregator,
+    AggregatorAccess,
+    QueueJob,
+}
+
+impl From<TargetTypeName> for AuditTargetType {
+    fn from(value: TargetTypeName) -> Self {
+        match value {
+            TargetTypeName::Account => Self::Account,
+            TargetTypeName::Membership => Self::Membership,
+            TargetTypeName::ApiToken => Self::ApiToken,
+            TargetTypeName::CollectorCredential => Self::CollectorCredential,
+            TargetTypeName::Task => Self::Task,
+            TargetTypeName::Aggregator => Self::Aggregator,
+            TargetTypeName::AggregatorAccess => Self::AggregatorAccess,
+            TargetTypeName::QueueJob => Self::QueueJob,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuditLogAction {
+    /// list changes made to the target account's resources, most recent first
+    List {
+        /// only list this kind of change
+        #[arg(long, value_enum)]
+        action: Option<ActionName>,
+
+        /// only list changes to this kind of resource
+        #[arg(long, value_enum)]
+        target_type: Option<TargetTypeName>,
+
+        /// only list changes to the resource with this id
+        #[arg(long)]
+        target_id: Option<String>,
+
+        /// only list changes made by this user email or api token id
+        #[arg(long)]
+        actor: Option<String>,
+
+        /// only list changes made within this long, for example 7d
+        #[arg(long)]
+        since: Option<Duration>,
+
+        /// list at most this many changes
+        #[arg(long)]
+        limit: Option<u64>,
+
+        #[cfg(feature = "admin")]
+        /// list changes for every account and for shared resources (ADMIN)
+        #[arg(long)]
+        all_accounts: bool,
+    },
+}
+
+impl AuditLogAction {
+    pub(crate) async fn run(
+        self,
+        account_id: DetermineAccountId,
+        client: DivviupClient,
+        output: Output,
+    ) -> CliResult {
+        match self {
+            AuditLogAction::List {
+                action,
+                target_type,
+                target_id,
+                actor,
+                since,
+                limit,
+                #[cfg(feature = "admin")]
+                all_accounts,
+            } => {
+                let filter = AuditEventFilter {
+                    action: action.map(Into::into),
+                    target_type: target_type.map(Into::into),
+                    target_id,
+                    actor,
+                    since: since
+                        .map(|since| OffsetDateTime::now_utc() - std::time::Duration::from(since)),
+                    limit,
+                    ..Default::default()
+                };
+
+                #[cfg(feature = "admin")]
+                if all_accounts {
+                    output.display(client.all_audit_events(&filter).await?);
+                    return Ok(());
+                }
+
+                output.display(client.audit_events(account_id.await?, &filter).await?);
+            }
+        }
+        Ok(())
+    }
+}
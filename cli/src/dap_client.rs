@@ -44,11 +44,11 @@ macro_rules! query_dispatch {
 macro_rules! vdaf_dispatch {
     ($divviup_vdaf:expr, ($janus_vdaf:ident) => $body:tt) => {
         match $divviup_vdaf {
-            divviup_client::Vdaf::Count => {
+            divviup_client::Vdaf::Count { .. } => {
                 let $janus_vdaf = Prio3Count::new_count(2).context("failed to instantiate VDAF")?;
                 $body
             }
-            divviup_client::Vdaf::Sum { bits } => {
+            divviup_client::Vdaf::Sum { bits, .. } => {
                 let $janus_vdaf =
                     Prio3Sum::new_sum(2, bits as usize).context("failed to instantiate VDAF")?;
                 $body
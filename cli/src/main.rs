@@ -11,15 +11,20 @@
 
 mod accounts;
 mod aggregators;
+mod alerts;
 mod api_tokens;
+mod audit_events;
 mod collector_credentials;
 mod dap_client;
 mod memberships;
 mod tasks;
+mod webhooks;
 
 use accounts::AccountAction;
 use aggregators::AggregatorAction;
+use alerts::AlertAction;
 use api_tokens::ApiTokenAction;
+use audit_events::AuditLogAction;
 use clap::{Parser, Subcommand, ValueEnum};
 use collector_credentials::CollectorCredentialAction;
 use colored::Colorize;
@@ -37,6 +42,7 @@ use std::{
 };
 use tasks::TaskAction;
 use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Layer, Registry};
+use webhooks::WebhookAction;
 pub const USER_AGENT: &str = concatcp!(
     "divviup-cli/",
     env!("CARGO_PKG_VERSION"),
@@ -112,6 +118,10 @@ enum Resource {
     #[command(subcommand)]
     ApiToken(ApiTokenAction),
 
+    /// who changed what in your account, and when
+    #[command(subcommand)]
+    Audit(AuditLogAction),
+
     /// privacy-preserving metrics in the divviup system
     #[command(subcommand)]
     Task(TaskAction),
@@ -131,12 +141,20 @@ enum Resource {
     /// manage asymmetrical encryption keys for collecting task aggregates later
     #[command(subcommand)]
     CollectorCredential(CollectorCredentialAction),
+
+    /// urls that are notified of changes in your account
+    #[command(subcommand)]
+    Webhook(WebhookAction),
+
+    /// rules that notify you when task report or aggregation error counters spike
+    #[command(subcommand)]
+    Alert(AlertAction),
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
-    Client(#[from] divviup_client::Error),
+    Client(Box<divviup_client::Error>),
 
     #[error("account id could not be determined")]
     CouldNotDetermineAccountId,
@@ -159,6 +177,12 @@ pub enum Error {
 
 pub type CliResult<T = ()> = Result<T, Error>;
 
+impl From<divviup_client::Error> for Error {
+    fn from(error: divviup_client::Error) -> Self {
+        Self::Client(Box::new(error))
+    }
+}
+
 impl ClientBin {
     fn client(&self) -> CliResult<DivviupClient> {
         let http_client = reqwest::Client::builder()
@@ -234,11 +258,14 @@ impl Resource {
         match self {
             Resource::Account(action) => action.run(account_id, client, output).await,
             Resource::ApiToken(action) => action.run(account_id, client, output).await,
+            Resource::Audit(action) => action.run(account_id, client, output).await,
             Resource::Task(action) => action.run(account_id, client, output).await,
             Resource::DapClient(action) => action.run(client).await,
             Resource::Aggregator(action) => action.run(account_id, client, output).await,
             Resource::Membership(action) => action.run(account_id, client, output).await,
             Resource::CollectorCredential(action) => action.run(account_id, client, output).await,
+            Resource::Webhook(action) => action.run(account_id, client, output).await,
+            Resource::Alert(action) => action.run(account_id, client, output).await,
         }
     }
 }
@@ -1,15 +1,48 @@
 use crate::{CliResult, DetermineAccountId, Output};
 use clap::Subcommand;
-use divviup_client::{DivviupClient, Uuid};
+use divviup_client::{DivviupClient, MembershipRole, Uuid};
 use email_address::EmailAddress;
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum RoleName {
+    Viewer,
+    Member,
+    Admin,
+    Owner,
+}
+
+impl From<RoleName> for MembershipRole {
+    fn from(value: RoleName) -> Self {
+        match value {
+            RoleName::Viewer => Self::Viewer,
+            RoleName::Member => Self::Member,
+            RoleName::Admin => Self::Admin,
+            RoleName::Owner => Self::Owner,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum MembershipAction {
     /// List all memberships for the target account
     List,
 
     /// Invite a user by email to the target account
-    Create { email: EmailAddress },
+    Create {
+        email: EmailAddress,
+
+        /// what the user is allowed to do in the account, defaulting to member
+        #[arg(long, value_enum)]
+        role: Option<RoleName>,
+    },
+
+    /// Change the role of a membership by id
+    SetRole {
+        membership_id: Uuid,
+
+        #[arg(value_enum)]
+        role: RoleName,
+    },
 
     /// Remove a membership by id from the target account
     Delete { membership_id: Uuid },
@@ -28,12 +61,30 @@ impl MembershipAction {
         match self {
             MembershipAction::List => output.display(client.memberships(account_id.await?).await?),
 
-            MembershipAction::Create { email } => output.display(
+            MembershipAction::Create { email, role: None } => output.display(
                 client
                     .create_membership(account_id.await?, email.as_ref())
                     .await?,
             ),
 
+            MembershipAction::Create {
+                email,
+                role: Some(role),
+            } => output.display(
+                client
+                    .create_membership_with_role(account_id.await?, email.as_ref(), role.into())
+                    .await?,
+            ),
+
+            MembershipAction::SetRole {
+                membership_id,
+                role,
+            } => output.display(
+                client
+                    .update_membership_role(membership_id, role.into())
+                    .await?,
+            ),
+
             MembershipAction::Delete { membership_id } => {
                 client.delete_membership(membership_id).await?;
             }
@@ -2,7 +2,7 @@ use crate::{CliResult, DetermineAccountId, Error, Output};
 use clap::Subcommand;
 use divviup_client::{
     dp_strategy::{self, PureDpBudget, PureDpDiscreteLaplace},
-    BigUint, DivviupClient, Histogram, NewTask, Ratio, SumVec, Uuid, Vdaf,
+    BigUint, DivviupClient, DpEstimateParams, Histogram, NewTask, Ratio, SumVec, Uuid, Vdaf,
 };
 use humantime::{Duration, Timestamp};
 use std::time::SystemTime;
@@ -22,11 +22,34 @@ pub enum DpStrategy {
     PureDpDiscreteLaplace,
 }
 
+#[derive(clap::Args, Debug)]
+pub struct VdafArgs {
+    #[arg(long)]
+    vdaf: VdafName,
+    #[arg(long, value_delimiter = ',')]
+    categorical_buckets: Option<Vec<String>>,
+    #[arg(long, value_delimiter = ',')]
+    continuous_buckets: Option<Vec<u64>>,
+    #[arg(long, required_if_eq_any([("vdaf", "count_vec"), ("vdaf", "sum_vec")]))]
+    length: Option<u64>,
+    #[arg(long, required_if_eq_any([("vdaf", "sum"), ("vdaf", "sum_vec")]))]
+    bits: Option<u8>,
+    #[arg(long)]
+    chunk_length: Option<u64>,
+    #[arg(long, requires = "differential_privacy_epsilon")]
+    differential_privacy_strategy: Option<DpStrategy>,
+    #[arg(long, requires = "differential_privacy_strategy")]
+    differential_privacy_epsilon: Option<f64>,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum TaskAction {
     /// list all tasks for the target account
     List,
 
+    /// summarize the metrics of every task in the target account, without refreshing them
+    Summary,
+
     /// retrieve details of a single task. this also refreshes cached data, such as metrics.
     Get { task_id: String },
 
@@ -38,8 +61,8 @@ pub enum TaskAction {
         leader_aggregator_id: Uuid,
         #[arg(long)]
         helper_aggregator_id: Uuid,
-        #[arg(long)]
-        vdaf: VdafName,
+        #[command(flatten)]
+        vdaf_args: VdafArgs,
         #[arg(long)]
         min_batch_size: u64,
         #[arg(long)]
@@ -50,22 +73,19 @@ pub enum TaskAction {
         time_precision: Duration,
         #[arg(long)]
         collector_credential_id: Uuid,
-        #[arg(long, value_delimiter = ',')]
-        categorical_buckets: Option<Vec<String>>,
-        #[arg(long, value_delimiter = ',')]
-        continuous_buckets: Option<Vec<u64>>,
-        #[arg(long, required_if_eq_any([("vdaf", "count_vec"), ("vdaf", "sum_vec")]))]
-        length: Option<u64>,
-        #[arg(long, required_if_eq_any([("vdaf", "sum"), ("vdaf", "sum_vec")]))]
-        bits: Option<u8>,
+    },
+
+    /// estimate the differential privacy noise a vdaf adds to each bucket of a collection
+    DpEstimate {
+        #[command(flatten)]
+        vdaf_args: VdafArgs,
         #[arg(long)]
-        chunk_length: Option<u64>,
-        #[arg(long, requires = "differential_privacy_epsilon")]
-        differential_privacy_strategy: Option<DpStrategy>,
-        #[arg(long, requires = "differential_privacy_strategy")]
-        differential_privacy_epsilon: Option<f64>,
+        min_batch_size: u64,
     },
 
+    /// estimate which batches have enough reports to collect, and when the next one will
+    Readiness { task_id: String },
+
     /// rename a task
     Rename { task_id: String, name: String },
 
@@ -101,152 +121,20 @@ impl TaskAction {
 
         match self {
             TaskAction::List => output.display(client.tasks(account_id).await?),
+            TaskAction::Summary => output.display(client.metrics_summary(account_id).await?),
             TaskAction::Get { task_id } => output.display(client.task(&task_id).await?),
             TaskAction::Create {
                 name,
                 leader_aggregator_id,
                 helper_aggregator_id,
-                vdaf,
+                vdaf_args,
                 min_batch_size,
                 max_batch_size,
                 batch_time_window_size,
                 time_precision,
                 collector_credential_id,
-                categorical_buckets,
-                continuous_buckets,
-                length,
-                bits,
-                chunk_length,
-                differential_privacy_strategy,
-                differential_privacy_epsilon,
             } => {
-                let vdaf = match vdaf {
-                    VdafName::Count => {
-                        if differential_privacy_strategy.is_some()
-                            || differential_privacy_epsilon.is_some()
-                        {
-                            return Err(Error::Other(
-                                "differential privacy noise is not yet supported with Prio3Count"
-                                    .into(),
-                            ));
-                        }
-                        Vdaf::Count
-                    }
-                    VdafName::Histogram => {
-                        let dp_strategy =
-                            match (differential_privacy_strategy, differential_privacy_epsilon) {
-                                (None, None) => dp_strategy::Prio3Histogram::NoDifferentialPrivacy,
-                                (None, Some(_)) => {
-                                    return Err(Error::Other(
-                                        "missing differential-privacy-strategy".into(),
-                                    ))
-                                }
-                                (Some(_), None) => {
-                                    return Err(Error::Other(
-                                        "missing differential-privacy-epsilon".into(),
-                                    ))
-                                }
-                                (Some(DpStrategy::PureDpDiscreteLaplace), Some(epsilon)) => {
-                                    dp_strategy::Prio3Histogram::PureDpDiscreteLaplace(
-                                        PureDpDiscreteLaplace {
-                                            budget: PureDpBudget {
-                                                epsilon: float_to_biguint_ratio(epsilon)
-                                                    .ok_or_else(|| {
-                                                        Error::Other("invalid epsilon".into())
-                                                    })?,
-                                            },
-                                        },
-                                    )
-                                }
-                            };
-                        match (length, categorical_buckets, continuous_buckets) {
-                            (Some(length), None, None) => Vdaf::Histogram(Histogram::Length {
-                                length,
-                                chunk_length,
-                                dp_strategy,
-                            }),
-                            (None, Some(buckets), None) => {
-                                Vdaf::Histogram(Histogram::Categorical {
-                                    buckets,
-                                    chunk_length,
-                                    dp_strategy,
-                                })
-                            }
-                            (None, None, Some(buckets)) => Vdaf::Histogram(Histogram::Continuous {
-                                buckets,
-                                chunk_length,
-                                dp_strategy,
-                            }),
-                            (None, None, None) => {
-                                return Err(Error::Other("continuous-buckets, categorical-buckets, or length are required for histogram vdaf".into()));
-                            }
-                            _ => {
-                                return Err(Error::Other("continuous-buckets, categorical-buckets, and length are mutually exclusive".into()));
-                            }
-                        }
-                    }
-                    VdafName::Sum => {
-                        if differential_privacy_strategy.is_some()
-                            || differential_privacy_epsilon.is_some()
-                        {
-                            return Err(Error::Other(
-                                "differential privacy noise is not yet supported with Prio3Sum"
-                                    .into(),
-                            ));
-                        }
-                        Vdaf::Sum {
-                            bits: bits.unwrap(),
-                        }
-                    }
-                    VdafName::CountVec => {
-                        if differential_privacy_strategy.is_some()
-                            || differential_privacy_epsilon.is_some()
-                        {
-                            return Err(Error::Other(
-                                "differential privacy noise is not supported with Prio3CountVec"
-                                    .into(),
-                            ));
-                        }
-                        Vdaf::CountVec {
-                            length: length.unwrap(),
-                            chunk_length,
-                        }
-                    }
-                    VdafName::SumVec => {
-                        let dp_strategy =
-                            match (differential_privacy_strategy, differential_privacy_epsilon) {
-                                (None, None) => dp_strategy::Prio3SumVec::NoDifferentialPrivacy,
-                                (None, Some(_)) => {
-                                    return Err(Error::Other(
-                                        "missing differential-privacy-strategy".into(),
-                                    ))
-                                }
-                                (Some(_), None) => {
-                                    return Err(Error::Other(
-                                        "missing differential-privacy-epsilon".into(),
-                                    ))
-                                }
-                                (Some(DpStrategy::PureDpDiscreteLaplace), Some(epsilon)) => {
-                                    dp_strategy::Prio3SumVec::PureDpDiscreteLaplace(
-                                        PureDpDiscreteLaplace {
-                                            budget: PureDpBudget {
-                                                epsilon: float_to_biguint_ratio(epsilon)
-                                                    .ok_or_else(|| {
-                                                        Error::Other("invalid epsilon".into())
-                                                    })?,
-                                            },
-                                        },
-                                    )
-                                }
-                            };
-                        Vdaf::SumVec(SumVec::new(
-                            bits.unwrap(),
-                            length.unwrap(),
-                            chunk_length,
-                            dp_strategy,
-                        ))
-                    }
-                };
+                let vdaf = vdaf_args.into_vdaf()?;
 
                 let time_precision_seconds = time_precision.as_secs();
                 let batch_time_window_size_seconds =
@@ -267,6 +155,22 @@ impl TaskAction {
                 output.display(client.create_task(account_id, task).await?)
             }
 
+            TaskAction::DpEstimate {
+                vdaf_args,
+                min_batch_size,
+            } => output.display(
+                client
+                    .dp_estimate(DpEstimateParams {
+                        vdaf: vdaf_args.into_vdaf()?,
+                        min_batch_size,
+                    })
+                    .await?,
+            ),
+
+            TaskAction::Readiness { task_id } => {
+                output.display(client.task_collection_readiness(&task_id).await?)
+            }
+
             TaskAction::Rename { task_id, name } => {
                 output.display(client.rename_task(&task_id, &name).await?)
             }
@@ -300,6 +204,105 @@ impl TaskAction {
     }
 }
 
+impl VdafArgs {
+    fn into_vdaf(self) -> Result<Vdaf, Error> {
+        let VdafArgs {
+            vdaf,
+            categorical_buckets,
+            continuous_buckets,
+            length,
+            bits,
+            chunk_length,
+            differential_privacy_strategy,
+            differential_privacy_epsilon,
+        } = self;
+        let pure_dp_discrete_laplace =
+            match (differential_privacy_strategy, differential_privacy_epsilon) {
+                (None, None) => None,
+                (None, Some(_)) => {
+                    return Err(Error::Other("missing differential-privacy-strategy".into()))
+                }
+                (Some(_), None) => {
+                    return Err(Error::Other("missing differential-privacy-epsilon".into()))
+                }
+                (Some(DpStrategy::PureDpDiscreteLaplace), Some(epsilon)) => {
+                    Some(PureDpDiscreteLaplace {
+                        budget: PureDpBudget {
+                            epsilon: float_to_biguint_ratio(epsilon)
+                                .ok_or_else(|| Error::Other("invalid epsilon".into()))?,
+                        },
+                    })
+                }
+            };
+
+        let vdaf = match vdaf {
+            VdafName::Count => Vdaf::Count {
+                dp_strategy: pure_dp_discrete_laplace.map_or(
+                    dp_strategy::Prio3Count::NoDifferentialPrivacy,
+                    dp_strategy::Prio3Count::PureDpDiscreteLaplace,
+                ),
+            },
+            VdafName::Histogram => {
+                let dp_strategy = pure_dp_discrete_laplace.map_or(
+                    dp_strategy::Prio3Histogram::NoDifferentialPrivacy,
+                    dp_strategy::Prio3Histogram::PureDpDiscreteLaplace,
+                );
+                match (length, categorical_buckets, continuous_buckets) {
+                    (Some(length), None, None) => Vdaf::Histogram(Histogram::Length {
+                        length,
+                        chunk_length,
+                        dp_strategy,
+                    }),
+                    (None, Some(buckets), None) => Vdaf::Histogram(Histogram::Categorical {
+                        buckets,
+                        chunk_length,
+                        dp_strategy,
+                    }),
+                    (None, None, Some(buckets)) => Vdaf::Histogram(Histogram::Continuous {
+                        buckets,
+                        chunk_length,
+                        dp_strategy,
+                    }),
+                    (None, None, None) => {
+                        return Err(Error::Other("continuous-buckets, categorical-buckets, or length are required for histogram vdaf".into()));
+                    }
+                    _ => {
+                        return Err(Error::Other("continuous-buckets, categorical-buckets, and length are mutually exclusive".into()));
+                    }
+                }
+            }
+            VdafName::Sum => Vdaf::Sum {
+                bits: bits.unwrap(),
+                dp_strategy: pure_dp_discrete_laplace.map_or(
+                    dp_strategy::Prio3Sum::NoDifferentialPrivacy,
+                    dp_strategy::Prio3Sum::PureDpDiscreteLaplace,
+                ),
+            },
+            VdafName::CountVec => {
+                if pure_dp_discrete_laplace.is_some() {
+                    return Err(Error::Other(
+                        "differential privacy noise is not supported with Prio3CountVec".into(),
+                    ));
+                }
+                Vdaf::CountVec {
+                    length: length.unwrap(),
+                    chunk_length,
+                }
+            }
+            VdafName::SumVec => Vdaf::SumVec(SumVec::new(
+                bits.unwrap(),
+                length.unwrap(),
+                chunk_length,
+                pure_dp_discrete_laplace.map_or(
+                    dp_strategy::Prio3SumVec::NoDifferentialPrivacy,
+                    dp_strategy::Prio3SumVec::PureDpDiscreteLaplace,
+                ),
+            )),
+        };
+        Ok(vdaf)
+    }
+}
+
 fn float_to_biguint_ratio(value: f64) -> Option<Ratio<BigUint>> {
     let signed_ratio = Ratio::from_float(value)?;
 
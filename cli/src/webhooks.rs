@@ -0,0 +1,142 @@
+use crate::{CliResult, DetermineAccountId, Output};
+use clap::Subcommand;
+use divviup_client::{DivviupClient, NewWebhook, UpdateWebhook, Url, Uuid, WebhookEventType};
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum EventTypeName {
+    TaskCreated,
+    TaskExpired,
+    TaskDeleted,
+    MembershipCreated,
+    MembershipUpdated,
+    MembershipDeleted,
+    AggregatorDecommissioned,
+    AggregatorCapabilitiesRemoved,
+    AggregatorDeleted,
+    AlertFired,
+    AlertResolved,
+}
+
+impl From<EventTypeName> for WebhookEventType {
+    fn from(value: EventTypeName) -> Self {
+        match value {
+            EventTypeName::TaskCreated => Self::TaskCreated,
+            EventTypeName::TaskExpired => Self::TaskExpired,
+            EventTypeName::TaskDeleted => Self::TaskDeleted,
+            EventTypeName::MembershipCreated => Self::MembershipCreated,
+            EventTypeName::MembershipUpdated => Self::MembershipUpdated,
+            EventTypeName::MembershipDeleted => Self::MembershipDeleted,
+            EventTypeName::AggregatorDecommissioned => Self::AggregatorDecommissioned,
+            EventTypeName::AggregatorCapabilitiesRemoved => Self::AggregatorCapabilitiesRemoved,
+            EventTypeName::AggregatorDeleted => Self::AggregatorDeleted,
+            EventTypeName::AlertFired => Self::AlertFired,
+            EventTypeName::AlertResolved => Self::AlertResolved,
+        }
+    }
+}
+
+fn event_types(event_types: Vec<EventTypeName>) -> Option<Vec<WebhookEventType>> {
+    (!event_types.is_empty()).then(|| event_types.into_iter().map(Into::into).collect())
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WebhookAction {
+    /// list all webhooks for the target account
+    List,
+
+    /// create a webhook that receives events from the target account
+    Create {
+        /// the https url that events are posted to
+        url: Url,
+
+        /// a name to identify the webhook by
+        #[arg(long)]
+        name: Option<String>,
+
+        /// only send these event types. webhooks without event types receive every event
+        #[arg(long = "event", value_enum)]
+        event_types: Vec<EventTypeName>,
+    },
+
+    /// change a webhook's name, url or event types
+    Update {
+        webhook_id: Uuid,
+
+        #[arg(long)]
+        name: Option<String>,
+
+        #[arg(long)]
+        url: Option<Url>,
+
+        /// only send these event types
+        #[arg(long = "event", value_enum, conflicts_with = "all_events")]
+        event_types: Vec<EventTypeName>,
+
+        /// send every event type, including ones added later
+        #[arg(long)]
+        all_events: bool,
+    },
+
+    /// deletes a webhook by id
+    Delete { webhook_id: Uuid },
+
+    /// show recent attempts to deliver events to a webhook
+    Deliveries { webhook_id: Uuid },
+}
+
+impl WebhookAction {
+    pub(crate) async fn run(
+        self,
+        account_id: DetermineAccountId,
+        client: DivviupClient,
+        output: Output,
+    ) -> CliResult {
+        match self {
+            WebhookAction::List => {
+                output.display(client.webhooks(account_id.await?).await?);
+            }
+
+            WebhookAction::Create {
+                url,
+                name,
+                event_types: types,
+            } => {
+                let new_webhook = NewWebhook {
+                    name,
+                    url,
+                    event_types: event_types(types),
+                };
+                output.display(
+                    client
+                        .create_webhook(account_id.await?, new_webhook)
+                        .await?,
+                );
+            }
+
+            WebhookAction::Update {
+                webhook_id,
+                name,
+                url,
+                event_types: types,
+                all_events,
+            } => {
+                let update_webhook = UpdateWebhook {
+                    name,
+                    url,
+                    event_types: event_types(types),
+                    all_event_types: all_events,
+                };
+                output.display(client.update_webhook(webhook_id, update_webhook).await?);
+            }
+
+            WebhookAction::Delete { webhook_id } => {
+                client.delete_webhook(webhook_id).await?;
+            }
+
+            WebhookAction::Deliveries { webhook_id } => {
+                output.display(client.webhook_deliveries(webhook_id).await?);
+            }
+        }
+        Ok(())
+    }
+}
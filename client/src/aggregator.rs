@@ -1,4 +1,4 @@
-use crate::Protocol;
+use crate::{Protocol, ValidationErrors};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use url::Url;
@@ -11,6 +11,15 @@ pub enum Role {
     Either,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Allowlist,
+    RequestAccess,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct Aggregator {
     pub id: Uuid,
@@ -32,6 +41,76 @@ pub struct Aggregator {
     pub query_types: Vec<String>,
     pub protocol: Protocol,
     pub features: Vec<String>,
+    #[serde(default)]
+    pub has_secondary_bearer_token: bool,
+    #[serde(default)]
+    pub has_client_identity: bool,
+    #[serde(default)]
+    pub ca_certificate: Option<String>,
+    #[serde(default)]
+    pub hpke_config_ids: Option<Vec<u8>>,
+    #[serde(default)]
+    pub hpke_config_error: Option<String>,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub hpke_configs_checked_at: Option<OffsetDateTime>,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub sunset_at: Option<OffsetDateTime>,
+    #[serde(default)]
+    pub visibility: Visibility,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AggregatorAccess {
+    pub id: Uuid,
+    pub aggregator_id: Uuid,
+    pub account_id: Uuid,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    // a granted_at of None indicates a request for access that has not been granted yet
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub granted_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AggregatorPairCompatibility {
+    pub compatible: bool,
+    pub errors: ValidationErrors,
+    pub protocol: Option<Protocol>,
+    pub vdafs: Vec<String>,
+    pub query_types: Vec<String>,
+    pub features: Vec<String>,
+    pub time_bucketed_fixed_size: bool,
+    pub creatable_vdafs: Vec<CreatableVdaf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct CreatableVdaf {
+    #[serde(rename = "type")]
+    pub vdaf_type: String,
+    pub dp_strategies: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TaskExpirationStatus {
+    Pending,
+    Success,
+    Failed,
+}
+
+/// How far expiring the tasks of an aggregator that was deleted along with its tasks has
+/// progressed.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct TaskExpirationProgress {
+    pub aggregator_id: Uuid,
+    pub status: TaskExpirationStatus,
+    pub expired_task_ids: Vec<String>,
+    // tasks that were expired but that one of their aggregators could not be told about
+    pub failed_task_ids: Vec<String>,
+    pub remaining_tasks: u64,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
@@ -41,6 +120,13 @@ pub struct NewAggregator {
     pub bearer_token: String,
 }
 
+#[cfg(feature = "admin")]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct AggregatorDecommission {
+    pub aggregator: Aggregator,
+    pub affected_tasks: Vec<crate::Task>,
+}
+
 #[cfg(feature = "admin")]
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct NewSharedAggregator {
@@ -48,6 +134,8 @@ pub struct NewSharedAggregator {
     pub api_url: Url,
     pub is_first_party: bool,
     pub bearer_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
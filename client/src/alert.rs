@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskCounter {
+    ReportCounterIntervalCollected,
+    ReportCounterDecodeFailure,
+    ReportCounterDecryptFailure,
+    ReportCounterExpired,
+    ReportCounterOutdatedKey,
+    ReportCounterSuccess,
+    ReportCounterTooEarly,
+    ReportCounterTaskExpired,
+    AggregationJobCounterSuccess,
+    AggregationJobCounterHelperBatchCollected,
+    AggregationJobCounterHelperReportReplayed,
+    AggregationJobCounterHelperReportDropped,
+    AggregationJobCounterHelperHpkeUnknownConfigId,
+    AggregationJobCounterHelperHpkeDecryptFailure,
+    AggregationJobCounterHelperVdafPrepError,
+    AggregationJobCounterHelperTaskExpired,
+    AggregationJobCounterHelperInvalidMessage,
+    AggregationJobCounterHelperReportTooEarly,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertChannel {
+    Email,
+    Webhook,
+}
+
+/// Fires for a task when `counter` increased by more than `threshold` over the last
+/// `window_seconds`, or by more than `threshold` times the increase of `relative_to`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    // None indicates a rule for every task in the account
+    pub task_id: Option<String>,
+    pub name: Option<String>,
+    pub counter: TaskCounter,
+    pub relative_to: Option<TaskCounter>,
+    pub threshold: f64,
+    pub window_seconds: u64,
+    pub channels: Vec<AlertChannel>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub deleted_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct NewAlertRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    pub counter: Option<TaskCounter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative_to: Option<TaskCounter>,
+    pub threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<Vec<AlertChannel>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UpdateAlertRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counter: Option<TaskCounter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relative_to: Option<TaskCounter>,
+    /// compare absolute increases of the counter instead of comparing to `relative_to`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub absolute: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<Vec<AlertChannel>>,
+}
+
+/// A period during which an alert rule's threshold was exceeded for a task.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub id: Uuid,
+    pub alert_rule_id: Uuid,
+    pub account_id: Uuid,
+    pub task_id: String,
+    pub value: f64,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub fired_at: OffsetDateTime,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub resolved_at: Option<OffsetDateTime>,
+}
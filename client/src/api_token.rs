@@ -1,7 +1,20 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum ApiTokenScope {
+    #[serde(rename = "read")]
+    Read,
+    #[serde(rename = "tasks:write")]
+    TasksWrite,
+    #[serde(rename = "aggregators:write")]
+    AggregatorsWrite,
+    #[serde(rename = "collector_credentials:write")]
+    CollectorCredentialsWrite,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct ApiToken {
     pub id: Uuid,
@@ -17,4 +30,44 @@ pub struct ApiToken {
     pub updated_at: OffsetDateTime,
     pub name: Option<String>,
     pub token: Option<String>,
+    // None indicates a token with full access to its account
+    #[serde(default)]
+    pub scopes: Option<Vec<ApiTokenScope>>,
+    // None indicates a token that is not limited to specific tasks
+    #[serde(default)]
+    pub task_ids: Option<Vec<String>>,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct NewApiToken {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<ApiTokenScope>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_ids: Option<Vec<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::time::serde::rfc3339::option"
+    )]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+/// How an api token was used during one hour.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ApiTokenUsage {
+    pub id: Uuid,
+    pub api_token_id: Uuid,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub hour: OffsetDateTime,
+    pub request_count: u64,
+    /// request counts by method and route
+    pub routes: BTreeMap<String, u64>,
+    /// request counts by source address
+    pub source_ips: BTreeMap<String, u64>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub last_used_at: OffsetDateTime,
 }
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use url::form_urlencoded;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Decommission,
+    RequestAccess,
+    GrantAccess,
+    RevokeAccess,
+    StageSecondaryBearerToken,
+    DiscardSecondaryBearerToken,
+    PromoteSecondaryBearerToken,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditTargetType {
+    Account,
+    Membership,
+    ApiToken,
+    CollectorCredential,
+    Task,
+    Aggregator,
+    AggregatorAccess,
+    QueueJob,
+    Webhook,
+    AlertRule,
+}
+
+/// A field's value before and after an audited change. Values are null when the target did not
+/// exist before or after it.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct AuditChange {
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub account_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub actor_api_token_id: Option<Uuid>,
+    pub action: AuditAction,
+    pub target_type: AuditTargetType,
+    pub target_id: String,
+    pub changes: BTreeMap<String, AuditChange>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Filters for listing audit events, all of which are optional.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AuditEventFilter {
+    /// only used when listing events for all accounts
+    pub account_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub target_type: Option<AuditTargetType>,
+    pub target_id: Option<String>,
+    /// a user's email or an api token's id
+    pub actor: Option<String>,
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+    pub limit: Option<u64>,
+}
+
+fn snake_case(value: impl Serialize) -> Option<String> {
+    match serde_json::to_value(value) {
+        Ok(Value::String(s)) => Some(s),
+        _ => None,
+    }
+}
+
+impl AuditEventFilter {
+    pub(crate) fn query_string(&self) -> String {
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        if let Some(account_id) = self.account_id {
+            query.append_pair("account_id", &account_id.to_string());
+        }
+        if let Some(action) = self.action.and_then(snake_case) {
+            query.append_pair("action", &action);
+        }
+        if let Some(target_type) = self.target_type.and_then(snake_case) {
+            query.append_pair("target_type", &target_type);
+        }
+        if let Some(target_id) = &self.target_id {
+            query.append_pair("target_id", target_id);
+        }
+        if let Some(actor) = &self.actor {
+            query.append_pair("actor", actor);
+        }
+        if let Some(since) = self.since.and_then(|since| since.format(&Rfc3339).ok()) {
+            query.append_pair("since", &since);
+        }
+        if let Some(until) = self.until.and_then(|until| until.format(&Rfc3339).ok()) {
+            query.append_pair("until", &until);
+        }
+        if let Some(limit) = self.limit {
+            query.append_pair("limit", &limit.to_string());
+        }
+        query.finish()
+    }
+}
@@ -2,6 +2,36 @@ use num_bigint_5::BigUint;
 use num_rational::Ratio;
 use serde::{Deserialize, Serialize};
 
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default)]
+#[serde(tag = "dp_strategy")]
+#[non_exhaustive]
+pub enum Prio3Count {
+    #[default]
+    NoDifferentialPrivacy,
+    PureDpDiscreteLaplace(PureDpDiscreteLaplace),
+}
+
+impl Prio3Count {
+    pub fn is_no_differential_privacy(&self) -> bool {
+        matches!(self, Self::NoDifferentialPrivacy)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default)]
+#[serde(tag = "dp_strategy")]
+#[non_exhaustive]
+pub enum Prio3Sum {
+    #[default]
+    NoDifferentialPrivacy,
+    PureDpDiscreteLaplace(PureDpDiscreteLaplace),
+}
+
+impl Prio3Sum {
+    pub fn is_no_differential_privacy(&self) -> bool {
+        matches!(self, Self::NoDifferentialPrivacy)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default)]
 #[serde(tag = "dp_strategy")]
 #[non_exhaustive]
@@ -11,13 +11,17 @@
 
 mod account;
 mod aggregator;
+mod alert;
 mod api_token;
+mod audit_event;
 mod collector_credentials;
 pub mod dp_strategy;
 mod membership;
+mod metrics_summary;
 mod protocol;
 mod task;
 mod validation_errors;
+mod webhook;
 
 pub const CONTENT_TYPE: &str = "application/vnd.divviup+json;version=0.1";
 pub const DEFAULT_URL: &str = "https://api.divviup.org/";
@@ -34,27 +38,37 @@ use std::fmt::Display;
 use time::format_description::well_known::Rfc3339;
 
 pub use account::Account;
-pub use aggregator::{Aggregator, CollectorAuthenticationToken, NewAggregator, Role};
-pub use api_token::ApiToken;
+pub use aggregator::{
+    Aggregator, AggregatorAccess, AggregatorPairCompatibility, CollectorAuthenticationToken,
+    CreatableVdaf, NewAggregator, Role, TaskExpirationProgress, TaskExpirationStatus, Visibility,
+};
+pub use alert::{Alert, AlertChannel, AlertRule, NewAlertRule, TaskCounter, UpdateAlertRule};
+pub use api_token::{ApiToken, ApiTokenScope, ApiTokenUsage, NewApiToken};
+pub use audit_event::{AuditAction, AuditChange, AuditEvent, AuditEventFilter, AuditTargetType};
 pub use collector_credentials::CollectorCredential;
 pub use http;
 pub use janus_messages::{
     codec::{CodecError, Decode, Encode},
     HpkeConfig, HpkePublicKey,
 };
-pub use membership::Membership;
+pub use membership::{Membership, MembershipRole};
+pub use metrics_summary::{ExpiringTask, MetricsSummary, TaskErrorRatio, TaskMetrics};
 pub use num_bigint_5::BigUint;
 pub use num_rational::Ratio;
 pub use protocol::Protocol;
 pub use reqwest;
-pub use task::{Histogram, NewTask, SumVec, Task, Vdaf};
+pub use task::{
+    BatchInterval, CollectionReadiness, DpEstimate, DpEstimateParams, HelperAggregationJobMetrics,
+    HelperMetrics, HelperUploadMetrics, Histogram, NewTask, SumVec, Task, Vdaf,
+};
 pub use time::OffsetDateTime;
 pub use url::Url;
 pub use uuid::Uuid;
 pub use validation_errors::ValidationErrors;
+pub use webhook::{NewWebhook, UpdateWebhook, Webhook, WebhookDelivery, WebhookEventType};
 
 #[cfg(feature = "admin")]
-pub use aggregator::NewSharedAggregator;
+pub use aggregator::{AggregatorDecommission, NewSharedAggregator};
 
 #[derive(Debug, Clone)]
 pub struct DivviupClient {
@@ -86,11 +100,15 @@ impl DivviupClient {
         &self.base_url
     }
 
-    fn url(&self, path: &str) -> ClientResult<Url> {
-        self.base_url.join(path).map_err(Error::from)
+    fn url(&self, path: &str) -> Result<Url, url::ParseError> {
+        self.base_url.join(path)
     }
 
-    fn request(&self, method: Method, path: &str) -> ClientResult<reqwest::RequestBuilder> {
+    fn request(
+        &self,
+        method: Method,
+        path: &str,
+    ) -> Result<reqwest::RequestBuilder, url::ParseError> {
         let url = self.url(path)?;
         Ok(self
             .client
@@ -145,6 +163,20 @@ impl DivviupClient {
         Ok(resp.json().await?)
     }
 
+    async fn put<T>(&self, path: &str, body: &impl Serialize) -> ClientResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let resp = self
+            .request(Method::PUT, path)?
+            .header(CONTENT_TYPE_HEADER, CONTENT_TYPE)
+            .json(body)
+            .send()
+            .await?;
+        let resp = Self::check_response(Method::PUT, resp).await?;
+        Ok(resp.json().await?)
+    }
+
     async fn post<T>(&self, path: &str, body: Option<&impl Serialize>) -> ClientResult<T>
     where
         T: DeserializeOwned,
@@ -223,6 +255,50 @@ impl DivviupClient {
         .await
     }
 
+    pub async fn stage_aggregator_secondary_bearer_token(
+        &self,
+        aggregator_id: Uuid,
+        bearer_token: &str,
+    ) -> ClientResult<Aggregator> {
+        self.put(
+            &format!("api/aggregators/{aggregator_id}/secondary_bearer_token"),
+            &json!({ "bearer_token": bearer_token }),
+        )
+        .await
+    }
+
+    pub async fn verify_aggregator_secondary_bearer_token(
+        &self,
+        aggregator_id: Uuid,
+    ) -> ClientResult<Aggregator> {
+        self.post(
+            &format!("api/aggregators/{aggregator_id}/secondary_bearer_token/verify"),
+            Option::<&()>::None,
+        )
+        .await
+    }
+
+    pub async fn promote_aggregator_secondary_bearer_token(
+        &self,
+        aggregator_id: Uuid,
+    ) -> ClientResult<Aggregator> {
+        self.post(
+            &format!("api/aggregators/{aggregator_id}/secondary_bearer_token/promote"),
+            Option::<&()>::None,
+        )
+        .await
+    }
+
+    pub async fn discard_aggregator_secondary_bearer_token(
+        &self,
+        aggregator_id: Uuid,
+    ) -> ClientResult {
+        self.delete(&format!(
+            "api/aggregators/{aggregator_id}/secondary_bearer_token"
+        ))
+        .await
+    }
+
     pub async fn update_aggregator_configuration(
         &self,
         aggregator_id: Uuid,
@@ -231,11 +307,62 @@ impl DivviupClient {
             .await
     }
 
+    pub async fn aggregator_pair_compatibility(
+        &self,
+        account_id: Uuid,
+        leader_aggregator_id: Uuid,
+        helper_aggregator_id: Uuid,
+    ) -> ClientResult<AggregatorPairCompatibility> {
+        self.get(&format!(
+            "api/accounts/{account_id}/aggregator_pairs/compatibility?leader={leader_aggregator_id}&helper={helper_aggregator_id}"
+        ))
+        .await
+    }
+
     pub async fn delete_aggregator(&self, aggregator_id: Uuid) -> ClientResult {
         self.delete(&format!("api/aggregators/{aggregator_id}"))
             .await
     }
 
+    /// Deletes an aggregator, expiring any unexpired tasks that use it. The tasks are expired
+    /// asynchronously after this returns.
+    pub async fn delete_aggregator_and_expire_tasks(&self, aggregator_id: Uuid) -> ClientResult {
+        self.delete(&format!("api/aggregators/{aggregator_id}?cascade=expire"))
+            .await
+    }
+
+    /// Reports how far expiring the tasks of an aggregator deleted with
+    /// [`Self::delete_aggregator_and_expire_tasks`] has progressed.
+    pub async fn aggregator_task_expiration(
+        &self,
+        aggregator_id: Uuid,
+    ) -> ClientResult<TaskExpirationProgress> {
+        self.get(&format!("api/aggregators/{aggregator_id}/task_expiration"))
+            .await
+    }
+
+    /// Lists this account's requests for, and grants of, access to shared aggregators that are
+    /// not public.
+    pub async fn aggregator_access_for_account(
+        &self,
+        account_id: Uuid,
+    ) -> ClientResult<Vec<AggregatorAccess>> {
+        self.get(&format!("api/accounts/{account_id}/aggregator_access"))
+            .await
+    }
+
+    pub async fn request_aggregator_access(
+        &self,
+        account_id: Uuid,
+        aggregator_id: Uuid,
+    ) -> ClientResult<AggregatorAccess> {
+        self.post(
+            &format!("api/accounts/{account_id}/aggregator_access"),
+            Some(&json!({ "aggregator_id": aggregator_id })),
+        )
+        .await
+    }
+
     pub async fn memberships(&self, account_id: Uuid) -> ClientResult<Vec<Membership>> {
         self.get(&format!("api/accounts/{account_id}/memberships"))
             .await
@@ -258,6 +385,38 @@ impl DivviupClient {
         .await
     }
 
+    pub async fn create_membership_with_role(
+        &self,
+        account_id: Uuid,
+        email: &str,
+        role: MembershipRole,
+    ) -> ClientResult<Membership> {
+        self.post(
+            &format!("api/accounts/{account_id}/memberships"),
+            Some(&json!({ "user_email": email, "role": role })),
+        )
+        .await
+    }
+
+    pub async fn update_membership_role(
+        &self,
+        membership_id: Uuid,
+        role: MembershipRole,
+    ) -> ClientResult<Membership> {
+        self.patch(
+            &format!("api/memberships/{membership_id}"),
+            &json!({ "role": role }),
+        )
+        .await
+    }
+
+    /// Counter totals and per-task breakdowns for every task in an account. This does not refresh
+    /// any task's metrics.
+    pub async fn metrics_summary(&self, account_id: Uuid) -> ClientResult<MetricsSummary> {
+        self.get(&format!("api/accounts/{account_id}/metrics_summary"))
+            .await
+    }
+
     pub async fn tasks(&self, account_id: Uuid) -> ClientResult<Vec<Task>> {
         self.get(&format!("api/accounts/{account_id}/tasks")).await
     }
@@ -266,6 +425,20 @@ impl DivviupClient {
         self.get(&format!("api/tasks/{task_id}")).await
     }
 
+    /// Estimates which batches of a task have enough reports to collect, and when the next one
+    /// will.
+    pub async fn task_collection_readiness(
+        &self,
+        task_id: &str,
+    ) -> ClientResult<CollectionReadiness> {
+        self.get(&format!("api/tasks/{task_id}/collection_readiness"))
+            .await
+    }
+
+    pub async fn dp_estimate(&self, params: DpEstimateParams) -> ClientResult<DpEstimate> {
+        self.post("api/dp_estimate", Some(&params)).await
+    }
+
     pub async fn create_task(&self, account_id: Uuid, task: NewTask) -> ClientResult<Task> {
         self.post(&format!("api/accounts/{account_id}/tasks"), Some(&task))
             .await
@@ -312,10 +485,30 @@ impl DivviupClient {
         .await
     }
 
+    /// Creates an api token with a name, scopes, task ids or an expiration, any of which can be
+    /// omitted.
+    pub async fn create_api_token_with_attributes(
+        &self,
+        account_id: Uuid,
+        new_api_token: NewApiToken,
+    ) -> ClientResult<ApiToken> {
+        self.post(
+            &format!("api/accounts/{account_id}/api_tokens"),
+            Some(&new_api_token),
+        )
+        .await
+    }
+
     pub async fn delete_api_token(&self, api_token_id: Uuid) -> ClientResult {
         self.delete(&format!("api/api_tokens/{api_token_id}")).await
     }
 
+    /// Hourly usage of an api token over the past 30 days, most recent first.
+    pub async fn api_token_usage(&self, api_token_id: Uuid) -> ClientResult<Vec<ApiTokenUsage>> {
+        self.get(&format!("api/api_tokens/{api_token_id}/usage"))
+            .await
+    }
+
     pub async fn collector_credentials(
         &self,
         account_id: Uuid,
@@ -362,10 +555,108 @@ impl DivviupClient {
     pub async fn shared_aggregators(&self) -> ClientResult<Vec<Aggregator>> {
         self.get("api/aggregators").await
     }
+
+    pub async fn webhooks(&self, account_id: Uuid) -> ClientResult<Vec<Webhook>> {
+        self.get(&format!("api/accounts/{account_id}/webhooks"))
+            .await
+    }
+
+    /// Creates a webhook. The returned webhook's secret is not available again.
+    pub async fn create_webhook(
+        &self,
+        account_id: Uuid,
+        new_webhook: NewWebhook,
+    ) -> ClientResult<Webhook> {
+        self.post(
+            &format!("api/accounts/{account_id}/webhooks"),
+            Some(&new_webhook),
+        )
+        .await
+    }
+
+    pub async fn update_webhook(
+        &self,
+        webhook_id: Uuid,
+        update_webhook: UpdateWebhook,
+    ) -> ClientResult<Webhook> {
+        self.patch(&format!("api/webhooks/{webhook_id}"), &update_webhook)
+            .await
+    }
+
+    pub async fn delete_webhook(&self, webhook_id: Uuid) -> ClientResult {
+        self.delete(&format!("api/webhooks/{webhook_id}")).await
+    }
+
+    /// Recent attempts to deliver events to a webhook, most recent first.
+    pub async fn webhook_deliveries(&self, webhook_id: Uuid) -> ClientResult<Vec<WebhookDelivery>> {
+        self.get(&format!("api/webhooks/{webhook_id}/deliveries"))
+            .await
+    }
+
+    pub async fn alert_rules(&self, account_id: Uuid) -> ClientResult<Vec<AlertRule>> {
+        self.get(&format!("api/accounts/{account_id}/alert_rules"))
+            .await
+    }
+
+    pub async fn create_alert_rule(
+        &self,
+        account_id: Uuid,
+        new_alert_rule: NewAlertRule,
+    ) -> ClientResult<AlertRule> {
+        self.post(
+            &format!("api/accounts/{account_id}/alert_rules"),
+            Some(&new_alert_rule),
+        )
+        .await
+    }
+
+    pub async fn update_alert_rule(
+        &self,
+        alert_rule_id: Uuid,
+        update_alert_rule: UpdateAlertRule,
+    ) -> ClientResult<AlertRule> {
+        self.patch(
+            &format!("api/alert_rules/{alert_rule_id}"),
+            &update_alert_rule,
+        )
+        .await
+    }
+
+    pub async fn delete_alert_rule(&self, alert_rule_id: Uuid) -> ClientResult {
+        self.delete(&format!("api/alert_rules/{alert_rule_id}"))
+            .await
+    }
+
+    /// Alerts that are currently firing in an account, most recent first.
+    pub async fn firing_alerts(&self, account_id: Uuid) -> ClientResult<Vec<Alert>> {
+        self.get(&format!("api/accounts/{account_id}/alerts")).await
+    }
+
+    /// Changes made to an account's resources, most recent first.
+    pub async fn audit_events(
+        &self,
+        account_id: Uuid,
+        filter: &AuditEventFilter,
+    ) -> ClientResult<Vec<AuditEvent>> {
+        self.get(&format!(
+            "api/accounts/{account_id}/audit_events?{}",
+            filter.query_string()
+        ))
+        .await
+    }
 }
 
 #[cfg(feature = "admin")]
 impl DivviupClient {
+    /// Changes made to every account's resources and to shared resources, most recent first.
+    pub async fn all_audit_events(
+        &self,
+        filter: &AuditEventFilter,
+    ) -> ClientResult<Vec<AuditEvent>> {
+        self.get(&format!("api/admin/audit_events?{}", filter.query_string()))
+            .await
+    }
+
     pub async fn create_account(&self, name: &str) -> ClientResult<Account> {
         self.post("api/accounts", Some(&json!({ "name": name })))
             .await
@@ -377,6 +668,69 @@ impl DivviupClient {
     ) -> ClientResult<Aggregator> {
         self.post("api/aggregators", Some(&aggregator)).await
     }
+
+    pub async fn set_aggregator_visibility(
+        &self,
+        aggregator_id: Uuid,
+        visibility: Visibility,
+    ) -> ClientResult<Aggregator> {
+        self.patch(
+            &format!("api/aggregators/{aggregator_id}"),
+            &json!({ "visibility": visibility }),
+        )
+        .await
+    }
+
+    pub async fn aggregator_access(
+        &self,
+        aggregator_id: Uuid,
+    ) -> ClientResult<Vec<AggregatorAccess>> {
+        self.get(&format!("api/aggregators/{aggregator_id}/access"))
+            .await
+    }
+
+    pub async fn grant_aggregator_access(
+        &self,
+        aggregator_id: Uuid,
+        account_id: Uuid,
+    ) -> ClientResult<AggregatorAccess> {
+        self.put(
+            &format!("api/aggregators/{aggregator_id}/access/{account_id}"),
+            &json!({}),
+        )
+        .await
+    }
+
+    pub async fn revoke_aggregator_access(
+        &self,
+        aggregator_id: Uuid,
+        account_id: Uuid,
+    ) -> ClientResult {
+        self.delete(&format!(
+            "api/aggregators/{aggregator_id}/access/{account_id}"
+        ))
+        .await
+    }
+
+    pub async fn aggregator_decommission(
+        &self,
+        aggregator_id: Uuid,
+    ) -> ClientResult<AggregatorDecommission> {
+        self.get(&format!("api/aggregators/{aggregator_id}/decommission"))
+            .await
+    }
+
+    pub async fn decommission_aggregator(
+        &self,
+        aggregator_id: Uuid,
+        sunset_at: &OffsetDateTime,
+    ) -> ClientResult<AggregatorDecommission> {
+        self.post(
+            &format!("api/aggregators/{aggregator_id}/decommission"),
+            Some(&json!({ "sunset_at": sunset_at.format(&Rfc3339)? })),
+        )
+        .await
+    }
 }
 
 pub type ClientResult<T = ()> = Result<T, Error>;
@@ -2,9 +2,23 @@ use email_address::EmailAddress;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// A member's level of access to an account, from least to most privileged.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MembershipRole {
+    Viewer,
+    Member,
+    Admin,
+    // memberships that predate roles had full control of their account
+    #[default]
+    Owner,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct Membership {
     pub id: Uuid,
     pub user_email: EmailAddress,
     pub account_id: Uuid,
+    #[serde(default)]
+    pub role: MembershipRole,
 }
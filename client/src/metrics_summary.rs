@@ -0,0 +1,42 @@
+use crate::TaskCounter;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::OffsetDateTime;
+
+/// The counters of every task in an account as of each task's last metrics refresh
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetricsSummary {
+    pub task_count: u64,
+    pub totals: BTreeMap<TaskCounter, i64>,
+    pub tasks: Vec<TaskMetrics>,
+    /// tasks with any errors, highest ratio first
+    pub highest_error_ratios: Vec<TaskErrorRatio>,
+    /// unexpired tasks that expire within a week, soonest first
+    pub expiring_soon: Vec<ExpiringTask>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskMetrics {
+    pub task_id: String,
+    pub name: String,
+    pub counters: BTreeMap<TaskCounter, i64>,
+    pub upload_error_ratio: f64,
+    pub aggregation_error_ratio: f64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskErrorRatio {
+    pub task_id: String,
+    pub name: String,
+    pub error_ratio: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExpiringTask {
+    pub task_id: String,
+    pub name: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expiration: OffsetDateTime,
+}
@@ -21,10 +21,10 @@ pub struct Task {
     #[serde(with = "time::serde::rfc3339::option")]
     pub deleted_at: Option<OffsetDateTime>,
     pub time_precision_seconds: u32,
-    #[deprecated = "Not populated. Will be removed in a future release."]
-    pub report_count: u32,
-    #[deprecated = "Not populated. Will be removed in a future release."]
-    pub aggregate_collection_count: u32,
+    /// reports included in finished collections, if the leader reports collection job metrics
+    pub report_count: i64,
+    /// finished collections, if the leader reports collection job metrics
+    pub aggregate_collection_count: i64,
     #[serde(default, with = "time::serde::rfc3339::option")]
     pub expiration: Option<OffsetDateTime>,
     pub leader_aggregator_id: Uuid,
@@ -59,6 +59,98 @@ pub struct Task {
     pub aggregation_job_counter_helper_invalid_message: i64,
     #[serde(default)]
     pub aggregation_job_counter_helper_report_too_early: i64,
+
+    #[serde(default)]
+    pub collection_job_counter_pending: i64,
+    #[serde(default)]
+    pub collection_job_counter_abandoned: i64,
+    #[serde(default)]
+    pub collection_job_counter_deleted: i64,
+
+    /// the task's metrics as reported by the helper, if the helper reports any
+    #[serde(default)]
+    pub helper_metrics: Option<HelperMetrics>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HelperMetrics {
+    pub uploads: Option<HelperUploadMetrics>,
+    pub aggregation_jobs: Option<HelperAggregationJobMetrics>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub refreshed_at: OffsetDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct HelperUploadMetrics {
+    pub interval_collected: u64,
+    pub report_decode_failure: u64,
+    pub report_decrypt_failure: u64,
+    pub report_expired: u64,
+    pub report_outdated_key: u64,
+    pub report_success: u64,
+    pub report_too_early: u64,
+    pub task_expired: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct HelperAggregationJobMetrics {
+    pub success: u64,
+    pub helper_batch_collected: u64,
+    pub helper_report_replayed: u64,
+    pub helper_report_dropped: u64,
+    pub helper_hpke_unknown_config_id: u64,
+    pub helper_hpke_decrypt_failure: u64,
+    pub helper_vdaf_prep_error: u64,
+    pub helper_task_expired: u64,
+    pub helper_invalid_message: u64,
+    pub helper_report_too_early: u64,
+}
+
+/// An estimate of what can be collected for a task, based on the rate reports have arrived at.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CollectionReadiness {
+    pub task_id: String,
+    /// either "TimeInterval" or "FixedSize"
+    pub query_type: String,
+    pub min_batch_size: u64,
+    pub report_count: u64,
+    pub reports_per_hour: f64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub rate_measured_since: OffsetDateTime,
+    pub late_report_count: u64,
+    pub batch_interval_seconds: Option<u64>,
+    pub ready_intervals: Option<Vec<BatchInterval>>,
+    pub ready_batches: Option<u64>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub next_ready_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BatchInterval {
+    #[serde(with = "time::serde::rfc3339")]
+    pub start: OffsetDateTime,
+    pub duration_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct DpEstimateParams {
+    pub vdaf: Vdaf,
+    pub min_batch_size: u64,
+}
+
+/// The noise a vdaf's differential privacy strategy adds to each bucket of a collected aggregate.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct DpEstimate {
+    /// None if the vdaf does not add noise
+    pub epsilon: Option<f64>,
+    pub sensitivity: u64,
+    /// the scale of the discrete Laplace distribution each aggregator draws noise from
+    pub noise_scale: f64,
+    /// the standard deviation of the noise both aggregators add to each bucket
+    pub standard_deviation: f64,
+    /// the standard deviation as a fraction of the largest value a bucket can reach in a batch of
+    /// `min_batch_size` reports
+    pub relative_error_at_min_batch_size: f64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -78,13 +170,26 @@ pub struct NewTask {
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Vdaf {
     #[serde(rename = "count")]
-    Count,
+    Count {
+        #[serde(
+            default,
+            skip_serializing_if = "dp_strategy::Prio3Count::is_no_differential_privacy"
+        )]
+        dp_strategy: dp_strategy::Prio3Count,
+    },
 
     #[serde(rename = "histogram")]
     Histogram(Histogram),
 
     #[serde(rename = "sum")]
-    Sum { bits: u8 },
+    Sum {
+        bits: u8,
+        #[serde(
+            default,
+            skip_serializing_if = "dp_strategy::Prio3Sum::is_no_differential_privacy"
+        )]
+        dp_strategy: dp_strategy::Prio3Sum,
+    },
 
     #[serde(rename = "count_vec")]
     CountVec {
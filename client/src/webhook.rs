@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use url::Url;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum WebhookEventType {
+    #[serde(rename = "task.created")]
+    TaskCreated,
+    #[serde(rename = "task.expired")]
+    TaskExpired,
+    #[serde(rename = "task.deleted")]
+    TaskDeleted,
+    #[serde(rename = "membership.created")]
+    MembershipCreated,
+    #[serde(rename = "membership.updated")]
+    MembershipUpdated,
+    #[serde(rename = "membership.deleted")]
+    MembershipDeleted,
+    #[serde(rename = "aggregator.decommissioned")]
+    AggregatorDecommissioned,
+    #[serde(rename = "aggregator.capabilities_removed")]
+    AggregatorCapabilitiesRemoved,
+    #[serde(rename = "aggregator.deleted")]
+    AggregatorDeleted,
+    #[serde(rename = "alert.fired")]
+    AlertFired,
+    #[serde(rename = "alert.resolved")]
+    AlertResolved,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub name: Option<String>,
+    pub url: Url,
+    // None indicates a webhook that receives every event type
+    #[serde(default)]
+    pub event_types: Option<Vec<WebhookEventType>>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub deleted_at: Option<OffsetDateTime>,
+    /// the key that requests are signed with, only present when the webhook is created
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct NewWebhook {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub url: Url,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_types: Option<Vec<WebhookEventType>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct UpdateWebhook {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_types: Option<Vec<WebhookEventType>>,
+    /// subscribe to every event type, including ones added later
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub all_event_types: bool,
+}
+
+/// One attempt to deliver an event to a webhook.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: WebhookEventType,
+    pub attempt: u32,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
@@ -1,5 +1,5 @@
 use crate::harness::{assert_eq, test, *};
-use divviup_client::NewAggregator;
+use divviup_client::{NewAggregator, TaskExpirationStatus};
 
 #[test(harness = with_configured_client)]
 async fn show_aggregator(
@@ -75,6 +75,25 @@ async fn delete_aggregator(
     Ok(())
 }
 
+#[test(harness = with_configured_client)]
+async fn delete_aggregator_and_expire_tasks(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let task = fixtures::task(&app, &account).await;
+    let aggregator = task.leader_aggregator(app.db()).await?;
+    assert!(client.delete_aggregator(aggregator.id).await.is_err());
+    client
+        .delete_aggregator_and_expire_tasks(aggregator.id)
+        .await?;
+    assert!(aggregator.reload(app.db()).await?.unwrap().is_tombstoned());
+    let progress = client.aggregator_task_expiration(aggregator.id).await?;
+    assert_eq!(progress.status, TaskExpirationStatus::Pending);
+    assert_eq!(progress.remaining_tasks, 1);
+    Ok(())
+}
+
 #[test(harness = with_configured_client)]
 async fn rename_aggregator(
     app: Arc<DivviupApi>,
@@ -117,3 +136,80 @@ async fn rotate_bearer_token(
     );
     Ok(())
 }
+
+#[test(harness = with_configured_client)]
+async fn stage_and_promote_secondary_bearer_token(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+    let new_bearer_token = fixtures::random_name();
+    let response = client
+        .stage_aggregator_secondary_bearer_token(aggregator.id, &new_bearer_token)
+        .await?;
+    assert!(response.has_secondary_bearer_token);
+
+    client
+        .verify_aggregator_secondary_bearer_token(aggregator.id)
+        .await?;
+
+    let response = client
+        .promote_aggregator_secondary_bearer_token(aggregator.id)
+        .await?;
+    assert!(!response.has_secondary_bearer_token);
+    assert_eq!(
+        Aggregators::find_by_id(aggregator.id)
+            .one(app.db())
+            .await?
+            .unwrap()
+            .bearer_token(app.crypter())
+            .unwrap(),
+        new_bearer_token
+    );
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn aggregator_pair_compatibility(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let (leader, helper) = fixtures::aggregator_pair(&app, &account).await;
+    let compatibility = client
+        .aggregator_pair_compatibility(account.id, leader.id, helper.id)
+        .await?;
+    assert!(compatibility.compatible);
+    assert_eq!(
+        compatibility
+            .creatable_vdafs
+            .iter()
+            .map(|vdaf| &*vdaf.vdaf_type)
+            .collect::<Vec<_>>(),
+        ["count", "histogram", "sum", "count_vec"]
+    );
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn request_aggregator_access(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let mut aggregator = fixtures::aggregator(&app, None).await.into_active_model();
+    aggregator.visibility = ActiveValue::Set(divviup_api::entity::Visibility::RequestAccess);
+    let aggregator = aggregator.update(app.db()).await?;
+
+    let access = client
+        .request_aggregator_access(account.id, aggregator.id)
+        .await?;
+    assert_eq!(access.aggregator_id, aggregator.id);
+    assert_eq!(access.granted_at, None);
+    assert_eq!(
+        client.aggregator_access_for_account(account.id).await?,
+        [access]
+    );
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::harness::{assert_eq, test, *};
+use divviup_client::{AlertChannel, NewAlertRule, TaskCounter, UpdateAlertRule};
+
+#[test(harness = with_configured_client)]
+async fn create_list_and_update_alert_rules(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let task = fixtures::task(&app, &account).await;
+    let alert_rule = client
+        .create_alert_rule(
+            account.id,
+            NewAlertRule {
+                task_id: Some(task.id.clone()),
+                counter: Some(TaskCounter::ReportCounterDecryptFailure),
+                relative_to: Some(TaskCounter::ReportCounterSuccess),
+                threshold: Some(0.05),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(alert_rule.task_id, Some(task.id));
+    assert_eq!(alert_rule.window_seconds, 3600);
+    assert_eq!(alert_rule.channels, vec![AlertChannel::Email]);
+
+    let alert_rules = client.alert_rules(account.id).await?;
+    assert_eq!(alert_rules, vec![alert_rule.clone()]);
+
+    let alert_rule = client
+        .update_alert_rule(
+            alert_rule.id,
+            UpdateAlertRule {
+                absolute: true,
+                threshold: Some(10.0),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(alert_rule.relative_to, None);
+    assert_eq!(alert_rule.threshold, 10.0);
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn delete_alert_rule_and_list_alerts(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let task = fixtures::task(&app, &account).await;
+    let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+    let alert = Alerts::fire(&alert_rule, &task, 0.5, app.db())
+        .await?
+        .unwrap();
+    let alerts = client.firing_alerts(account.id).await?;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].id, alert.id);
+    assert_eq!(alerts[0].value, 0.5);
+
+    client.delete_alert_rule(alert_rule.id).await?;
+    assert!(AlertRules::find_by_id(alert_rule.id)
+        .one(app.db())
+        .await?
+        .unwrap()
+        .is_tombstoned());
+    Ok(())
+}
@@ -37,3 +37,88 @@ async fn delete_api_token(
         .is_tombstoned());
     Ok(())
 }
+
+#[test(harness = with_configured_client)]
+async fn create_api_token_with_attributes(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let task = fixtures::task(&app, &account).await;
+    let token = client
+        .create_api_token_with_attributes(
+            account.id,
+            divviup_client::NewApiToken {
+                scopes: Some(vec![divviup_client::ApiTokenScope::Read]),
+                task_ids: Some(vec![task.id.clone()]),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert!(token.token.is_some());
+    assert_eq!(
+        token.scopes,
+        Some(vec![divviup_client::ApiTokenScope::Read])
+    );
+    assert_eq!(token.task_ids, Some(vec![task.id.clone()]));
+
+    let api_token = ApiTokens::find_by_id(token.id)
+        .one(app.db())
+        .await?
+        .unwrap();
+    assert!(api_token.is_scoped());
+    assert!(api_token.allows_task(&task.id));
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn create_named_expiring_api_token(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let expires_at = (OffsetDateTime::now_utc() + time::Duration::days(90))
+        .replace_nanosecond(0)
+        .unwrap();
+    let token = client
+        .create_api_token_with_attributes(
+            account.id,
+            divviup_client::NewApiToken {
+                name: Some("ci deploys".into()),
+                expires_at: Some(expires_at),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(token.expires_at, Some(expires_at));
+    assert_eq!(token.name.as_deref(), Some("ci deploys"));
+    assert_eq!(token.scopes, None);
+
+    let api_token = ApiTokens::find_by_id(token.id)
+        .one(app.db())
+        .await?
+        .unwrap();
+    assert_eq!(api_token.expires_at, Some(expires_at));
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn api_token_usage(
+    _app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let tokens = client.api_tokens(account.id).await?;
+    assert_eq!(tokens.len(), 1);
+    let usage = client.api_token_usage(tokens[0].id).await?;
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].api_token_id, tokens[0].id);
+    assert!(usage[0].request_count >= 2);
+    assert_eq!(
+        usage[0]
+            .routes
+            .get("GET /api/accounts/{account_id}/api_tokens"),
+        Some(&1)
+    );
+    Ok(())
+}
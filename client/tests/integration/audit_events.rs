@@ -0,0 +1,63 @@
+use crate::harness::{assert_eq, test, *};
+use divviup_client::{AuditEventFilter, AuditTargetType};
+
+#[test(harness = with_configured_client)]
+async fn audit_events(
+    _app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let name = fixtures::random_name();
+    client.rename_account(account.id, &name).await?;
+    let api_token = client.create_api_token(account.id).await?;
+
+    let events = client
+        .audit_events(account.id, &AuditEventFilter::default())
+        .await?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].target_type, AuditTargetType::Account);
+    assert_eq!(events[1].changes["name"].after, json!(name));
+
+    let events = client
+        .audit_events(
+            account.id,
+            &AuditEventFilter {
+                target_type: Some(AuditTargetType::ApiToken),
+                action: Some(divviup_client::AuditAction::Create),
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].target_id, api_token.id.to_string());
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn all_audit_events(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let other_account = fixtures::account(&app).await;
+    let account = fixtures::make_account_admin(&app, account).await;
+    client.rename_account(account.id, "renamed").await?;
+    client
+        .rename_account(other_account.id, "also renamed")
+        .await?;
+
+    let events = client
+        .all_audit_events(&AuditEventFilter::default())
+        .await?;
+    assert_eq!(events.len(), 2);
+
+    let events = client
+        .all_audit_events(&AuditEventFilter {
+            account_id: Some(other_account.id),
+            ..Default::default()
+        })
+        .await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].target_id, other_account.id.to_string());
+    Ok(())
+}
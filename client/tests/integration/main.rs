@@ -1,8 +1,11 @@
 mod accounts;
 mod aggregators;
+mod alerts;
 mod api_tokens;
+mod audit_events;
 mod basic_client_behavior;
 mod collector_credentials;
 mod harness;
 mod memberships;
 mod tasks;
+mod webhooks;
@@ -6,7 +6,7 @@ async fn membership_list(
     account: Account,
     client: DivviupClient,
 ) -> TestResult {
-    let memberships = vec![
+    let memberships = [
         fixtures::membership(&app, &account, &fixtures::user()).await,
         fixtures::membership(&app, &account, &fixtures::user()).await,
         fixtures::membership(&app, &account, &fixtures::user()).await,
@@ -59,3 +59,56 @@ async fn delete_membership(
     assert!(membership.reload(app.db()).await?.is_none());
     Ok(())
 }
+
+#[test(harness = with_configured_client)]
+async fn create_membership_with_role(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let email = fixtures::random_email();
+    let response_membership = client
+        .create_membership_with_role(account.id, &email, divviup_client::MembershipRole::Viewer)
+        .await?;
+    assert_eq!(
+        response_membership.role,
+        divviup_client::MembershipRole::Viewer
+    );
+    assert_eq!(
+        Memberships::find_by_id(response_membership.id)
+            .one(app.db())
+            .await?
+            .unwrap()
+            .role,
+        divviup_api::entity::MembershipRole::Viewer
+    );
+
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn update_membership_role(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let membership = fixtures::membership_with_role(
+        &app,
+        &account,
+        &fixtures::user(),
+        divviup_api::entity::MembershipRole::Viewer,
+    )
+    .await;
+    let response_membership = client
+        .update_membership_role(membership.id, divviup_client::MembershipRole::Member)
+        .await?;
+    assert_eq!(
+        response_membership.role,
+        divviup_client::MembershipRole::Member
+    );
+    assert_eq!(
+        membership.reload(app.db()).await?.unwrap().role,
+        divviup_api::entity::MembershipRole::Member
+    );
+    Ok(())
+}
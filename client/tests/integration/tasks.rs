@@ -1,6 +1,9 @@
 use crate::harness::{assert_eq, test, *};
 use divviup_api::entity::aggregator::{Feature, Features};
-use divviup_client::{NewTask, Vdaf};
+use divviup_client::{
+    dp_strategy::{self, PureDpBudget, PureDpDiscreteLaplace},
+    BigUint, DpEstimateParams, Histogram, NewTask, Ratio, TaskCounter, Vdaf,
+};
 
 #[test(harness = with_configured_client)]
 async fn task_list(app: Arc<DivviupApi>, account: Account, client: DivviupClient) -> TestResult {
@@ -32,7 +35,9 @@ async fn create_task(app: Arc<DivviupApi>, account: Account, client: DivviupClie
                 name: fixtures::random_name(),
                 leader_aggregator_id: leader.id,
                 helper_aggregator_id: helper.id,
-                vdaf: Vdaf::Count,
+                vdaf: Vdaf::Count {
+                    dp_strategy: Default::default(),
+                },
                 min_batch_size: fastrand::i64(100..).try_into().unwrap(),
                 max_batch_size: None,
                 batch_time_window_size_seconds: None,
@@ -72,7 +77,9 @@ async fn create_task_time_bucketed_fixed_size(
                 name: fixtures::random_name(),
                 leader_aggregator_id: leader.id,
                 helper_aggregator_id: helper.id,
-                vdaf: Vdaf::Count,
+                vdaf: Vdaf::Count {
+                    dp_strategy: Default::default(),
+                },
                 min_batch_size,
                 max_batch_size: Some(min_batch_size),
                 batch_time_window_size_seconds: Some(time_precision_seconds * 2),
@@ -157,3 +164,65 @@ async fn force_delete_task(
     assert!(response_tasks.is_empty());
     Ok(())
 }
+
+#[test(harness = with_configured_client)]
+async fn task_collection_readiness(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let task = fixtures::task(&app, &account).await;
+    let readiness = client.task_collection_readiness(&task.id).await?;
+    assert_eq!(readiness.task_id, task.id);
+    assert_eq!(readiness.min_batch_size, task.min_batch_size as u64);
+    assert_eq!(readiness.report_count, 0);
+    assert_eq!(readiness.next_ready_at, None);
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn metrics_summary(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let task = fixtures::task(&app, &account).await;
+    let summary = client.metrics_summary(account.id).await?;
+    assert_eq!(summary.task_count, 1);
+    assert_eq!(summary.tasks.len(), 1);
+    assert_eq!(summary.tasks[0].task_id, task.id);
+    assert_eq!(
+        summary.totals[&TaskCounter::ReportCounterSuccess],
+        task.report_counter_success
+    );
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn dp_estimate(
+    _app: Arc<DivviupApi>,
+    _account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let estimate = client
+        .dp_estimate(DpEstimateParams {
+            vdaf: Vdaf::Histogram(Histogram::Length {
+                length: 4,
+                chunk_length: None,
+                dp_strategy: dp_strategy::Prio3Histogram::PureDpDiscreteLaplace(
+                    PureDpDiscreteLaplace {
+                        budget: PureDpBudget {
+                            epsilon: Ratio::new(BigUint::from(1u32), BigUint::from(2u32)),
+                        },
+                    },
+                ),
+            }),
+            min_batch_size: 100,
+        })
+        .await?;
+    assert_eq!(estimate.epsilon, Some(0.5));
+    assert_eq!(estimate.sensitivity, 2);
+    assert_eq!(estimate.noise_scale, 4.0);
+    assert!(estimate.standard_deviation > 0.0);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::harness::{assert_eq, test, *};
+use divviup_client::{NewWebhook, UpdateWebhook, WebhookEventType};
+
+#[test(harness = with_configured_client)]
+async fn create_list_and_update_webhooks(
+    _app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let webhook = client
+        .create_webhook(
+            account.id,
+            NewWebhook {
+                name: Some("alerts".into()),
+                url: "https://hooks.example/divviup".parse()?,
+                event_types: Some(vec![WebhookEventType::TaskCreated]),
+            },
+        )
+        .await?;
+    assert!(webhook.secret.is_some());
+    assert_eq!(webhook.url.as_str(), "https://hooks.example/divviup");
+
+    let webhooks = client.webhooks(account.id).await?;
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0].secret, None);
+
+    let webhook = client
+        .update_webhook(
+            webhook.id,
+            UpdateWebhook {
+                all_event_types: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+    assert_eq!(webhook.event_types, None);
+    assert_eq!(webhook.name.as_deref(), Some("alerts"));
+    Ok(())
+}
+
+#[test(harness = with_configured_client)]
+async fn delete_webhook(
+    app: Arc<DivviupApi>,
+    account: Account,
+    client: DivviupClient,
+) -> TestResult {
+    let (webhook, _) = fixtures::webhook(&app, &account).await;
+    assert!(client.webhook_deliveries(webhook.id).await?.is_empty());
+    client.delete_webhook(webhook.id).await?;
+    assert!(Webhooks::find_by_id(webhook.id)
+        .one(app.db())
+        .await?
+        .unwrap()
+        .is_tombstoned());
+    Ok(())
+}
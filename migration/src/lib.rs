@@ -26,6 +26,23 @@ mod m20240214_215101_upload_metrics;
 mod m20240411_195358_time_bucketed_fixed_size;
 mod m20240416_172920_task_deleted_at;
 mod m20250801_164739_aggregation_job_metrics;
+mod m20261018_151204_create_aggregator_capability_changes;
+mod m20261018_162740_add_secondary_bearer_token_to_aggregators;
+mod m20261019_093015_add_tls_settings_to_aggregators;
+mod m20261019_121544_add_hpke_config_status_to_aggregators;
+mod m20261019_154210_add_sunset_at_to_aggregators;
+mod m20261019_173408_add_visibility_to_aggregators;
+mod m20261019_190521_add_role_to_memberships;
+mod m20261019_204417_add_scopes_to_api_tokens;
+mod m20261019_215730_add_expires_at_to_api_tokens;
+mod m20261020_083112_create_api_token_usage;
+mod m20261020_101544_create_audit_events;
+mod m20261020_140233_create_webhooks;
+mod m20261020_171342_create_alerts;
+mod m20261020_190214_add_helper_metrics_to_tasks;
+mod m20261020_203517_collection_job_metrics;
+mod m20261021_094512_add_expiration_notified_at_to_tasks;
+mod m20261021_121508_add_firing_alert_unique_index;
 
 pub struct Migrator;
 
@@ -59,6 +76,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20240411_195358_time_bucketed_fixed_size::Migration),
             Box::new(m20240416_172920_task_deleted_at::Migration),
             Box::new(m20250801_164739_aggregation_job_metrics::Migration),
+            Box::new(m20261018_151204_create_aggregator_capability_changes::Migration),
+            Box::new(m20261018_162740_add_secondary_bearer_token_to_aggregators::Migration),
+            Box::new(m20261019_093015_add_tls_settings_to_aggregators::Migration),
+            Box::new(m20261019_121544_add_hpke_config_status_to_aggregators::Migration),
+            Box::new(m20261019_154210_add_sunset_at_to_aggregators::Migration),
+            Box::new(m20261019_173408_add_visibility_to_aggregators::Migration),
+            Box::new(m20261019_190521_add_role_to_memberships::Migration),
+            Box::new(m20261019_204417_add_scopes_to_api_tokens::Migration),
+            Box::new(m20261019_215730_add_expires_at_to_api_tokens::Migration),
+            Box::new(m20261020_083112_create_api_token_usage::Migration),
+            Box::new(m20261020_101544_create_audit_events::Migration),
+            Box::new(m20261020_140233_create_webhooks::Migration),
+            Box::new(m20261020_171342_create_alerts::Migration),
+            Box::new(m20261020_190214_add_helper_metrics_to_tasks::Migration),
+            Box::new(m20261020_203517_collection_job_metrics::Migration),
+            Box::new(m20261021_094512_add_expiration_notified_at_to_tasks::Migration),
+            Box::new(m20261021_121508_add_firing_alert_unique_index::Migration),
         ]
     }
 }
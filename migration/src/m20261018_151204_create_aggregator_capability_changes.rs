@@ -0,0 +1,92 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AggregatorCapabilityChange::Table)
+                    .col(
+                        ColumnDef::new(AggregatorCapabilityChange::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorCapabilityChange::AggregatorId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorCapabilityChange::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorCapabilityChange::Previous)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorCapabilityChange::Current)
+                            .json()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-aggregator-capability-change-aggregator-id")
+                            .from(
+                                AggregatorCapabilityChange::Table,
+                                AggregatorCapabilityChange::AggregatorId,
+                            )
+                            .to(Aggregator::Table, Aggregator::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-aggregator-capability-change-aggregator-id")
+                    .table(AggregatorCapabilityChange::Table)
+                    .col(AggregatorCapabilityChange::AggregatorId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(AggregatorCapabilityChange::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum AggregatorCapabilityChange {
+    Table,
+    Id,
+    AggregatorId,
+    CreatedAt,
+    Previous,
+    Current,
+}
+
+#[derive(Iden)]
+enum Aggregator {
+    Table,
+    Id,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Aggregator::Table)
+                    .add_column(
+                        ColumnDef::new(Aggregator::EncryptedClientIdentity)
+                            .binary()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Aggregator::Table)
+                    .add_column(ColumnDef::new(Aggregator::CaCertificate).text().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Aggregator::Table)
+                    .drop_column(Aggregator::CaCertificate)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Aggregator::Table)
+                    .drop_column(Aggregator::EncryptedClientIdentity)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Aggregator {
+    Table,
+    EncryptedClientIdentity,
+    CaCertificate,
+}
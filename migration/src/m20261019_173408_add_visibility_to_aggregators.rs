@@ -0,0 +1,141 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Aggregator::Table)
+                    .add_column(
+                        ColumnDef::new(Aggregator::Visibility)
+                            .string()
+                            .not_null()
+                            .default("public"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(AggregatorAccess::Table)
+                    .col(
+                        ColumnDef::new(AggregatorAccess::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorAccess::AggregatorId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorAccess::AccountId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorAccess::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorAccess::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AggregatorAccess::GrantedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-aggregator-access-aggregator-id")
+                            .from(AggregatorAccess::Table, AggregatorAccess::AggregatorId)
+                            .to(Aggregator::Table, Aggregator::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-aggregator-access-account-id")
+                            .from(AggregatorAccess::Table, AggregatorAccess::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-aggregator-access-aggregator-id-account-id")
+                    .table(AggregatorAccess::Table)
+                    .col(AggregatorAccess::AggregatorId)
+                    .col(AggregatorAccess::AccountId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-aggregator-access-account-id")
+                    .table(AggregatorAccess::Table)
+                    .col(AggregatorAccess::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AggregatorAccess::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Aggregator::Table)
+                    .drop_column(Aggregator::Visibility)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Aggregator {
+    Table,
+    Id,
+    Visibility,
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum AggregatorAccess {
+    Table,
+    Id,
+    AggregatorId,
+    AccountId,
+    CreatedAt,
+    UpdatedAt,
+    GrantedAt,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // every existing membership had full control of its account
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Membership::Table)
+                    .add_column(
+                        ColumnDef::new(Membership::Role)
+                            .string()
+                            .not_null()
+                            .default("owner"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Membership::Table)
+                    .drop_column(Membership::Role)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Membership {
+    Table,
+    Role,
+}
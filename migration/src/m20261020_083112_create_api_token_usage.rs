@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiTokenUsage::Table)
+                    .col(
+                        ColumnDef::new(ApiTokenUsage::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ApiTokenUsage::ApiTokenId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(ApiTokenUsage::Hour)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiTokenUsage::RequestCount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApiTokenUsage::Routes).json().not_null())
+                    .col(ColumnDef::new(ApiTokenUsage::SourceIps).json().not_null())
+                    .col(
+                        ColumnDef::new(ApiTokenUsage::LastUsedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-api-token-usage-api-token-id")
+                            .from(ApiTokenUsage::Table, ApiTokenUsage::ApiTokenId)
+                            .to(ApiToken::Table, ApiToken::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-api-token-usage-api-token-id-hour")
+                    .table(ApiTokenUsage::Table)
+                    .col(ApiTokenUsage::ApiTokenId)
+                    .col(ApiTokenUsage::Hour)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiTokenUsage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApiTokenUsage {
+    Table,
+    Id,
+    ApiTokenId,
+    Hour,
+    RequestCount,
+    Routes,
+    SourceIps,
+    LastUsedAt,
+}
+
+#[derive(DeriveIden)]
+enum ApiToken {
+    Table,
+    Id,
+}
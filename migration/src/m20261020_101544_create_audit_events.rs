@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditEvent::Table)
+                    .col(
+                        ColumnDef::new(AuditEvent::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditEvent::AccountId).uuid().null())
+                    .col(ColumnDef::new(AuditEvent::ActorEmail).string().null())
+                    .col(ColumnDef::new(AuditEvent::ActorApiTokenId).uuid().null())
+                    .col(ColumnDef::new(AuditEvent::Action).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::TargetType).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::TargetId).string().not_null())
+                    .col(ColumnDef::new(AuditEvent::Changes).json().not_null())
+                    .col(
+                        ColumnDef::new(AuditEvent::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-audit-event-account-id")
+                            .from(AuditEvent::Table, AuditEvent::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-audit-event-account-id-created-at")
+                    .table(AuditEvent::Table)
+                    .col(AuditEvent::AccountId)
+                    .col(AuditEvent::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-audit-event-target")
+                    .table(AuditEvent::Table)
+                    .col(AuditEvent::TargetType)
+                    .col(AuditEvent::TargetId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditEvent {
+    Table,
+    Id,
+    AccountId,
+    ActorEmail,
+    ActorApiTokenId,
+    Action,
+    TargetType,
+    TargetId,
+    Changes,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    Id,
+}
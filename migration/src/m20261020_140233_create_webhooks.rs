@@ -0,0 +1,140 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Webhook::Table)
+                    .col(ColumnDef::new(Webhook::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Webhook::AccountId).uuid().not_null())
+                    .col(ColumnDef::new(Webhook::Name).string().null())
+                    .col(ColumnDef::new(Webhook::EncryptedUrl).binary().not_null())
+                    .col(ColumnDef::new(Webhook::EncryptedSecret).binary().not_null())
+                    .col(ColumnDef::new(Webhook::EventTypes).json().null())
+                    .col(
+                        ColumnDef::new(Webhook::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Webhook::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Webhook::DeletedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-webhook-account-id")
+                            .from(Webhook::Table, Webhook::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WebhookDelivery::Table)
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::WebhookId).uuid().not_null())
+                    .col(ColumnDef::new(WebhookDelivery::EventId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::EventType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WebhookDelivery::Attempt)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WebhookDelivery::StatusCode).integer().null())
+                    .col(ColumnDef::new(WebhookDelivery::Error).string().null())
+                    .col(
+                        ColumnDef::new(WebhookDelivery::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-webhook-delivery-webhook-id")
+                            .from(WebhookDelivery::Table, WebhookDelivery::WebhookId)
+                            .to(Webhook::Table, Webhook::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-webhook-delivery-webhook-id-created-at")
+                    .table(WebhookDelivery::Table)
+                    .col(WebhookDelivery::WebhookId)
+                    .col(WebhookDelivery::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WebhookDelivery::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Webhook::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Webhook {
+    Table,
+    Id,
+    AccountId,
+    Name,
+    EncryptedUrl,
+    EncryptedSecret,
+    EventTypes,
+    CreatedAt,
+    UpdatedAt,
+    DeletedAt,
+}
+
+#[derive(DeriveIden)]
+enum WebhookDelivery {
+    Table,
+    Id,
+    WebhookId,
+    EventId,
+    EventType,
+    Attempt,
+    StatusCode,
+    Error,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    Id,
+}
@@ -0,0 +1,241 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AlertRule::Table)
+                    .col(
+                        ColumnDef::new(AlertRule::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AlertRule::AccountId).uuid().not_null())
+                    .col(ColumnDef::new(AlertRule::TaskId).string().null())
+                    .col(ColumnDef::new(AlertRule::Name).string().null())
+                    .col(ColumnDef::new(AlertRule::Counter).string().not_null())
+                    .col(ColumnDef::new(AlertRule::RelativeTo).string().null())
+                    .col(ColumnDef::new(AlertRule::Threshold).double().not_null())
+                    .col(
+                        ColumnDef::new(AlertRule::WindowSeconds)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AlertRule::Channels).json().not_null())
+                    .col(
+                        ColumnDef::new(AlertRule::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AlertRule::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(AlertRule::DeletedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-alert-rule-account-id")
+                            .from(AlertRule::Table, AlertRule::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-alert-rule-task-id")
+                            .from(AlertRule::Table, AlertRule::TaskId)
+                            .to(Task::Table, Task::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alert::Table)
+                    .col(ColumnDef::new(Alert::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Alert::RuleId).uuid().not_null())
+                    .col(ColumnDef::new(Alert::AccountId).uuid().not_null())
+                    .col(ColumnDef::new(Alert::TaskId).string().not_null())
+                    .col(ColumnDef::new(Alert::Value).double().not_null())
+                    .col(
+                        ColumnDef::new(Alert::FiredAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alert::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alert::ResolvedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-alert-alert-rule-id")
+                            .from(Alert::Table, Alert::RuleId)
+                            .to(AlertRule::Table, AlertRule::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-alert-account-id")
+                            .from(Alert::Table, Alert::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-alert-task-id")
+                            .from(Alert::Table, Alert::TaskId)
+                            .to(Task::Table, Task::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-alert-account-id-resolved-at")
+                    .table(Alert::Table)
+                    .col(Alert::AccountId)
+                    .col(Alert::ResolvedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TaskCounterSnapshot::Table)
+                    .col(
+                        ColumnDef::new(TaskCounterSnapshot::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskCounterSnapshot::TaskId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskCounterSnapshot::Counters)
+                            .json()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(TaskCounterSnapshot::CapturedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fkey-task-counter-snapshot-task-id")
+                            .from(TaskCounterSnapshot::Table, TaskCounterSnapshot::TaskId)
+                            .to(Task::Table, Task::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-task-counter-snapshot-task-id-captured-at")
+                    .table(TaskCounterSnapshot::Table)
+                    .col(TaskCounterSnapshot::TaskId)
+                    .col(TaskCounterSnapshot::CapturedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskCounterSnapshot::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alert::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(AlertRule::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AlertRule {
+    Table,
+    Id,
+    AccountId,
+    TaskId,
+    Name,
+    Counter,
+    RelativeTo,
+    Threshold,
+    WindowSeconds,
+    Channels,
+    CreatedAt,
+    UpdatedAt,
+    DeletedAt,
+}
+
+#[derive(DeriveIden)]
+enum Alert {
+    Table,
+    Id,
+    #[sea_orm(iden = "alert_rule_id")]
+    RuleId,
+    AccountId,
+    TaskId,
+    Value,
+    FiredAt,
+    UpdatedAt,
+    ResolvedAt,
+}
+
+#[derive(DeriveIden)]
+enum TaskCounterSnapshot {
+    Table,
+    Id,
+    TaskId,
+    Counters,
+    CapturedAt,
+}
+
+#[derive(DeriveIden)]
+enum Account {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Task {
+    Table,
+    Id,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Task::Table)
+                    .add_column(ColumnDef::new(Task::ReportCount).big_integer().default(0))
+                    .add_column(
+                        ColumnDef::new(Task::AggregateCollectionCount)
+                            .big_integer()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(Task::CollectionJobCounterPending)
+                            .big_integer()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(Task::CollectionJobCounterAbandoned)
+                            .big_integer()
+                            .default(0),
+                    )
+                    .add_column(
+                        ColumnDef::new(Task::CollectionJobCounterDeleted)
+                            .big_integer()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Task::Table)
+                    .drop_column(Task::ReportCount)
+                    .drop_column(Task::AggregateCollectionCount)
+                    .drop_column(Task::CollectionJobCounterPending)
+                    .drop_column(Task::CollectionJobCounterAbandoned)
+                    .drop_column(Task::CollectionJobCounterDeleted)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Task {
+    Table,
+    ReportCount,
+    AggregateCollectionCount,
+    CollectionJobCounterPending,
+    CollectionJobCounterAbandoned,
+    CollectionJobCounterDeleted,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // concurrent evaluations may already have fired more than one alert for a rule and task,
+        // in which case all but the first are resolved
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE alert SET resolved_at = updated_at
+                WHERE resolved_at IS NULL AND EXISTS (
+                    SELECT 1 FROM alert AS earlier
+                    WHERE earlier.alert_rule_id = alert.alert_rule_id
+                    AND earlier.task_id = alert.task_id
+                    AND earlier.resolved_at IS NULL
+                    AND (earlier.fired_at < alert.fired_at
+                        OR (earlier.fired_at = alert.fired_at AND earlier.id < alert.id))
+                )",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("index-alert-alert-rule-id-task-id-firing")
+                    .table(Alert::Table)
+                    .col(Alert::RuleId)
+                    .col(Alert::TaskId)
+                    .unique()
+                    .and_where(Expr::col(Alert::ResolvedAt).is_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("index-alert-alert-rule-id-task-id-firing")
+                    .table(Alert::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Alert {
+    Table,
+    #[sea_orm(iden = "alert_rule_id")]
+    RuleId,
+    TaskId,
+    ResolvedAt,
+}
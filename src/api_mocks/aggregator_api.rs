@@ -1,9 +1,10 @@
 use super::random_chars;
 use crate::{
     clients::aggregator_client::api_types::{
-        AggregatorApiConfig, AggregatorVdaf, AuthenticationToken, HpkeAeadId, HpkeConfig,
-        HpkeKdfId, HpkeKemId, HpkePublicKey, JanusDuration, QueryType, Role, TaskCreate, TaskId,
-        TaskIds, TaskPatch, TaskResponse, TaskUploadMetrics,
+        AggregatorApiConfig, AggregatorVdaf, AuthenticationToken, Encode, HpkeAeadId, HpkeConfig,
+        HpkeConfigList, HpkeKdfId, HpkeKemId, HpkePublicKey, JanusDuration, QueryType, Role,
+        TaskAggregationJobMetrics, TaskCollectionJobMetrics, TaskCreate, TaskId, TaskIds,
+        TaskPatch, TaskResponse, TaskUploadMetrics,
     },
     entity::aggregator::{Feature, Features},
 };
@@ -36,7 +37,26 @@ pub fn mock() -> Router {
             "/tasks/{task_id}/metrics/uploads",
             routing::get(get_task_upload_metrics),
         )
+        .route(
+            "/tasks/{task_id}/metrics/aggregations",
+            routing::get(get_task_aggregation_job_metrics),
+        )
+        .route(
+            "/tasks/{task_id}/metrics/collections",
+            routing::get(get_task_collection_job_metrics),
+        )
         .layer(middleware::from_fn(bearer_token_check))
+        // the dap api is unauthenticated, and in tests shares a mock server with the aggregator api
+        .route("/hpke_config", routing::get(hpke_config))
+}
+
+async fn hpke_config() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, HpkeConfigList::MEDIA_TYPE)],
+        HpkeConfigList::new(repeat_with(random_hpke_config).take(2).collect())
+            .get_encoded()
+            .unwrap(),
+    )
 }
 
 async fn bearer_token_check(request: Request, next: Next) -> Response {
@@ -83,6 +103,31 @@ async fn get_task_upload_metrics() -> Json<TaskUploadMetrics> {
     })
 }
 
+async fn get_task_aggregation_job_metrics() -> Json<TaskAggregationJobMetrics> {
+    Json(TaskAggregationJobMetrics {
+        success: fastrand::u64(..1000),
+        helper_batch_collected: fastrand::u64(..1000),
+        helper_report_replayed: fastrand::u64(..1000),
+        helper_report_dropped: fastrand::u64(..1000),
+        helper_hpke_unknown_config_id: fastrand::u64(..1000),
+        helper_hpke_decrypt_failure: fastrand::u64(..1000),
+        helper_vdaf_prep_error: fastrand::u64(..1000),
+        helper_task_expired: fastrand::u64(..1000),
+        helper_invalid_message: fastrand::u64(..1000),
+        helper_report_too_early: fastrand::u64(..1000),
+    })
+}
+
+async fn get_task_collection_job_metrics() -> Json<TaskCollectionJobMetrics> {
+    Json(TaskCollectionJobMetrics {
+        pending: fastrand::u64(..1000),
+        finished: fastrand::u64(..1000),
+        abandoned: fastrand::u64(..1000),
+        deleted: fastrand::u64(..1000),
+        report_count: fastrand::u64(..1000),
+    })
+}
+
 async fn get_task(Path(task_id): Path<String>) -> Json<TaskResponse> {
     Json(TaskResponse {
         task_id: task_id.parse().unwrap(),
@@ -72,6 +72,10 @@ async fn main() {
     let app = build_app(config).await;
 
     let queue_handle = Queue::new(&app.db, &app.config, cancel.clone()).spawn_workers();
+    let usage_handle = app
+        .api_token_usage
+        .clone()
+        .spawn_flusher(app.db.clone(), cancel.clone());
 
     let listener = TcpListener::bind(listen_address)
         .await
@@ -82,9 +86,13 @@ async fn main() {
         env!("CARGO_PKG_VERSION")
     );
 
-    let serve_result = axum::serve(listener, app.router)
-        .with_graceful_shutdown(shutdown_signal(cancel.clone()))
-        .await;
+    let serve_result = axum::serve(
+        listener,
+        app.router
+            .into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal(cancel.clone()))
+    .await;
     // Ensure queue workers stop even if serve exits without a signal.
     cancel.cancel();
 
@@ -96,6 +104,10 @@ async fn main() {
         tracing::error!("queue worker panic: {e}");
     }
 
+    if let Err(e) = usage_handle.await {
+        tracing::error!("api token usage flusher panic: {e}");
+    }
+
     let _ = monitoring_handle.await;
 
     // Shut down telemetry providers last so in-flight spans and metrics from
@@ -1,23 +1,97 @@
 pub mod aggregator_client;
 pub mod auth0_client;
 pub mod postmark_client;
+mod request_policy;
+pub(crate) mod ssrf;
 
 pub use aggregator_client::AggregatorClient;
 pub use auth0_client::Auth0Client;
 pub use postmark_client::PostmarkClient;
+pub use request_policy::{CircuitBreakers, RequestPolicy};
+pub use ssrf::{NonPublicAddress, PublicAddressResolver};
 
 use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
-use reqwest::{header::HOST, Method};
+use reqwest::{header::HOST, Method, RequestBuilder, Response};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use url::Url;
+use uuid::Uuid;
 
 /// Header injected by `HttpClient` when proxy rewriting is active, carrying
 /// the original (pre-rewrite) URL. Test infrastructure (`ClientLogs`) can
 /// read this to reconstruct the intended URL instead of the proxied one.
 pub static ORIGINAL_URL_HEADER: HeaderName = HeaderName::from_static("x-original-url");
 
+/// Clients built by [`HttpClient::with_tls`], one for each key they were built for, so that
+/// requests to one aggregator share connections. A key's client is replaced when its TLS settings
+/// change, so rotating a certificate does not leave the previous client behind. Clones share
+/// state.
+#[derive(Debug, Clone, Default)]
+struct TlsClients(Arc<Mutex<HashMap<Uuid, TlsClient>>>);
+
+#[derive(Debug)]
+struct TlsClient {
+    /// Digest of the TLS settings `client` was built with.
+    digest: [u8; 32],
+    client: reqwest::Client,
+}
+
+impl TlsClients {
+    fn get_or_build(
+        &self,
+        key: Uuid,
+        identity_pem: Option<&[u8]>,
+        ca_certificate_pem: Option<&[u8]>,
+        build: impl FnOnce() -> Result<reqwest::Client, ClientError>,
+    ) -> Result<reqwest::Client, ClientError> {
+        let mut digest = Sha256::new();
+        for pem in [identity_pem, ca_certificate_pem] {
+            match pem {
+                Some(pem) => {
+                    digest.update([1]);
+                    digest.update((pem.len() as u64).to_be_bytes());
+                    digest.update(pem);
+                }
+                None => digest.update([0]),
+            }
+        }
+        let digest: [u8; 32] = digest.finalize().into();
+
+        // the lock is held while building, so that concurrent first requests build one client
+        match self.0.lock().unwrap().entry(key) {
+            Entry::Occupied(entry) if entry.get().digest == digest => {
+                Ok(entry.get().client.clone())
+            }
+            Entry::Occupied(mut entry) => {
+                let client = build()?;
+                entry.insert(TlsClient {
+                    digest,
+                    client: client.clone(),
+                });
+                Ok(client)
+            }
+            Entry::Vacant(entry) => {
+                let client = build()?;
+                entry.insert(TlsClient {
+                    digest,
+                    client: client.clone(),
+                });
+                Ok(client)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct HttpClient {
     inner: reqwest::Client,
+    /// Produces the configuration `inner` was built from, so that clients with additional TLS
+    /// settings can be derived from it.
+    client_builder: fn() -> reqwest::ClientBuilder,
     base_url: Option<Url>,
     default_headers: HeaderMap,
     /// When set, all requests are redirected to this address (scheme + host +
@@ -25,18 +99,109 @@ pub struct HttpClient {
     /// lets tests point every client at a single mock server that dispatches
     /// by `Host`.
     proxy_base: Option<Url>,
+    request_policy: RequestPolicy,
+    circuit_breakers: CircuitBreakers,
+    /// When set, connections to non-public addresses are refused.
+    public_address_resolver: Option<PublicAddressResolver>,
+    tls_clients: TlsClients,
 }
 
 impl HttpClient {
     pub fn new(inner: reqwest::Client) -> Self {
+        Self::with_client_builder(inner, reqwest::Client::builder)
+    }
+
+    /// Builds a client from `client_builder`, which is retained for [`HttpClient::with_tls`].
+    pub fn from_builder(client_builder: fn() -> reqwest::ClientBuilder) -> Self {
+        Self::with_client_builder(
+            client_builder()
+                .build()
+                .expect("failed to build reqwest client"),
+            client_builder,
+        )
+    }
+
+    fn with_client_builder(
+        inner: reqwest::Client,
+        client_builder: fn() -> reqwest::ClientBuilder,
+    ) -> Self {
         Self {
             inner,
+            client_builder,
             base_url: None,
             default_headers: HeaderMap::new(),
             proxy_base: None,
+            request_policy: RequestPolicy::default(),
+            circuit_breakers: CircuitBreakers::default(),
+            public_address_resolver: None,
+            tls_clients: TlsClients::default(),
         }
     }
 
+    pub fn with_request_policy(mut self, request_policy: RequestPolicy) -> Self {
+        self.request_policy = request_policy;
+        self
+    }
+
+    pub fn request_policy(&self) -> &RequestPolicy {
+        &self.request_policy
+    }
+
+    pub fn circuit_breakers(&self) -> &CircuitBreakers {
+        &self.circuit_breakers
+    }
+
+    /// Refuses connections to loopback, private, link-local and other non-public addresses,
+    /// checking every address that `resolver` returns at connection time.
+    pub fn with_ssrf_protection(mut self, resolver: PublicAddressResolver) -> Self {
+        self.public_address_resolver = Some(resolver);
+        // clients built without the resolver must not be reused
+        self.tls_clients = TlsClients::default();
+        self.inner = self
+            .configure((self.client_builder)())
+            .build()
+            .expect("failed to build reqwest client");
+        self
+    }
+
+    fn configure(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        match &self.public_address_resolver {
+            Some(resolver) => resolver.configure(builder),
+            None => builder,
+        }
+    }
+
+    /// Returns a client that presents `identity_pem` (a certificate chain and private key) to
+    /// servers that request a client certificate, and that additionally trusts the certificates
+    /// in `ca_certificate_pem`. All other settings, including circuit state, are shared with
+    /// `self`, and the underlying client is reused for every call with the same `key` and TLS
+    /// settings.
+    pub fn with_tls(
+        &self,
+        key: Uuid,
+        identity_pem: Option<&[u8]>,
+        ca_certificate_pem: Option<&[u8]>,
+    ) -> Result<Self, ClientError> {
+        let inner = self
+            .tls_clients
+            .get_or_build(key, identity_pem, ca_certificate_pem, || {
+                let mut builder = self.configure((self.client_builder)());
+                if let Some(identity_pem) = identity_pem {
+                    builder = builder.identity(reqwest::Identity::from_pem(identity_pem)?);
+                }
+                if let Some(ca_certificate_pem) = ca_certificate_pem {
+                    for certificate in reqwest::Certificate::from_pem_bundle(ca_certificate_pem)? {
+                        builder = builder.add_root_certificate(certificate);
+                    }
+                }
+                Ok(builder.build()?)
+            })?;
+        Ok(Self {
+            inner,
+            ..self.clone()
+        })
+    }
+
     pub(crate) fn reqwest_client(&self) -> &reqwest::Client {
         &self.inner
     }
@@ -127,20 +292,98 @@ impl HttpClient {
     /// Send a GET to an absolute URL, ignoring the base URL but applying
     /// default headers and proxy rewriting.
     pub fn get_url(&self, url: Url) -> reqwest::RequestBuilder {
+        self.build_url_request(Method::GET, url)
+    }
+
+    /// Send a POST to an absolute URL, ignoring the base URL but applying
+    /// default headers and proxy rewriting.
+    pub fn post_url(&self, url: Url) -> reqwest::RequestBuilder {
+        self.build_url_request(Method::POST, url)
+    }
+
+    fn build_url_request(&self, method: Method, url: Url) -> reqwest::RequestBuilder {
         let original_url = url.clone();
         let mut url = url;
         let builder = if let Some(original_host) = self.proxy_rewrite(&mut url) {
             self.inner
-                .get(url)
+                .request(method, url)
                 .header(HOST, original_host)
                 .header(&ORIGINAL_URL_HEADER, original_url.as_str())
         } else {
-            self.inner.get(url)
+            self.inner.request(method, url)
         };
         builder.headers(self.default_headers.clone())
     }
 }
 
+impl HttpClient {
+    /// Sends the request built by `request`, applying the [`RequestPolicy`] timeout, retrying
+    /// idempotent requests after transient failures, and tracking failures in the circuit
+    /// identified by `circuit`.
+    pub async fn send_with_policy(
+        &self,
+        circuit: &Url,
+        method: Method,
+        request: impl Fn() -> RequestBuilder,
+    ) -> Result<Response, ClientError> {
+        let policy = &self.request_policy;
+        self.circuit_breakers.check(circuit)?;
+
+        let mut attempt = 0;
+        loop {
+            let result = match self.execute(request().timeout(policy.timeout)).await {
+                Ok(response) => response.success_or_client_error(method.clone()).await,
+                Err(error) => Err(error),
+            };
+
+            match result {
+                Err(error) if error.is_transient() => {
+                    self.circuit_breakers.record_failure(circuit, policy);
+                    if attempt >= policy.retries_for(&method)
+                        || self.circuit_breakers.is_open(circuit)
+                    {
+                        return Err(error);
+                    }
+                    tokio::time::sleep(policy.retry_delay(attempt)).await;
+                    attempt += 1;
+                }
+
+                result => {
+                    self.circuit_breakers.record_success(circuit);
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Sends `request` once, applying the [`RequestPolicy`] timeout but neither retries nor a
+    /// circuit breaker, for callers such as queue jobs that retry on their own schedule.
+    pub async fn send(
+        &self,
+        method: Method,
+        request: RequestBuilder,
+    ) -> Result<Response, ClientError> {
+        self.execute(request.timeout(self.request_policy.timeout))
+            .await?
+            .success_or_client_error(method)
+            .await
+    }
+
+    async fn execute(&self, request: RequestBuilder) -> Result<Response, ClientError> {
+        let request = request.build()?;
+        if self.public_address_resolver.is_some() {
+            ssrf::check_ip_literal(request.url())?;
+        }
+        self.inner
+            .execute(request)
+            .await
+            .map_err(|error| match ssrf::non_public_address(&error) {
+                Some(non_public_address) => non_public_address.into(),
+                None => error.into(),
+            })
+    }
+}
+
 #[derive(Debug)]
 pub struct HttpStatusNotSuccess {
     pub method: Method,
@@ -162,6 +405,38 @@ pub enum ClientError {
 
     #[error("{0}")]
     Other(String),
+
+    #[error(transparent)]
+    NonPublicAddress(#[from] NonPublicAddress),
+
+    #[error("circuit open for {url}, failing fast for another {}ms", retry_in.as_millis())]
+    CircuitOpen { url: Box<Url>, retry_in: Duration },
+}
+
+impl ClientError {
+    /// Whether the failure is likely to resolve on its own: timeouts, connection errors,
+    /// rate limiting and server errors. Requests that could not be built or sent for any other
+    /// reason would fail the same way again.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Http(e) => e.is_timeout() || e.is_connect(),
+            Self::HttpStatusNotSuccess(e) => e.status.is_some_and(|status| {
+                status.is_server_error()
+                    || status == StatusCode::REQUEST_TIMEOUT
+                    || status == StatusCode::TOO_MANY_REQUESTS
+            }),
+            _ => false,
+        }
+    }
+
+    /// Whether the remote service rejected our credentials.
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(
+            self,
+            Self::HttpStatusNotSuccess(e)
+                if matches!(e.status, Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN))
+        )
+    }
 }
 
 /// Extension trait on [`reqwest::Response`] to check for success status codes
@@ -1,12 +1,17 @@
 use crate::{
-    clients::{ClientError, HttpClient, ResponseExt},
+    clients::{ClientError, HttpClient},
     entity::{task::ProvisionableTask, Aggregator},
     handler::Error,
 };
-use api_types::TaskAggregationJobMetrics;
+use api_types::{TaskAggregationJobMetrics, TaskCollectionJobMetrics};
 use axum::http::{header, Method};
 use janus_messages::Time as JanusTime;
+use reqwest::RequestBuilder;
 use serde::{de::DeserializeOwned, Serialize};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 use url::Url;
 pub mod api_types;
 pub use api_types::{
@@ -18,6 +23,8 @@ const CONTENT_TYPE: &str = "application/vnd.janus.aggregator+json;version=0.1";
 #[derive(Debug, Clone)]
 pub struct AggregatorClient {
     client: HttpClient,
+    secondary_client: Option<HttpClient>,
+    secondary_confirmed: Arc<AtomicBool>,
     aggregator: Aggregator,
 }
 
@@ -25,10 +32,40 @@ impl AggregatorClient {
     pub fn new(client: HttpClient, aggregator: Aggregator, bearer_token: &str) -> Self {
         let client = client
             .with_base(aggregator.api_url.clone())
-            .with_default_header(header::AUTHORIZATION, format!("Bearer {bearer_token}"))
             .with_default_header(header::ACCEPT, CONTENT_TYPE);
 
-        Self { client, aggregator }
+        Self {
+            client: client
+                .clone()
+                .with_default_header(header::AUTHORIZATION, format!("Bearer {bearer_token}")),
+            secondary_client: None,
+            secondary_confirmed: Arc::default(),
+            aggregator,
+        }
+    }
+
+    /// Requests that the aggregator rejects with 401 or 403 are retried with this token.
+    pub fn with_secondary_bearer_token(mut self, bearer_token: &str) -> Self {
+        self.secondary_client = Some(
+            self.client
+                .clone()
+                .with_default_header(header::AUTHORIZATION, format!("Bearer {bearer_token}")),
+        );
+        self
+    }
+
+    /// Whether any request made through this client succeeded only with the secondary
+    /// bearer token, indicating that the aggregator no longer accepts the primary.
+    pub fn secondary_bearer_token_confirmed(&self) -> bool {
+        self.secondary_confirmed.load(Ordering::Relaxed)
+    }
+
+    /// Fetches the aggregator's configuration, falling back to the secondary bearer token
+    /// like any other request.
+    pub async fn get_aggregator_config(&self) -> Result<AggregatorApiConfig, ClientError> {
+        let url: Url = self.aggregator.api_url.clone().into();
+        self.send(Method::GET, |client| client.get_url(url.clone()))
+            .await
     }
 
     pub async fn get_config(
@@ -37,12 +74,12 @@ impl AggregatorClient {
         token: &str,
     ) -> Result<AggregatorApiConfig, ClientError> {
         client
-            .get_url(base_url)
-            .header(header::AUTHORIZATION, format!("Bearer {token}"))
-            .header(header::ACCEPT, CONTENT_TYPE)
-            .send()
-            .await?
-            .success_or_client_error(Method::GET)
+            .send_with_policy(&base_url, Method::GET, || {
+                client
+                    .get_url(base_url.clone())
+                    .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                    .header(header::ACCEPT, CONTENT_TYPE)
+            })
             .await?
             .json()
             .await
@@ -88,6 +125,14 @@ impl AggregatorClient {
             .await
     }
 
+    pub async fn get_task_collection_job_metrics(
+        &self,
+        task_id: &str,
+    ) -> Result<TaskCollectionJobMetrics, ClientError> {
+        self.get(&format!("tasks/{task_id}/metrics/collections"))
+            .await
+    }
+
     pub async fn create_task(&self, task: &ProvisionableTask) -> Result<TaskResponse, Error> {
         let task_create = TaskCreate::build(&self.aggregator, task)?;
         self.post("tasks", &task_create).await.map_err(Into::into)
@@ -109,15 +154,7 @@ impl AggregatorClient {
     }
 
     async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
-        self.client
-            .get(path)
-            .send()
-            .await?
-            .success_or_client_error(Method::GET)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        self.send(Method::GET, |client| client.get(path)).await
     }
 
     async fn post<T: DeserializeOwned>(
@@ -125,17 +162,13 @@ impl AggregatorClient {
         path: &str,
         body: &impl Serialize,
     ) -> Result<T, ClientError> {
-        self.client
-            .post(path)
-            .header(header::CONTENT_TYPE, CONTENT_TYPE)
-            .json(body)
-            .send()
-            .await?
-            .success_or_client_error(Method::POST)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        self.send(Method::POST, |client| {
+            client
+                .post(path)
+                .header(header::CONTENT_TYPE, CONTENT_TYPE)
+                .json(body)
+        })
+        .await
     }
 
     async fn patch<T: DeserializeOwned>(
@@ -143,16 +176,37 @@ impl AggregatorClient {
         path: &str,
         body: &impl Serialize,
     ) -> Result<T, ClientError> {
-        self.client
-            .patch(path)
-            .header(header::CONTENT_TYPE, CONTENT_TYPE)
-            .json(body)
-            .send()
-            .await?
-            .success_or_client_error(Method::PATCH)
-            .await?
-            .json()
-            .await
-            .map_err(Into::into)
+        self.send(Method::PATCH, |client| {
+            client
+                .patch(path)
+                .header(header::CONTENT_TYPE, CONTENT_TYPE)
+                .json(body)
+        })
+        .await
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        request: impl Fn(&HttpClient) -> RequestBuilder,
+    ) -> Result<T, ClientError> {
+        let circuit: Url = self.aggregator.api_url.clone().into();
+        let response = match (
+            self.client
+                .send_with_policy(&circuit, method.clone(), || request(&self.client))
+                .await,
+            &self.secondary_client,
+        ) {
+            (Err(e), Some(secondary_client)) if e.is_unauthorized() => {
+                let response = secondary_client
+                    .send_with_policy(&circuit, method, || request(secondary_client))
+                    .await?;
+                self.secondary_confirmed.store(true, Ordering::Relaxed);
+                response
+            }
+            (result, _) => result?,
+        };
+
+        response.json().await.map_err(Into::into)
     }
 }
@@ -4,7 +4,7 @@ use crate::{
             Features, QueryTypeName, QueryTypeNameSet, Role as AggregatorRole, VdafNameSet,
         },
         task::vdaf::{
-            BucketLength, ContinuousBuckets, CountVec, DpBudget, DpStrategy, DpStrategyKind,
+            BucketLength, ContinuousBuckets, Count, CountVec, DpBudget, DpStrategy, DpStrategyKind,
             Histogram, Sum, SumVec, Vdaf,
         },
         Aggregator, Protocol, ProvisionableTask, Task,
@@ -27,9 +27,15 @@ pub mod dp_strategies;
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum AggregatorVdaf {
+    /// A count without differential privacy, which aggregators expect as a bare string.
     Prio3Count,
     Prio3Sum {
         bits: u8,
+        #[serde(
+            default,
+            skip_serializing_if = "dp_strategies::Prio3Sum::is_no_differential_privacy"
+        )]
+        dp_strategy: dp_strategies::Prio3Sum,
     },
     Prio3Histogram(HistogramType),
     Prio3CountVec {
@@ -42,6 +48,20 @@ pub enum AggregatorVdaf {
         chunk_length: Option<u64>,
         dp_strategy: dp_strategies::Prio3SumVec,
     },
+    /// A count with a differential privacy strategy, as `{"Prio3Count": {"dp_strategy": ..}}`.
+    #[serde(untagged)]
+    Prio3CountWithDp(Prio3CountWithDp),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Prio3CountWithDp {
+    #[serde(rename = "Prio3Count")]
+    pub prio3_count: Prio3CountDp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Prio3CountDp {
+    pub dp_strategy: dp_strategies::Prio3Count,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -62,8 +82,42 @@ pub enum HistogramType {
 impl From<AggregatorVdaf> for Vdaf {
     fn from(value: AggregatorVdaf) -> Self {
         match value {
-            AggregatorVdaf::Prio3Count => Self::Count,
-            AggregatorVdaf::Prio3Sum { bits } => Self::Sum(Sum { bits: Some(bits) }),
+            AggregatorVdaf::Prio3Count => Self::Count(Count::default()),
+            AggregatorVdaf::Prio3CountWithDp(Prio3CountWithDp {
+                prio3_count: Prio3CountDp { dp_strategy },
+            }) => {
+                let dp_strategy = match dp_strategy {
+                    dp_strategies::Prio3Count::NoDifferentialPrivacy => DpStrategy {
+                        dp_strategy: DpStrategyKind::NoDifferentialPrivacy,
+                        budget: DpBudget { epsilon: None },
+                    },
+                    dp_strategies::Prio3Count::PureDpDiscreteLaplace(dp_strategy) => DpStrategy {
+                        dp_strategy: DpStrategyKind::PureDpDiscreteLaplace,
+                        budget: DpBudget {
+                            epsilon: Some(dp_strategy.budget.epsilon.to_vec()),
+                        },
+                    },
+                };
+                Self::Count(Count { dp_strategy })
+            }
+            AggregatorVdaf::Prio3Sum { bits, dp_strategy } => {
+                let dp_strategy = match dp_strategy {
+                    dp_strategies::Prio3Sum::NoDifferentialPrivacy => DpStrategy {
+                        dp_strategy: DpStrategyKind::NoDifferentialPrivacy,
+                        budget: DpBudget { epsilon: None },
+                    },
+                    dp_strategies::Prio3Sum::PureDpDiscreteLaplace(dp_strategy) => DpStrategy {
+                        dp_strategy: DpStrategyKind::PureDpDiscreteLaplace,
+                        budget: DpBudget {
+                            epsilon: Some(dp_strategy.budget.epsilon.to_vec()),
+                        },
+                    },
+                };
+                Self::Sum(Sum {
+                    bits: Some(bits),
+                    dp_strategy,
+                })
+            }
             AggregatorVdaf::Prio3Histogram(HistogramType::Buckets {
                 buckets,
                 chunk_length,
@@ -420,6 +474,30 @@ impl PartialEq<Task> for TaskAggregationJobMetrics {
     }
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskCollectionJobMetrics {
+    /// Collection jobs that have not finished yet.
+    pub pending: u64,
+    /// Collection jobs that finished, producing an aggregate share.
+    pub finished: u64,
+    /// Collection jobs that were abandoned after repeatedly failing.
+    pub abandoned: u64,
+    /// Collection jobs that were deleted by the collector.
+    pub deleted: u64,
+    /// Reports included in finished collection jobs.
+    pub report_count: u64,
+}
+
+impl PartialEq<Task> for TaskCollectionJobMetrics {
+    fn eq(&self, other: &Task) -> bool {
+        other.collection_job_counter_pending == self.pending as i64
+            && other.aggregate_collection_count == self.finished as i64
+            && other.collection_job_counter_abandoned == self.abandoned as i64
+            && other.collection_job_counter_deleted == self.deleted as i64
+            && other.report_count == self.report_count as i64
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct AggregatorApiConfig {
     pub dap_url: Url,
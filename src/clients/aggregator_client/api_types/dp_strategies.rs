@@ -1,5 +1,26 @@
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "dp_strategy")]
+pub enum Prio3Count {
+    NoDifferentialPrivacy,
+    PureDpDiscreteLaplace(PureDpDiscreteLaplace),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "dp_strategy")]
+pub enum Prio3Sum {
+    #[default]
+    NoDifferentialPrivacy,
+    PureDpDiscreteLaplace(PureDpDiscreteLaplace),
+}
+
+impl Prio3Sum {
+    pub fn is_no_differential_privacy(&self) -> bool {
+        matches!(self, Self::NoDifferentialPrivacy)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "dp_strategy")]
 pub enum Prio3Histogram {
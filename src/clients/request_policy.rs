@@ -0,0 +1,105 @@
+use super::ClientError;
+use reqwest::Method;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use url::Url;
+
+/// Timeout, retry and circuit breaker settings applied to aggregator api requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestPolicy {
+    /// Upper bound on each individual attempt, including reading the response.
+    pub timeout: Duration,
+    /// How many times an idempotent request (GET or PATCH) is retried after a transient failure.
+    pub max_retries: u32,
+    /// Retry delays start here and double with every attempt, plus up to the same amount of
+    /// random jitter.
+    pub retry_base_delay: Duration,
+    /// Consecutive transient failures after which an aggregator's circuit opens.
+    pub circuit_failure_threshold: u32,
+    /// How long an open circuit fails requests without contacting the aggregator.
+    pub circuit_open_duration: Duration,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            max_retries: 2,
+            retry_base_delay: Duration::from_millis(250),
+            circuit_failure_threshold: 5,
+            circuit_open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RequestPolicy {
+    pub(crate) fn retries_for(&self, method: &Method) -> u32 {
+        if matches!(*method, Method::GET | Method::PATCH) {
+            self.max_retries
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn retry_delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt));
+        let jitter_millis = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX);
+        delay + Duration::from_millis(fastrand::u64(0..=jitter_millis))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Per-aggregator circuit state, keyed by aggregator api url. Clones share state, so every
+/// [`HttpClient`](super::HttpClient) cloned from the application's client observes the same
+/// circuits.
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreakers(Arc<Mutex<HashMap<Url, Circuit>>>);
+
+impl CircuitBreakers {
+    /// Fails fast if the circuit for `url` is open. Once the open duration has elapsed,
+    /// requests are let through again, and a single further failure reopens the circuit.
+    pub(crate) fn check(&self, url: &Url) -> Result<(), ClientError> {
+        let circuits = self.0.lock().unwrap();
+        match circuits.get(url).and_then(|circuit| circuit.open_until) {
+            Some(open_until) if open_until > Instant::now() => Err(ClientError::CircuitOpen {
+                url: Box::new(url.clone()),
+                retry_in: open_until - Instant::now(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn record_success(&self, url: &Url) {
+        self.0.lock().unwrap().remove(url);
+    }
+
+    pub(crate) fn record_failure(&self, url: &Url, policy: &RequestPolicy) {
+        let mut circuits = self.0.lock().unwrap();
+        let circuit = circuits.entry(url.clone()).or_default();
+        circuit.consecutive_failures += 1;
+        if circuit.consecutive_failures >= policy.circuit_failure_threshold {
+            if circuit
+                .open_until
+                .is_none_or(|open_until| open_until <= Instant::now())
+            {
+                tracing::warn!(%url, "opening circuit after repeated aggregator failures");
+            }
+            circuit.open_until = Some(Instant::now() + policy.circuit_open_duration);
+        }
+    }
+
+    /// Whether requests to `url` are currently failing fast.
+    pub fn is_open(&self, url: &Url) -> bool {
+        self.check(url).is_err()
+    }
+}
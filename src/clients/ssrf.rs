@@ -0,0 +1,235 @@
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use std::{
+    fmt::{self, Debug, Formatter},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+use url::{Host, Url};
+
+const MAX_REDIRECTS: usize = 10;
+
+/// Raised when a request would connect to a loopback, private, link-local or otherwise
+/// non-public address.
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("{0} resolves to a non-public address")]
+pub struct NonPublicAddress(pub String);
+
+/// A dns resolver that refuses to return non-public addresses.
+///
+/// Because addresses are checked every time a connection is made rather than once when an
+/// aggregator is created, a hostname that later re-resolves to an internal address (dns
+/// rebinding) is still refused.
+#[derive(Clone)]
+pub struct PublicAddressResolver(Arc<dyn Resolve>);
+
+impl Debug for PublicAddressResolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PublicAddressResolver").finish()
+    }
+}
+
+impl Default for PublicAddressResolver {
+    fn default() -> Self {
+        Self::new(SystemResolver)
+    }
+}
+
+impl PublicAddressResolver {
+    /// Filters the addresses returned by `resolver`.
+    pub fn new(resolver: impl Resolve + 'static) -> Self {
+        Self(Arc::new(resolver))
+    }
+
+    pub(super) fn configure(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        builder
+            .dns_resolver(Arc::new(self.clone()))
+            .redirect(redirect::Policy::custom(|attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else if let Err(error) = check_ip_literal(attempt.url()) {
+                    attempt.error(error)
+                } else {
+                    attempt.follow()
+                }
+            }))
+    }
+}
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        let resolving = self.0.resolve(name);
+        Box::pin(async move {
+            let addrs = resolving.await?.collect::<Vec<_>>();
+            if addrs.iter().any(|addr| is_private_ip(addr.ip())) {
+                return Err(NonPublicAddress(host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Resolves with the operating system's resolver, as reqwest does by default.
+struct SystemResolver;
+
+impl Resolve for SystemResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .collect::<Vec<SocketAddr>>();
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Hosts that are ip literals are connected to without consulting the resolver, so they are
+/// checked separately.
+pub(super) fn check_ip_literal(url: &Url) -> Result<(), NonPublicAddress> {
+    let is_private = match url.host() {
+        Some(Host::Ipv4(ip)) => is_private_ipv4(ip),
+        Some(Host::Ipv6(ip)) => is_private_ipv6(ip),
+        _ => false,
+    };
+    if is_private {
+        Err(NonPublicAddress(
+            url.host_str().unwrap_or_default().to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Finds a [`NonPublicAddress`] raised by the resolver or redirect policy.
+pub(super) fn non_public_address(error: &reqwest::Error) -> Option<NonPublicAddress> {
+    let mut source = std::error::Error::source(error);
+    while let Some(error) = source {
+        if let Some(non_public_address) = error.downcast_ref::<NonPublicAddress>() {
+            return Some(non_public_address.clone());
+        }
+        source = error.source();
+    }
+    None
+}
+
+pub(crate) fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => is_private_ipv6(ip),
+    }
+}
+
+pub(crate) fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_loopback()         // 127.0.0.0/8
+        || ip.is_private()   // 10/8, 172.16/12, 192.168/16
+        || ip.is_link_local()   // 169.254.0.0/16 (includes cloud metadata)
+        || ip.is_broadcast()    // 255.255.255.255
+        || ip.is_unspecified()  // 0.0.0.0
+        || ip.is_documentation() // 192.0.2.0/24, 198.51.100.0/24, 203.0.113.0/24
+        || ip.octets()[0] == 100 && (ip.octets()[1] & 0xC0) == 64 // 100.64.0.0/10 (RFC 6598)
+}
+
+pub(crate) fn is_private_ipv6(ip: Ipv6Addr) -> bool {
+    ip.is_loopback()         // ::1
+        || ip.is_unspecified() // ::
+        || {
+            if let Some(ipv4) = ip.to_ipv4_mapped() {
+                is_private_ipv4(ipv4)
+            } else {
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                let segments = ip.segments();
+                (segments[0] & 0xfe00) == 0xfc00 || (segments[0] & 0xffc0) == 0xfe80
+            }
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_ipv4_addresses() {
+        assert!(is_private_ipv4(Ipv4Addr::new(127, 0, 0, 1))); // loopback
+        assert!(is_private_ipv4(Ipv4Addr::new(10, 0, 0, 1))); // RFC 1918
+        assert!(is_private_ipv4(Ipv4Addr::new(172, 16, 0, 1))); // RFC 1918
+        assert!(is_private_ipv4(Ipv4Addr::new(192, 168, 1, 1))); // RFC 1918
+        assert!(is_private_ipv4(Ipv4Addr::new(169, 254, 169, 254))); // link-local / metadata
+        assert!(is_private_ipv4(Ipv4Addr::new(0, 0, 0, 0))); // unspecified
+        assert!(is_private_ipv4(Ipv4Addr::new(100, 64, 0, 1))); // RFC 6598
+        assert!(is_private_ipv4(Ipv4Addr::new(100, 127, 255, 255))); // RFC 6598 upper
+        assert!(!is_private_ipv4(Ipv4Addr::new(8, 8, 8, 8))); // public
+        assert!(!is_private_ipv4(Ipv4Addr::new(100, 128, 0, 1))); // just outside RFC 6598
+    }
+
+    #[test]
+    fn private_ipv6_addresses() {
+        assert!(is_private_ipv6(Ipv6Addr::LOCALHOST)); // ::1
+        assert!(is_private_ipv6(Ipv6Addr::UNSPECIFIED)); // ::
+        assert!(is_private_ipv6("fc00::1".parse().unwrap())); // unique local
+        assert!(is_private_ipv6("fe80::1".parse().unwrap())); // link-local
+        assert!(is_private_ipv6("::ffff:127.0.0.1".parse().unwrap())); // IPv4-mapped loopback
+        assert!(is_private_ipv6("::ffff:169.254.169.254".parse().unwrap())); // IPv4-mapped metadata
+        assert!(!is_private_ipv6("2001:4860:4860::8888".parse().unwrap())); // public
+    }
+
+    #[test]
+    fn private_ip_dispatch() {
+        assert!(is_private_ip(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert!(is_private_ip(IpAddr::V6(Ipv6Addr::LOCALHOST)));
+        assert!(!is_private_ip(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8))));
+    }
+
+    struct StubResolver(Vec<IpAddr>);
+
+    impl Resolve for StubResolver {
+        fn resolve(&self, _: Name) -> Resolving {
+            let addrs = self
+                .0
+                .iter()
+                .map(|ip| SocketAddr::new(*ip, 0))
+                .collect::<Vec<_>>();
+            Box::pin(async move { Ok(Box::new(addrs.into_iter()) as Addrs) })
+        }
+    }
+
+    async fn resolve(addrs: &[&str]) -> Result<Vec<SocketAddr>, String> {
+        let stub = StubResolver(addrs.iter().map(|addr| addr.parse().unwrap()).collect());
+        PublicAddressResolver::new(stub)
+            .resolve("aggregator.example".parse().unwrap())
+            .await
+            .map(Iterator::collect)
+            .map_err(|error| error.to_string())
+    }
+
+    #[tokio::test]
+    async fn resolver_allows_public_addresses() {
+        assert_eq!(
+            resolve(&["8.8.8.8", "2001:4860:4860::8888"]).await.unwrap(),
+            vec![
+                "8.8.8.8:0".parse().unwrap(),
+                "[2001:4860:4860::8888]:0".parse().unwrap()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn resolver_refuses_any_non_public_address() {
+        assert_eq!(
+            resolve(&["8.8.8.8", "169.254.169.254"]).await.unwrap_err(),
+            "aggregator.example resolves to a non-public address"
+        );
+        assert!(resolve(&["::1"]).await.is_err());
+    }
+
+    #[test]
+    fn ip_literals() {
+        let url = |s: &str| Url::parse(s).unwrap();
+        assert!(check_ip_literal(&url("https://169.254.169.254/latest/")).is_err());
+        assert!(check_ip_literal(&url("https://[::ffff:10.0.0.1]/")).is_err());
+        assert!(check_ip_literal(&url("https://8.8.8.8/")).is_ok());
+        assert!(check_ip_literal(&url("https://localhost/")).is_ok()); // left to the resolver
+    }
+}
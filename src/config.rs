@@ -13,11 +13,12 @@ use std::{
     error::Error,
     net::{IpAddr, Ipv6Addr, SocketAddr},
     str::FromStr,
+    time::Duration,
 };
 use thiserror::Error;
 use url::Url;
 
-use crate::clients::HttpClient;
+use crate::clients::{HttpClient, PublicAddressResolver, RequestPolicy};
 
 const POSTMARK_URL: &str = "https://api.postmarkapp.com";
 
@@ -128,13 +129,29 @@ where
     }
 }
 
-fn build_client() -> HttpClient {
-    HttpClient::new(
-        reqwest::Client::builder()
-            .use_rustls_tls()
-            .build()
-            .expect("failed to build reqwest client"),
-    )
+fn build_client(request_policy: RequestPolicy) -> HttpClient {
+    HttpClient::from_builder(|| reqwest::Client::builder().use_rustls_tls())
+        .with_request_policy(request_policy)
+}
+
+fn request_policy_from_env() -> Result<RequestPolicy, ConfigError> {
+    let default = RequestPolicy::default();
+    let millis = |name, default: Duration| {
+        var_optional(name, u64::try_from(default.as_millis()).unwrap()).map(Duration::from_millis)
+    };
+    Ok(RequestPolicy {
+        timeout: millis("AGGREGATOR_API_TIMEOUT_MS", default.timeout)?,
+        max_retries: var_optional("AGGREGATOR_API_MAX_RETRIES", default.max_retries)?,
+        retry_base_delay: millis("AGGREGATOR_API_RETRY_DELAY_MS", default.retry_base_delay)?,
+        circuit_failure_threshold: var_optional(
+            "AGGREGATOR_API_CIRCUIT_FAILURE_THRESHOLD",
+            default.circuit_failure_threshold,
+        )?,
+        circuit_open_duration: millis(
+            "AGGREGATOR_API_CIRCUIT_OPEN_MS",
+            default.circuit_open_duration,
+        )?,
+    })
 }
 
 impl Config {
@@ -146,7 +163,7 @@ impl Config {
             auth_client_id: var("AUTH_CLIENT_ID")?,
             auth_client_secret: var("AUTH_CLIENT_SECRET")?,
             auth_url: var("AUTH_URL")?,
-            client: build_client(),
+            client: build_client(request_policy_from_env()?),
             crypter: var("DATABASE_ENCRYPTION_KEYS")?,
             database_url: var("DATABASE_URL")?,
             email_address: var("EMAIL_ADDRESS")?,
@@ -189,6 +206,18 @@ impl Config {
         }
     }
 
+    /// The client for requests to aggregator and webhook urls. These are supplied by users, so
+    /// connections to non-public addresses are refused when ssrf validation is enabled.
+    pub fn user_url_client(&self) -> HttpClient {
+        if self.ssrf_validation_enabled {
+            self.client
+                .clone()
+                .with_ssrf_protection(PublicAddressResolver::default())
+        } else {
+            self.client.clone()
+        }
+    }
+
     pub fn trace_config(&self) -> TraceConfig {
         TraceConfig {
             use_test_writer: self.trace_use_test_writer,
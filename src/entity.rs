@@ -1,6 +1,12 @@
 pub mod account;
 pub mod aggregator;
+pub mod aggregator_access;
+pub mod aggregator_capability_change;
+pub mod alert;
+pub mod alert_rule;
 pub mod api_token;
+pub mod api_token_usage;
+pub mod audit_event;
 pub mod codec;
 pub mod collector_credential;
 mod json;
@@ -8,7 +14,10 @@ pub mod membership;
 pub mod queue;
 pub mod session;
 pub mod task;
+pub mod task_counter_snapshot;
 mod url;
+pub mod webhook;
+pub mod webhook_delivery;
 
 pub use account::{
     Column as AccountColumn, Entity as Accounts, Model as Account, NewAccount,
@@ -16,19 +25,54 @@ pub use account::{
 };
 pub use aggregator::{
     Column as AggregatorColumn, Entity as Aggregators, Model as Aggregator, NewAggregator,
-    Protocol, Role, UnrecognizedProtocol, UnrecognizedRole, UpdateAggregator,
+    Protocol, Role, StageBearerToken, UnrecognizedProtocol, UnrecognizedRole, UpdateAggregator,
+    Visibility,
+};
+pub use aggregator_access::{
+    Column as AggregatorAccessColumn, Entity as AggregatorAccesses, Model as AggregatorAccess,
+    RequestAggregatorAccess,
+};
+pub use aggregator_capability_change::{
+    Column as AggregatorCapabilityChangeColumn, Entity as AggregatorCapabilityChanges,
+    Model as AggregatorCapabilityChange,
+};
+pub use alert::{Column as AlertColumn, Entity as Alerts, Model as Alert};
+pub use alert_rule::{
+    AlertChannel, Column as AlertRuleColumn, Entity as AlertRules, Model as AlertRule,
+    NewAlertRule, UpdateAlertRule,
 };
 pub use api_token::{
-    Column as ApiTokenColumn, Entity as ApiTokens, Model as ApiToken, UpdateApiToken,
+    ApiTokenScope, Column as ApiTokenColumn, Entity as ApiTokens, Model as ApiToken, NewApiToken,
+    UpdateApiToken,
+};
+pub use api_token_usage::{
+    Column as ApiTokenUsageColumn, Entity as ApiTokenUsages, Model as ApiTokenUsage,
+};
+pub use audit_event::{
+    AuditAction, AuditChange, AuditEventFilter, AuditTarget, AuditTargetType,
+    Column as AuditEventColumn, Entity as AuditEvents, Model as AuditEvent,
 };
 pub use collector_credential::{
     Column as CollectorCredentialColumn, Entity as CollectorCredentials,
     Model as CollectorCredential, NewCollectorCredential, UpdateCollectorCredential,
 };
 pub use membership::{
-    Column as MembershipColumn, CreateMembership, Entity as Memberships, Model as Membership,
+    Column as MembershipColumn, CreateMembership, Entity as Memberships, MembershipRole,
+    Model as Membership, UpdateMembership,
 };
 pub use session::{Column as SessionColumn, Entity as Sessions, Model as Session};
 pub use task::{
-    Column as TaskColumn, Entity as Tasks, Model as Task, NewTask, ProvisionableTask, UpdateTask,
+    Column as TaskColumn, Entity as Tasks, Model as Task, NewTask, ProvisionableTask, TaskCounter,
+    UpdateTask,
+};
+pub use task_counter_snapshot::{
+    Column as TaskCounterSnapshotColumn, Entity as TaskCounterSnapshots,
+    Model as TaskCounterSnapshot,
+};
+pub use webhook::{
+    Column as WebhookColumn, Entity as Webhooks, Model as Webhook, NewWebhook, UpdateWebhook,
+    WebhookEvent, WebhookEventType,
+};
+pub use webhook_delivery::{
+    Column as WebhookDeliveryColumn, Entity as WebhookDeliveries, Model as WebhookDelivery,
 };
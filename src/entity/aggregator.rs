@@ -1,31 +1,50 @@
+mod capabilities;
+mod compatibility;
+mod decommission_aggregator;
 mod feature;
+mod hpke_configs;
 mod new_aggregator;
 mod protocol;
 mod query_type_name;
 mod role;
+mod stage_bearer_token;
+mod tls;
 mod update_aggregator;
 mod vdaf_name;
+mod visibility;
 
-use super::{json::Json, url::Url, AccountColumn, AccountRelation, Accounts, Memberships};
+use super::{
+    json::Json, url::Url, AccountColumn, AccountRelation, Accounts, AggregatorAccesses,
+    Memberships, TaskColumn, Tasks,
+};
 use crate::{
     clients::{AggregatorClient, HttpClient},
     Crypter, Error,
 };
 use sea_orm::{
-    ActiveModelBehavior, ActiveValue, DeriveEntityModel, DerivePrimaryKey, DeriveRelation,
-    EntityTrait, EnumIter, IntoActiveModel, PrimaryKeyTrait, Related, RelationDef, RelationTrait,
+    sea_query::{all, any},
+    ActiveModelBehavior, ActiveValue, ColumnTrait, ConnectionTrait, DbErr, DeriveEntityModel,
+    DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel, PaginatorTrait,
+    PrimaryKeyTrait, QueryFilter, QuerySelect, Related, RelationDef, RelationTrait, Select,
 };
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use time::OffsetDateTime;
 use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
 
+pub use capabilities::{Capabilities, RemovedCapabilities};
+pub use compatibility::{Compatibility, CreatableVdaf};
+pub use decommission_aggregator::{Decommission, DecommissionAggregator};
 pub use feature::{Feature, Features};
+pub use hpke_configs::{fetch_hpke_config_ids, HpkeConfigError};
 pub use new_aggregator::NewAggregator;
 pub use protocol::{Protocol, UnrecognizedProtocol};
 pub use query_type_name::{QueryTypeName, QueryTypeNameSet};
 pub use role::{Role, UnrecognizedRole};
+pub use stage_bearer_token::StageBearerToken;
 pub use update_aggregator::UpdateAggregator;
 pub use vdaf_name::{VdafName, VdafNameSet};
+pub use visibility::Visibility;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "aggregator")]
@@ -52,6 +71,87 @@ pub struct Model {
     #[serde(skip)]
     pub encrypted_bearer_token: Vec<u8>,
     pub features: Json<Features>,
+    // a staged bearer token that is used if the aggregator rejects the primary one
+    #[serde(
+        rename = "has_secondary_bearer_token",
+        serialize_with = "serialize_is_some",
+        skip_deserializing
+    )]
+    pub encrypted_secondary_bearer_token: Option<Vec<u8>>,
+    // a client certificate chain and private key, presented to aggregators that require mutual tls
+    #[serde(
+        rename = "has_client_identity",
+        serialize_with = "serialize_is_some",
+        skip_deserializing
+    )]
+    pub encrypted_client_identity: Option<Vec<u8>>,
+    // additional trust anchors for aggregators with certificates issued by a private ca
+    pub ca_certificate: Option<String>,
+    // ids of the hpke configs published at dap_url when they were last fetched successfully
+    pub hpke_config_ids: Option<Json<Vec<u8>>>,
+    // why the most recent attempt to fetch hpke configs from dap_url failed, if it did
+    pub hpke_config_error: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub hpke_configs_checked_at: Option<OffsetDateTime>,
+    // a sunset_at of Some indicates a shared aggregator that is being decommissioned, and whose
+    // remaining tasks will be expired at that time
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub sunset_at: Option<OffsetDateTime>,
+    // which accounts can see and use a shared aggregator
+    pub visibility: Visibility,
+}
+
+fn serialize_is_some<S: Serializer>(
+    value: &Option<Vec<u8>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bool(value.is_some())
+}
+
+impl Entity {
+    /// Undeleted aggregators that belong to, or are shared with, any of these accounts. Shared
+    /// aggregators are visible to every account unless they are allowlisted.
+    pub fn visible_to(account_ids: Vec<Uuid>) -> Select<Self> {
+        Self::find().filter(all![
+            Column::DeletedAt.is_null(),
+            any![
+                Column::AccountId.is_in(account_ids.clone()),
+                all![
+                    Column::AccountId.is_null(),
+                    any![
+                        Column::Visibility.ne(Visibility::Allowlist),
+                        Column::Id
+                            .in_subquery(AggregatorAccesses::granted_aggregator_ids(account_ids))
+                    ]
+                ]
+            ]
+        ])
+    }
+
+    /// Locks an undeleted aggregator against deletion until the transaction ends, so that a task
+    /// created with it cannot race the aggregator's deletion.
+    pub async fn lock_for_task(
+        id: Uuid,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .lock_shared()
+            .one(db)
+            .await
+    }
+
+    /// Locks an undeleted aggregator for deletion until the transaction ends.
+    pub async fn lock_for_delete(
+        id: Uuid,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::find_by_id(id)
+            .filter(Column::DeletedAt.is_null())
+            .lock_exclusive()
+            .one(db)
+            .await
+    }
 }
 
 impl Model {
@@ -66,25 +166,162 @@ impl Model {
         self.deleted_at.is_some()
     }
 
+    /// Reports an aggregator that is being decommissioned, which new tasks cannot use, as an
+    /// error on `field`.
+    pub fn validate_not_decommissioned(&self, field: &'static str, errors: &mut ValidationErrors) {
+        if self.sunset_at.is_some() {
+            errors.add(field, ValidationError::new("decommissioned"));
+        }
+    }
+
+    /// Tasks that use this aggregator in either role and have not yet expired.
+    pub fn unexpired_tasks(&self) -> Select<Tasks> {
+        Tasks::find().filter(all![
+            TaskColumn::DeletedAt.is_null(),
+            any![
+                TaskColumn::Expiration.is_null(),
+                TaskColumn::Expiration.gt(OffsetDateTime::now_utc())
+            ],
+            any![
+                TaskColumn::LeaderAggregatorId.eq(self.id),
+                TaskColumn::HelperAggregatorId.eq(self.id)
+            ],
+        ])
+    }
+
+    /// Whether any of these accounts can see this aggregator.
+    pub async fn is_visible_to(
+        &self,
+        account_ids: Vec<Uuid>,
+        db: &impl ConnectionTrait,
+    ) -> Result<bool, Error> {
+        match self.account_id {
+            Some(account_id) => Ok(account_ids.contains(&account_id)),
+            None if self.visibility == Visibility::Allowlist => {
+                self.is_granted_to(account_ids, db).await
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Whether tasks can be created with this aggregator by this account.
+    pub async fn is_usable_by(
+        &self,
+        account_id: Uuid,
+        db: &impl ConnectionTrait,
+    ) -> Result<bool, Error> {
+        match self.account_id {
+            Some(owner_id) => Ok(owner_id == account_id),
+            None if self.visibility == Visibility::Public => Ok(true),
+            None => self.is_granted_to(vec![account_id], db).await,
+        }
+    }
+
+    async fn is_granted_to(
+        &self,
+        account_ids: Vec<Uuid>,
+        db: &impl ConnectionTrait,
+    ) -> Result<bool, Error> {
+        Ok(Entity::find()
+            .filter(all![
+                Column::Id.eq(self.id),
+                Column::Id.in_subquery(AggregatorAccesses::granted_aggregator_ids(account_ids))
+            ])
+            .count(db)
+            .await?
+            > 0)
+    }
+
     pub fn client(
         &self,
         http_client: HttpClient,
         crypter: &Crypter,
     ) -> Result<AggregatorClient, Error> {
-        Ok(AggregatorClient::new(
-            http_client,
+        let client = AggregatorClient::new(
+            self.http_client(http_client, crypter)?,
             self.clone(),
             &self.bearer_token(crypter)?,
-        ))
+        );
+        Ok(match self.secondary_bearer_token(crypter)? {
+            Some(secondary_bearer_token) => {
+                client.with_secondary_bearer_token(&secondary_bearer_token)
+            }
+            None => client,
+        })
+    }
+
+    /// The application's http client, configured with this aggregator's tls settings.
+    pub fn http_client(
+        &self,
+        http_client: HttpClient,
+        crypter: &Crypter,
+    ) -> Result<HttpClient, Error> {
+        tls::http_client(
+            http_client,
+            self.id,
+            self.client_identity(crypter)?.as_deref(),
+            self.ca_certificate.as_deref(),
+        )
+    }
+
+    pub fn client_identity(&self, crypter: &Crypter) -> Result<Option<Vec<u8>>, Error> {
+        self.encrypted_client_identity
+            .as_deref()
+            .map(|encrypted| crypter.decrypt(self.api_url.as_ref().as_bytes(), encrypted))
+            .transpose()
+            .map_err(Into::into)
     }
 
     pub fn bearer_token(&self, crypter: &Crypter) -> Result<String, Error> {
-        let bearer_token_bytes = crypter.decrypt(
-            self.api_url.as_ref().as_bytes(),
-            &self.encrypted_bearer_token,
-        )?;
+        self.decrypt_token(crypter, &self.encrypted_bearer_token)
+    }
+
+    pub fn secondary_bearer_token(&self, crypter: &Crypter) -> Result<Option<String>, Error> {
+        self.encrypted_secondary_bearer_token
+            .as_deref()
+            .map(|encrypted| self.decrypt_token(crypter, encrypted))
+            .transpose()
+    }
+
+    fn decrypt_token(&self, crypter: &Crypter, encrypted: &[u8]) -> Result<String, Error> {
+        let bearer_token_bytes = crypter.decrypt(self.api_url.as_ref().as_bytes(), encrypted)?;
         String::from_utf8(bearer_token_bytes).map_err(Into::into)
     }
+
+    /// Confirms that the aggregator accepts the staged secondary bearer token.
+    pub async fn verify_secondary_bearer_token(
+        &self,
+        http_client: HttpClient,
+        crypter: &Crypter,
+    ) -> Result<(), Error> {
+        let Some(secondary_bearer_token) = self.secondary_bearer_token(crypter)? else {
+            let mut validation_errors = ValidationErrors::new();
+            validation_errors.add("bearer_token", ValidationError::new("required"));
+            return Err(validation_errors.into());
+        };
+
+        AggregatorClient::get_config(
+            self.http_client(http_client, crypter)?,
+            self.api_url.clone().into(),
+            &secondary_bearer_token,
+        )
+        .await
+        .map_err(update_aggregator::token_not_recognized)?;
+        Ok(())
+    }
+
+    /// Replaces the primary bearer token with the staged secondary bearer token, retiring
+    /// the previous primary.
+    pub fn promote_secondary_bearer_token(self) -> ActiveModel {
+        let secondary = self.encrypted_secondary_bearer_token.clone();
+        let mut aggregator = self.into_active_model();
+        if let Some(secondary) = secondary {
+            aggregator.encrypted_bearer_token = ActiveValue::Set(secondary);
+            aggregator.encrypted_secondary_bearer_token = ActiveValue::Set(None);
+            aggregator.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        }
+        aggregator
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,83 @@
+use super::{Feature, Features, Model, Protocol, QueryTypeName, QueryTypeNameSet, VdafNameSet};
+use crate::{clients::aggregator_client::api_types::AggregatorApiConfig, entity::Task};
+use serde::{Deserialize, Serialize};
+
+/// The subset of an aggregator's configuration that is advertised by its
+/// aggregator api and determines which tasks it can serve.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub protocol: Protocol,
+    pub vdafs: VdafNameSet,
+    pub query_types: QueryTypeNameSet,
+    pub features: Features,
+}
+
+impl Capabilities {
+    /// Returns the capabilities present in `self` but absent from `current`.
+    pub fn removed_in(&self, current: &Capabilities) -> RemovedCapabilities {
+        RemovedCapabilities {
+            protocol: self.protocol != current.protocol,
+            vdafs: self.vdafs.difference(&current.vdafs),
+            query_types: self.query_types.difference(&current.query_types),
+            features: self.features.difference(&current.features),
+        }
+    }
+}
+
+impl From<&Model> for Capabilities {
+    fn from(aggregator: &Model) -> Self {
+        Self {
+            protocol: aggregator.protocol,
+            vdafs: aggregator.vdafs.0.clone(),
+            query_types: aggregator.query_types.0.clone(),
+            features: aggregator.features.0.clone(),
+        }
+    }
+}
+
+impl From<AggregatorApiConfig> for Capabilities {
+    fn from(config: AggregatorApiConfig) -> Self {
+        Self {
+            protocol: config.protocol,
+            vdafs: config.vdafs,
+            query_types: config.query_types,
+            features: config.features,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RemovedCapabilities {
+    pub protocol: bool,
+    pub vdafs: VdafNameSet,
+    pub query_types: QueryTypeNameSet,
+    pub features: Features,
+}
+
+impl RemovedCapabilities {
+    pub fn is_empty(&self) -> bool {
+        !self.protocol
+            && self.vdafs.is_empty()
+            && self.query_types.is_empty()
+            && self.features.is_empty()
+    }
+
+    /// Whether `task` depends on any of the removed capabilities when served
+    /// by this aggregator, in the role indicated by `is_leader`.
+    pub fn affects_task(&self, task: &Task, is_leader: bool) -> bool {
+        let query_type = if task.max_batch_size.is_some() {
+            QueryTypeName::FixedSize
+        } else {
+            QueryTypeName::TimeInterval
+        };
+
+        self.protocol
+            || self.vdafs.contains(&task.vdaf.name())
+            || self.query_types.contains(&query_type)
+            || (task.vdaf.uses_pure_dp_discrete_laplace()
+                && self.features.contains(&Feature::PureDpDiscreteLaplace))
+            || (is_leader
+                && task.batch_time_window_size_seconds.is_some()
+                && self.features.contains(&Feature::TimeBucketedFixedSize))
+    }
+}
@@ -0,0 +1,126 @@
+use super::{
+    Feature, Features, Model, Protocol, QueryTypeName, QueryTypeNameSet, Role, VdafNameSet,
+};
+use crate::entity::task::vdaf::{DpStrategyKind, Vdaf};
+use serde::Serialize;
+use validator::{ValidationError, ValidationErrors};
+
+/// What a leader and helper have in common, and therefore which tasks can be
+/// created with them.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Compatibility {
+    /// Whether any task can be created with this pair
+    pub compatible: bool,
+    /// The same errors that task creation would report for this pair,
+    /// regardless of the task's other attributes
+    pub errors: ValidationErrors,
+    pub protocol: Option<Protocol>,
+    pub vdafs: VdafNameSet,
+    pub query_types: QueryTypeNameSet,
+    pub features: Features,
+    pub time_bucketed_fixed_size: bool,
+    pub creatable_vdafs: Vec<CreatableVdaf>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CreatableVdaf {
+    #[serde(rename = "type")]
+    pub vdaf_type: String,
+    pub dp_strategies: Vec<DpStrategyKind>,
+}
+
+impl Compatibility {
+    pub fn between(leader: &Model, helper: &Model) -> Self {
+        let mut errors = ValidationErrors::new();
+
+        if leader == helper {
+            errors.add("leader_aggregator_id", ValidationError::new("same"));
+            errors.add("helper_aggregator_id", ValidationError::new("same"));
+        }
+
+        leader.validate_not_decommissioned("leader_aggregator_id", &mut errors);
+        helper.validate_not_decommissioned("helper_aggregator_id", &mut errors);
+
+        if !leader.is_first_party && !helper.is_first_party {
+            errors.add(
+                "leader_aggregator_id",
+                ValidationError::new("no-first-party"),
+            );
+            errors.add(
+                "helper_aggregator_id",
+                ValidationError::new("no-first-party"),
+            );
+        }
+
+        let protocol = if leader.protocol == helper.protocol {
+            Some(leader.protocol)
+        } else {
+            errors.add("leader_aggregator_id", ValidationError::new("protocol"));
+            errors.add("helper_aggregator_id", ValidationError::new("protocol"));
+            None
+        };
+
+        if leader.role == Role::Helper {
+            errors.add("leader_aggregator_id", ValidationError::new("role"));
+        }
+
+        if helper.role == Role::Leader {
+            errors.add("helper_aggregator_id", ValidationError::new("role"));
+        }
+
+        let vdafs = leader.vdafs.intersect(&helper.vdafs);
+        let query_types = leader.query_types.intersect(&helper.query_types);
+        let features = leader.features.intersect(&helper.features);
+        let compatible = errors.is_empty() && !query_types.is_empty();
+
+        let creatable_vdafs = if compatible {
+            let dp_strategies = if features.contains(&Feature::PureDpDiscreteLaplace) {
+                vec![
+                    DpStrategyKind::NoDifferentialPrivacy,
+                    DpStrategyKind::PureDpDiscreteLaplace,
+                ]
+            } else {
+                vec![DpStrategyKind::NoDifferentialPrivacy]
+            };
+
+            Vdaf::creatable()
+                .iter()
+                .filter(|vdaf| vdafs.contains(&vdaf.name()))
+                .map(|vdaf| CreatableVdaf {
+                    vdaf_type: vdaf.type_name().into(),
+                    dp_strategies: if vdaf.uses_pure_dp_discrete_laplace() {
+                        dp_strategies.clone()
+                    } else {
+                        vec![DpStrategyKind::NoDifferentialPrivacy]
+                    },
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
+        Self {
+            compatible: compatible && !creatable_vdafs.is_empty(),
+            time_bucketed_fixed_size: compatible
+                && query_types.contains(&QueryTypeName::FixedSize)
+                && leader.features.contains(&Feature::TimeBucketedFixedSize),
+            errors,
+            protocol,
+            vdafs,
+            query_types,
+            features,
+            creatable_vdafs,
+        }
+    }
+
+    /// Reports that the account cannot create tasks with one of the pair until it has been
+    /// granted access to that aggregator.
+    pub fn without_access_to(mut self, field: &'static str) -> Self {
+        self.errors
+            .add(field, ValidationError::new("access-required"));
+        self.compatible = false;
+        self.time_bucketed_fixed_size = false;
+        self.creatable_vdafs.clear();
+        self
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    entity::{Aggregator, Task, TaskColumn},
+    Error,
+};
+use sea_orm::{ActiveValue, ConnectionTrait, IntoActiveModel, QueryOrder};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use validator::{Validate, ValidationError};
+
+#[derive(Deserialize, Validate, Debug, Clone, Copy)]
+pub struct DecommissionAggregator {
+    #[validate(required)]
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub sunset_at: Option<OffsetDateTime>,
+}
+
+impl DecommissionAggregator {
+    pub fn build(self, aggregator: Aggregator) -> Result<super::ActiveModel, Error> {
+        let mut errors = self.validate().err().unwrap_or_default();
+        if self
+            .sunset_at
+            .is_some_and(|sunset_at| sunset_at <= OffsetDateTime::now_utc())
+        {
+            errors.add("sunset_at", ValidationError::new("past"));
+        }
+        if aggregator.account_id.is_some() {
+            errors.add("account_id", ValidationError::new("not-shared"));
+        }
+        if aggregator.is_tombstoned() {
+            errors.add("deleted_at", ValidationError::new("tombstoned"));
+        }
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+
+        let mut aggregator = aggregator.into_active_model();
+        aggregator.sunset_at = ActiveValue::Set(self.sunset_at);
+        aggregator.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        Ok(aggregator)
+    }
+}
+
+/// A shared aggregator along with the tasks, across all accounts, that still depend on it.
+#[derive(Serialize, Debug, Clone)]
+pub struct Decommission {
+    pub aggregator: Aggregator,
+    pub affected_tasks: Vec<Task>,
+}
+
+impl Decommission {
+    pub async fn load(aggregator: Aggregator, db: &impl ConnectionTrait) -> Result<Self, Error> {
+        let affected_tasks = aggregator
+            .unexpired_tasks()
+            .order_by_asc(TaskColumn::AccountId)
+            .all(db)
+            .await?;
+        Ok(Self {
+            aggregator,
+            affected_tasks,
+        })
+    }
+}
@@ -6,6 +6,7 @@ pub enum Feature {
     TokenHash,
     UploadMetrics,
     AggregationJobMetrics,
+    CollectionJobMetrics,
     TimeBucketedFixedSize,
     PureDpDiscreteLaplace,
     #[serde(untagged)]
@@ -28,6 +29,10 @@ impl Features {
         self.0.contains(&Feature::AggregationJobMetrics)
     }
 
+    pub fn collection_job_metrics_enabled(&self) -> bool {
+        self.0.contains(&Feature::CollectionJobMetrics)
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -40,6 +45,10 @@ impl Features {
         self.0.intersection(&other.0).cloned().collect()
     }
 
+    pub fn difference(&self, other: &Features) -> Self {
+        self.0.difference(&other.0).cloned().collect()
+    }
+
     pub fn contains(&self, feature: &Feature) -> bool {
         self.0.contains(feature)
     }
@@ -0,0 +1,144 @@
+use super::{ActiveModel, Model};
+use crate::clients::{ClientError, HttpClient};
+use janus_messages::{
+    codec::{CodecError, Decode},
+    HpkeAeadId, HpkeConfig, HpkeConfigList, HpkeKdfId, HpkeKemId,
+};
+use reqwest::{header::ACCEPT, Method};
+use sea_orm::{ActiveValue, IntoActiveModel};
+use time::OffsetDateTime;
+use url::Url;
+use validator::ValidationError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum HpkeConfigError {
+    #[error("could not fetch hpke configs: {0}")]
+    Fetch(#[from] ClientError),
+    #[error("could not parse hpke configs: {0}")]
+    Parse(#[from] CodecError),
+    #[error("no hpke config uses algorithms that clients support")]
+    Unsupported,
+}
+
+impl HpkeConfigError {
+    pub(super) fn validation_error(&self) -> ValidationError {
+        ValidationError::new(match self {
+            Self::Fetch(_) => "hpke-config-unavailable",
+            Self::Parse(_) => "hpke-config-invalid",
+            Self::Unsupported => "hpke-config-unsupported",
+        })
+    }
+}
+
+/// Whether report upload clients implement all of this config's algorithms.
+pub fn is_supported(hpke_config: &HpkeConfig) -> bool {
+    matches!(
+        hpke_config.kem_id(),
+        HpkeKemId::X25519HkdfSha256 | HpkeKemId::P256HkdfSha256
+    ) && matches!(
+        hpke_config.kdf_id(),
+        HpkeKdfId::HkdfSha256 | HpkeKdfId::HkdfSha384 | HpkeKdfId::HkdfSha512
+    ) && matches!(
+        hpke_config.aead_id(),
+        HpkeAeadId::Aes128Gcm | HpkeAeadId::Aes256Gcm | HpkeAeadId::ChaCha20Poly1305
+    )
+}
+
+/// Fetches the hpke config list published at `dap_url`, returning the ids of the configs in it.
+/// At least one of the configs must be [supported](is_supported).
+pub async fn fetch_hpke_config_ids(
+    http_client: &HttpClient,
+    dap_url: &Url,
+) -> Result<Vec<u8>, HpkeConfigError> {
+    let mut url = dap_url.clone();
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    let url = url
+        .join("hpke_config")
+        .map_err(|e| ClientError::Other(e.to_string()))?;
+
+    let response = http_client
+        .send_with_policy(dap_url, Method::GET, || {
+            http_client
+                .get_url(url.clone())
+                .header(ACCEPT, HpkeConfigList::MEDIA_TYPE)
+        })
+        .await?;
+    let body = response.bytes().await.map_err(ClientError::from)?;
+    let hpke_config_list = HpkeConfigList::get_decoded(&body)?;
+
+    if !hpke_config_list.hpke_configs().iter().any(is_supported) {
+        return Err(HpkeConfigError::Unsupported);
+    }
+
+    Ok(hpke_config_list
+        .hpke_configs()
+        .iter()
+        .map(|hpke_config| u8::from(*hpke_config.id()))
+        .collect())
+}
+
+impl Model {
+    /// Records the outcome of fetching this aggregator's hpke configs. A failure keeps the
+    /// previously known config ids and flags the aggregator with the error.
+    pub fn hpke_configs_checked(self, result: &Result<Vec<u8>, HpkeConfigError>) -> ActiveModel {
+        let mut aggregator = self.into_active_model();
+        match result {
+            Ok(hpke_config_ids) => {
+                aggregator.hpke_config_ids = ActiveValue::Set(Some(hpke_config_ids.clone().into()));
+                aggregator.hpke_config_error = ActiveValue::Set(None);
+            }
+            Err(error) => {
+                aggregator.hpke_config_error = ActiveValue::Set(Some(error.to_string()));
+            }
+        }
+        aggregator.hpke_configs_checked_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
+        aggregator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use janus_messages::{HpkeConfigId, HpkePublicKey};
+
+    fn hpke_config(kem_id: HpkeKemId, kdf_id: HpkeKdfId, aead_id: HpkeAeadId) -> HpkeConfig {
+        HpkeConfig::new(
+            HpkeConfigId::from(1),
+            kem_id,
+            kdf_id,
+            aead_id,
+            HpkePublicKey::from(vec![0; 32]),
+        )
+    }
+
+    #[test]
+    fn supported_algorithms() {
+        assert!(is_supported(&hpke_config(
+            HpkeKemId::X25519HkdfSha256,
+            HpkeKdfId::HkdfSha256,
+            HpkeAeadId::Aes128Gcm
+        )));
+        assert!(is_supported(&hpke_config(
+            HpkeKemId::P256HkdfSha256,
+            HpkeKdfId::HkdfSha512,
+            HpkeAeadId::ChaCha20Poly1305
+        )));
+        assert!(!is_supported(&hpke_config(
+            HpkeKemId::X448HkdfSha512,
+            HpkeKdfId::HkdfSha256,
+            HpkeAeadId::Aes128Gcm
+        )));
+        assert!(!is_supported(&hpke_config(
+            HpkeKemId::X25519HkdfSha256,
+            HpkeKdfId::Other(0xffff),
+            HpkeAeadId::Aes128Gcm
+        )));
+        assert!(!is_supported(&hpke_config(
+            HpkeKemId::X25519HkdfSha256,
+            HpkeKdfId::HkdfSha256,
+            HpkeAeadId::Other(0xffff)
+        )));
+    }
+}
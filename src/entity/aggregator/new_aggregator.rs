@@ -1,20 +1,17 @@
-use super::ActiveModel;
+use super::{fetch_hpke_config_ids, tls, ActiveModel, Visibility};
 use crate::clients::HttpClient;
 use crate::{
     clients::{AggregatorClient, ClientError},
-    entity::{url::Url, Account, Aggregator},
+    entity::{
+        url::{validate_public_url, Url},
+        Account, Aggregator,
+    },
     handler::Error,
 };
 use axum::http::StatusCode;
 use sea_orm::IntoActiveModel;
 use serde::{Deserialize, Serialize};
-use std::{
-    net::{IpAddr, Ipv4Addr, Ipv6Addr},
-    str::FromStr,
-};
 use time::OffsetDateTime;
-use tokio::net::lookup_host;
-use url::Host;
 use uuid::Uuid;
 use validator::{Validate, ValidationError, ValidationErrors};
 
@@ -24,99 +21,20 @@ pub struct NewAggregator {
     pub name: Option<String>,
     #[cfg_attr(
         not(feature = "integration-testing"),
-        validate(custom(function = "https"))
+        validate(custom(function = "crate::entity::url::https"))
     )]
     #[validate(length(max = 2048))]
     pub api_url: Option<String>,
     #[validate(length(max = 4096))]
     pub bearer_token: Option<String>,
     pub is_first_party: Option<bool>,
-}
-
-#[cfg_attr(feature = "integration-testing", allow(dead_code))]
-fn https(url: &str) -> Result<(), ValidationError> {
-    let url = url::Url::from_str(url).map_err(|_| ValidationError::new("https-url"))?;
-    if url.scheme() != "https" {
-        return Err(ValidationError::new("https-url"));
-    }
-    Ok(())
-}
-
-async fn validate_aggregator_url(url: &url::Url) -> Result<(), Error> {
-    fn ssrf_error(code: &'static str) -> Error {
-        let mut ve = ValidationErrors::new();
-        ve.add("api_url", ValidationError::new(code));
-        ve.into()
-    }
-
-    let host = url.host_str().ok_or_else(|| ssrf_error("invalid-url"))?;
-    let port = url.port().unwrap_or(443);
-
-    // For IP-literal hosts, check directly without DNS.
-    match url.host() {
-        Some(Host::Ipv4(ip)) => {
-            if is_private_ipv4(ip) {
-                return Err(ssrf_error("private-address"));
-            }
-            return Ok(());
-        }
-        Some(Host::Ipv6(ip)) => {
-            if is_private_ipv6(ip) {
-                return Err(ssrf_error("private-address"));
-            }
-            return Ok(());
-        }
-        _ => {}
-    }
-
-    // Reject "localhost" before resolving.
-    if host.eq_ignore_ascii_case("localhost") {
-        return Err(ssrf_error("private-address"));
-    }
-
-    // Resolve the hostname and check all resulting addresses.
-    let addrs = lookup_host(format!("{host}:{port}"))
-        .await
-        .map_err(|_| ssrf_error("dns-resolution-failed"))?;
-
-    for addr in addrs {
-        if is_private_ip(addr.ip()) {
-            return Err(ssrf_error("private-address"));
-        }
-    }
-
-    Ok(())
-}
-
-fn is_private_ip(ip: IpAddr) -> bool {
-    match ip {
-        IpAddr::V4(ip) => is_private_ipv4(ip),
-        IpAddr::V6(ip) => is_private_ipv6(ip),
-    }
-}
-
-fn is_private_ipv4(ip: Ipv4Addr) -> bool {
-    ip.is_loopback()         // 127.0.0.0/8
-        || ip.is_private()   // 10/8, 172.16/12, 192.168/16
-        || ip.is_link_local()   // 169.254.0.0/16 (includes cloud metadata)
-        || ip.is_broadcast()    // 255.255.255.255
-        || ip.is_unspecified()  // 0.0.0.0
-        || ip.is_documentation() // 192.0.2.0/24, 198.51.100.0/24, 203.0.113.0/24
-        || ip.octets()[0] == 100 && (ip.octets()[1] & 0xC0) == 64 // 100.64.0.0/10 (RFC 6598)
-}
-
-fn is_private_ipv6(ip: Ipv6Addr) -> bool {
-    ip.is_loopback()         // ::1
-        || ip.is_unspecified() // ::
-        || {
-            if let Some(ipv4) = ip.to_ipv4_mapped() {
-                is_private_ipv4(ipv4)
-            } else {
-                // Unique local (fc00::/7) and link-local (fe80::/10)
-                let segments = ip.segments();
-                (segments[0] & 0xfe00) == 0xfc00 || (segments[0] & 0xffc0) == 0xfe80
-            }
-        }
+    #[validate(length(max = 65536))]
+    pub client_certificate: Option<String>,
+    #[validate(length(max = 65536))]
+    pub client_key: Option<String>,
+    #[validate(length(max = 65536), custom(function = "tls::ca_certificate"))]
+    pub ca_certificate: Option<String>,
+    pub visibility: Option<Visibility>,
 }
 
 impl NewAggregator {
@@ -128,15 +46,27 @@ impl NewAggregator {
         ssrf_validation_enabled: bool,
     ) -> Result<ActiveModel, Error> {
         self.validate()?;
+        let client_identity = tls::client_identity(
+            self.client_certificate.as_deref(),
+            self.client_key.as_deref(),
+        )?;
 
         let api_url: Url = self.api_url.as_ref().unwrap().parse()?;
 
         if ssrf_validation_enabled {
-            validate_aggregator_url(&api_url).await?;
+            validate_public_url(&api_url, "api_url").await?;
         }
 
-        let aggregator_config = AggregatorClient::get_config(
+        let id = Uuid::new_v4();
+        let client = tls::http_client(
             client,
+            id,
+            client_identity.as_deref().map(str::as_bytes),
+            self.ca_certificate.as_deref(),
+        )?;
+
+        let aggregator_config = AggregatorClient::get_config(
+            client.clone(),
             api_url.clone().into(),
             self.bearer_token.as_ref().unwrap(),
         )
@@ -153,6 +83,12 @@ impl NewAggregator {
                 ve.into()
             }
 
+            ClientError::NonPublicAddress(_) => {
+                let mut ve = ValidationErrors::new();
+                ve.add("api_url", ValidationError::new("private-address"));
+                ve.into()
+            }
+
             ClientError::Http(_) => {
                 let mut ve = ValidationErrors::new();
                 ve.add("api_url", ValidationError::new("http-error"));
@@ -168,6 +104,15 @@ impl NewAggregator {
             other => Error::from(other),
         })?;
 
+        // a misconfigured dap url would otherwise only be noticed once report uploads fail
+        let hpke_config_ids = fetch_hpke_config_ids(&client, &aggregator_config.dap_url)
+            .await
+            .map_err(|error| {
+                let mut ve = ValidationErrors::new();
+                ve.add("dap_url", error.validation_error());
+                Error::from(ve)
+            })?;
+
         // unwrap safety: the below unwraps will never panic, because
         // the above call to `NewAggregator::validate` will
         // early-return if any of the required `Option`s is `None`.
@@ -186,13 +131,19 @@ impl NewAggregator {
             self.bearer_token.as_deref().unwrap_or_default().as_bytes(),
         )?;
 
+        let encrypted_client_identity = client_identity
+            .map(|client_identity| {
+                crypter.encrypt(api_url.as_ref().as_bytes(), client_identity.as_bytes())
+            })
+            .transpose()?;
+
         Ok(Aggregator {
             role: aggregator_config.role,
             name: self.name.unwrap(),
             api_url,
             dap_url: aggregator_config.dap_url.into(),
             encrypted_bearer_token,
-            id: Uuid::new_v4(),
+            id,
             account_id: account.map(|account| account.id),
             created_at: OffsetDateTime::now_utc(),
             updated_at: OffsetDateTime::now_utc(),
@@ -202,83 +153,19 @@ impl NewAggregator {
             vdafs: aggregator_config.vdafs.into(),
             protocol: aggregator_config.protocol,
             features: aggregator_config.features.into(),
+            encrypted_secondary_bearer_token: None,
+            encrypted_client_identity,
+            ca_certificate: self.ca_certificate,
+            hpke_config_ids: Some(hpke_config_ids.into()),
+            hpke_config_error: None,
+            hpke_configs_checked_at: Some(OffsetDateTime::now_utc()),
+            sunset_at: None,
+            visibility: if account.is_some() {
+                Visibility::Public
+            } else {
+                self.visibility.unwrap_or_default()
+            },
         }
         .into_active_model())
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn private_ipv4_addresses() {
-        assert!(is_private_ipv4(Ipv4Addr::new(127, 0, 0, 1))); // loopback
-        assert!(is_private_ipv4(Ipv4Addr::new(10, 0, 0, 1))); // RFC 1918
-        assert!(is_private_ipv4(Ipv4Addr::new(172, 16, 0, 1))); // RFC 1918
-        assert!(is_private_ipv4(Ipv4Addr::new(192, 168, 1, 1))); // RFC 1918
-        assert!(is_private_ipv4(Ipv4Addr::new(169, 254, 169, 254))); // link-local / metadata
-        assert!(is_private_ipv4(Ipv4Addr::new(0, 0, 0, 0))); // unspecified
-        assert!(is_private_ipv4(Ipv4Addr::new(100, 64, 0, 1))); // RFC 6598
-        assert!(is_private_ipv4(Ipv4Addr::new(100, 127, 255, 255))); // RFC 6598 upper
-        assert!(!is_private_ipv4(Ipv4Addr::new(8, 8, 8, 8))); // public
-        assert!(!is_private_ipv4(Ipv4Addr::new(100, 128, 0, 1))); // just outside RFC 6598
-    }
-
-    #[test]
-    fn private_ipv6_addresses() {
-        assert!(is_private_ipv6(Ipv6Addr::LOCALHOST)); // ::1
-        assert!(is_private_ipv6(Ipv6Addr::UNSPECIFIED)); // ::
-        assert!(is_private_ipv6("fc00::1".parse().unwrap())); // unique local
-        assert!(is_private_ipv6("fe80::1".parse().unwrap())); // link-local
-        assert!(is_private_ipv6("::ffff:127.0.0.1".parse().unwrap())); // IPv4-mapped loopback
-        assert!(is_private_ipv6("::ffff:169.254.169.254".parse().unwrap())); // IPv4-mapped metadata
-        assert!(!is_private_ipv6("2001:4860:4860::8888".parse().unwrap())); // public
-    }
-
-    #[test]
-    fn private_ip_dispatch() {
-        assert!(is_private_ip(IpAddr::V4(Ipv4Addr::LOCALHOST)));
-        assert!(is_private_ip(IpAddr::V6(Ipv6Addr::LOCALHOST)));
-        assert!(!is_private_ip(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8))));
-    }
-
-    fn url(s: &str) -> url::Url {
-        url::Url::parse(s).unwrap()
-    }
-
-    #[tokio::test]
-    async fn validate_rejects_private_ip_literals() {
-        assert!(
-            validate_aggregator_url(&url("https://169.254.169.254/latest/"))
-                .await
-                .is_err()
-        );
-        assert!(validate_aggregator_url(&url("https://127.0.0.1/"))
-            .await
-            .is_err());
-        assert!(validate_aggregator_url(&url("https://10.0.0.1/"))
-            .await
-            .is_err());
-        assert!(validate_aggregator_url(&url("https://[::1]/"))
-            .await
-            .is_err());
-    }
-
-    #[tokio::test]
-    async fn validate_rejects_localhost_hostname() {
-        assert!(validate_aggregator_url(&url("https://localhost/"))
-            .await
-            .is_err());
-        assert!(validate_aggregator_url(&url("https://LOCALHOST/"))
-            .await
-            .is_err());
-    }
-
-    #[tokio::test]
-    async fn validate_accepts_public_ip_literal() {
-        assert!(validate_aggregator_url(&url("https://8.8.8.8/"))
-            .await
-            .is_ok());
-    }
-}
@@ -112,6 +112,10 @@ impl QueryTypeNameSet {
         self.0.intersection(&other.0).cloned().collect()
     }
 
+    pub fn difference(&self, other: &QueryTypeNameSet) -> QueryTypeNameSet {
+        self.0.difference(&other.0).cloned().collect()
+    }
+
     pub fn contains(&self, name: &QueryTypeName) -> bool {
         self.0.contains(name)
     }
@@ -0,0 +1,30 @@
+use crate::{entity::Aggregator, Crypter, Error};
+use sea_orm::{ActiveValue, IntoActiveModel};
+use serde::Deserialize;
+use time::OffsetDateTime;
+use validator::Validate;
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct StageBearerToken {
+    #[validate(required, length(min = 1, max = 4096))]
+    pub bearer_token: Option<String>,
+}
+
+impl StageBearerToken {
+    pub fn build(
+        self,
+        aggregator: Aggregator,
+        crypter: &Crypter,
+    ) -> Result<super::ActiveModel, Error> {
+        self.validate()?;
+        let encrypted_secondary_bearer_token = crypter.encrypt(
+            aggregator.api_url.as_ref().as_bytes(),
+            self.bearer_token.as_deref().unwrap_or_default().as_bytes(),
+        )?;
+        let mut aggregator = aggregator.into_active_model();
+        aggregator.encrypted_secondary_bearer_token =
+            ActiveValue::Set(Some(encrypted_secondary_bearer_token));
+        aggregator.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        Ok(aggregator)
+    }
+}
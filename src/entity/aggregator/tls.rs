@@ -0,0 +1,62 @@
+use crate::{clients::HttpClient, Error};
+use uuid::Uuid;
+use validator::{ValidationError, ValidationErrors};
+
+/// Combines a PEM client certificate chain and private key into the client identity stored
+/// (encrypted) on an aggregator. The certificate and key must be provided together.
+pub(super) fn client_identity(
+    client_certificate: Option<&str>,
+    client_key: Option<&str>,
+) -> Result<Option<String>, ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+    let client_identity = match (client_certificate, client_key) {
+        (Some(client_certificate), Some(client_key)) => {
+            let client_identity = format!("{client_certificate}\n{client_key}");
+            if reqwest::Identity::from_pem(client_identity.as_bytes()).is_err() {
+                errors.add("client_certificate", ValidationError::new("invalid-pem"));
+            }
+            Some(client_identity)
+        }
+        (Some(_), None) => {
+            errors.add("client_key", ValidationError::new("required"));
+            None
+        }
+        (None, Some(_)) => {
+            errors.add("client_certificate", ValidationError::new("required"));
+            None
+        }
+        (None, None) => None,
+    };
+
+    if errors.is_empty() {
+        Ok(client_identity)
+    } else {
+        Err(errors)
+    }
+}
+
+pub(super) fn ca_certificate(ca_certificate: &str) -> Result<(), ValidationError> {
+    match reqwest::Certificate::from_pem_bundle(ca_certificate.as_bytes()) {
+        Ok(certificates) if !certificates.is_empty() => Ok(()),
+        _ => Err(ValidationError::new("invalid-pem")),
+    }
+}
+
+/// Applies an aggregator's TLS settings to the application's http client. Aggregators
+/// without TLS settings share the application's client.
+pub(super) fn http_client(
+    http_client: HttpClient,
+    aggregator_id: Uuid,
+    client_identity: Option<&[u8]>,
+    ca_certificate: Option<&str>,
+) -> Result<HttpClient, Error> {
+    if client_identity.is_none() && ca_certificate.is_none() {
+        return Ok(http_client);
+    }
+
+    Ok(http_client.with_tls(
+        aggregator_id,
+        client_identity,
+        ca_certificate.map(str::as_bytes),
+    )?)
+}
@@ -1,10 +1,11 @@
+use super::{tls, Visibility};
 use crate::clients::HttpClient;
 use crate::{
     clients::{AggregatorClient, ClientError},
+    deserialize_some,
     entity::Aggregator,
     Crypter, Error,
 };
-use axum::http::StatusCode;
 use sea_orm::{ActiveValue, IntoActiveModel};
 use serde::Deserialize;
 use time::OffsetDateTime;
@@ -16,6 +17,17 @@ pub struct UpdateAggregator {
     pub name: Option<String>,
     #[validate(length(max = 4096))]
     pub bearer_token: Option<String>,
+    // for the tls settings, an explicit null removes the current value
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[validate(length(max = 65536))]
+    pub client_certificate: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[validate(length(max = 65536))]
+    pub client_key: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    #[validate(length(max = 65536), custom(function = "tls::ca_certificate"))]
+    pub ca_certificate: Option<Option<String>>,
+    pub visibility: Option<Visibility>,
 }
 
 impl UpdateAggregator {
@@ -26,43 +38,117 @@ impl UpdateAggregator {
         crypter: &Crypter,
     ) -> Result<super::ActiveModel, Error> {
         self.validate()?;
-        let api_url = aggregator.api_url.clone().into();
+        if self.visibility.is_some() && aggregator.account_id.is_some() {
+            let mut errors = ValidationErrors::new();
+            errors.add("visibility", ValidationError::new("not-shared"));
+            return Err(errors.into());
+        }
+
+        let client_identity = match (&self.client_certificate, &self.client_key) {
+            (None, None) => None,
+            (Some(None), Some(None)) => Some(None),
+            (Some(None), None) | (Some(Some(_)), Some(None)) => {
+                let mut errors = ValidationErrors::new();
+                errors.add("client_key", ValidationError::new("required"));
+                return Err(errors.into());
+            }
+            (None, Some(None)) | (Some(None), Some(Some(_))) => {
+                let mut errors = ValidationErrors::new();
+                errors.add("client_certificate", ValidationError::new("required"));
+                return Err(errors.into());
+            }
+            (client_certificate, client_key) => Some(tls::client_identity(
+                client_certificate.as_ref().and_then(Option::as_deref),
+                client_key.as_ref().and_then(Option::as_deref),
+            )?),
+        };
+
+        // apply any new tls settings before contacting the aggregator, so that they are
+        // verified along with the rest of the update
+        let mut aggregator = aggregator;
+        let tls_updated = client_identity.is_some() || self.ca_certificate.is_some();
+        if let Some(client_identity) = client_identity {
+            aggregator.encrypted_client_identity = client_identity
+                .map(|client_identity| {
+                    crypter.encrypt(
+                        aggregator.api_url.as_ref().as_bytes(),
+                        client_identity.as_bytes(),
+                    )
+                })
+                .transpose()?;
+        }
+        if let Some(ca_certificate) = self.ca_certificate {
+            aggregator.ca_certificate = ca_certificate;
+        }
+        let encrypted_client_identity = aggregator.encrypted_client_identity.clone();
+        let ca_certificate = aggregator.ca_certificate.clone();
+
+        let (aggregator_config, mut aggregator) = match self.bearer_token {
+            Some(bearer_token) => {
+                let config = AggregatorClient::get_config(
+                    aggregator.http_client(client, crypter)?,
+                    aggregator.api_url.clone().into(),
+                    &bearer_token,
+                )
+                .await
+                .map_err(token_not_recognized)?;
+                let mut aggregator = aggregator.into_active_model();
+                aggregator.encrypted_bearer_token = ActiveValue::Set(crypter.encrypt(
+                    aggregator.api_url.as_ref().as_ref().as_bytes(),
+                    bearer_token.as_bytes(),
+                )?);
+                // a staged token was meant to replace the old primary, so promoting it later
+                // would undo this update
+                aggregator.encrypted_secondary_bearer_token = ActiveValue::Set(None);
+                (config, aggregator)
+            }
 
-        let bearer_token = match self.bearer_token {
-            Some(bearer_token) => bearer_token,
-            None => aggregator.bearer_token(crypter)?,
+            None => {
+                let aggregator_client = aggregator.client(client, crypter)?;
+                let config = aggregator_client
+                    .get_aggregator_config()
+                    .await
+                    .map_err(token_not_recognized)?;
+                let aggregator = if aggregator_client.secondary_bearer_token_confirmed() {
+                    aggregator.promote_secondary_bearer_token()
+                } else {
+                    aggregator.into_active_model()
+                };
+                (config, aggregator)
+            }
         };
 
-        let mut aggregator = aggregator.into_active_model();
+        if tls_updated {
+            aggregator.encrypted_client_identity = ActiveValue::Set(encrypted_client_identity);
+            aggregator.ca_certificate = ActiveValue::Set(ca_certificate);
+        }
+
         if let Some(name) = self.name {
             aggregator.name = ActiveValue::Set(name);
         }
-        let aggregator_config = AggregatorClient::get_config(client, api_url, &bearer_token)
-            .await
-            .map_err(|e| match e {
-                ClientError::HttpStatusNotSuccess(ref e)
-                    if matches!(
-                        e.status,
-                        Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
-                    ) =>
-                {
-                    let mut validation_errors = ValidationErrors::new();
-                    validation_errors
-                        .add("bearer_token", ValidationError::new("token-not-recognized"));
-                    validation_errors.into()
-                }
 
-                other => Error::from(other),
-            })?;
+        if let Some(visibility) = self.visibility {
+            aggregator.visibility = ActiveValue::Set(visibility);
+        }
 
         aggregator.query_types = ActiveValue::Set(aggregator_config.query_types.into());
         aggregator.vdafs = ActiveValue::Set(aggregator_config.vdafs.into());
         aggregator.features = ActiveValue::Set(aggregator_config.features.into());
-        aggregator.encrypted_bearer_token = ActiveValue::Set(crypter.encrypt(
-            aggregator.api_url.as_ref().as_ref().as_bytes(),
-            bearer_token.as_bytes(),
-        )?);
         aggregator.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
         Ok(aggregator)
     }
 }
+
+pub(super) fn token_not_recognized(error: ClientError) -> Error {
+    if error.is_unauthorized() {
+        let mut validation_errors = ValidationErrors::new();
+        validation_errors.add("bearer_token", ValidationError::new("token-not-recognized"));
+        validation_errors.into()
+    } else if matches!(error, ClientError::NonPublicAddress(_)) {
+        let mut validation_errors = ValidationErrors::new();
+        validation_errors.add("api_url", ValidationError::new("private-address"));
+        validation_errors.into()
+    } else {
+        Error::from(error)
+    }
+}
@@ -123,6 +123,10 @@ impl VdafNameSet {
         self.0.intersection(&other.0).cloned().collect()
     }
 
+    pub fn difference(&self, other: &VdafNameSet) -> VdafNameSet {
+        self.0.difference(&other.0).cloned().collect()
+    }
+
     pub fn contains(&self, name: &VdafName) -> bool {
         self.0.contains(name)
     }
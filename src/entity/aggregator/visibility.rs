@@ -0,0 +1,23 @@
+use sea_orm::{prelude::StringLen, DeriveActiveEnum, EnumIter};
+use serde::{Deserialize, Serialize};
+
+/// Which accounts can see and use a shared aggregator. Aggregators that belong to an account are
+/// always public.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, DeriveActiveEnum, Serialize, Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Listed for, and usable by, every account
+    #[default]
+    #[sea_orm(string_value = "public")]
+    Public,
+    /// Only listed for, and usable by, accounts that have been granted access
+    #[sea_orm(string_value = "allowlist")]
+    Allowlist,
+    /// Listed for every account, but only usable by accounts that have been granted access,
+    /// which any account can request
+    #[sea_orm(string_value = "request_access")]
+    RequestAccess,
+}
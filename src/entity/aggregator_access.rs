@@ -0,0 +1,210 @@
+use crate::{
+    entity::{
+        aggregator::Visibility, Account, AccountColumn, Accounts, Aggregator, AggregatorColumn,
+        Aggregators,
+    },
+    Error,
+};
+use sea_orm::{
+    sea_query::{all, OnConflict, SelectStatement},
+    ActiveModelBehavior, ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr,
+    DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel,
+    PrimaryKeyTrait, QueryFilter, QuerySelect, QueryTrait, Related, RelationDef, RelationTrait,
+    Select,
+};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// An account's access to a shared aggregator that is not public. A granted_at of None indicates
+/// a request for access that has not been granted yet.
+#[derive(Clone, Copy, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "aggregator_access")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique_key = "aggregator_account")]
+    pub aggregator_id: Uuid,
+    #[sea_orm(unique_key = "aggregator_account")]
+    pub account_id: Uuid,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub granted_at: Option<OffsetDateTime>,
+}
+
+impl Entity {
+    pub fn for_aggregator_and_account(aggregator_id: Uuid, account_id: Uuid) -> Select<Self> {
+        Self::find().filter(all![
+            Column::AggregatorId.eq(aggregator_id),
+            Column::AccountId.eq(account_id)
+        ])
+    }
+
+    /// The ids of aggregators that any of these accounts have been granted access to.
+    pub fn granted_aggregator_ids(account_ids: Vec<Uuid>) -> SelectStatement {
+        Self::find()
+            .select_only()
+            .column(Column::AggregatorId)
+            .filter(all![
+                Column::AccountId.is_in(account_ids),
+                Column::GrantedAt.is_not_null()
+            ])
+            .into_query()
+    }
+
+    /// Grants an account access to a shared aggregator, whether or not it had requested it.
+    pub async fn grant(
+        aggregator: &Aggregator,
+        account: &Account,
+        db: &impl ConnectionTrait,
+    ) -> Result<Model, Error> {
+        if aggregator.account_id.is_some() {
+            let mut errors = ValidationErrors::new();
+            errors.add("aggregator_id", ValidationError::new("not-shared"));
+            return Err(errors.into());
+        }
+
+        let now = OffsetDateTime::now_utc();
+        let access = match Self::insert_if_absent(aggregator.id, account.id, Some(now), db).await? {
+            (access, true) => return Ok(access),
+            (access, false) => access,
+        };
+        if access.granted_at.is_some() {
+            return Ok(access);
+        }
+
+        let mut access = access.into_active_model();
+        access.granted_at = ActiveValue::Set(Some(now));
+        access.updated_at = ActiveValue::Set(now);
+        Ok(access.update(db).await?)
+    }
+
+    /// Requests access to a shared aggregator for an account. Returns the account's existing
+    /// request or grant if it has one, and whether the request is new.
+    pub async fn request(
+        aggregator: &Aggregator,
+        account: &Account,
+        db: &impl ConnectionTrait,
+    ) -> Result<(Model, bool), DbErr> {
+        Self::insert_if_absent(aggregator.id, account.id, None, db).await
+    }
+
+    /// Inserts an access unless the account already has one for the aggregator, in which case
+    /// that one is locked until the transaction ends. Returns the account's access, and whether
+    /// it was inserted.
+    async fn insert_if_absent(
+        aggregator_id: Uuid,
+        account_id: Uuid,
+        granted_at: Option<OffsetDateTime>,
+        db: &impl ConnectionTrait,
+    ) -> Result<(Model, bool), DbErr> {
+        let inserted = Self::insert(Model::build(aggregator_id, account_id, granted_at))
+            .on_conflict(
+                OnConflict::columns([Column::AggregatorId, Column::AccountId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?
+            > 0;
+
+        let access = Self::for_aggregator_and_account(aggregator_id, account_id)
+            .lock_exclusive()
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("aggregator access {aggregator_id}")))?;
+        Ok((access, inserted))
+    }
+}
+
+impl Model {
+    pub fn build(
+        aggregator_id: Uuid,
+        account_id: Uuid,
+        granted_at: Option<OffsetDateTime>,
+    ) -> ActiveModel {
+        let now = OffsetDateTime::now_utc();
+        Self {
+            id: Uuid::new_v4(),
+            aggregator_id,
+            account_id,
+            created_at: now,
+            updated_at: now,
+            granted_at,
+        }
+        .into_active_model()
+    }
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, Copy)]
+pub struct RequestAggregatorAccess {
+    #[validate(required)]
+    pub aggregator_id: Option<Uuid>,
+}
+
+impl RequestAggregatorAccess {
+    /// Loads the aggregator that access is being requested to. Only shared aggregators with
+    /// [`Visibility::RequestAccess`] accept requests, and allowlisted aggregators are reported as
+    /// missing so that requests do not reveal them.
+    pub async fn aggregator(self, db: &impl ConnectionTrait) -> Result<Aggregator, Error> {
+        self.validate()?;
+        let aggregator = Aggregators::find()
+            .filter(all![
+                AggregatorColumn::Id.eq(self.aggregator_id),
+                AggregatorColumn::AccountId.is_null(),
+                AggregatorColumn::DeletedAt.is_null()
+            ])
+            .one(db)
+            .await?;
+
+        let mut errors = ValidationErrors::new();
+        match aggregator {
+            Some(aggregator) if aggregator.visibility == Visibility::RequestAccess => {
+                return Ok(aggregator)
+            }
+            Some(aggregator) if aggregator.visibility == Visibility::Public => {
+                errors.add("aggregator_id", ValidationError::new("public"));
+            }
+            _ => errors.add("aggregator_id", ValidationError::new("required")),
+        }
+        Err(errors.into())
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Aggregators",
+        from = "Column::AggregatorId",
+        to = "AggregatorColumn::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Aggregator,
+    #[sea_orm(
+        belongs_to = "Accounts",
+        from = "Column::AccountId",
+        to = "AccountColumn::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Account,
+}
+
+impl Related<Aggregators> for Entity {
+    fn to() -> RelationDef {
+        Relation::Aggregator.def()
+    }
+}
+
+impl Related<Accounts> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
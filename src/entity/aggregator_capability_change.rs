@@ -0,0 +1,65 @@
+use crate::entity::{
+    aggregator::{Capabilities, RemovedCapabilities},
+    json::Json,
+    AggregatorColumn, Aggregators,
+};
+use sea_orm::{
+    ActiveModelBehavior, DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait,
+    EnumIter, IntoActiveModel, PrimaryKeyTrait, Related, RelationDef, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "aggregator_capability_change")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub aggregator_id: Uuid,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    pub previous: Json<Capabilities>,
+    pub current: Json<Capabilities>,
+}
+
+impl Model {
+    pub fn build(
+        aggregator_id: Uuid,
+        previous: Capabilities,
+        current: Capabilities,
+    ) -> ActiveModel {
+        Self {
+            id: Uuid::new_v4(),
+            aggregator_id,
+            created_at: OffsetDateTime::now_utc(),
+            previous: previous.into(),
+            current: current.into(),
+        }
+        .into_active_model()
+    }
+
+    pub fn removed(&self) -> RemovedCapabilities {
+        self.previous.removed_in(&self.current)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Aggregators",
+        from = "Column::AggregatorId",
+        to = "AggregatorColumn::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Aggregator,
+}
+
+impl Related<Aggregators> for Entity {
+    fn to() -> RelationDef {
+        Relation::Aggregator.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
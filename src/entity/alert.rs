@@ -0,0 +1,190 @@
+use super::{
+    AccountColumn, Accounts, AlertRule, AlertRuleColumn, AlertRules, Task, TaskColumn, Tasks,
+};
+use sea_orm::{
+    sea_query::{all, Expr, IntoCondition, OnConflict},
+    ActiveModelBehavior, ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr,
+    DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel,
+    PrimaryKeyTrait, QueryFilter, QueryOrder, Related, RelationDef, RelationTrait, Select,
+};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A period during which an alert rule's threshold was exceeded for a task. Alerts without a
+/// `resolved_at` are still firing.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "alert")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub alert_rule_id: Uuid,
+    pub account_id: Uuid,
+    pub task_id: String,
+    /// the most recently measured value, as described by [`AlertRule::measure`]
+    pub value: f64,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub fired_at: OffsetDateTime,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub resolved_at: Option<OffsetDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "AlertRules",
+        from = "Column::AlertRuleId",
+        to = "AlertRuleColumn::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    AlertRule,
+
+    #[sea_orm(
+        belongs_to = "Accounts",
+        from = "Column::AccountId",
+        to = "AccountColumn::Id"
+    )]
+    Account,
+
+    #[sea_orm(belongs_to = "Tasks", from = "Column::TaskId", to = "TaskColumn::Id")]
+    Task,
+}
+
+impl Related<AlertRules> for Entity {
+    fn to() -> RelationDef {
+        Relation::AlertRule.def()
+    }
+}
+
+impl Related<Accounts> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl Related<Tasks> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn is_firing(&self) -> bool {
+        self.resolved_at.is_none()
+    }
+
+    pub async fn update_value(self, value: f64, db: &impl ConnectionTrait) -> Result<Self, DbErr> {
+        let mut alert = self.into_active_model();
+        alert.value = ActiveValue::Set(value);
+        alert.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        alert.update(db).await
+    }
+
+    pub async fn resolve(self, value: f64, db: &impl ConnectionTrait) -> Result<Self, DbErr> {
+        let mut alert = self.into_active_model();
+        alert.value = ActiveValue::Set(value);
+        alert.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        alert.resolved_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
+        alert.update(db).await
+    }
+}
+
+impl Entity {
+    /// Fires an alert for a rule and task, unless one is already firing, in which case `None` is
+    /// returned. At most one alert can be firing for each rule and task, which a unique partial
+    /// index enforces for evaluations that run concurrently.
+    pub async fn fire(
+        alert_rule: &AlertRule,
+        task: &Task,
+        value: f64,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<Model>, DbErr> {
+        let now = OffsetDateTime::now_utc();
+        let alert = Model {
+            id: Uuid::new_v4(),
+            alert_rule_id: alert_rule.id,
+            account_id: task.account_id,
+            task_id: task.id.clone(),
+            value,
+            fired_at: now,
+            updated_at: now,
+            resolved_at: None,
+        };
+        let inserted = Self::insert(alert.clone().into_active_model())
+            .on_conflict(
+                OnConflict::columns([Column::AlertRuleId, Column::TaskId])
+                    .target_and_where(Column::ResolvedAt.is_null())
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?
+            > 0;
+        Ok(inserted.then_some(alert))
+    }
+
+    /// The alert that a rule currently has firing for a task, if any.
+    pub async fn firing(
+        alert_rule: &AlertRule,
+        task: &Task,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<Model>, DbErr> {
+        Self::find()
+            .filter(all![
+                Column::AlertRuleId.eq(alert_rule.id),
+                Column::TaskId.eq(&task.id),
+                Column::ResolvedAt.is_null(),
+            ])
+            .one(db)
+            .await
+    }
+
+    /// Alerts that are firing in an account, most recent first. Alerts for deleted rules or tasks
+    /// are excluded.
+    pub fn firing_in_account(account_id: Uuid) -> Select<Self> {
+        Self::find()
+            .inner_join(AlertRules)
+            .inner_join(Tasks)
+            .filter(all![
+                Column::AccountId.eq(account_id),
+                Column::ResolvedAt.is_null(),
+                AlertRuleColumn::DeletedAt.is_null(),
+                TaskColumn::DeletedAt.is_null(),
+            ])
+            .order_by_desc(Column::FiredAt)
+    }
+
+    /// Resolves the alerts that a rule has firing, when the rule is deleted.
+    pub async fn resolve_for_alert_rule(
+        alert_rule_id: Uuid,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        Self::resolve_where(Column::AlertRuleId.eq(alert_rule_id), db).await
+    }
+
+    /// Resolves the alerts that are firing for a task, when the task is deleted. Deleted tasks are
+    /// no longer evaluated, so nothing else would resolve them.
+    pub async fn resolve_for_task(task_id: &str, db: &impl ConnectionTrait) -> Result<(), DbErr> {
+        Self::resolve_where(Column::TaskId.eq(task_id), db).await
+    }
+
+    async fn resolve_where(
+        condition: impl IntoCondition,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        let now = OffsetDateTime::now_utc();
+        Self::update_many()
+            .col_expr(Column::ResolvedAt, Expr::value(now))
+            .col_expr(Column::UpdatedAt, Expr::value(now))
+            .filter(condition)
+            .filter(Column::ResolvedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
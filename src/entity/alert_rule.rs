@@ -0,0 +1,248 @@
+use super::{
+    json::Json, task::TaskCounter, task_counter_snapshot::RETENTION, Account, AccountColumn,
+    Accounts, Task, TaskColumn, TaskCounterSnapshot, Tasks,
+};
+use crate::Error;
+use sea_orm::{
+    sea_query::any, ActiveModelBehavior, ActiveValue, ColumnTrait, ConnectionTrait, DbErr,
+    DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel,
+    PaginatorTrait, PrimaryKeyTrait, QueryFilter, Related, RelationDef, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+/// The window that rules are evaluated over unless they specify one.
+pub const DEFAULT_WINDOW_SECONDS: i64 = 60 * 60;
+
+/// Metrics are refreshed at most every five minutes, so shorter windows would never see a change.
+const MIN_WINDOW_SECONDS: i64 = 5 * 60;
+
+/// Older snapshots are cleaned up, so longer windows could not be measured.
+const MAX_WINDOW_SECONDS: i64 = RETENTION.whole_seconds();
+
+/// How an alert rule tells the account that it started or stopped firing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertChannel {
+    /// emails every member of the account
+    Email,
+    /// delivers `alert.fired` and `alert.resolved` events to the account's webhooks
+    Webhook,
+}
+
+/// Fires for a task when `counter` increased by more than `threshold` over the last
+/// `window_seconds`. Rules with `relative_to` instead compare the increase of `counter` to the
+/// increase of `relative_to`, so a threshold of 0.05 means "more than 5% of".
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "alert_rule")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    /// None applies the rule to every task in the account
+    pub task_id: Option<String>,
+    pub name: Option<String>,
+    pub counter: TaskCounter,
+    pub relative_to: Option<TaskCounter>,
+    pub threshold: f64,
+    pub window_seconds: i64,
+    pub channels: Json<Vec<AlertChannel>>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub deleted_at: Option<OffsetDateTime>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Accounts",
+        from = "Column::AccountId",
+        to = "AccountColumn::Id"
+    )]
+    Account,
+
+    #[sea_orm(belongs_to = "Tasks", from = "Column::TaskId", to = "TaskColumn::Id")]
+    Task,
+}
+
+impl Related<Accounts> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl Related<Tasks> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn window(&self) -> Duration {
+        Duration::seconds(self.window_seconds)
+    }
+
+    /// How far the rule's counter moved since the baseline snapshot, or how far it moved relative
+    /// to `relative_to`. A baseline of None counts from zero.
+    ///
+    /// When `relative_to` did not move at all the increase of `counter` is compared to one,
+    /// so that a task with only failures still fires.
+    pub fn measure(&self, task: &Task, baseline: Option<&TaskCounterSnapshot>) -> f64 {
+        let increase = |counter: TaskCounter| {
+            let before = baseline.map_or(0, |baseline| baseline.counter(counter));
+            counter.value(task).saturating_sub(before).max(0)
+        };
+        let increase_of_counter = increase(self.counter) as f64;
+        match self.relative_to {
+            Some(relative_to) => increase_of_counter / increase(relative_to).max(1) as f64,
+            None => increase_of_counter,
+        }
+    }
+
+    pub fn is_exceeded_by(&self, value: f64) -> bool {
+        value > self.threshold
+    }
+
+    pub fn notifies_by(&self, channel: AlertChannel) -> bool {
+        self.channels.contains(&channel)
+    }
+
+    pub fn tombstone(self) -> ActiveModel {
+        let mut alert_rule = self.into_active_model();
+        alert_rule.deleted_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
+        alert_rule.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        alert_rule
+    }
+
+    pub fn is_tombstoned(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+}
+
+fn channels(channels: Option<Vec<AlertChannel>>) -> Json<Vec<AlertChannel>> {
+    let mut channels = channels.unwrap_or_else(|| vec![AlertChannel::Email]);
+    channels.sort();
+    channels.dedup();
+    Json(channels)
+}
+
+#[derive(Deserialize, Serialize, Validate, Debug, Clone, Default)]
+pub struct NewAlertRule {
+    #[validate(length(max = 255))]
+    pub name: Option<String>,
+    pub task_id: Option<String>,
+    #[validate(required)]
+    pub counter: Option<TaskCounter>,
+    pub relative_to: Option<TaskCounter>,
+    #[validate(required, range(min = 0.0))]
+    pub threshold: Option<f64>,
+    #[validate(range(min = MIN_WINDOW_SECONDS, max = MAX_WINDOW_SECONDS))]
+    pub window_seconds: Option<i64>,
+    #[validate(length(min = 1))]
+    pub channels: Option<Vec<AlertChannel>>,
+}
+
+impl NewAlertRule {
+    pub async fn build(
+        self,
+        account: &Account,
+        db: &impl ConnectionTrait,
+    ) -> Result<ActiveModel, Error> {
+        let mut errors = self.validate().err().unwrap_or_default();
+        if let Some(task_id) = &self.task_id {
+            let found = Tasks::find_by_id(task_id)
+                .filter(TaskColumn::AccountId.eq(account.id))
+                .filter(TaskColumn::DeletedAt.is_null())
+                .count(db)
+                .await?;
+            if found == 0 {
+                errors.add("task_id", ValidationError::new("not-found"));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+
+        // unwrap safety: validate requires a counter and a threshold
+        Ok(Model {
+            id: Uuid::new_v4(),
+            account_id: account.id,
+            task_id: self.task_id,
+            name: self.name.filter(|name| !name.is_empty()),
+            counter: self.counter.unwrap(),
+            relative_to: self.relative_to,
+            threshold: self.threshold.unwrap(),
+            window_seconds: self.window_seconds.unwrap_or(DEFAULT_WINDOW_SECONDS),
+            channels: channels(self.channels),
+            created_at: OffsetDateTime::now_utc(),
+            updated_at: OffsetDateTime::now_utc(),
+            deleted_at: None,
+        }
+        .into_active_model())
+    }
+}
+
+#[derive(Deserialize, Serialize, Validate, Debug, Clone, Default)]
+pub struct UpdateAlertRule {
+    #[validate(length(max = 255))]
+    pub name: Option<String>,
+    pub counter: Option<TaskCounter>,
+    pub relative_to: Option<TaskCounter>,
+    /// compare absolute increases of `counter` instead of comparing to `relative_to`
+    #[serde(default)]
+    pub absolute: bool,
+    #[validate(range(min = 0.0))]
+    pub threshold: Option<f64>,
+    #[validate(range(min = MIN_WINDOW_SECONDS, max = MAX_WINDOW_SECONDS))]
+    pub window_seconds: Option<i64>,
+    #[validate(length(min = 1))]
+    pub channels: Option<Vec<AlertChannel>>,
+}
+
+impl UpdateAlertRule {
+    pub fn build(self, alert_rule: Model) -> Result<ActiveModel, Error> {
+        self.validate()?;
+        let mut alert_rule = alert_rule.into_active_model();
+        if let Some(name) = self.name {
+            alert_rule.name = ActiveValue::Set(Some(name).filter(|name| !name.is_empty()));
+        }
+        if let Some(counter) = self.counter {
+            alert_rule.counter = ActiveValue::Set(counter);
+        }
+        if self.absolute {
+            alert_rule.relative_to = ActiveValue::Set(None);
+        } else if self.relative_to.is_some() {
+            alert_rule.relative_to = ActiveValue::Set(self.relative_to);
+        }
+        if let Some(threshold) = self.threshold {
+            alert_rule.threshold = ActiveValue::Set(threshold);
+        }
+        if let Some(window_seconds) = self.window_seconds {
+            alert_rule.window_seconds = ActiveValue::Set(window_seconds);
+        }
+        if self.channels.is_some() {
+            alert_rule.channels = ActiveValue::Set(channels(self.channels));
+        }
+        alert_rule.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        Ok(alert_rule)
+    }
+}
+
+impl Entity {
+    /// The live rules that apply to a task.
+    pub async fn for_task(task: &Task, db: &impl ConnectionTrait) -> Result<Vec<Model>, DbErr> {
+        Self::find()
+            .filter(Column::AccountId.eq(task.account_id))
+            .filter(Column::DeletedAt.is_null())
+            .filter(any![Column::TaskId.is_null(), Column::TaskId.eq(&task.id)])
+            .all(db)
+            .await
+    }
+}
@@ -1,10 +1,13 @@
-use super::{Account, AccountColumn, AccountRelation, Accounts, Memberships};
+use super::{
+    json::Json, Account, AccountColumn, AccountRelation, Accounts, Memberships, TaskColumn, Tasks,
+};
+use crate::Error;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use rand::random;
 use sea_orm::{
-    ActiveModelBehavior, ActiveValue, ColumnTrait, ConnectionTrait, DeriveEntityModel,
-    DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel, PrimaryKeyTrait,
-    QueryFilter, Related, RelationDef, RelationTrait,
+    sea_query::any, ActiveModelBehavior, ActiveValue, ColumnTrait, ConnectionTrait,
+    DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel,
+    PaginatorTrait, PrimaryKeyTrait, QueryFilter, Related, RelationDef, RelationTrait,
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -12,6 +15,10 @@ use std::fmt::Debug;
 use subtle::ConstantTimeEq;
 use time::OffsetDateTime;
 use uuid::Uuid;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+mod scope;
+pub use scope::ApiTokenScope;
 
 const TOKEN_IDENTIFIER: &str = "DUAT";
 
@@ -32,6 +39,15 @@ pub struct Model {
     #[serde(with = "::time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
     pub name: Option<String>,
+    // None indicates a token with full access to its account
+    pub scopes: Option<Json<Vec<ApiTokenScope>>>,
+    // None indicates a token that is not limited to specific tasks
+    pub task_ids: Option<Json<Vec<String>>>,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+    // when account members were warned that this token will expire soon
+    #[serde(skip)]
+    pub expiration_notified_at: Option<OffsetDateTime>,
     #[sea_orm(ignore)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub token: Option<String>,
@@ -81,6 +97,9 @@ impl Debug for Model {
             .field("deleted_at", &self.deleted_at)
             .field("last_used_at", &self.last_used_at)
             .field("updated_at", &self.deleted_at)
+            .field("scopes", &self.scopes)
+            .field("task_ids", &self.task_ids)
+            .field("expires_at", &self.expires_at)
             .finish()
     }
 }
@@ -149,18 +168,16 @@ impl Model {
                 token: None,
                 last_used_at: None,
                 name: None,
+                scopes: None,
+                task_ids: None,
+                expires_at: None,
+                expiration_notified_at: None,
             }
             .into_active_model(),
             encode_token(id, &token),
         )
     }
 
-    pub fn mark_last_used(self) -> ActiveModel {
-        let mut api_token = self.into_active_model();
-        api_token.last_used_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
-        api_token
-    }
-
     pub fn tombstone(self) -> ActiveModel {
         let mut api_token = self.into_active_model();
         api_token.deleted_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
@@ -171,6 +188,106 @@ impl Model {
     pub fn is_tombstoned(&self) -> bool {
         self.deleted_at.is_some()
     }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc())
+    }
+
+    /// Whether this token is limited by scopes or to specific tasks, rather than having full
+    /// access to its account.
+    pub fn is_scoped(&self) -> bool {
+        self.scopes.is_some() || self.task_ids.is_some()
+    }
+
+    pub fn has_scope(&self, scope: ApiTokenScope) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.contains(&scope))
+    }
+
+    pub fn has_write_scope(&self) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.iter().any(ApiTokenScope::is_write))
+    }
+
+    pub fn allows_task(&self, task_id: &str) -> bool {
+        self.task_ids
+            .as_ref()
+            .is_none_or(|task_ids| task_ids.iter().any(|id| id == task_id))
+    }
+}
+
+#[derive(Deserialize, Validate, Debug, Clone, Default)]
+pub struct NewApiToken {
+    pub name: Option<String>,
+    #[validate(length(min = 1))]
+    pub scopes: Option<Vec<ApiTokenScope>>,
+    #[validate(length(min = 1))]
+    pub task_ids: Option<Vec<String>>,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl NewApiToken {
+    pub fn is_scoped(&self) -> bool {
+        self.scopes.is_some() || self.task_ids.is_some()
+    }
+
+    pub async fn build(
+        self,
+        account: &Account,
+        db: &impl ConnectionTrait,
+    ) -> Result<(ActiveModel, String), Error> {
+        self.validate()?;
+        let scopes = self.scopes.map(|mut scopes| {
+            scopes.sort();
+            scopes.dedup();
+            scopes
+        });
+        let task_ids = self.task_ids.map(|mut task_ids| {
+            task_ids.sort();
+            task_ids.dedup();
+            task_ids
+        });
+
+        let mut errors = ValidationErrors::new();
+        if self
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc())
+        {
+            errors.add("expires_at", ValidationError::new("past"));
+        }
+
+        if let Some(task_ids) = &task_ids {
+            // a token limited to specific tasks must also be limited in what it can do with them
+            if scopes.is_none() {
+                errors.add("scopes", ValidationError::new("required"));
+            }
+
+            let found = Tasks::find()
+                .filter(TaskColumn::Id.is_in(task_ids.iter().cloned()))
+                .filter(TaskColumn::AccountId.eq(account.id))
+                .filter(TaskColumn::DeletedAt.is_null())
+                .count(db)
+                .await?;
+            if usize::try_from(found).ok() != Some(task_ids.len()) {
+                errors.add("task_ids", ValidationError::new("not-found"));
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+
+        let (mut api_token, token) = Model::build(account);
+        api_token.scopes = ActiveValue::Set(scopes.map(Json));
+        api_token.task_ids = ActiveValue::Set(task_ids.map(Json));
+        api_token.expires_at = ActiveValue::Set(self.expires_at);
+        api_token.name = ActiveValue::Set(self.name.filter(|name| !name.is_empty()));
+        Ok((api_token, token))
+    }
 }
 
 impl Entity {
@@ -182,6 +299,10 @@ impl Entity {
         let sha = Sha256::digest(token);
         let (api_token, account) = Self::find_by_id(id)
             .filter(Column::DeletedAt.is_null())
+            .filter(any![
+                Column::ExpiresAt.is_null(),
+                Column::ExpiresAt.gt(OffsetDateTime::now_utc())
+            ])
             .find_also_related(Accounts)
             .one(db)
             .await
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Something a scoped api token is allowed to do. Tokens without scopes can do anything an account
+/// owner can. Scoped tokens can read the account's resources with the read scope, and the kind of
+/// resource each write scope allows changing.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ApiTokenScope {
+    #[serde(rename = "read")]
+    Read,
+    #[serde(rename = "tasks:write")]
+    TasksWrite,
+    #[serde(rename = "aggregators:write")]
+    AggregatorsWrite,
+    #[serde(rename = "collector_credentials:write")]
+    CollectorCredentialsWrite,
+}
+
+impl ApiTokenScope {
+    pub fn is_write(&self) -> bool {
+        !matches!(self, Self::Read)
+    }
+}
@@ -0,0 +1,231 @@
+use super::{json::Json, ApiTokenColumn, ApiTokens};
+use sea_orm::{
+    sea_query::{all, any, Expr, OnConflict},
+    ActiveModelBehavior, ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DbErr,
+    DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel,
+    PrimaryKeyTrait, QueryFilter, QueryOrder, QuerySelect, Related, RelationDef, RelationTrait,
+    Select,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::{Duration, OffsetDateTime, Time};
+use uuid::Uuid;
+
+/// The most distinct source addresses that are recorded for a token in any hour, so that a token
+/// used from many addresses cannot grow a row without bound. Requests from other addresses are
+/// still counted.
+pub const MAX_SOURCE_IPS: usize = 100;
+
+/// How far back usage is reported.
+pub const RETENTION: Duration = Duration::days(30);
+
+/// How an api token was used during one hour.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "api_token_usage")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    #[sea_orm(unique_key = "api_token_hour")]
+    pub api_token_id: Uuid,
+    /// the start of the hour
+    #[sea_orm(unique_key = "api_token_hour")]
+    #[serde(with = "::time::serde::rfc3339")]
+    pub hour: OffsetDateTime,
+    pub request_count: i64,
+    /// request counts by method and route
+    pub routes: Json<BTreeMap<String, i64>>,
+    /// request counts by source address
+    pub source_ips: Json<BTreeMap<String, i64>>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub last_used_at: OffsetDateTime,
+}
+
+/// Usage of one api token during one hour that has not been written yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingUsage {
+    /// the id of the hour's row, if this usage is the first written for that hour
+    pub id: Uuid,
+    pub request_count: i64,
+    pub routes: BTreeMap<String, i64>,
+    pub source_ips: BTreeMap<String, i64>,
+    pub last_used_at: OffsetDateTime,
+}
+
+impl PendingUsage {
+    pub fn new(last_used_at: OffsetDateTime) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            request_count: 0,
+            routes: BTreeMap::new(),
+            source_ips: BTreeMap::new(),
+            last_used_at,
+        }
+    }
+
+    pub fn record(&mut self, route: Option<String>, source_ip: Option<String>) {
+        self.request_count += 1;
+        if let Some(route) = route {
+            *self.routes.entry(route).or_default() += 1;
+        }
+        if let Some(source_ip) = source_ip {
+            add_source_ip(&mut self.source_ips, source_ip, 1);
+        }
+    }
+}
+
+fn add_source_ip(source_ips: &mut BTreeMap<String, i64>, source_ip: String, count: i64) {
+    if source_ips.len() < MAX_SOURCE_IPS || source_ips.contains_key(&source_ip) {
+        *source_ips.entry(source_ip).or_default() += count;
+    }
+}
+
+/// The start of the hour that contains this time.
+pub fn hour_of(time: OffsetDateTime) -> OffsetDateTime {
+    time.replace_time(Time::from_hms(time.hour(), 0, 0).unwrap_or(Time::MIDNIGHT))
+}
+
+impl Entity {
+    /// Deletes usage that is too old to be reported.
+    pub async fn clean_up(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+        Self::delete_many()
+            .filter(Column::Hour.lt(hour_of(OffsetDateTime::now_utc() - RETENTION)))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub fn for_api_token(api_token_id: Uuid) -> Select<Self> {
+        Self::find()
+            .filter(all![
+                Column::ApiTokenId.eq(api_token_id),
+                Column::Hour.gte(hour_of(OffsetDateTime::now_utc() - RETENTION))
+            ])
+            .order_by_desc(Column::Hour)
+    }
+
+    /// Usage of this token as written, with `pending` usage that has not been written yet added
+    /// to it.
+    pub async fn for_api_token_with_pending(
+        api_token_id: Uuid,
+        pending: Vec<(OffsetDateTime, PendingUsage)>,
+        db: &impl ConnectionTrait,
+    ) -> Result<Vec<Model>, DbErr> {
+        let mut usages = Self::for_api_token(api_token_id).all(db).await?;
+        for (hour, usage) in pending {
+            match usages.iter_mut().find(|model| model.hour == hour) {
+                Some(model) => *model = model.clone().merged(usage),
+                None => usages.push(Model::new(api_token_id, hour, usage)),
+            }
+        }
+        usages.sort_by_key(|usage| std::cmp::Reverse(usage.hour));
+        Ok(usages)
+    }
+
+    /// Adds usage to the hour's row for this token, creating it if needed, and advances the
+    /// token's `last_used_at`. This should be called within a transaction, which holds the row's
+    /// lock until the usage has been added.
+    pub async fn add(
+        api_token_id: Uuid,
+        hour: OffsetDateTime,
+        usage: PendingUsage,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        let last_used_at = usage.last_used_at;
+
+        // the row is created empty if needed and then locked, so that concurrent writers of
+        // the same hour add to it one at a time rather than overwriting each other
+        let empty = PendingUsage {
+            id: usage.id,
+            ..PendingUsage::new(last_used_at)
+        };
+        Self::insert(Model::new(api_token_id, hour, empty).into_active_model())
+            .on_conflict(
+                OnConflict::columns([Column::ApiTokenId, Column::Hour])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+
+        let existing = Self::find()
+            .filter(all![
+                Column::ApiTokenId.eq(api_token_id),
+                Column::Hour.eq(hour)
+            ])
+            .lock_exclusive()
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("api token usage {api_token_id}")))?;
+        existing.merge(usage).update(db).await?;
+
+        ApiTokens::update_many()
+            .col_expr(ApiTokenColumn::LastUsedAt, Expr::value(last_used_at))
+            .filter(all![
+                ApiTokenColumn::Id.eq(api_token_id),
+                any![
+                    ApiTokenColumn::LastUsedAt.is_null(),
+                    ApiTokenColumn::LastUsedAt.lt(last_used_at)
+                ]
+            ])
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl Model {
+    fn new(api_token_id: Uuid, hour: OffsetDateTime, usage: PendingUsage) -> Self {
+        Self {
+            id: usage.id,
+            api_token_id,
+            hour,
+            request_count: usage.request_count,
+            routes: Json(usage.routes),
+            source_ips: Json(usage.source_ips),
+            last_used_at: usage.last_used_at,
+        }
+    }
+
+    fn merged(mut self, usage: PendingUsage) -> Self {
+        for (route, count) in usage.routes {
+            *self.routes.entry(route).or_default() += count;
+        }
+        for (source_ip, count) in usage.source_ips {
+            add_source_ip(&mut self.source_ips, source_ip, count);
+        }
+        self.request_count += usage.request_count;
+        self.last_used_at = self.last_used_at.max(usage.last_used_at);
+        self
+    }
+
+    fn merge(self, usage: PendingUsage) -> ActiveModel {
+        let merged = self.clone().merged(usage);
+        let mut active_model = self.into_active_model();
+        active_model.request_count = ActiveValue::Set(merged.request_count);
+        active_model.routes = ActiveValue::Set(merged.routes);
+        active_model.source_ips = ActiveValue::Set(merged.source_ips);
+        active_model.last_used_at = ActiveValue::Set(merged.last_used_at);
+        active_model
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "ApiTokens",
+        from = "Column::ApiTokenId",
+        to = "ApiTokenColumn::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    ApiToken,
+}
+
+impl Related<ApiTokens> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApiToken.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
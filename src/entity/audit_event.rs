@@ -0,0 +1,176 @@
+use super::{json::Json, AccountColumn, Accounts};
+use crate::PermissionsActor;
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, DeriveEntityModel,
+    DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel, PrimaryKeyTrait,
+    QueryFilter, QueryOrder, QuerySelect, Related, RelationDef, RelationTrait, Select,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+mod action;
+mod target;
+pub use action::AuditAction;
+pub use target::{AuditTarget, AuditTargetType};
+
+/// The most events returned by one listing.
+pub const MAX_LIMIT: u64 = 1000;
+const DEFAULT_LIMIT: u64 = 100;
+
+/// A record of one change made through the api, and who made it.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "audit_event")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub account_id: Option<Uuid>,
+    // exactly one of actor_email and actor_api_token_id is set
+    pub actor_email: Option<String>,
+    pub actor_api_token_id: Option<Uuid>,
+    pub action: AuditAction,
+    pub target_type: AuditTargetType,
+    pub target_id: String,
+    /// the fields of the target that changed, by name
+    pub changes: Json<BTreeMap<String, AuditChange>>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// A field's serialized value before and after an audited change. Values are null when the
+/// target did not exist before or after it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditChange {
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Accounts",
+        from = "Column::AccountId",
+        to = "AccountColumn::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Account,
+}
+
+impl Related<Accounts> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn fields(target: Option<&impl Serialize>) -> BTreeMap<String, Value> {
+    match target.map(serde_json::to_value) {
+        Some(Ok(Value::Object(fields))) => fields.into_iter().collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+fn diff<T: AuditTarget>(before: Option<&T>, after: Option<&T>) -> BTreeMap<String, AuditChange> {
+    let before = fields(before);
+    let after = fields(after);
+    before
+        .keys()
+        .chain(after.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|field| !T::redacted_fields().contains(&field.as_str()))
+        .filter_map(|field| {
+            let before = before.get(field).cloned().unwrap_or(Value::Null);
+            let after = after.get(field).cloned().unwrap_or(Value::Null);
+            (before != after).then(|| (field.clone(), AuditChange { before, after }))
+        })
+        .collect()
+}
+
+impl Entity {
+    /// Records a change to a resource. `before` is None for resources that were created, and
+    /// `after` is None for resources that were deleted outright.
+    pub async fn record<T: AuditTarget>(
+        actor: &PermissionsActor,
+        action: AuditAction,
+        before: Option<&T>,
+        after: Option<&T>,
+        db: &impl ConnectionTrait,
+    ) -> Result<Model, DbErr> {
+        // unwrap safety: one of before and after is always provided by callers
+        let target = after.or(before).unwrap();
+        let (actor_email, actor_api_token_id) = match actor {
+            PermissionsActor::ApiToken(token) => (None, Some(token.api_token.id)),
+            PermissionsActor::User(user, _) => (Some(user.email.clone()), None),
+        };
+
+        Model {
+            id: Uuid::new_v4(),
+            account_id: target.audit_account_id(),
+            actor_email,
+            actor_api_token_id,
+            action,
+            target_type: T::TARGET_TYPE,
+            target_id: target.audit_target_id(),
+            changes: Json(diff(before, after)),
+            created_at: OffsetDateTime::now_utc(),
+        }
+        .into_active_model()
+        .insert(db)
+        .await
+    }
+}
+
+/// Filters for listing audit events, all of which are optional.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub account_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub target_type: Option<AuditTargetType>,
+    pub target_id: Option<String>,
+    /// a user's email or an api token's id
+    pub actor: Option<String>,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub since: Option<OffsetDateTime>,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub until: Option<OffsetDateTime>,
+    pub limit: Option<u64>,
+}
+
+impl AuditEventFilter {
+    /// Matching events, most recent first.
+    pub fn query(self) -> Select<Entity> {
+        let mut select = Entity::find()
+            .order_by_desc(Column::CreatedAt)
+            .limit(self.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT));
+        if let Some(account_id) = self.account_id {
+            select = select.filter(Column::AccountId.eq(account_id));
+        }
+        if let Some(action) = self.action {
+            select = select.filter(Column::Action.eq(action));
+        }
+        if let Some(target_type) = self.target_type {
+            select = select.filter(Column::TargetType.eq(target_type));
+        }
+        if let Some(target_id) = self.target_id {
+            select = select.filter(Column::TargetId.eq(target_id));
+        }
+        if let Some(actor) = self.actor {
+            select = match actor.parse::<Uuid>() {
+                Ok(api_token_id) => select.filter(Column::ActorApiTokenId.eq(api_token_id)),
+                Err(_) => select.filter(Column::ActorEmail.eq(actor)),
+            };
+        }
+        if let Some(since) = self.since {
+            select = select.filter(Column::CreatedAt.gte(since));
+        }
+        if let Some(until) = self.until {
+            select = select.filter(Column::CreatedAt.lt(until));
+        }
+        select
+    }
+}
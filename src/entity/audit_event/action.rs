@@ -0,0 +1,41 @@
+use sea_orm::{prelude::StringLen, DeriveActiveEnum, EnumIter};
+use serde::{Deserialize, Serialize};
+
+/// What an audited request did to its target.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    #[sea_orm(string_value = "create")]
+    Create,
+    #[sea_orm(string_value = "update")]
+    Update,
+    #[sea_orm(string_value = "delete")]
+    Delete,
+    #[sea_orm(string_value = "decommission")]
+    Decommission,
+    #[sea_orm(string_value = "request_access")]
+    RequestAccess,
+    #[sea_orm(string_value = "grant_access")]
+    GrantAccess,
+    #[sea_orm(string_value = "revoke_access")]
+    RevokeAccess,
+    #[sea_orm(string_value = "stage_secondary_bearer_token")]
+    StageSecondaryBearerToken,
+    #[sea_orm(string_value = "discard_secondary_bearer_token")]
+    DiscardSecondaryBearerToken,
+    #[sea_orm(string_value = "promote_secondary_bearer_token")]
+    PromoteSecondaryBearerToken,
+}
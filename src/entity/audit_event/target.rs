@@ -0,0 +1,196 @@
+use crate::entity::{
+    queue, Account, Aggregator, AggregatorAccess, AlertRule, ApiToken, CollectorCredential,
+    Membership, Task, Webhook,
+};
+use sea_orm::{prelude::StringLen, DeriveActiveEnum, EnumIter};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The kind of resource an audit event describes.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditTargetType {
+    #[sea_orm(string_value = "account")]
+    Account,
+    #[sea_orm(string_value = "membership")]
+    Membership,
+    #[sea_orm(string_value = "api_token")]
+    ApiToken,
+    #[sea_orm(string_value = "collector_credential")]
+    CollectorCredential,
+    #[sea_orm(string_value = "task")]
+    Task,
+    #[sea_orm(string_value = "aggregator")]
+    Aggregator,
+    #[sea_orm(string_value = "aggregator_access")]
+    AggregatorAccess,
+    #[sea_orm(string_value = "queue_job")]
+    QueueJob,
+    #[sea_orm(string_value = "webhook")]
+    Webhook,
+    #[sea_orm(string_value = "alert_rule")]
+    AlertRule,
+}
+
+/// A resource whose changes are recorded in the audit log.
+pub trait AuditTarget: Serialize {
+    const TARGET_TYPE: AuditTargetType;
+
+    fn audit_target_id(&self) -> String;
+
+    /// The account whose audit log includes changes to this resource, if any. Shared aggregators
+    /// and queue jobs do not belong to an account, so only admins can see their events.
+    fn audit_account_id(&self) -> Option<Uuid>;
+
+    /// Fields that are never recorded, because they hold or are derived from secrets.
+    fn redacted_fields() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+impl AuditTarget for Account {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::Account;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        Some(self.id)
+    }
+}
+
+impl AuditTarget for Membership {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::Membership;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        Some(self.account_id)
+    }
+}
+
+impl AuditTarget for ApiToken {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::ApiToken;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        Some(self.account_id)
+    }
+
+    fn redacted_fields() -> &'static [&'static str] {
+        &["token", "token_hash"]
+    }
+}
+
+impl AuditTarget for CollectorCredential {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::CollectorCredential;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        Some(self.account_id)
+    }
+
+    fn redacted_fields() -> &'static [&'static str] {
+        &["token", "token_hash"]
+    }
+}
+
+impl AuditTarget for Task {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::Task;
+
+    fn audit_target_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        Some(self.account_id)
+    }
+}
+
+impl AuditTarget for Aggregator {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::Aggregator;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        self.account_id
+    }
+}
+
+impl AuditTarget for AggregatorAccess {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::AggregatorAccess;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    // access to a shared aggregator is recorded for the account it was requested or granted for
+    fn audit_account_id(&self) -> Option<Uuid> {
+        Some(self.account_id)
+    }
+}
+
+impl AuditTarget for queue::Model {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::QueueJob;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        None
+    }
+}
+
+impl AuditTarget for Webhook {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::Webhook;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        Some(self.account_id)
+    }
+
+    // webhook urls are stored encrypted, so they count as secrets too
+    fn redacted_fields() -> &'static [&'static str] {
+        &["secret", "url"]
+    }
+}
+
+impl AuditTarget for AlertRule {
+    const TARGET_TYPE: AuditTargetType = AuditTargetType::AlertRule;
+
+    fn audit_target_id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn audit_account_id(&self) -> Option<Uuid> {
+        Some(self.account_id)
+    }
+}
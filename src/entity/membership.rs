@@ -1,17 +1,21 @@
+mod role;
+
 use crate::{
     entity::{Account, AccountColumn, AccountRelation, Accounts, Aggregators, Tasks},
     User,
 };
 use sea_orm::{
-    ActiveModelBehavior, ColumnTrait, DeriveEntityModel, DerivePrimaryKey, DeriveRelation,
-    EntityTrait, EnumIter, IntoActiveModel, PrimaryKeyTrait, QueryFilter, Related, RelationDef,
-    RelationTrait, Select,
+    ActiveModelBehavior, ActiveValue, ColumnTrait, DeriveEntityModel, DerivePrimaryKey,
+    DeriveRelation, EntityTrait, EnumIter, IntoActiveModel, Iterable, PrimaryKeyTrait, QueryFilter,
+    Related, RelationDef, RelationTrait, Select,
 };
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use uuid::Uuid;
 use validator::{Validate, ValidationErrors};
 
+pub use role::MembershipRole;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "membership")]
 pub struct Model {
@@ -21,6 +25,7 @@ pub struct Model {
     pub user_email: String,
     #[serde(with = "::time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
+    pub role: MembershipRole,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -39,6 +44,16 @@ impl Entity {
     pub fn for_user(user: &User) -> Select<Self> {
         Self::find().filter(Column::UserEmail.eq(&user.email))
     }
+
+    pub fn for_account(account_id: Uuid) -> Select<Self> {
+        Self::find().filter(Column::AccountId.eq(account_id))
+    }
+
+    /// Memberships of the account whose role allows at least what `role` does.
+    pub fn for_account_with_role(account_id: Uuid, role: MembershipRole) -> Select<Self> {
+        Self::for_account(account_id)
+            .filter(Column::Role.is_in(MembershipRole::iter().filter(|r| *r >= role)))
+    }
 }
 
 impl Related<Accounts> for Entity {
@@ -68,9 +83,14 @@ impl Related<Aggregators> for Entity {
 }
 
 impl Model {
-    pub fn build(email: String, account: &Account) -> Result<ActiveModel, ValidationErrors> {
+    pub fn build(
+        email: String,
+        account: &Account,
+        role: MembershipRole,
+    ) -> Result<ActiveModel, ValidationErrors> {
         CreateMembership {
             user_email: Some(email),
+            role: Some(role),
         }
         .build(account)
     }
@@ -80,6 +100,8 @@ impl Model {
 pub struct CreateMembership {
     #[validate(required, email)]
     pub user_email: Option<String>,
+    // defaults to member
+    pub role: Option<MembershipRole>,
 }
 
 impl CreateMembership {
@@ -90,9 +112,25 @@ impl CreateMembership {
             account_id: account.id,
             user_email: self.user_email.unwrap(),
             created_at: OffsetDateTime::now_utc(),
+            role: self.role.unwrap_or(MembershipRole::Member),
         }
         .into_active_model())
     }
 }
 
+#[derive(serde::Deserialize, Clone, Copy, Debug, Validate)]
+pub struct UpdateMembership {
+    #[validate(required)]
+    pub role: Option<MembershipRole>,
+}
+
+impl UpdateMembership {
+    pub fn build(self, membership: Model) -> Result<ActiveModel, ValidationErrors> {
+        self.validate()?;
+        let mut membership = membership.into_active_model();
+        membership.role = ActiveValue::Set(self.role.unwrap());
+        Ok(membership)
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
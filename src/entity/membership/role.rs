@@ -0,0 +1,34 @@
+use sea_orm::{prelude::StringLen, DeriveActiveEnum, EnumIter};
+use serde::{Deserialize, Serialize};
+
+/// What a membership allows its user to do within an account. Variants are declared from least
+/// to most privileged, so that roles can be compared with `>=`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum MembershipRole {
+    /// Can read everything in the account, but cannot change anything
+    #[sea_orm(string_value = "viewer")]
+    Viewer,
+    /// Can also create and change tasks, aggregators and collector credentials
+    #[sea_orm(string_value = "member")]
+    Member,
+    /// Can also manage memberships, other than owners, and api tokens
+    #[sea_orm(string_value = "admin")]
+    Admin,
+    /// Can also manage owners
+    #[sea_orm(string_value = "owner")]
+    Owner,
+}
@@ -2,15 +2,18 @@ use super::json::Json;
 use crate::queue::{EnqueueJob, Job, JobError};
 use sea_orm::{
     sea_query::{all, any, LockBehavior, LockType},
-    ActiveModelBehavior, ActiveValue, ColumnTrait, DatabaseTransaction, DbErr, DeriveActiveEnum,
-    DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, PrimaryKeyTrait,
-    QueryFilter, QueryOrder, QuerySelect,
+    ActiveModelBehavior, ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseTransaction, DbErr,
+    DeriveActiveEnum, DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter,
+    IntoActiveModel, PrimaryKeyTrait, QueryFilter, QueryOrder, QuerySelect,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
+/// How long a worker may take to fetch for a job before another worker can pick it up.
+pub const LEASE: Duration = Duration::minutes(10);
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
 #[sea_orm(table_name = "queue")]
 pub struct Model {
@@ -92,4 +95,32 @@ impl Entity {
 
         select.one(tx).await
     }
+
+    /// Claims a job that [`Self::next`] returned until [`LEASE`] from now, by scheduling it then,
+    /// so that no other worker picks it up once this transaction commits. A job whose worker
+    /// stopped before performing it becomes due again when the lease runs out.
+    pub async fn claim(job: Model, tx: &DatabaseTransaction) -> Result<Model, DbErr> {
+        // whole seconds survive the round trip through the database unchanged
+        let lease = (OffsetDateTime::now_utc() + LEASE)
+            .replace_nanosecond(0)
+            .map_err(|error| DbErr::Custom(error.to_string()))?;
+        let mut job = job.into_active_model();
+        job.scheduled_at = ActiveValue::Set(Some(lease));
+        job.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        job.update(tx).await
+    }
+
+    /// Locks a job that [`Self::claim`] claimed in an earlier transaction, unless another worker
+    /// has claimed, rescheduled or performed it since.
+    pub async fn relock(claimed: &Model, tx: &DatabaseTransaction) -> Result<Option<Model>, DbErr> {
+        let mut select = Entity::find_by_id(claimed.id).filter(all![
+            Column::Status.eq(JobStatus::Pending),
+            Column::ScheduledAt.eq(claimed.scheduled_at),
+        ]);
+
+        QuerySelect::query(&mut select)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked);
+
+        select.one(tx).await
+    }
 }
@@ -12,7 +12,18 @@ use validator::{Validate, ValidationError};
 
 pub mod vdaf;
 use vdaf::Vdaf;
+mod collection_readiness;
+pub use collection_readiness::{BatchInterval, CollectionReadiness};
+mod counter;
+pub use counter::TaskCounter;
+mod dp_estimate;
+pub use dp_estimate::{DpEstimate, DpEstimateParams};
+mod metrics_refresh;
+pub use metrics_refresh::FetchedTaskMetrics;
+mod metrics_summary;
+pub use metrics_summary::{ExpiringTask, MetricsSummary, TaskErrorRatio, TaskMetrics};
 mod new_task;
+pub(crate) use new_task::load_aggregator;
 pub use new_task::NewTask;
 mod update_task;
 pub use update_task::UpdateTask;
@@ -0,0 +1,148 @@
+use super::{Model, TaskCounter};
+use crate::entity::{aggregator::QueryTypeName, TaskCounterSnapshot, TaskCounterSnapshots};
+use sea_orm::{ConnectionTrait, DbErr};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+/// How far back the upload rate is measured from, when counter snapshots reach back that far.
+pub const RATE_WINDOW: Duration = Duration::days(1);
+
+/// The most recent ready batch intervals to list for a time-interval task.
+pub const MAX_READY_INTERVALS: usize = 24;
+
+/// An estimate of what a task's collector can collect, derived from the leader's upload counters.
+///
+/// Reports are assumed to have arrived at a steady rate since `rate_measured_since`. Collections
+/// are not visible to divviup, so reports that have already been collected are still counted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CollectionReadiness {
+    pub task_id: String,
+    pub query_type: QueryTypeName,
+    pub min_batch_size: u64,
+    pub report_count: u64,
+    pub reports_per_hour: f64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub rate_measured_since: OffsetDateTime,
+    /// Reports that arrived after their interval was collected. A growing count suggests
+    /// collecting later.
+    pub late_report_count: u64,
+    /// The shortest batch interval expected to hold `min_batch_size` reports. Time-interval only.
+    pub batch_interval_seconds: Option<u64>,
+    /// Batch intervals that should hold enough reports, most recent first. Time-interval only.
+    pub ready_intervals: Option<Vec<BatchInterval>>,
+    /// Batches filled since the task was created. Fixed-size only.
+    pub ready_batches: Option<u64>,
+    /// None if no reports are arriving or the task has expired before then.
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub next_ready_at: Option<OffsetDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchInterval {
+    #[serde(with = "time::serde::rfc3339")]
+    pub start: OffsetDateTime,
+    pub duration_seconds: u64,
+}
+
+impl CollectionReadiness {
+    pub async fn estimate(task: &Model, db: &impl ConnectionTrait) -> Result<Self, DbErr> {
+        let baseline = TaskCounterSnapshots::baseline(task, RATE_WINDOW, db).await?;
+        Ok(Self::from_baseline(
+            task,
+            baseline.as_ref(),
+            OffsetDateTime::now_utc(),
+        ))
+    }
+
+    /// Measures the upload rate from `baseline`, or from the task's creation if there is none.
+    pub fn from_baseline(
+        task: &Model,
+        baseline: Option<&TaskCounterSnapshot>,
+        now: OffsetDateTime,
+    ) -> Self {
+        let report_count = task.report_counter_success.max(0) as u64;
+        let (rate_measured_since, reports_since) = match baseline {
+            Some(snapshot) => (
+                snapshot.captured_at,
+                report_count.saturating_sub(
+                    snapshot.counter(TaskCounter::ReportCounterSuccess).max(0) as u64,
+                ),
+            ),
+            None => (task.created_at, report_count),
+        };
+        let elapsed = (now - rate_measured_since).as_seconds_f64();
+        let reports_per_second = if elapsed >= 1.0 {
+            reports_since as f64 / elapsed
+        } else {
+            0.0
+        };
+
+        let min_batch_size = task.min_batch_size.max(1) as u64;
+        let mut readiness = Self {
+            task_id: task.id.clone(),
+            query_type: QueryTypeName::TimeInterval,
+            min_batch_size,
+            report_count,
+            reports_per_hour: reports_per_second * 3600.0,
+            rate_measured_since,
+            late_report_count: task.report_counter_interval_collected.max(0) as u64,
+            batch_interval_seconds: None,
+            ready_intervals: None,
+            ready_batches: None,
+            next_ready_at: None,
+        };
+
+        let next_ready_at = if task.max_batch_size.is_some() {
+            readiness.query_type = QueryTypeName::FixedSize;
+            readiness.ready_batches = Some(report_count / min_batch_size);
+            let remaining = min_batch_size - report_count % min_batch_size;
+            (reports_per_second > 0.0)
+                .then(|| remaining as f64 / reports_per_second)
+                .and_then(Duration::checked_seconds_f64)
+                .and_then(|wait| now.checked_add(wait))
+        } else {
+            readiness.estimate_intervals(task, reports_per_second, now)
+        };
+        readiness.next_ready_at =
+            next_ready_at.filter(|at| task.expiration.is_none_or(|expiration| *at <= expiration));
+        readiness
+    }
+
+    fn estimate_intervals(
+        &mut self,
+        task: &Model,
+        reports_per_second: f64,
+        now: OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
+        let precision = i64::from(task.time_precision_seconds.max(1));
+        let reports_per_precision = reports_per_second * precision as f64;
+        let mut ready_intervals = vec![];
+        if reports_per_precision <= 0.0 {
+            self.ready_intervals = Some(ready_intervals);
+            return None;
+        }
+
+        // batch intervals must be a whole number of time precisions
+        let precisions = (self.min_batch_size as f64 / reports_per_precision)
+            .ceil()
+            .max(1.0);
+        let duration = precision.saturating_mul(precisions.min(i64::MAX as f64) as i64);
+        self.batch_interval_seconds = Some(duration as u64);
+
+        // intervals start on a multiple of the time precision, so aligning to a multiple of the
+        // interval duration keeps them valid
+        let measured_since = self.rate_measured_since.unix_timestamp();
+        let current_start = now.unix_timestamp().div_euclid(duration) * duration;
+        let mut start = current_start - duration;
+        while start >= measured_since && ready_intervals.len() < MAX_READY_INTERVALS {
+            ready_intervals.push(BatchInterval {
+                start: OffsetDateTime::from_unix_timestamp(start).ok()?,
+                duration_seconds: duration as u64,
+            });
+            start -= duration;
+        }
+        self.ready_intervals = Some(ready_intervals);
+
+        OffsetDateTime::from_unix_timestamp(current_start.checked_add(duration)?).ok()
+    }
+}
@@ -0,0 +1,177 @@
+use super::{Column, Model};
+use sea_orm::{prelude::StringLen, DeriveActiveEnum, EnumIter, Iterable};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One of the report or aggregation job counters that are refreshed from a task's leader.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+#[serde(rename_all = "snake_case")]
+pub enum TaskCounter {
+    #[sea_orm(string_value = "report_counter_interval_collected")]
+    ReportCounterIntervalCollected,
+    #[sea_orm(string_value = "report_counter_decode_failure")]
+    ReportCounterDecodeFailure,
+    #[sea_orm(string_value = "report_counter_decrypt_failure")]
+    ReportCounterDecryptFailure,
+    #[sea_orm(string_value = "report_counter_expired")]
+    ReportCounterExpired,
+    #[sea_orm(string_value = "report_counter_outdated_key")]
+    ReportCounterOutdatedKey,
+    #[sea_orm(string_value = "report_counter_success")]
+    ReportCounterSuccess,
+    #[sea_orm(string_value = "report_counter_too_early")]
+    ReportCounterTooEarly,
+    #[sea_orm(string_value = "report_counter_task_expired")]
+    ReportCounterTaskExpired,
+    #[sea_orm(string_value = "aggregation_job_counter_success")]
+    AggregationJobCounterSuccess,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_batch_collected")]
+    AggregationJobCounterHelperBatchCollected,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_report_replayed")]
+    AggregationJobCounterHelperReportReplayed,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_report_dropped")]
+    AggregationJobCounterHelperReportDropped,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_hpke_unknown_config_id")]
+    AggregationJobCounterHelperHpkeUnknownConfigId,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_hpke_decrypt_failure")]
+    AggregationJobCounterHelperHpkeDecryptFailure,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_vdaf_prep_error")]
+    AggregationJobCounterHelperVdafPrepError,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_task_expired")]
+    AggregationJobCounterHelperTaskExpired,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_invalid_message")]
+    AggregationJobCounterHelperInvalidMessage,
+    #[sea_orm(string_value = "aggregation_job_counter_helper_report_too_early")]
+    AggregationJobCounterHelperReportTooEarly,
+}
+
+impl TaskCounter {
+    pub fn value(self, task: &Model) -> i64 {
+        match self {
+            Self::ReportCounterIntervalCollected => task.report_counter_interval_collected,
+            Self::ReportCounterDecodeFailure => task.report_counter_decode_failure,
+            Self::ReportCounterDecryptFailure => task.report_counter_decrypt_failure,
+            Self::ReportCounterExpired => task.report_counter_expired,
+            Self::ReportCounterOutdatedKey => task.report_counter_outdated_key,
+            Self::ReportCounterSuccess => task.report_counter_success,
+            Self::ReportCounterTooEarly => task.report_counter_too_early,
+            Self::ReportCounterTaskExpired => task.report_counter_task_expired,
+            Self::AggregationJobCounterSuccess => task.aggregation_job_counter_success,
+            Self::AggregationJobCounterHelperBatchCollected => {
+                task.aggregation_job_counter_helper_batch_collected
+            }
+            Self::AggregationJobCounterHelperReportReplayed => {
+                task.aggregation_job_counter_helper_report_replayed
+            }
+            Self::AggregationJobCounterHelperReportDropped => {
+                task.aggregation_job_counter_helper_report_dropped
+            }
+            Self::AggregationJobCounterHelperHpkeUnknownConfigId => {
+                task.aggregation_job_counter_helper_hpke_unknown_config_id
+            }
+            Self::AggregationJobCounterHelperHpkeDecryptFailure => {
+                task.aggregation_job_counter_helper_hpke_decrypt_failure
+            }
+            Self::AggregationJobCounterHelperVdafPrepError => {
+                task.aggregation_job_counter_helper_vdaf_prep_error
+            }
+            Self::AggregationJobCounterHelperTaskExpired => {
+                task.aggregation_job_counter_helper_task_expired
+            }
+            Self::AggregationJobCounterHelperInvalidMessage => {
+                task.aggregation_job_counter_helper_invalid_message
+            }
+            Self::AggregationJobCounterHelperReportTooEarly => {
+                task.aggregation_job_counter_helper_report_too_early
+            }
+        }
+    }
+}
+
+impl TaskCounter {
+    pub fn column(self) -> Column {
+        match self {
+            Self::ReportCounterIntervalCollected => Column::ReportCounterIntervalCollected,
+            Self::ReportCounterDecodeFailure => Column::ReportCounterDecodeFailure,
+            Self::ReportCounterDecryptFailure => Column::ReportCounterDecryptFailure,
+            Self::ReportCounterExpired => Column::ReportCounterExpired,
+            Self::ReportCounterOutdatedKey => Column::ReportCounterOutdatedKey,
+            Self::ReportCounterSuccess => Column::ReportCounterSuccess,
+            Self::ReportCounterTooEarly => Column::ReportCounterTooEarly,
+            Self::ReportCounterTaskExpired => Column::ReportCounterTaskExpired,
+            Self::AggregationJobCounterSuccess => Column::AggregationJobCounterSuccess,
+            Self::AggregationJobCounterHelperBatchCollected => {
+                Column::AggregationJobCounterHelperBatchCollected
+            }
+            Self::AggregationJobCounterHelperReportReplayed => {
+                Column::AggregationJobCounterHelperReportReplayed
+            }
+            Self::AggregationJobCounterHelperReportDropped => {
+                Column::AggregationJobCounterHelperReportDropped
+            }
+            Self::AggregationJobCounterHelperHpkeUnknownConfigId => {
+                Column::AggregationJobCounterHelperHpkeUnknownConfigId
+            }
+            Self::AggregationJobCounterHelperHpkeDecryptFailure => {
+                Column::AggregationJobCounterHelperHpkeDecryptFailure
+            }
+            Self::AggregationJobCounterHelperVdafPrepError => {
+                Column::AggregationJobCounterHelperVdafPrepError
+            }
+            Self::AggregationJobCounterHelperTaskExpired => {
+                Column::AggregationJobCounterHelperTaskExpired
+            }
+            Self::AggregationJobCounterHelperInvalidMessage => {
+                Column::AggregationJobCounterHelperInvalidMessage
+            }
+            Self::AggregationJobCounterHelperReportTooEarly => {
+                Column::AggregationJobCounterHelperReportTooEarly
+            }
+        }
+    }
+
+    /// Whether this counts reports as they are uploaded, rather than as they are aggregated.
+    pub fn is_upload(self) -> bool {
+        matches!(
+            self,
+            Self::ReportCounterIntervalCollected
+                | Self::ReportCounterDecodeFailure
+                | Self::ReportCounterDecryptFailure
+                | Self::ReportCounterExpired
+                | Self::ReportCounterOutdatedKey
+                | Self::ReportCounterSuccess
+                | Self::ReportCounterTooEarly
+                | Self::ReportCounterTaskExpired
+        )
+    }
+
+    pub fn is_success(self) -> bool {
+        matches!(
+            self,
+            Self::ReportCounterSuccess | Self::AggregationJobCounterSuccess
+        )
+    }
+}
+
+impl Model {
+    /// The current value of every counter.
+    pub fn counters(&self) -> BTreeMap<TaskCounter, i64> {
+        TaskCounter::iter()
+            .map(|counter| (counter, counter.value(self)))
+            .collect()
+    }
+}
@@ -0,0 +1,112 @@
+use super::vdaf::{Count, DpStrategyKind, Sum, SumVec, Vdaf};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// Both aggregators add noise to their aggregate shares independently.
+const NOISY_AGGREGATORS: f64 = 2.0;
+
+#[derive(Deserialize, Validate, Debug, Clone)]
+pub struct DpEstimateParams {
+    #[validate(required, nested)]
+    pub vdaf: Option<Vdaf>,
+
+    #[validate(required, range(min = 100))]
+    pub min_batch_size: Option<u64>,
+}
+
+/// How much noise a vdaf's differential privacy strategy adds to each bucket of a collected
+/// aggregate.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DpEstimate {
+    /// None if the vdaf does not add noise.
+    pub epsilon: Option<f64>,
+    /// The most that replacing one report can change the aggregate by, summed over buckets.
+    pub sensitivity: u64,
+    /// The scale of the discrete Laplace distribution each aggregator draws noise from.
+    pub noise_scale: f64,
+    /// The standard deviation of the combined noise in each bucket.
+    pub standard_deviation: f64,
+    /// The standard deviation as a fraction of the largest value a bucket can reach in a batch of
+    /// `min_batch_size` reports.
+    pub relative_error_at_min_batch_size: f64,
+}
+
+impl DpEstimateParams {
+    pub fn estimate(&self) -> Result<DpEstimate, ValidationErrors> {
+        self.validate()?;
+        let vdaf = self.vdaf.as_ref().unwrap();
+        let min_batch_size = self.min_batch_size.unwrap();
+
+        // sensitivities assume substitution of one report, as prio does for its noised vdafs
+        let (dp_strategy, sensitivity, max_bucket_value) = match vdaf {
+            Vdaf::Count(Count { dp_strategy }) => (dp_strategy, 1, 1),
+            Vdaf::Sum(Sum {
+                bits: Some(bits),
+                dp_strategy,
+            }) => {
+                let max_measurement = max_value(*bits);
+                (dp_strategy, max_measurement, max_measurement)
+            }
+            Vdaf::Histogram(histogram) => (histogram.dp_strategy(), 2, 1),
+            Vdaf::SumVec(SumVec {
+                bits: Some(bits),
+                length: Some(length),
+                dp_strategy,
+                ..
+            }) => {
+                let max_element = max_value(*bits);
+                (
+                    dp_strategy,
+                    max_element.saturating_mul(*length),
+                    max_element,
+                )
+            }
+            _ => return Err(error("vdaf", "dp_not_supported")),
+        };
+
+        let epsilon = match dp_strategy.dp_strategy {
+            DpStrategyKind::NoDifferentialPrivacy => None,
+            DpStrategyKind::PureDpDiscreteLaplace => Some(
+                dp_strategy
+                    .budget
+                    .epsilon_f64()
+                    .filter(|epsilon| epsilon.is_finite() && *epsilon > 0.0)
+                    .ok_or_else(|| error("vdaf", "invalid_epsilon"))?,
+            ),
+        };
+
+        let noise_scale = epsilon.map_or(0.0, |epsilon| sensitivity as f64 / epsilon);
+        let standard_deviation =
+            (NOISY_AGGREGATORS * discrete_laplace_variance(noise_scale)).sqrt();
+        Ok(DpEstimate {
+            epsilon,
+            sensitivity,
+            noise_scale,
+            standard_deviation,
+            relative_error_at_min_batch_size: standard_deviation
+                / (min_batch_size as f64 * max_bucket_value as f64),
+        })
+    }
+}
+
+/// The largest value that `bits` bits can hold.
+fn max_value(bits: u8) -> u64 {
+    1u64.checked_shl(bits.into())
+        .map_or(u64::MAX, |limit| limit - 1)
+}
+
+/// The variance of the discrete Laplace distribution that draws each integer `x` with probability
+/// proportional to exp(-|x| / scale).
+fn discrete_laplace_variance(scale: f64) -> f64 {
+    if scale <= 0.0 {
+        return 0.0;
+    }
+    let p = (-1.0 / scale).exp();
+    2.0 * p / (1.0 - p).powi(2)
+}
+
+fn error(field: &'static str, code: &'static str) -> ValidationErrors {
+    let mut errors = ValidationErrors::new();
+    errors.add(field, ValidationError::new(code));
+    errors
+}
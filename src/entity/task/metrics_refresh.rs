@@ -0,0 +1,161 @@
+use super::{HelperMetrics, Model};
+use crate::{
+    clients::{
+        aggregator_client::{
+            api_types::{TaskAggregationJobMetrics, TaskCollectionJobMetrics},
+            TaskUploadMetrics,
+        },
+        HttpClient,
+    },
+    entity::{Aggregator, Aggregators, TaskCounterSnapshots},
+    Crypter, Error,
+};
+use sea_orm::{ActiveModelTrait, ConnectionTrait, DbErr, EntityTrait};
+use time::OffsetDateTime;
+use tracing::warn;
+
+/// A task's metrics as its aggregators reported them. They are fetched before anything is
+/// written, so that no transaction is held open while the aggregators are asked for them.
+#[derive(Debug)]
+pub struct FetchedTaskMetrics {
+    uploads: TaskUploadMetrics,
+    aggregation_jobs: TaskAggregationJobMetrics,
+    collection_jobs: TaskCollectionJobMetrics,
+    helper: Option<HelperMetrics>,
+    // aggregators, as they were fetched, that accepted their secondary bearer token
+    confirmed_secondary_bearer_tokens: Vec<Aggregator>,
+}
+
+impl FetchedTaskMetrics {
+    pub async fn fetch(
+        task: &Model,
+        client: &HttpClient,
+        crypter: &Crypter,
+        db: &impl ConnectionTrait,
+    ) -> Result<Self, Error> {
+        let aggregator = task.leader_aggregator(db).await?;
+        let aggregator_client = aggregator.client(client.clone(), crypter)?;
+        let uploads = if aggregator.features.upload_metrics_enabled() {
+            aggregator_client.get_task_upload_metrics(&task.id).await?
+        } else {
+            TaskUploadMetrics::default()
+        };
+
+        let aggregation_jobs = if aggregator.features.aggregation_job_metrics_enabled() {
+            aggregator_client
+                .get_task_aggregation_job_metrics(&task.id)
+                .await?
+        } else {
+            TaskAggregationJobMetrics::default()
+        };
+
+        let collection_jobs = if aggregator.features.collection_job_metrics_enabled() {
+            aggregator_client
+                .get_task_collection_job_metrics(&task.id)
+                .await?
+        } else {
+            TaskCollectionJobMetrics::default()
+        };
+        let mut confirmed_secondary_bearer_tokens = vec![];
+        if aggregator_client.secondary_bearer_token_confirmed() {
+            confirmed_secondary_bearer_tokens.push(aggregator);
+        }
+
+        let helper = task.helper_aggregator(db).await?;
+        let helper = if helper.features.upload_metrics_enabled()
+            || helper.features.aggregation_job_metrics_enabled()
+        {
+            match fetch_helper_metrics(&task.id, &helper, client, crypter).await {
+                Ok((metrics, secondary_bearer_token_confirmed)) => {
+                    if secondary_bearer_token_confirmed {
+                        confirmed_secondary_bearer_tokens.push(helper);
+                    }
+                    Some(metrics)
+                }
+                Err(err) => {
+                    warn!(?err, "failed to refresh helper-side metrics, ignoring");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            uploads,
+            aggregation_jobs,
+            collection_jobs,
+            helper,
+            confirmed_secondary_bearer_tokens,
+        })
+    }
+
+    pub async fn write(self, task: Model, db: &impl ConnectionTrait) -> Result<Model, DbErr> {
+        for fetched in self.confirmed_secondary_bearer_tokens {
+            // the aggregator may have changed or been deleted while its metrics were being fetched
+            let aggregator =
+                Aggregators::find_by_id(fetched.id)
+                    .one(db)
+                    .await?
+                    .filter(|aggregator| {
+                        !aggregator.is_tombstoned()
+                            && aggregator.encrypted_secondary_bearer_token
+                                == fetched.encrypted_secondary_bearer_token
+                    });
+            if let Some(aggregator) = aggregator {
+                aggregator
+                    .promote_secondary_bearer_token()
+                    .update(db)
+                    .await?;
+            }
+        }
+
+        let task = task.update_task_upload_metrics(self.uploads, db).await?;
+        let task = task
+            .update_task_aggregation_job_metrics(self.aggregation_jobs, db)
+            .await?;
+        let task = task
+            .update_task_collection_job_metrics(self.collection_jobs, db)
+            .await?;
+        let task = match self.helper {
+            Some(metrics) => task.update_helper_metrics(metrics, db).await?,
+            None => task,
+        };
+
+        // every refresh is snapshotted, so that rates can be measured for any task
+        TaskCounterSnapshots::record(&task, db).await?;
+        TaskCounterSnapshots::clean_up(&task.id, db).await?;
+        Ok(task)
+    }
+}
+
+// the helper's metrics are only diagnostic, so a helper that cannot be reached should not prevent
+// the task and the leader's metrics from being shown
+async fn fetch_helper_metrics(
+    task_id: &str,
+    helper: &Aggregator,
+    client: &HttpClient,
+    crypter: &Crypter,
+) -> Result<(HelperMetrics, bool), Error> {
+    let helper_client = helper.client(client.clone(), crypter)?;
+    let uploads = if helper.features.upload_metrics_enabled() {
+        Some(helper_client.get_task_upload_metrics(task_id).await?)
+    } else {
+        None
+    };
+    let aggregation_jobs = if helper.features.aggregation_job_metrics_enabled() {
+        Some(
+            helper_client
+                .get_task_aggregation_job_metrics(task_id)
+                .await?,
+        )
+    } else {
+        None
+    };
+    let metrics = HelperMetrics {
+        uploads,
+        aggregation_jobs,
+        refreshed_at: OffsetDateTime::now_utc(),
+    };
+    Ok((metrics, helper_client.secondary_bearer_token_confirmed()))
+}
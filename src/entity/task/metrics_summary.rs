@@ -0,0 +1,191 @@
+use super::{Column, Entity, Model, TaskCounter};
+use crate::entity::Account;
+use sea_orm::{
+    sea_query::{all, Expr, ExprTrait, Func},
+    ActiveEnum, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
+    IsolationLevel, Iterable, QueryFilter, QueryOrder, QuerySelect, QueryTrait, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::{Duration, OffsetDateTime};
+
+/// Tasks expiring within this long are listed in [`MetricsSummary::expiring_soon`].
+pub const EXPIRING_SOON: Duration = Duration::days(7);
+
+/// How many tasks to list in [`MetricsSummary::highest_error_ratios`].
+pub const HIGHEST_ERROR_RATIO_COUNT: usize = 5;
+
+/// The counters of every task in an account, as of each task's last metrics refresh. Building
+/// this does not refresh any task's metrics.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetricsSummary {
+    pub task_count: u64,
+    pub totals: BTreeMap<TaskCounter, i64>,
+    pub tasks: Vec<TaskMetrics>,
+    /// Tasks with any errors, highest ratio first.
+    pub highest_error_ratios: Vec<TaskErrorRatio>,
+    /// Unexpired tasks that will expire within [`EXPIRING_SOON`], soonest first.
+    pub expiring_soon: Vec<ExpiringTask>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskMetrics {
+    pub task_id: String,
+    pub name: String,
+    pub counters: BTreeMap<TaskCounter, i64>,
+    /// Rejected uploads as a fraction of all uploads.
+    pub upload_error_ratio: f64,
+    /// Reports the helper rejected as a fraction of all reports aggregated.
+    pub aggregation_error_ratio: f64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskErrorRatio {
+    pub task_id: String,
+    pub name: String,
+    /// The higher of the task's upload and aggregation error ratios.
+    pub error_ratio: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExpiringTask {
+    pub task_id: String,
+    pub name: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expiration: OffsetDateTime,
+}
+
+impl TaskMetrics {
+    fn new(task: &Model) -> Self {
+        let counters = task.counters();
+        let error_ratio = |upload| {
+            let (successes, failures) = counters
+                .iter()
+                .filter(|(counter, _)| counter.is_upload() == upload)
+                .fold((0i64, 0i64), |(successes, failures), (counter, value)| {
+                    if counter.is_success() {
+                        (successes.saturating_add(*value), failures)
+                    } else {
+                        (successes, failures.saturating_add(*value))
+                    }
+                });
+            if failures > 0 {
+                failures as f64 / (successes as f64 + failures as f64)
+            } else {
+                0.0
+            }
+        };
+
+        Self {
+            task_id: task.id.clone(),
+            name: task.name.clone(),
+            upload_error_ratio: error_ratio(true),
+            aggregation_error_ratio: error_ratio(false),
+            counters,
+            updated_at: task.updated_at,
+        }
+    }
+
+    fn error_ratio(&self) -> f64 {
+        self.upload_error_ratio.max(self.aggregation_error_ratio)
+    }
+}
+
+impl MetricsSummary {
+    /// Summarizes the account's undeleted tasks, limited to `task_ids` if present.
+    pub async fn for_account(
+        account: &Account,
+        task_ids: Option<&[String]>,
+        db: &DatabaseConnection,
+    ) -> Result<Self, DbErr> {
+        let mut condition = all![
+            Column::AccountId.eq(account.id),
+            Column::DeletedAt.is_null()
+        ];
+        if let Some(task_ids) = task_ids {
+            condition = condition.add(Column::Id.is_in(task_ids.iter().cloned()));
+        }
+
+        // the totals and the per-task counters are read from one snapshot so that they agree
+        let tx = db
+            .begin_with_config(Some(IsolationLevel::RepeatableRead), None)
+            .await?;
+        let backend = tx.get_database_backend();
+        let mut totals_query = Entity::find()
+            .select_only()
+            .filter(condition.clone())
+            .expr_as(Expr::col(Column::Id).count(), "task_count");
+        for counter in TaskCounter::iter() {
+            totals_query = totals_query.expr_as(
+                saturating_sum(counter.column(), backend),
+                counter.to_value(),
+            );
+        }
+        let row = tx
+            .query_one_raw(totals_query.build(backend))
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("task totals".into()))?;
+        let task_count = u64::try_from(row.try_get::<i64>("", "task_count")?).unwrap_or_default();
+        let totals = TaskCounter::iter()
+            .map(|counter| Ok((counter, row.try_get::<i64>("", &counter.to_value())?)))
+            .collect::<Result<_, DbErr>>()?;
+
+        let tasks = Entity::find()
+            .filter(condition)
+            .order_by_asc(Column::CreatedAt)
+            .all(&tx)
+            .await?;
+        tx.commit().await?;
+
+        let now = OffsetDateTime::now_utc();
+        let mut expiring_soon = tasks
+            .iter()
+            .filter_map(|task| {
+                let expiration = task.expiration?;
+                (expiration > now && expiration <= now + EXPIRING_SOON).then(|| ExpiringTask {
+                    task_id: task.id.clone(),
+                    name: task.name.clone(),
+                    expiration,
+                })
+            })
+            .collect::<Vec<_>>();
+        expiring_soon.sort_by_key(|task| task.expiration);
+
+        let tasks = tasks.iter().map(TaskMetrics::new).collect::<Vec<_>>();
+        let mut highest_error_ratios = tasks
+            .iter()
+            .filter(|task| task.error_ratio() > 0.0)
+            .map(|task| TaskErrorRatio {
+                task_id: task.task_id.clone(),
+                name: task.name.clone(),
+                error_ratio: task.error_ratio(),
+            })
+            .collect::<Vec<_>>();
+        highest_error_ratios.sort_by(|a, b| b.error_ratio.total_cmp(&a.error_ratio));
+        highest_error_ratios.truncate(HIGHEST_ERROR_RATIO_COUNT);
+
+        Ok(Self {
+            task_count,
+            totals,
+            tasks,
+            highest_error_ratios,
+            expiring_soon,
+        })
+    }
+}
+
+// sum(bigint) is numeric in postgres and overflows in sqlite, whose total() is a double instead, so
+// the sum is clamped to a bigint, as the per-task counters saturate
+fn saturating_sum(column: Column, backend: DbBackend) -> Expr {
+    let sum = match backend {
+        DbBackend::Sqlite => Func::cust("MIN")
+            .arg(Func::cust("TOTAL").arg(Expr::col(column)))
+            .arg(i64::MAX),
+        _ => Func::cust("LEAST")
+            .arg(Func::coalesce([Expr::col(column).sum(), Expr::val(0)]))
+            .arg(i64::MAX),
+    };
+    Expr::from(sum).cast_as("bigint")
+}
@@ -1,5 +1,8 @@
 use crate::{
-    clients::aggregator_client::{api_types::TaskAggregationJobMetrics, TaskUploadMetrics},
+    clients::aggregator_client::{
+        api_types::{TaskAggregationJobMetrics, TaskCollectionJobMetrics},
+        TaskUploadMetrics,
+    },
     entity::{
         account, json::Json, membership, AccountColumn, Accounts, Aggregator, AggregatorColumn,
         Aggregators, CollectorCredentialColumn, CollectorCredentials,
@@ -37,17 +40,18 @@ pub struct Model {
     pub deleted_at: Option<OffsetDateTime>,
     pub time_precision_seconds: i32,
 
-    /// Deprecated metrics field. Never populated, only reads zero.
-    #[sea_orm(ignore)]
+    /// Reports included in finished collections, if the leader reports collection job metrics.
     #[serde(default)]
-    pub report_count: i32,
-    /// Deprecated metrics field. Never populated, only reads zero.
-    #[sea_orm(ignore)]
+    pub report_count: i64,
+    /// Finished collections, if the leader reports collection job metrics.
     #[serde(default)]
-    pub aggregate_collection_count: i32,
+    pub aggregate_collection_count: i64,
 
     #[serde(default, with = "time::serde::rfc3339::option")]
     pub expiration: Option<OffsetDateTime>,
+    // when the task.expired webhook event was last sent for this task
+    #[serde(skip)]
+    pub expiration_notified_at: Option<OffsetDateTime>,
     pub leader_aggregator_id: Uuid,
     pub helper_aggregator_id: Uuid,
     pub collector_credential_id: Uuid,
@@ -73,13 +77,36 @@ pub struct Model {
     pub aggregation_job_counter_helper_task_expired: i64,
     pub aggregation_job_counter_helper_invalid_message: i64,
     pub aggregation_job_counter_helper_report_too_early: i64,
+
+    // Collection job metrics
+    #[serde(default)]
+    pub collection_job_counter_pending: i64,
+    #[serde(default)]
+    pub collection_job_counter_abandoned: i64,
+    #[serde(default)]
+    pub collection_job_counter_deleted: i64,
+
+    /// Metrics reported by the helper, if it reports any. The counters above are the leader's.
+    #[serde(default)]
+    pub helper_metrics: Option<Json<HelperMetrics>>,
+}
+
+/// A task's metrics as reported by its helper, which can differ from what the leader observed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HelperMetrics {
+    /// None if the helper does not report upload metrics
+    pub uploads: Option<TaskUploadMetrics>,
+    /// None if the helper does not report aggregation job metrics
+    pub aggregation_jobs: Option<TaskAggregationJobMetrics>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub refreshed_at: OffsetDateTime,
 }
 
 impl Model {
     pub async fn update_task_upload_metrics(
         self,
         metrics: TaskUploadMetrics,
-        db: impl ConnectionTrait,
+        db: &impl ConnectionTrait,
     ) -> Result<Self, DbErr> {
         let mut task = self.into_active_model();
         task.report_counter_interval_collected =
@@ -103,13 +130,13 @@ impl Model {
         task.report_counter_task_expired =
             ActiveValue::Set(metrics.task_expired.try_into().unwrap_or(i64::MAX));
         task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
-        task.update(&db).await
+        task.update(db).await
     }
 
     pub async fn update_task_aggregation_job_metrics(
         self,
         metrics: TaskAggregationJobMetrics,
-        db: impl ConnectionTrait,
+        db: &impl ConnectionTrait,
     ) -> Result<Self, DbErr> {
         let mut task = self.into_active_model();
         task.aggregation_job_counter_success =
@@ -161,7 +188,37 @@ impl Model {
                 .unwrap_or(i64::MAX),
         );
         task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
-        task.update(&db).await
+        task.update(db).await
+    }
+
+    pub async fn update_task_collection_job_metrics(
+        self,
+        metrics: TaskCollectionJobMetrics,
+        db: &impl ConnectionTrait,
+    ) -> Result<Self, DbErr> {
+        let mut task = self.into_active_model();
+        task.report_count = ActiveValue::Set(metrics.report_count.try_into().unwrap_or(i64::MAX));
+        task.aggregate_collection_count =
+            ActiveValue::Set(metrics.finished.try_into().unwrap_or(i64::MAX));
+        task.collection_job_counter_pending =
+            ActiveValue::Set(metrics.pending.try_into().unwrap_or(i64::MAX));
+        task.collection_job_counter_abandoned =
+            ActiveValue::Set(metrics.abandoned.try_into().unwrap_or(i64::MAX));
+        task.collection_job_counter_deleted =
+            ActiveValue::Set(metrics.deleted.try_into().unwrap_or(i64::MAX));
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        task.update(db).await
+    }
+
+    pub async fn update_helper_metrics(
+        self,
+        metrics: HelperMetrics,
+        db: &impl ConnectionTrait,
+    ) -> Result<Self, DbErr> {
+        let mut task = self.into_active_model();
+        task.helper_metrics = ActiveValue::Set(Some(Json(metrics)));
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        task.update(db).await
     }
 
     pub async fn leader_aggregator(
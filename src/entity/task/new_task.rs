@@ -13,7 +13,6 @@ use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
 use sha2::{Digest, Sha256};
 use std::borrow::Cow;
 use validator::{ValidationErrors, ValidationErrorsKind};
-use vdaf::{DpStrategy, DpStrategyKind, SumVec};
 
 #[derive(Deserialize, Validate, Debug, Clone, Default)]
 pub struct NewTask {
@@ -52,13 +51,18 @@ pub struct NewTask {
     pub collector_credential_id: Option<String>,
 }
 
-async fn load_aggregator(
+/// Loads an aggregator that `account` can see, or returns the validation error to report for `id`.
+pub(crate) async fn load_aggregator(
     account: &Account,
     id: Option<&str>,
     db: &impl ConnectionTrait,
-) -> Result<Option<Aggregator>, Error> {
-    let Some(id) = id.map(Uuid::parse_str).transpose()? else {
-        return Ok(None);
+) -> Result<Result<Aggregator, ValidationError>, Error> {
+    let Some(id) = id else {
+        return Ok(Err(ValidationError::new("required")));
+    };
+
+    let Ok(id) = Uuid::parse_str(id) else {
+        return Ok(Err(ValidationError::new("invalid-uuid")));
     };
 
     let aggregator = Aggregators::find_by_id(id)
@@ -66,14 +70,11 @@ async fn load_aggregator(
         .one(db)
         .await?;
 
-    let Some(aggregator) = aggregator else {
-        return Ok(None);
-    };
-
-    if aggregator.account_id.is_none() || aggregator.account_id == Some(account.id) {
-        Ok(Some(aggregator))
-    } else {
-        Ok(None)
+    match aggregator {
+        Some(aggregator) if aggregator.is_visible_to(vec![account.id], db).await? => {
+            Ok(Ok(aggregator))
+        }
+        _ => Ok(Err(ValidationError::new("required"))),
     }
 }
 
@@ -167,25 +168,19 @@ impl NewTask {
         account: &Account,
         db: &impl ConnectionTrait,
         errors: &mut ValidationErrors,
-    ) -> Option<(Aggregator, Aggregator, Protocol)> {
+    ) -> Result<Option<(Aggregator, Aggregator, Protocol)>, Error> {
         let leader = load_aggregator(account, self.leader_aggregator_id.as_deref(), db)
-            .await
-            .ok()
-            .flatten();
-        if leader.is_none() {
-            errors.add("leader_aggregator_id", ValidationError::new("required"));
-        }
+            .await?
+            .map_err(|error| errors.add("leader_aggregator_id", error))
+            .ok();
 
         let helper = load_aggregator(account, self.helper_aggregator_id.as_deref(), db)
-            .await
-            .ok()
-            .flatten();
-        if helper.is_none() {
-            errors.add("helper_aggregator_id", ValidationError::new("required"));
-        }
+            .await?
+            .map_err(|error| errors.add("helper_aggregator_id", error))
+            .ok();
 
         let (Some(leader), Some(helper)) = (leader, helper) else {
-            return None;
+            return Ok(None);
         };
 
         if leader == helper {
@@ -193,6 +188,23 @@ impl NewTask {
             errors.add("helper_aggregator_id", ValidationError::new("same"));
         }
 
+        leader.validate_not_decommissioned("leader_aggregator_id", errors);
+        helper.validate_not_decommissioned("helper_aggregator_id", errors);
+
+        if !leader.is_usable_by(account.id, db).await? {
+            errors.add(
+                "leader_aggregator_id",
+                ValidationError::new("access-required"),
+            );
+        }
+
+        if !helper.is_usable_by(account.id, db).await? {
+            errors.add(
+                "helper_aggregator_id",
+                ValidationError::new("access-required"),
+            );
+        }
+
         if !leader.is_first_party && !helper.is_first_party {
             errors.add(
                 "leader_aggregator_id",
@@ -209,7 +221,7 @@ impl NewTask {
         } else {
             errors.add("leader_aggregator_id", ValidationError::new("protocol"));
             errors.add("helper_aggregator_id", ValidationError::new("protocol"));
-            return None;
+            return Ok(None);
         };
 
         if leader.role == Role::Helper {
@@ -229,21 +241,10 @@ impl NewTask {
             )
         }
 
-        let uses_pure_dp_discrete_laplace = match &self.vdaf {
-            Some(Vdaf::SumVec(SumVec {
-                dp_strategy:
-                    DpStrategy {
-                        dp_strategy: DpStrategyKind::PureDpDiscreteLaplace,
-                        ..
-                    },
-                ..
-            })) => true,
-            Some(Vdaf::Histogram(histogram)) => matches!(
-                histogram.dp_strategy().dp_strategy,
-                DpStrategyKind::PureDpDiscreteLaplace
-            ),
-            _ => false,
-        };
+        let uses_pure_dp_discrete_laplace = self
+            .vdaf
+            .as_ref()
+            .is_some_and(Vdaf::uses_pure_dp_discrete_laplace);
         if uses_pure_dp_discrete_laplace
             && !leader.features.contains(&Feature::PureDpDiscreteLaplace)
         {
@@ -262,9 +263,9 @@ impl NewTask {
         }
 
         if errors.is_empty() {
-            Some((leader, helper, resolved_protocol))
+            Ok(Some((leader, helper, resolved_protocol)))
         } else {
-            None
+            Ok(None)
         }
     }
 
@@ -339,11 +340,11 @@ impl NewTask {
         &mut self,
         account: Account,
         db: &impl ConnectionTrait,
-    ) -> Result<ProvisionableTask, ValidationErrors> {
+    ) -> Result<ProvisionableTask, Error> {
         let mut errors = Validate::validate(self).err().unwrap_or_default();
         self.validate_min_lte_max(&mut errors);
         self.validate_batch_time_window_size(&mut errors);
-        let aggregators = self.validate_aggregators(&account, db, &mut errors).await;
+        let aggregators = self.validate_aggregators(&account, db, &mut errors).await?;
         let collector_credential = self
             .validate_collector_credential(
                 &account,
@@ -391,7 +392,7 @@ impl NewTask {
                 protocol,
             })
         } else {
-            Err(errors)
+            Err(errors.into())
         }
     }
 
@@ -101,6 +101,7 @@ impl ProvisionableTask {
             report_count: 0,
             aggregate_collection_count: 0,
             expiration: self.expiration,
+            expiration_notified_at: None,
             leader_aggregator_id: self.leader_aggregator.id,
             helper_aggregator_id: self.helper_aggregator.id,
             collector_credential_id: self.collector_credential.id,
@@ -122,6 +123,10 @@ impl ProvisionableTask {
             aggregation_job_counter_helper_task_expired: 0,
             aggregation_job_counter_helper_invalid_message: 0,
             aggregation_job_counter_helper_report_too_early: 0,
+            collection_job_counter_pending: 0,
+            collection_job_counter_abandoned: 0,
+            collection_job_counter_deleted: 0,
+            helper_metrics: None,
         }
         .into_active_model())
     }
@@ -1,15 +1,18 @@
 use crate::{
     clients::aggregator_client::api_types::{
         dp_strategies::{self, PureDpBudget, PureDpDiscreteLaplace},
-        AggregatorVdaf, HistogramType,
+        AggregatorVdaf, HistogramType, Prio3CountDp, Prio3CountWithDp,
     },
     entity::{aggregator::VdafName, Protocol},
 };
 use prio::vdaf::prio3::optimal_chunk_length;
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use std::{collections::HashSet, hash::Hash};
 use validator::{Validate, ValidationError, ValidationErrors};
 
+mod epsilon;
+pub use epsilon::EpsilonRatio;
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 #[serde(untagged)]
 pub enum Histogram {
@@ -132,18 +135,54 @@ pub enum DpStrategyKind {
     PureDpDiscreteLaplace,
 }
 
-#[derive(Serialize, Deserialize, Validate, Debug, Clone, Eq, PartialEq, Default)]
+/// Accepts an epsilon as limbs, as a decimal number or string, as a "numerator/denominator"
+/// string, or as an [`EpsilonRatio`]. Serializes both the limbs and an [`EpsilonRatio`].
+#[derive(Deserialize, Validate, Debug, Clone, Eq, PartialEq, Default)]
 pub struct DpBudget {
+    #[serde(default, deserialize_with = "epsilon::deserialize")]
     #[validate(length(equal = 2))]
     pub epsilon: Option<Vec<Vec<u32>>>,
 }
 
+impl Serialize for DpBudget {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DpBudget", 2)?;
+        state.serialize_field("epsilon", &self.epsilon)?;
+        state.serialize_field("epsilon_ratio", &self.epsilon_ratio())?;
+        state.end()
+    }
+}
+
+impl DpBudget {
+    pub fn epsilon_ratio(&self) -> Option<EpsilonRatio> {
+        EpsilonRatio::from_limbs(self.epsilon.as_deref()?)
+    }
+
+    /// The epsilon as a float, if it is a well-formed ratio of two little-endian base 2^32
+    /// integers.
+    pub fn epsilon_f64(&self) -> Option<f64> {
+        let [numerator, denominator] = self.epsilon.as_deref()? else {
+            return None;
+        };
+        let to_f64 = |digits: &[u32]| {
+            digits
+                .iter()
+                .rev()
+                .fold(0.0, |acc, digit| acc * 2f64.powi(32) + f64::from(*digit))
+        };
+        Some(to_f64(numerator) / to_f64(denominator))
+    }
+}
+
 impl DpStrategy {
-    fn representation_histogram(&self) -> Result<dp_strategies::Prio3Histogram, ValidationErrors> {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The aggregator representation of this strategy's noise, or None if it adds none.
+    fn pure_dp_discrete_laplace(&self) -> Result<Option<PureDpDiscreteLaplace>, ValidationErrors> {
         match (self.dp_strategy, &self.budget.epsilon) {
-            (DpStrategyKind::NoDifferentialPrivacy, None) => {
-                Ok(dp_strategies::Prio3Histogram::NoDifferentialPrivacy)
-            }
+            (DpStrategyKind::NoDifferentialPrivacy, None) => Ok(None),
             (DpStrategyKind::NoDifferentialPrivacy, Some(_))
             | (DpStrategyKind::PureDpDiscreteLaplace, None) => {
                 Err(dp_strategy_error("invalid_dp_strategy"))
@@ -153,36 +192,43 @@ impl DpStrategy {
                     .clone()
                     .try_into()
                     .map_err(|_| dp_strategy_error("invalid_epsilon"))?;
-                Ok(dp_strategies::Prio3Histogram::PureDpDiscreteLaplace(
-                    PureDpDiscreteLaplace {
-                        budget: PureDpBudget { epsilon },
-                    },
-                ))
+                Ok(Some(PureDpDiscreteLaplace {
+                    budget: PureDpBudget { epsilon },
+                }))
             }
         }
     }
 
+    fn representation_count(&self) -> Result<AggregatorVdaf, ValidationErrors> {
+        Ok(match self.pure_dp_discrete_laplace()? {
+            Some(strategy) => AggregatorVdaf::Prio3CountWithDp(Prio3CountWithDp {
+                prio3_count: Prio3CountDp {
+                    dp_strategy: dp_strategies::Prio3Count::PureDpDiscreteLaplace(strategy),
+                },
+            }),
+            None => AggregatorVdaf::Prio3Count,
+        })
+    }
+
+    fn representation_sum(&self) -> Result<dp_strategies::Prio3Sum, ValidationErrors> {
+        Ok(match self.pure_dp_discrete_laplace()? {
+            Some(strategy) => dp_strategies::Prio3Sum::PureDpDiscreteLaplace(strategy),
+            None => dp_strategies::Prio3Sum::NoDifferentialPrivacy,
+        })
+    }
+
+    fn representation_histogram(&self) -> Result<dp_strategies::Prio3Histogram, ValidationErrors> {
+        Ok(match self.pure_dp_discrete_laplace()? {
+            Some(strategy) => dp_strategies::Prio3Histogram::PureDpDiscreteLaplace(strategy),
+            None => dp_strategies::Prio3Histogram::NoDifferentialPrivacy,
+        })
+    }
+
     fn representation_sumvec(&self) -> Result<dp_strategies::Prio3SumVec, ValidationErrors> {
-        match (self.dp_strategy, &self.budget.epsilon) {
-            (DpStrategyKind::NoDifferentialPrivacy, None) => {
-                Ok(dp_strategies::Prio3SumVec::NoDifferentialPrivacy)
-            }
-            (DpStrategyKind::NoDifferentialPrivacy, Some(_))
-            | (DpStrategyKind::PureDpDiscreteLaplace, None) => {
-                Err(dp_strategy_error("invalid_dp_strategy"))
-            }
-            (DpStrategyKind::PureDpDiscreteLaplace, Some(epsilon)) => {
-                let epsilon: [Vec<u32>; 2] = epsilon
-                    .clone()
-                    .try_into()
-                    .map_err(|_| dp_strategy_error("invalid_epsilon"))?;
-                Ok(dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(
-                    PureDpDiscreteLaplace {
-                        budget: PureDpBudget { epsilon },
-                    },
-                ))
-            }
-        }
+        Ok(match self.pure_dp_discrete_laplace()? {
+            Some(strategy) => dp_strategies::Prio3SumVec::PureDpDiscreteLaplace(strategy),
+            None => dp_strategies::Prio3SumVec::NoDifferentialPrivacy,
+        })
     }
 }
 
@@ -228,10 +274,21 @@ fn increasing(buckets: &[u64]) -> Result<(), ValidationError> {
     Ok(())
 }
 
-#[derive(Serialize, Deserialize, Validate, Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, Eq, PartialEq, Default)]
+pub struct Count {
+    #[serde(default, skip_serializing_if = "DpStrategy::is_default")]
+    #[validate(nested, custom(function = "validate_dp_strategy"))]
+    pub dp_strategy: DpStrategy,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug, Clone, Eq, PartialEq)]
 pub struct Sum {
     #[validate(required)]
     pub bits: Option<u8>,
+
+    #[serde(default, skip_serializing_if = "DpStrategy::is_default")]
+    #[validate(nested, custom(function = "validate_dp_strategy"))]
+    pub dp_strategy: DpStrategy,
 }
 
 #[derive(Serialize, Deserialize, Validate, Debug, Clone, Copy, Eq, PartialEq)]
@@ -263,7 +320,7 @@ pub struct SumVec {
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Vdaf {
     #[serde(rename = "count")]
-    Count,
+    Count(Count),
 
     #[serde(rename = "histogram")]
     Histogram(Histogram),
@@ -282,9 +339,56 @@ pub enum Vdaf {
 }
 
 impl Vdaf {
+    /// One vdaf of every type that a task can be created with, using differential privacy
+    /// wherever the type accepts a `dp_strategy`.
+    pub fn creatable() -> [Vdaf; 5] {
+        let dp_strategy = DpStrategy {
+            dp_strategy: DpStrategyKind::PureDpDiscreteLaplace,
+            budget: DpBudget {
+                epsilon: Some(vec![vec![1], vec![1]]),
+            },
+        };
+        [
+            Vdaf::Count(Count {
+                dp_strategy: dp_strategy.clone(),
+            }),
+            Vdaf::Histogram(Histogram::Opaque(BucketLength {
+                length: 1,
+                chunk_length: None,
+                dp_strategy: dp_strategy.clone(),
+            })),
+            Vdaf::Sum(Sum {
+                bits: Some(1),
+                dp_strategy: dp_strategy.clone(),
+            }),
+            Vdaf::CountVec(CountVec {
+                length: Some(1),
+                chunk_length: None,
+            }),
+            Vdaf::SumVec(SumVec {
+                bits: Some(1),
+                length: Some(1),
+                chunk_length: None,
+                dp_strategy,
+            }),
+        ]
+    }
+
+    /// The `type` that this vdaf is serialized with.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Vdaf::Count(_) => "count",
+            Vdaf::Histogram(_) => "histogram",
+            Vdaf::Sum(_) => "sum",
+            Vdaf::CountVec(_) => "count_vec",
+            Vdaf::SumVec(_) => "sum_vec",
+            Vdaf::Unrecognized => "unrecognized",
+        }
+    }
+
     pub fn name(&self) -> VdafName {
         match self {
-            Vdaf::Count => VdafName::Prio3Count,
+            Vdaf::Count(_) => VdafName::Prio3Count,
             Vdaf::Histogram(_) => VdafName::Prio3Histogram,
             Vdaf::Sum(_) => VdafName::Prio3Sum,
             Vdaf::CountVec(_) => VdafName::Prio3Count,
@@ -293,14 +397,34 @@ impl Vdaf {
         }
     }
 
+    pub fn uses_pure_dp_discrete_laplace(&self) -> bool {
+        let dp_strategy = match self {
+            Vdaf::Count(Count { dp_strategy })
+            | Vdaf::Sum(Sum { dp_strategy, .. })
+            | Vdaf::SumVec(SumVec { dp_strategy, .. }) => dp_strategy,
+            Vdaf::Histogram(histogram) => histogram.dp_strategy(),
+            _ => return false,
+        };
+        matches!(
+            dp_strategy.dp_strategy,
+            DpStrategyKind::PureDpDiscreteLaplace
+        )
+    }
+
     pub fn representation_for_protocol(
         &self,
         protocol: &Protocol,
     ) -> Result<AggregatorVdaf, ValidationErrors> {
         match self {
             Self::Histogram(histogram) => histogram.representation_for_protocol(protocol),
-            Self::Count => Ok(AggregatorVdaf::Prio3Count),
-            Self::Sum(Sum { bits: Some(bits) }) => Ok(AggregatorVdaf::Prio3Sum { bits: *bits }),
+            Self::Count(Count { dp_strategy }) => dp_strategy.representation_count(),
+            Self::Sum(Sum {
+                bits: Some(bits),
+                dp_strategy,
+            }) => Ok(AggregatorVdaf::Prio3Sum {
+                bits: *bits,
+                dp_strategy: dp_strategy.representation_sum()?,
+            }),
             Self::SumVec(SumVec {
                 length: Some(length),
                 bits: Some(bits),
@@ -379,7 +503,7 @@ impl Vdaf {
             | Self::SumVec(SumVec { length: None, .. }) => {}
 
             // Chunk length is not applicable due to VDAF choice.
-            Self::Count | Self::Sum { .. } | Self::Unrecognized => {}
+            Self::Count(_) | Self::Sum(_) | Self::Unrecognized => {}
         }
     }
 }
@@ -387,7 +511,7 @@ impl Vdaf {
 impl Validate for Vdaf {
     fn validate(&self) -> Result<(), ValidationErrors> {
         match self {
-            Vdaf::Count => Ok(()),
+            Vdaf::Count(c) => c.validate(),
             Vdaf::Histogram(Histogram::Continuous(buckets)) => buckets.validate(),
             Vdaf::Histogram(Histogram::Categorical(buckets)) => buckets.validate(),
             Vdaf::Histogram(Histogram::Opaque(length)) => length.validate(),
@@ -410,6 +534,20 @@ mod tests {
 
     mod serde;
 
+    #[test]
+    fn creatable() {
+        for vdaf in Vdaf::creatable() {
+            assert!(vdaf.validate().is_ok(), "{vdaf:?}");
+            let round_trip: Vdaf =
+                serde_json::from_value(serde_json::to_value(&vdaf).unwrap()).unwrap();
+            assert_eq!(round_trip, vdaf);
+            assert_eq!(
+                serde_json::to_value(&vdaf).unwrap()["type"],
+                vdaf.type_name()
+            );
+        }
+    }
+
     #[test]
     fn validate_continuous_histogram() {
         assert!(ContinuousBuckets {
@@ -0,0 +1,106 @@
+use num_bigint_5::BigUint;
+use num_rational::Ratio;
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+
+/// An epsilon with its numerator and denominator written in decimal, for readability.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct EpsilonRatio {
+    pub numerator: String,
+    pub denominator: String,
+}
+
+impl EpsilonRatio {
+    pub(super) fn from_limbs(limbs: &[Vec<u32>]) -> Option<Self> {
+        let [numerator, denominator] = limbs else {
+            return None;
+        };
+        Some(Self {
+            numerator: BigUint::new(numerator.clone()).to_string(),
+            denominator: BigUint::new(denominator.clone()).to_string(),
+        })
+    }
+}
+
+/// Every form that an epsilon is accepted in.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EpsilonInput {
+    /// Little-endian base 2^32 digits of the numerator and denominator, as stored.
+    Limbs(Vec<Vec<u32>>),
+    Number(f64),
+    /// A decimal such as "0.25", or a fraction such as "1/3".
+    String(String),
+    Ratio {
+        numerator: Integer,
+        denominator: Integer,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Integer {
+    Number(u64),
+    String(String),
+}
+
+impl Integer {
+    fn parse(&self) -> Option<BigUint> {
+        match self {
+            Integer::Number(number) => Some(BigUint::from(*number)),
+            Integer::String(string) => parse_digits(string.trim()),
+        }
+    }
+}
+
+/// Deserializes any [`EpsilonInput`] into limbs. Fractions that were not given as limbs are
+/// reduced, and must be positive.
+pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<Vec<u32>>>, D::Error> {
+    let ratio = match Option::<EpsilonInput>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(EpsilonInput::Limbs(limbs)) => return Ok(Some(limbs)),
+        Some(EpsilonInput::Number(number)) => parse_decimal(&number.to_string()),
+        Some(EpsilonInput::String(string)) => parse_decimal(string.trim()),
+        Some(EpsilonInput::Ratio {
+            numerator,
+            denominator,
+        }) => numerator
+            .parse()
+            .zip(denominator.parse())
+            .and_then(|(numerator, denominator)| ratio(numerator, denominator)),
+    };
+    let ratio =
+        ratio.ok_or_else(|| D::Error::custom("epsilon must be a positive decimal or fraction"))?;
+    Ok(Some(Vec::from([
+        ratio.numer().to_u32_digits(),
+        ratio.denom().to_u32_digits(),
+    ])))
+}
+
+fn parse_decimal(decimal: &str) -> Option<Ratio<BigUint>> {
+    if let Some((numerator, denominator)) = decimal.split_once('/') {
+        return ratio(
+            parse_digits(numerator.trim())?,
+            parse_digits(denominator.trim())?,
+        );
+    }
+
+    let (whole, fraction) = decimal.split_once('.').unwrap_or((decimal, ""));
+    let numerator = parse_digits(&format!("{whole}{fraction}"))?;
+    let places = u32::try_from(fraction.len()).ok()?;
+    ratio(numerator, BigUint::from(10u32).pow(places))
+}
+
+/// BigUint's parser also accepts signs and underscores, so check for plain digits first.
+fn parse_digits(digits: &str) -> Option<BigUint> {
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn ratio(numerator: BigUint, denominator: BigUint) -> Option<Ratio<BigUint>> {
+    (numerator != BigUint::ZERO && denominator != BigUint::ZERO)
+        .then(|| Ratio::new(numerator, denominator))
+}
@@ -1,12 +1,12 @@
 use crate::entity::task::vdaf::{
-    BucketLength, CategoricalBuckets, ContinuousBuckets, CountVec, DpBudget, DpStrategy,
+    BucketLength, CategoricalBuckets, ContinuousBuckets, Count, CountVec, DpBudget, DpStrategy,
     DpStrategyKind, Histogram, Sum, SumVec, Vdaf,
 };
 
 #[test]
 fn json_vdaf() {
     for (serialized, vdaf) in [
-        (r#"{"type":"count"}"#, Vdaf::Count),
+        (r#"{"type":"count"}"#, Vdaf::Count(Count::default())),
         (
             r#"{"type":"histogram","buckets":["A","B"]}"#,
             Vdaf::Histogram(Histogram::Categorical(CategoricalBuckets {
@@ -57,7 +57,10 @@ fn json_vdaf() {
         ),
         (
             r#"{"type":"sum","bits":8}"#,
-            Vdaf::Sum(Sum { bits: Some(8) }),
+            Vdaf::Sum(Sum {
+                bits: Some(8),
+                dp_strategy: DpStrategy::default(),
+            }),
         ),
         (
             r#"{"type":"count_vec","length":5}"#,
@@ -103,6 +106,29 @@ fn json_vdaf() {
                 },
             })),
         ),
+        (
+            r#"{"type":"count","dp_strategy":{"dp_strategy":"PureDpDiscreteLaplace","budget":{"epsilon":[[1],[1]]}}}"#,
+            Vdaf::Count(Count {
+                dp_strategy: DpStrategy {
+                    dp_strategy: DpStrategyKind::PureDpDiscreteLaplace,
+                    budget: DpBudget {
+                        epsilon: Some(Vec::from([Vec::from([1]), Vec::from([1])])),
+                    },
+                },
+            }),
+        ),
+        (
+            r#"{"type":"sum","bits":8,"dp_strategy":{"dp_strategy":"PureDpDiscreteLaplace","budget":{"epsilon":"0.5"}}}"#,
+            Vdaf::Sum(Sum {
+                bits: Some(8),
+                dp_strategy: DpStrategy {
+                    dp_strategy: DpStrategyKind::PureDpDiscreteLaplace,
+                    budget: DpBudget {
+                        epsilon: Some(Vec::from([Vec::from([1]), Vec::from([2])])),
+                    },
+                },
+            }),
+        ),
         (
             r#"{"type":"sum_vec","bits":2,"length":8,"chunk_length":4,"dp_strategy":{"dp_strategy":"PureDpDiscreteLaplace","budget":{"epsilon":[[1],[1]]}}}"#,
             Vdaf::SumVec(SumVec {
@@ -121,3 +147,66 @@ fn json_vdaf() {
         assert_eq!(serde_json::from_str::<Vdaf>(serialized).unwrap(), vdaf);
     }
 }
+
+#[test]
+fn json_epsilon() {
+    for (epsilon, limbs) in [
+        (r#"[[1],[2]]"#, Vec::from([Vec::from([1]), Vec::from([2])])),
+        (r#""0.25""#, Vec::from([Vec::from([1]), Vec::from([4])])),
+        (r#"0.5"#, Vec::from([Vec::from([1]), Vec::from([2])])),
+        (r#"3"#, Vec::from([Vec::from([3]), Vec::from([1])])),
+        (r#"" 2/6 ""#, Vec::from([Vec::from([1]), Vec::from([3])])),
+        (
+            r#"{"numerator":"8589934592","denominator":3}"#,
+            Vec::from([Vec::from([0, 2]), Vec::from([3])]),
+        ),
+    ] {
+        let budget: DpBudget =
+            serde_json::from_str(&format!(r#"{{"epsilon":{epsilon}}}"#)).unwrap();
+        assert_eq!(budget.epsilon, Some(limbs), "{epsilon}");
+    }
+
+    for epsilon in [
+        r#""""#,
+        r#""-1""#,
+        r#""1e-3""#,
+        r#""1_000""#,
+        r#""1/0""#,
+        r#""0.0""#,
+        r#"{"numerator":1,"denominator":0}"#,
+    ] {
+        assert!(
+            serde_json::from_str::<DpBudget>(&format!(r#"{{"epsilon":{epsilon}}}"#)).is_err(),
+            "{epsilon}"
+        );
+    }
+
+    assert_eq!(
+        serde_json::from_str::<DpBudget>("{}").unwrap(),
+        DpBudget { epsilon: None }
+    );
+    assert_eq!(
+        serde_json::to_value(DpBudget {
+            epsilon: Some(Vec::from([Vec::from([0, 2]), Vec::from([3])])),
+        })
+        .unwrap(),
+        serde_json::json!({
+            "epsilon": [[0, 2], [3]],
+            "epsilon_ratio": {"numerator": "8589934592", "denominator": "3"},
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(DpBudget { epsilon: None }).unwrap(),
+        serde_json::json!({"epsilon": null, "epsilon_ratio": null})
+    );
+}
+
+#[test]
+fn json_vdaf_without_dp_strategy() {
+    // count and sum tasks without noise keep the representation they had before they could
+    // carry a dp_strategy
+    for serialized in [r#"{"type":"count"}"#, r#"{"type":"sum","bits":8}"#] {
+        let vdaf: Vdaf = serde_json::from_str(serialized).unwrap();
+        assert_eq!(serde_json::to_string(&vdaf).unwrap(), serialized);
+    }
+}
@@ -0,0 +1,111 @@
+use super::{json::Json, task::TaskCounter, Task, TaskColumn, Tasks};
+use sea_orm::{
+    sea_query::all, ActiveModelBehavior, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr,
+    DeriveEntityModel, DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel,
+    PrimaryKeyTrait, QueryFilter, QueryOrder, Related, RelationDef, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+/// How long snapshots are kept, which bounds how far back alert rules can look.
+pub const RETENTION: Duration = Duration::days(7);
+
+/// A task's counters as of one metrics refresh. Counters only ever increase, so the difference
+/// between two snapshots is how much each counter moved between them.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "task_counter_snapshot")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub task_id: String,
+    pub counters: Json<BTreeMap<TaskCounter, i64>>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub captured_at: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Tasks",
+        from = "Column::TaskId",
+        to = "TaskColumn::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Task,
+}
+
+impl Related<Tasks> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn counter(&self, counter: TaskCounter) -> i64 {
+        self.counters.get(&counter).copied().unwrap_or_default()
+    }
+}
+
+impl Entity {
+    pub async fn record(task: &Task, db: &impl ConnectionTrait) -> Result<Model, DbErr> {
+        Model {
+            id: Uuid::new_v4(),
+            task_id: task.id.clone(),
+            counters: Json(task.counters()),
+            captured_at: OffsetDateTime::now_utc(),
+        }
+        .into_active_model()
+        .insert(db)
+        .await
+    }
+
+    /// The snapshot that a window of this length ending now should be measured from: the most
+    /// recent one taken before the window started, or the oldest one within the window if the
+    /// history does not reach back that far. Tasks created within the window are measured from
+    /// zero, since every count they have happened inside it.
+    pub async fn baseline(
+        task: &Task,
+        window: Duration,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<Model>, DbErr> {
+        let window_start = OffsetDateTime::now_utc() - window;
+        if task.created_at >= window_start {
+            return Ok(None);
+        }
+
+        let before_window = Self::find()
+            .filter(all![
+                Column::TaskId.eq(&task.id),
+                Column::CapturedAt.lte(window_start),
+            ])
+            .order_by_desc(Column::CapturedAt)
+            .one(db)
+            .await?;
+        if before_window.is_some() {
+            return Ok(before_window);
+        }
+
+        Self::find()
+            .filter(Column::TaskId.eq(&task.id))
+            .order_by_asc(Column::CapturedAt)
+            .one(db)
+            .await
+    }
+
+    /// Deletes snapshots of a task that are too old for any alert rule to use.
+    pub async fn clean_up(task_id: &str, db: &impl ConnectionTrait) -> Result<(), DbErr> {
+        Self::delete_many()
+            .filter(all![
+                Column::TaskId.eq(task_id),
+                Column::CapturedAt.lt(OffsetDateTime::now_utc() - RETENTION),
+            ])
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
+use crate::{
+    clients::ssrf::{is_private_ip, is_private_ipv4, is_private_ipv6},
+    handler::Error,
+};
 use sea_orm::prelude::StringLen;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -5,6 +9,10 @@ use std::{
     ops::{Deref, DerefMut},
     str::FromStr,
 };
+use tokio::net::lookup_host;
+use url::Host;
+use validator::{ValidationError, ValidationErrors};
+
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(transparent)]
 pub struct Url(url::Url);
@@ -107,3 +115,104 @@ impl sea_orm::sea_query::Nullable for Url {
         String::null()
     }
 }
+
+#[cfg_attr(feature = "integration-testing", allow(dead_code))]
+pub(super) fn https(url: &str) -> Result<(), ValidationError> {
+    let url = url::Url::from_str(url).map_err(|_| ValidationError::new("https-url"))?;
+    if url.scheme() != "https" {
+        return Err(ValidationError::new("https-url"));
+    }
+    Ok(())
+}
+
+/// Rejects urls that refer to loopback, private, link-local or otherwise non-public addresses,
+/// reporting the problem as a validation error on `field`.
+pub(super) async fn validate_public_url(url: &url::Url, field: &'static str) -> Result<(), Error> {
+    let ssrf_error = |code: &'static str| -> Error {
+        let mut ve = ValidationErrors::new();
+        ve.add(field, ValidationError::new(code));
+        ve.into()
+    };
+
+    let host = url.host_str().ok_or_else(|| ssrf_error("invalid-url"))?;
+    let port = url.port().unwrap_or(443);
+
+    // For IP-literal hosts, check directly without DNS.
+    match url.host() {
+        Some(Host::Ipv4(ip)) => {
+            if is_private_ipv4(ip) {
+                return Err(ssrf_error("private-address"));
+            }
+            return Ok(());
+        }
+        Some(Host::Ipv6(ip)) => {
+            if is_private_ipv6(ip) {
+                return Err(ssrf_error("private-address"));
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Reject "localhost" before resolving.
+    if host.eq_ignore_ascii_case("localhost") {
+        return Err(ssrf_error("private-address"));
+    }
+
+    // Resolve the hostname and check all resulting addresses.
+    let addrs = lookup_host(format!("{host}:{port}"))
+        .await
+        .map_err(|_| ssrf_error("dns-resolution-failed"))?;
+
+    for addr in addrs {
+        if is_private_ip(addr.ip()) {
+            return Err(ssrf_error("private-address"));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> url::Url {
+        url::Url::parse(s).unwrap()
+    }
+
+    #[tokio::test]
+    async fn validate_rejects_private_ip_literals() {
+        assert!(
+            validate_public_url(&url("https://169.254.169.254/latest/"), "api_url")
+                .await
+                .is_err()
+        );
+        assert!(validate_public_url(&url("https://127.0.0.1/"), "api_url")
+            .await
+            .is_err());
+        assert!(validate_public_url(&url("https://10.0.0.1/"), "api_url")
+            .await
+            .is_err());
+        assert!(validate_public_url(&url("https://[::1]/"), "api_url")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn validate_rejects_localhost_hostname() {
+        assert!(validate_public_url(&url("https://localhost/"), "api_url")
+            .await
+            .is_err());
+        assert!(validate_public_url(&url("https://LOCALHOST/"), "api_url")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn validate_accepts_public_ip_literal() {
+        assert!(validate_public_url(&url("https://8.8.8.8/"), "api_url")
+            .await
+            .is_ok());
+    }
+}
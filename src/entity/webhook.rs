@@ -0,0 +1,283 @@
+use super::{json::Json, url::validate_public_url, Account, AccountColumn, Accounts};
+use crate::{
+    queue::{DeliverWebhook, Job},
+    Crypter, Error,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::random;
+use sea_orm::{
+    ActiveModelBehavior, ActiveValue, ColumnTrait, ConnectionTrait, DbErr, DeriveEntityModel,
+    DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel, PrimaryKeyTrait,
+    QueryFilter, Related, RelationDef, RelationTrait,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Debug;
+use time::OffsetDateTime;
+use uuid::Uuid;
+use validator::Validate;
+
+mod event;
+mod event_type;
+pub use event::{signature, WebhookEvent};
+pub use event_type::WebhookEventType;
+
+const SECRET_IDENTIFIER: &str = "whsec_";
+
+/// Carries the signature of a webhook request, as described by [`signature`].
+pub const SIGNATURE_HEADER: &str = "x-divviup-signature";
+/// Carries the type of the event, so receivers can route requests without parsing them.
+pub const EVENT_HEADER: &str = "x-divviup-event";
+/// Carries the id of the event, which is the same for every delivery attempt.
+pub const DELIVERY_HEADER: &str = "x-divviup-delivery";
+
+#[derive(Clone, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub account_id: Uuid,
+    pub name: Option<String>,
+    #[serde(skip)]
+    pub encrypted_url: Vec<u8>,
+    #[serde(skip)]
+    pub encrypted_secret: Vec<u8>,
+    // None subscribes to every event type
+    pub event_types: Option<Json<Vec<WebhookEventType>>>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+    #[serde(default, with = "::time::serde::rfc3339::option")]
+    pub deleted_at: Option<OffsetDateTime>,
+    // decrypted from encrypted_url for responses
+    #[sea_orm(ignore)]
+    #[serde(default)]
+    pub url: String,
+    // only present in the response to creating a webhook
+    #[sea_orm(ignore)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+impl Debug for Model {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Webhook")
+            .field("id", &self.id)
+            .field("account_id", &self.account_id)
+            .field("name", &self.name)
+            .field("event_types", &self.event_types)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("deleted_at", &self.deleted_at)
+            .finish()
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Accounts",
+        from = "Column::AccountId",
+        to = "AccountColumn::Id"
+    )]
+    Account,
+}
+
+impl Related<Accounts> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// The id is bound into each ciphertext so that encrypted values cannot be moved between webhooks
+// or between the two columns.
+fn url_associated_data(id: Uuid) -> Vec<u8> {
+    format!("webhook/{id}/url").into_bytes()
+}
+
+fn secret_associated_data(id: Uuid) -> Vec<u8> {
+    format!("webhook/{id}/secret").into_bytes()
+}
+
+impl Model {
+    pub fn decrypt_url(&self, crypter: &Crypter) -> Result<url::Url, Error> {
+        let url = crypter.decrypt(&url_associated_data(self.id), &self.encrypted_url)?;
+        Ok(String::from_utf8(url)?.parse()?)
+    }
+
+    pub fn secret(&self, crypter: &Crypter) -> Result<String, Error> {
+        let secret = crypter.decrypt(&secret_associated_data(self.id), &self.encrypted_secret)?;
+        String::from_utf8(secret).map_err(Into::into)
+    }
+
+    /// Fills in [`Model::url`] for a response.
+    pub fn with_url(mut self, crypter: &Crypter) -> Result<Self, Error> {
+        self.url = self.decrypt_url(crypter)?.into();
+        Ok(self)
+    }
+
+    pub fn subscribes_to(&self, event_type: WebhookEventType) -> bool {
+        self.event_types
+            .as_ref()
+            .is_none_or(|event_types| event_types.contains(&event_type))
+    }
+
+    pub fn tombstone(self) -> ActiveModel {
+        let mut webhook = self.into_active_model();
+        webhook.deleted_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
+        webhook.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        webhook
+    }
+
+    pub fn is_tombstoned(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+}
+
+fn event_types(event_types: Option<Vec<WebhookEventType>>) -> Option<Json<Vec<WebhookEventType>>> {
+    event_types.map(|mut event_types| {
+        event_types.sort();
+        event_types.dedup();
+        Json(event_types)
+    })
+}
+
+async fn encrypt_url(
+    id: Uuid,
+    url: &str,
+    crypter: &Crypter,
+    ssrf_validation_enabled: bool,
+) -> Result<Vec<u8>, Error> {
+    let url: url::Url = url.parse()?;
+    if ssrf_validation_enabled {
+        validate_public_url(&url, "url").await?;
+    }
+    Ok(crypter.encrypt(&url_associated_data(id), url.as_str().as_bytes())?)
+}
+
+#[derive(Deserialize, Serialize, Validate, Debug, Clone, Default)]
+pub struct NewWebhook {
+    #[validate(length(max = 255))]
+    pub name: Option<String>,
+    #[cfg_attr(
+        not(feature = "integration-testing"),
+        validate(custom(function = "crate::entity::url::https"))
+    )]
+    #[validate(required, length(max = 2048))]
+    pub url: Option<String>,
+    #[validate(length(min = 1))]
+    pub event_types: Option<Vec<WebhookEventType>>,
+}
+
+impl NewWebhook {
+    /// Builds a webhook for the account and the secret that its requests are signed with, which
+    /// is only ever shown once.
+    pub async fn build(
+        self,
+        account: &Account,
+        crypter: &Crypter,
+        ssrf_validation_enabled: bool,
+    ) -> Result<(ActiveModel, String), Error> {
+        self.validate()?;
+        let id = Uuid::new_v4();
+        // unwrap safety: validate requires a url
+        let encrypted_url = encrypt_url(
+            id,
+            self.url.as_deref().unwrap(),
+            crypter,
+            ssrf_validation_enabled,
+        )
+        .await?;
+        let secret = format!(
+            "{SECRET_IDENTIFIER}{}",
+            URL_SAFE_NO_PAD.encode(random::<[u8; 32]>())
+        );
+        let encrypted_secret = crypter.encrypt(&secret_associated_data(id), secret.as_bytes())?;
+        let webhook = Model {
+            id,
+            account_id: account.id,
+            name: self.name.filter(|name| !name.is_empty()),
+            encrypted_url,
+            encrypted_secret,
+            event_types: event_types(self.event_types),
+            created_at: OffsetDateTime::now_utc(),
+            updated_at: OffsetDateTime::now_utc(),
+            deleted_at: None,
+            url: String::new(),
+            secret: None,
+        };
+        Ok((webhook.into_active_model(), secret))
+    }
+}
+
+#[derive(Deserialize, Serialize, Validate, Debug, Clone, Default)]
+pub struct UpdateWebhook {
+    #[validate(length(max = 255))]
+    pub name: Option<String>,
+    #[cfg_attr(
+        not(feature = "integration-testing"),
+        validate(custom(function = "crate::entity::url::https"))
+    )]
+    #[validate(length(max = 2048))]
+    pub url: Option<String>,
+    #[validate(length(min = 1))]
+    pub event_types: Option<Vec<WebhookEventType>>,
+    /// subscribe to every event type, including ones added later
+    #[serde(default)]
+    pub all_event_types: bool,
+}
+
+impl UpdateWebhook {
+    pub async fn build(
+        self,
+        webhook: Model,
+        crypter: &Crypter,
+        ssrf_validation_enabled: bool,
+    ) -> Result<ActiveModel, Error> {
+        self.validate()?;
+        let id = webhook.id;
+        let mut webhook = webhook.into_active_model();
+        if let Some(name) = self.name {
+            webhook.name = ActiveValue::Set(Some(name).filter(|name| !name.is_empty()));
+        }
+        if let Some(url) = self.url {
+            webhook.encrypted_url =
+                ActiveValue::Set(encrypt_url(id, &url, crypter, ssrf_validation_enabled).await?);
+        }
+        if self.all_event_types {
+            webhook.event_types = ActiveValue::Set(None);
+        } else if self.event_types.is_some() {
+            webhook.event_types = ActiveValue::Set(event_types(self.event_types));
+        }
+        webhook.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        Ok(webhook)
+    }
+}
+
+impl Entity {
+    /// Queues delivery of an event to each of the account's webhooks that subscribe to it.
+    pub async fn enqueue(
+        account_id: Uuid,
+        event_type: WebhookEventType,
+        data: Value,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        let webhooks = Self::find()
+            .filter(Column::AccountId.eq(account_id))
+            .filter(Column::DeletedAt.is_null())
+            .all(db)
+            .await?;
+        let event = WebhookEvent::new(account_id, event_type, data);
+        for webhook in webhooks {
+            if webhook.subscribes_to(event_type) {
+                Job::from(DeliverWebhook::new(webhook.id, event.clone()))
+                    .insert(db)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use super::WebhookEventType;
+use hmac::{Hmac, KeyInit, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::fmt::Write;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// The body of every webhook request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WebhookEvent {
+    /// shared by every delivery attempt of this event, so receivers can ignore duplicates
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    pub account_id: Uuid,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    pub data: Value,
+}
+
+impl WebhookEvent {
+    pub fn new(account_id: Uuid, event_type: WebhookEventType, data: Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event_type,
+            account_id,
+            created_at: OffsetDateTime::now_utc(),
+            data,
+        }
+    }
+}
+
+/// The value of the signature header for a request body sent at `timestamp`, in the form
+/// `t=<unix timestamp>,v1=<hex hmac-sha256>`. The signed message is the timestamp and the body
+/// joined by a period, so that a captured request cannot be replayed later with a new timestamp.
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    // unwrap safety: hmac accepts keys of any length
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    let digest = mac.finalize().into_bytes();
+    let mut signature = format!("t={timestamp},v1=");
+    for byte in digest {
+        let _ = write!(signature, "{byte:02x}");
+    }
+    signature
+}
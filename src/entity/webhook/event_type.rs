@@ -0,0 +1,61 @@
+use sea_orm::{prelude::StringLen, ActiveEnum, DeriveActiveEnum, EnumIter};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// Something that happened in an account that webhooks can subscribe to.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum WebhookEventType {
+    #[sea_orm(string_value = "task.created")]
+    #[serde(rename = "task.created")]
+    TaskCreated,
+    #[sea_orm(string_value = "task.expired")]
+    #[serde(rename = "task.expired")]
+    TaskExpired,
+    #[sea_orm(string_value = "task.deleted")]
+    #[serde(rename = "task.deleted")]
+    TaskDeleted,
+    #[sea_orm(string_value = "membership.created")]
+    #[serde(rename = "membership.created")]
+    MembershipCreated,
+    #[sea_orm(string_value = "membership.updated")]
+    #[serde(rename = "membership.updated")]
+    MembershipUpdated,
+    #[sea_orm(string_value = "membership.deleted")]
+    #[serde(rename = "membership.deleted")]
+    MembershipDeleted,
+    #[sea_orm(string_value = "aggregator.decommissioned")]
+    #[serde(rename = "aggregator.decommissioned")]
+    AggregatorDecommissioned,
+    #[sea_orm(string_value = "aggregator.capabilities_removed")]
+    #[serde(rename = "aggregator.capabilities_removed")]
+    AggregatorCapabilitiesRemoved,
+    #[sea_orm(string_value = "aggregator.deleted")]
+    #[serde(rename = "aggregator.deleted")]
+    AggregatorDeleted,
+    #[sea_orm(string_value = "alert.fired")]
+    #[serde(rename = "alert.fired")]
+    AlertFired,
+    #[sea_orm(string_value = "alert.resolved")]
+    #[serde(rename = "alert.resolved")]
+    AlertResolved,
+}
+
+impl Display for WebhookEventType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_value())
+    }
+}
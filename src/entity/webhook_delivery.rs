@@ -0,0 +1,95 @@
+use super::{WebhookColumn, WebhookEventType, Webhooks};
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, DeriveEntityModel,
+    DerivePrimaryKey, DeriveRelation, EntityTrait, EnumIter, IntoActiveModel, PrimaryKeyTrait,
+    QueryFilter, QueryOrder, QuerySelect, Related, RelationDef, RelationTrait, Select,
+};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+/// The most deliveries returned by one listing.
+const LIMIT: u64 = 100;
+
+/// How long delivery attempts are kept before [`Entity::clean_up`] deletes them.
+pub const RETENTION: Duration = Duration::days(30);
+
+/// One attempt to deliver an event to a webhook.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "webhook_delivery")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: WebhookEventType,
+    /// starts at 1 and counts up with each retry of the same event
+    pub attempt: i32,
+    /// the http status the webhook responded with, if it responded at all
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    #[serde(with = "::time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "Webhooks",
+        from = "Column::WebhookId",
+        to = "WebhookColumn::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Webhook,
+}
+
+impl Related<Webhooks> for Entity {
+    fn to() -> RelationDef {
+        Relation::Webhook.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Entity {
+    /// The most recent delivery attempts for a webhook, newest first.
+    pub fn for_webhook(webhook_id: Uuid) -> Select<Self> {
+        Self::find()
+            .filter(Column::WebhookId.eq(webhook_id))
+            .order_by_desc(Column::CreatedAt)
+            .limit(LIMIT)
+    }
+
+    pub async fn clean_up(db: &impl ConnectionTrait) -> Result<(), DbErr> {
+        Self::delete_many()
+            .filter(Column::CreatedAt.lt(OffsetDateTime::now_utc() - RETENTION))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn record(
+        webhook_id: Uuid,
+        event_id: Uuid,
+        event_type: WebhookEventType,
+        attempt: i32,
+        status_code: Option<u16>,
+        error: Option<String>,
+        db: &impl ConnectionTrait,
+    ) -> Result<Model, DbErr> {
+        Model {
+            id: Uuid::new_v4(),
+            webhook_id,
+            event_id,
+            event_type,
+            attempt,
+            status_code: status_code.map(i32::from),
+            error,
+            created_at: OffsetDateTime::now_utc(),
+        }
+        .into_active_model()
+        .insert(db)
+        .await
+    }
+}
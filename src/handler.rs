@@ -1,4 +1,5 @@
 pub(crate) mod account_bearer_token;
+pub(crate) mod api_token_usage;
 pub(crate) mod assets;
 pub(crate) mod cors;
 pub(crate) mod custom_mime_types;
@@ -17,7 +18,7 @@ use axum::{
     body::Body,
     extract::{DefaultBodyLimit, FromRef},
     http::{header, HeaderValue, Request},
-    routing,
+    routing, Extension,
 };
 use cors::axum_cors_layer;
 use http_metrics::HttpMetrics;
@@ -29,6 +30,7 @@ use tower_http::{
     compression::CompressionLayer, set_header::SetResponseHeaderLayer, trace::TraceLayer,
 };
 
+pub use api_token_usage::ApiTokenUsageBuffer;
 pub use error::Error;
 
 /// Shared state for the Axum application.
@@ -95,6 +97,7 @@ pub struct BuiltApp {
     pub router: axum::Router,
     pub db: Db,
     pub config: Arc<Config>,
+    pub api_token_usage: ApiTokenUsageBuffer,
 }
 
 /// Build the Axum application router and connect to the database.
@@ -103,6 +106,7 @@ pub async fn build_app(config: Config) -> BuiltApp {
     let db = Db::connect(config.database_url.as_ref()).await;
 
     let auth0_client = Auth0Client::new(&config);
+    let api_token_usage = ApiTokenUsageBuffer::default();
     let axum_state = AxumAppState {
         db: db.clone(),
         config: config.clone(),
@@ -110,7 +114,7 @@ pub async fn build_app(config: Config) -> BuiltApp {
         oauth_client: OauthClient::new(&config.oauth_config()),
         crypter: config.crypter.clone(),
         feature_flags: config.feature_flags(),
-        client: config.client.clone(),
+        client: config.user_url_client(),
     };
 
     let middleware = ServiceBuilder::new()
@@ -140,6 +144,7 @@ pub async fn build_app(config: Config) -> BuiltApp {
             }),
         )
         .layer(DefaultBodyLimit::max(MAX_REQUEST_BODY_SIZE))
+        .layer(Extension(api_token_usage.clone()))
         .layer(CompressionLayer::new())
         .layer(SetResponseHeaderLayer::if_not_present(
             header::CACHE_CONTROL,
@@ -165,7 +170,12 @@ pub async fn build_app(config: Config) -> BuiltApp {
         .layer(middleware)
         .with_state(axum_state);
 
-    BuiltApp { router, db, config }
+    BuiltApp {
+        router,
+        db,
+        config,
+        api_token_usage,
+    }
 }
 
 /// Axum middleware that injects an admin [`User`](crate::User) into every
@@ -1,10 +1,10 @@
+use super::ApiTokenUsageBuffer;
 use crate::{
     entity::{Account, ApiToken, ApiTokens},
     Db,
 };
 use axum::extract::FromRef;
 use axum::http::{header, request::Parts};
-use sea_orm::ActiveModelTrait;
 
 #[derive(Clone, Debug)]
 pub struct AccountBearerToken {
@@ -36,7 +36,10 @@ impl AccountBearerToken {
 
         let db = Db::from_ref(state);
         let (api_token, account) = ApiTokens::load_and_check(bearer, &db).await?;
-        let api_token = api_token.mark_last_used().update(&db).await.ok()?;
+        // usage, including last_used_at, is written in batches by the buffer's flusher
+        if let Some(usage) = parts.extensions.get::<ApiTokenUsageBuffer>() {
+            usage.record_request(api_token.id, parts);
+        }
         let result = Self { account, api_token };
         parts.extensions.insert(result.clone());
         Some(result)
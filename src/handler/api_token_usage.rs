@@ -0,0 +1,112 @@
+use crate::{
+    entity::{
+        api_token_usage::{hour_of, PendingUsage},
+        ApiTokenUsages,
+    },
+    Db,
+};
+use axum::{
+    extract::{ConnectInfo, MatchedPath},
+    http::request::Parts,
+};
+use sea_orm::{DbErr, TransactionTrait};
+use std::{
+    collections::HashMap,
+    mem,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use time::OffsetDateTime;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// How often buffered api token usage is written to the database.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Api token usage that has been recorded in memory but not yet written, so that authenticating
+/// a request does not require a database write.
+#[derive(Clone, Debug, Default)]
+pub struct ApiTokenUsageBuffer(Arc<Mutex<HashMap<(Uuid, OffsetDateTime), PendingUsage>>>);
+
+async fn write(
+    api_token_id: Uuid,
+    hour: OffsetDateTime,
+    usage: PendingUsage,
+    db: &Db,
+) -> Result<(), DbErr> {
+    let tx = db.begin().await?;
+    ApiTokenUsages::add(api_token_id, hour, usage, &tx).await?;
+    tx.commit().await
+}
+
+impl ApiTokenUsageBuffer {
+    pub fn record(&self, api_token_id: Uuid, route: Option<String>, source_ip: Option<String>) {
+        let now = OffsetDateTime::now_utc();
+        let mut pending = self.0.lock().unwrap();
+        let usage = pending
+            .entry((api_token_id, hour_of(now)))
+            .or_insert_with(|| PendingUsage::new(now));
+        usage.last_used_at = usage.last_used_at.max(now);
+        usage.record(route, source_ip);
+    }
+
+    pub(crate) fn record_request(&self, api_token_id: Uuid, parts: &Parts) {
+        let route = parts
+            .extensions
+            .get::<MatchedPath>()
+            .map(|path| format!("{} {}", parts.method, path.as_str()));
+        let source_ip = parts
+            .headers
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.rsplit(',').next())
+            .map(|ip| ip.trim().to_string())
+            .filter(|ip| !ip.is_empty())
+            // requests that did not come through a proxy are attributed to their peer
+            .or_else(|| {
+                parts
+                    .extensions
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(address)| address.ip().to_string())
+            });
+        self.record(api_token_id, route, source_ip);
+    }
+
+    /// This token's usage that has not been written yet, by hour.
+    pub fn pending_for(&self, api_token_id: Uuid) -> Vec<(OffsetDateTime, PendingUsage)> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((id, _), _)| *id == api_token_id)
+            .map(|((_, hour), usage)| (*hour, usage.clone()))
+            .collect()
+    }
+
+    /// Writes all buffered usage. Usage that fails to write is logged and dropped rather than
+    /// retried, since it is informational.
+    pub async fn flush(&self, db: &Db) {
+        let pending = mem::take(&mut *self.0.lock().unwrap());
+        for ((api_token_id, hour), usage) in pending {
+            if let Err(error) = write(api_token_id, hour, usage, db).await {
+                tracing::warn!(?error, %api_token_id, "failed to write api token usage");
+            }
+        }
+    }
+
+    /// Flushes periodically until cancelled, and once more before returning.
+    pub fn spawn_flusher(self, db: Db, cancel: CancellationToken) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => self.flush(&db).await,
+                    _ = cancel.cancelled() => break,
+                }
+            }
+            self.flush(&db).await;
+        })
+    }
+}
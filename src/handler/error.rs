@@ -1,5 +1,5 @@
 use crate::clients::ClientError;
-use axum::http::StatusCode;
+use axum::http::{header, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::Json as AxumJson;
 use sea_orm::DbErr;
@@ -100,6 +100,17 @@ impl From<ClientError> for Error {
 /// Error-to-response conversion for Axum handlers.
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
+        if let Error::Client(e) = &self {
+            if let ClientError::CircuitOpen { retry_in, .. } = &**e {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    [(header::RETRY_AFTER, retry_in.as_secs().max(1).to_string())],
+                    e.to_string(),
+                )
+                    .into_response();
+            }
+        }
+
         match self {
             Error::AccessDenied => StatusCode::FORBIDDEN.into_response(),
 
@@ -186,6 +197,17 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
     }
 
+    #[test]
+    fn circuit_open_is_503() {
+        let err = Error::from(ClientError::CircuitOpen {
+            url: Box::new("https://aggregator.example".parse().unwrap()),
+            retry_in: std::time::Duration::from_secs(12),
+        });
+        let resp = err.into_response();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(resp.headers()[header::RETRY_AFTER], "12");
+    }
+
     #[test]
     fn other_error_is_500() {
         let err = Error::String("something broke");
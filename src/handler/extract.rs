@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::body::Bytes;
-use axum::extract::{FromRef, FromRequest, FromRequestParts, Path, Request};
+use axum::extract::{FromRef, FromRequest, FromRequestParts, OptionalFromRequest, Path, Request};
 use axum::http::{request::Parts, StatusCode};
 use axum::response::{IntoResponse, Response};
 use sea_orm::EntityTrait;
@@ -28,25 +28,52 @@ where
     type Rejection = Error;
 
     async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
-        let bytes = Bytes::from_request(req, state).await.map_err(|e| {
-            if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
-                Error::PayloadTooLarge
-            } else {
-                Error::Other(Arc::new(e))
-            }
-        })?;
-        let deserializer = &mut serde_json::Deserializer::from_slice(&bytes);
-        serde_path_to_error::deserialize(deserializer)
-            .map(Json)
-            .map_err(|err| {
-                Error::Json(ApiError::ParseError {
-                    path: err.path().to_string(),
-                    message: err.inner().to_string(),
-                })
-            })
+        let bytes = body_bytes(req, state).await?;
+        parse(&bytes)
     }
 }
 
+/// An empty request body is extracted as `None`, for endpoints whose body was
+/// introduced after clients that send none.
+impl<T, S> OptionalFromRequest<S> for Json<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        let bytes = body_bytes(req, state).await?;
+        if bytes.iter().all(u8::is_ascii_whitespace) {
+            Ok(None)
+        } else {
+            parse(&bytes).map(Some)
+        }
+    }
+}
+
+async fn body_bytes<S: Send + Sync>(req: Request, state: &S) -> Result<Bytes, Error> {
+    Bytes::from_request(req, state).await.map_err(|e| {
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            Error::PayloadTooLarge
+        } else {
+            Error::Other(Arc::new(e))
+        }
+    })
+}
+
+fn parse<T: DeserializeOwned>(bytes: &[u8]) -> Result<Json<T>, Error> {
+    let deserializer = &mut serde_json::Deserializer::from_slice(bytes);
+    serde_path_to_error::deserialize(deserializer)
+        .map(Json)
+        .map_err(|err| {
+            Error::Json(ApiError::ParseError {
+                path: err.path().to_string(),
+                message: err.inner().to_string(),
+            })
+        })
+}
+
 impl<T: Serialize> IntoResponse for Json<T> {
     fn into_response(self) -> Response {
         axum::Json(self.0).into_response()
@@ -1,5 +1,5 @@
 use crate::{
-    entity::Membership,
+    entity::{ApiTokenScope, Membership, MembershipRole},
     handler::{account_bearer_token::AccountBearerToken, Error},
     Db, User,
 };
@@ -15,7 +15,10 @@ pub enum PermissionsActor {
 impl PermissionsActor {
     pub fn is_admin(&self) -> bool {
         match self {
-            PermissionsActor::ApiToken(token) => token.account.admin,
+            // scoped tokens never act as admins, even for admin accounts
+            PermissionsActor::ApiToken(token) => {
+                token.account.admin && !token.api_token.is_scoped()
+            }
             PermissionsActor::User(user, _) => user.is_admin(),
         }
     }
@@ -48,6 +51,82 @@ impl PermissionsActor {
         matches!(self, Self::ApiToken(_))
     }
 
+    /// The actor's role in an account, if it belongs to that account. Unscoped api tokens act as
+    /// account owners, as they could do everything before roles existed, scoped tokens act as
+    /// members if they have any write scope, and read-only tokens act as viewers.
+    pub fn role_in(&self, account_id: &uuid::Uuid) -> Option<MembershipRole> {
+        match self {
+            PermissionsActor::ApiToken(token) if token.account.id == *account_id => {
+                Some(if !token.api_token.is_scoped() {
+                    MembershipRole::Owner
+                } else if token.api_token.has_write_scope() {
+                    MembershipRole::Member
+                } else {
+                    MembershipRole::Viewer
+                })
+            }
+            PermissionsActor::ApiToken(_) => None,
+            PermissionsActor::User(_, memberships) => memberships
+                .iter()
+                .find(|m| m.account_id == *account_id)
+                .map(|m| m.role),
+        }
+    }
+
+    /// Whether the actor is an admin or has at least this role in an account.
+    pub fn has_role(&self, account_id: &uuid::Uuid, role: MembershipRole) -> bool {
+        self.is_admin() || self.role_in(account_id).is_some_and(|r| r >= role)
+    }
+
+    /// Whether the actor can do what this scope describes. Users and unscoped api tokens have
+    /// every scope.
+    pub fn has_scope(&self, scope: ApiTokenScope) -> bool {
+        match self {
+            PermissionsActor::ApiToken(token) => token.api_token.has_scope(scope),
+            PermissionsActor::User(_, _) => true,
+        }
+    }
+
+    /// Whether the actor can read the kind of resource that `write_scope` allows writing. Scoped
+    /// api tokens need either that scope or the read scope.
+    pub fn can_read(&self, write_scope: Option<ApiTokenScope>) -> bool {
+        self.has_scope(ApiTokenScope::Read)
+            || write_scope.is_some_and(|scope| self.has_scope(scope))
+    }
+
+    /// Whether the actor can read an account's resources that do not belong to a task, such as
+    /// its aggregators or memberships. Api tokens limited to specific tasks cannot.
+    pub fn can_read_account_resources(&self, write_scope: Option<ApiTokenScope>) -> bool {
+        self.can_access_all_tasks() && self.can_read(write_scope)
+    }
+
+    /// Whether the actor can access a task, since api tokens can be limited to specific tasks.
+    /// This does not check the actor's role in the task's account.
+    pub fn can_access_task(&self, task_id: &str) -> bool {
+        match self {
+            PermissionsActor::ApiToken(token) => token.api_token.allows_task(task_id),
+            PermissionsActor::User(_, _) => true,
+        }
+    }
+
+    /// Whether the actor can access every task in its accounts, including ones it creates.
+    pub fn can_access_all_tasks(&self) -> bool {
+        match self {
+            PermissionsActor::ApiToken(token) => token.api_token.task_ids.is_none(),
+            PermissionsActor::User(_, _) => true,
+        }
+    }
+
+    /// The tasks an api token is limited to, or None if the actor can access every task.
+    pub fn task_ids(&self) -> Option<&[String]> {
+        match self {
+            PermissionsActor::ApiToken(token) => {
+                token.api_token.task_ids.as_deref().map(|ids| &ids[..])
+            }
+            PermissionsActor::User(_, _) => None,
+        }
+    }
+
     pub fn account_ids(&self) -> Vec<uuid::Uuid> {
         match self {
             PermissionsActor::ApiToken(token) => vec![token.account.id],
@@ -84,13 +84,103 @@ impl Queue {
         }
         tx.commit().await?;
 
+        let tx = self.db.begin().await?;
+        let refresh_aggregator_capabilities_jobs = Entity::find()
+            .filter(all![
+                Expr::cust_with_expr("job->>'type' = $1", "RefreshAggregatorCapabilities"),
+                Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .count(&tx)
+            .await?;
+
+        if refresh_aggregator_capabilities_jobs == 0 {
+            Job::from(RefreshAggregatorCapabilities).insert(&tx).await?;
+        }
+        tx.commit().await?;
+
+        let tx = self.db.begin().await?;
+        let refresh_aggregator_hpke_configs_jobs = Entity::find()
+            .filter(all![
+                Expr::cust_with_expr("job->>'type' = $1", "RefreshAggregatorHpkeConfigs"),
+                Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .count(&tx)
+            .await?;
+
+        if refresh_aggregator_hpke_configs_jobs == 0 {
+            Job::from(RefreshAggregatorHpkeConfigs).insert(&tx).await?;
+        }
+        tx.commit().await?;
+
+        let tx = self.db.begin().await?;
+        let expire_api_tokens_jobs = Entity::find()
+            .filter(all![
+                Expr::cust_with_expr("job->>'type' = $1", "ExpireApiTokens"),
+                Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .count(&tx)
+            .await?;
+
+        if expire_api_tokens_jobs == 0 {
+            Job::from(ExpireApiTokens).insert(&tx).await?;
+        }
+        tx.commit().await?;
+
+        let tx = self.db.begin().await?;
+        let notify_expired_tasks_jobs = Entity::find()
+            .filter(all![
+                Expr::cust_with_expr("job->>'type' = $1", "NotifyExpiredTasks"),
+                Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .count(&tx)
+            .await?;
+
+        if notify_expired_tasks_jobs == 0 {
+            Job::from(NotifyExpiredTasks).insert(&tx).await?;
+        }
+        tx.commit().await?;
+
+        let tx = self.db.begin().await?;
+        let refresh_task_metrics_jobs = Entity::find()
+            .filter(all![
+                Expr::cust_with_expr("job->>'type' = $1", "RefreshTaskMetrics"),
+                Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .count(&tx)
+            .await?;
+
+        if refresh_task_metrics_jobs == 0 {
+            Job::from(RefreshTaskMetrics).insert(&tx).await?;
+        }
+        tx.commit().await?;
+
         Ok(())
     }
 
     // TODO(#2262): use TaskTracker to wait for in-flight jobs during graceful shutdown
     pub async fn perform_one_queue_job(&self) -> Result<Option<Model>, DbErr> {
-        let tx = self.db.begin().await?;
-        let model = if let Some(queue_item) = Entity::next(&tx).await? {
+        let mut tx = self.db.begin().await?;
+        let model = if let Some(mut queue_item) = Entity::next(&tx).await? {
+            // Jobs that make requests to aggregators or webhook receivers do so before their
+            // transaction begins, so that a slow server cannot hold it open. The job is claimed
+            // first, so that other workers do not fetch for it in the meantime.
+            let fetched = if queue_item.job.fetches() {
+                let claimed = Entity::claim(queue_item, &tx).await?;
+                tx.commit().await?;
+                let fetched = claimed.job.fetch(&self.job_state, &self.db).await;
+                tx = self.db.begin().await?;
+                match Entity::relock(&claimed, &tx).await? {
+                    Some(relocked) => queue_item = relocked,
+                    None => {
+                        tx.commit().await?;
+                        return Ok(Some(claimed));
+                    }
+                }
+                Some(fetched)
+            } else {
+                None
+            };
+
             let mut queue_item = queue_item.into_active_model();
 
             let mut job = queue_item.job.take().ok_or_else(|| {
@@ -100,7 +190,11 @@ impl Queue {
                 ))
             })?;
 
-            let result = job.perform(&self.job_state, &tx).await;
+            let result = match fetched {
+                Some(Ok(fetched)) => job.perform_fetched(fetched, &self.job_state, &tx).await,
+                Some(Err(error)) => Err(error),
+                None => job.perform(&self.job_state, &tx).await,
+            };
             queue_item.job = ActiveValue::Set(job);
 
             match result {
@@ -1,7 +1,8 @@
 use crate::{
-    clients::{Auth0Client, ClientError, PostmarkClient},
+    clients::{Auth0Client, ClientError, HttpClient, PostmarkClient},
+    config::FeatureFlags,
     entity::Membership,
-    Config,
+    Config, Crypter,
 };
 use sea_orm::{ActiveModelTrait, ConnectionTrait, DbErr};
 use serde::{Deserialize, Serialize};
@@ -10,7 +11,13 @@ use time::{Duration, OffsetDateTime};
 use url::Url;
 
 mod v1;
-pub use v1::{CreateUser, QueueCleanup, ResetPassword, SendInvitationEmail, SessionCleanup, V1};
+pub use v1::{
+    CreateUser, DeliverWebhook, EvaluateTaskAlerts, ExpireAggregatorTasks, ExpireApiTokens,
+    Fetched, NotifyExpiredTasks, QueueCleanup, RefreshAggregatorCapabilities,
+    RefreshAggregatorHpkeConfigs, RefreshTaskMetrics, ResetPassword, SendAlertEmail,
+    SendApiTokenExpirationEmail, SendCapabilityRemovedEmail, SendDecommissionEmail,
+    SendInvitationEmail, SessionCleanup, SunsetAggregator, TaskExpirationProgress, V1,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(tag = "version")]
@@ -36,6 +43,9 @@ pub enum JobError {
         status: Option<u16>,
         body: String,
     },
+
+    #[error("{0}")]
+    Permanent(String),
 }
 
 impl JobError {
@@ -71,12 +81,18 @@ impl From<ClientError> for JobError {
 pub struct SharedJobState {
     pub auth0_client: Auth0Client,
     pub postmark_client: PostmarkClient,
+    pub http_client: HttpClient,
+    pub crypter: Crypter,
+    pub feature_flags: FeatureFlags,
 }
 impl From<&Config> for SharedJobState {
     fn from(config: &Config) -> Self {
         Self {
             auth0_client: Auth0Client::new(config),
             postmark_client: PostmarkClient::new(config),
+            http_client: config.user_url_client(),
+            crypter: config.crypter.clone(),
+            feature_flags: config.feature_flags(),
         }
     }
 }
@@ -124,6 +140,34 @@ impl Job {
         }
     }
 
+    /// See [`V1::fetches`].
+    pub fn fetches(&self) -> bool {
+        match self {
+            Job::V1(job) => job.fetches(),
+        }
+    }
+
+    pub async fn fetch(
+        &self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<Fetched>, JobError> {
+        match self {
+            Job::V1(job) => job.fetch(job_state, db).await,
+        }
+    }
+
+    pub async fn perform_fetched(
+        &mut self,
+        fetched: Option<Fetched>,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        match self {
+            Job::V1(job) => job.perform_fetched(fetched, job_state, db).await,
+        }
+    }
+
     pub async fn insert(
         self,
         db: &impl ConnectionTrait,
@@ -1,8 +1,21 @@
 mod create_user;
+mod deliver_webhook;
+mod evaluate_task_alerts;
+mod expire_aggregator_tasks;
+mod expire_api_tokens;
+mod notify_expired_tasks;
 mod queue_cleanup;
+mod refresh_aggregator_capabilities;
+mod refresh_aggregator_hpke_configs;
+mod refresh_task_metrics;
 mod reset_password;
+mod send_alert_email;
+mod send_api_token_expiration_email;
+mod send_capability_removed_email;
+mod send_decommission_email;
 mod send_invitation_email;
 mod session_cleanup;
+mod sunset_aggregator;
 
 use crate::queue::EnqueueJob;
 
@@ -11,10 +24,25 @@ use sea_orm::ConnectionTrait;
 use serde::{Deserialize, Serialize};
 
 pub use create_user::CreateUser;
+pub use deliver_webhook::{DeliverWebhook, FetchedWebhookDelivery};
+pub use evaluate_task_alerts::EvaluateTaskAlerts;
+pub use expire_aggregator_tasks::{
+    ExpireAggregatorTasks, FetchedTaskExpirations, TaskExpirationProgress,
+};
+pub use expire_api_tokens::ExpireApiTokens;
+pub use notify_expired_tasks::NotifyExpiredTasks;
 pub use queue_cleanup::QueueCleanup;
+pub use refresh_aggregator_capabilities::{FetchedCapabilities, RefreshAggregatorCapabilities};
+pub use refresh_aggregator_hpke_configs::{FetchedHpkeConfigs, RefreshAggregatorHpkeConfigs};
+pub use refresh_task_metrics::{FetchedTasksMetrics, RefreshTaskMetrics};
 pub use reset_password::ResetPassword;
+pub use send_alert_email::SendAlertEmail;
+pub use send_api_token_expiration_email::SendApiTokenExpirationEmail;
+pub use send_capability_removed_email::SendCapabilityRemovedEmail;
+pub use send_decommission_email::SendDecommissionEmail;
 pub use send_invitation_email::SendInvitationEmail;
 pub use session_cleanup::SessionCleanup;
+pub use sunset_aggregator::SunsetAggregator;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(tag = "type")]
@@ -24,9 +52,97 @@ pub enum V1 {
     ResetPassword(ResetPassword),
     SessionCleanup(SessionCleanup),
     QueueCleanup(QueueCleanup),
+    RefreshAggregatorCapabilities(RefreshAggregatorCapabilities),
+    SendCapabilityRemovedEmail(SendCapabilityRemovedEmail),
+    RefreshAggregatorHpkeConfigs(RefreshAggregatorHpkeConfigs),
+    ExpireAggregatorTasks(ExpireAggregatorTasks),
+    SendDecommissionEmail(SendDecommissionEmail),
+    SunsetAggregator(SunsetAggregator),
+    ExpireApiTokens(ExpireApiTokens),
+    SendApiTokenExpirationEmail(SendApiTokenExpirationEmail),
+    DeliverWebhook(DeliverWebhook),
+    EvaluateTaskAlerts(EvaluateTaskAlerts),
+    SendAlertEmail(SendAlertEmail),
+    NotifyExpiredTasks(NotifyExpiredTasks),
+    RefreshTaskMetrics(RefreshTaskMetrics),
+}
+
+/// What a job received from aggregators or webhook receivers before its transaction began.
+#[derive(Debug)]
+pub enum Fetched {
+    Capabilities(FetchedCapabilities),
+    HpkeConfigs(FetchedHpkeConfigs),
+    TaskExpirations(FetchedTaskExpirations),
+    WebhookDelivery(FetchedWebhookDelivery),
+    TasksMetrics(FetchedTasksMetrics),
 }
 
 impl V1 {
+    /// Whether this job makes requests to aggregators or webhook receivers, which [`Self::fetch`]
+    /// makes outside of the job's transaction so that a slow server cannot hold it open.
+    pub fn fetches(&self) -> bool {
+        matches!(
+            self,
+            V1::RefreshAggregatorCapabilities(_)
+                | V1::RefreshAggregatorHpkeConfigs(_)
+                | V1::ExpireAggregatorTasks(_)
+                | V1::DeliverWebhook(_)
+                | V1::RefreshTaskMetrics(_)
+        )
+    }
+
+    pub async fn fetch(
+        &self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<Fetched>, JobError> {
+        Ok(match self {
+            V1::RefreshAggregatorCapabilities(job) => {
+                Some(Fetched::Capabilities(job.fetch(job_state, db).await?))
+            }
+            V1::RefreshAggregatorHpkeConfigs(job) => {
+                Some(Fetched::HpkeConfigs(job.fetch(job_state, db).await?))
+            }
+            V1::ExpireAggregatorTasks(job) => {
+                Some(Fetched::TaskExpirations(job.fetch(job_state, db).await?))
+            }
+            V1::DeliverWebhook(job) => {
+                Some(Fetched::WebhookDelivery(job.fetch(job_state, db).await?))
+            }
+            V1::RefreshTaskMetrics(job) => {
+                Some(Fetched::TasksMetrics(job.fetch(job_state, db).await?))
+            }
+            _ => None,
+        })
+    }
+
+    /// Performs the job with what [`Self::fetch`] returned for it.
+    pub async fn perform_fetched(
+        &mut self,
+        fetched: Option<Fetched>,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        match (self, fetched) {
+            (V1::RefreshAggregatorCapabilities(job), Some(Fetched::Capabilities(fetched))) => {
+                job.perform_fetched(fetched, db).await
+            }
+            (V1::RefreshAggregatorHpkeConfigs(job), Some(Fetched::HpkeConfigs(fetched))) => {
+                job.perform_fetched(fetched, db).await
+            }
+            (V1::ExpireAggregatorTasks(job), Some(Fetched::TaskExpirations(fetched))) => {
+                job.perform_fetched(fetched, db).await
+            }
+            (V1::DeliverWebhook(job), Some(Fetched::WebhookDelivery(fetched))) => {
+                job.perform_fetched(fetched, db).await
+            }
+            (V1::RefreshTaskMetrics(job), Some(Fetched::TasksMetrics(fetched))) => {
+                job.perform_fetched(fetched, db).await
+            }
+            (job, _) => job.perform(job_state, db).await,
+        }
+    }
+
     pub async fn perform(
         &mut self,
         job_state: &SharedJobState,
@@ -38,6 +154,19 @@ impl V1 {
             V1::ResetPassword(job) => job.perform(job_state, db).await,
             V1::SessionCleanup(job) => job.perform(job_state, db).await,
             V1::QueueCleanup(job) => job.perform(job_state, db).await,
+            V1::RefreshAggregatorCapabilities(job) => job.perform(job_state, db).await,
+            V1::SendCapabilityRemovedEmail(job) => job.perform(job_state, db).await,
+            V1::RefreshAggregatorHpkeConfigs(job) => job.perform(job_state, db).await,
+            V1::ExpireAggregatorTasks(job) => job.perform(job_state, db).await,
+            V1::SendDecommissionEmail(job) => job.perform(job_state, db).await,
+            V1::SunsetAggregator(job) => job.perform(job_state, db).await,
+            V1::ExpireApiTokens(job) => job.perform(job_state, db).await,
+            V1::SendApiTokenExpirationEmail(job) => job.perform(job_state, db).await,
+            V1::DeliverWebhook(job) => job.perform(job_state, db).await,
+            V1::EvaluateTaskAlerts(job) => job.perform(job_state, db).await,
+            V1::SendAlertEmail(job) => job.perform(job_state, db).await,
+            V1::NotifyExpiredTasks(job) => job.perform(job_state, db).await,
+            V1::RefreshTaskMetrics(job) => job.perform(job_state, db).await,
         }
     }
 }
@@ -0,0 +1,188 @@
+use crate::{
+    clients::ClientError,
+    entity::{webhook, *},
+    queue::{EnqueueJob, Job, JobError, SharedJobState, V1},
+};
+use reqwest::{header::CONTENT_TYPE, Method, StatusCode};
+use sea_orm::{ConnectionTrait, EntityTrait};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Sends one event to one webhook. Failed deliveries are retried by the queue with its usual
+/// backoff, unless the receiver rejected the event or it could not be sent at all, and every
+/// attempt is recorded in the webhook's delivery log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeliverWebhook {
+    pub webhook_id: Uuid,
+    pub event: WebhookEvent,
+    #[serde(default)]
+    pub attempts: i32,
+}
+
+/// The outcome of posting an event to a webhook, made before the job's transaction began. `None`
+/// if the webhook was deleted after the event was queued.
+#[derive(Debug)]
+pub struct FetchedWebhookDelivery(Option<WebhookAttempt>);
+
+#[derive(Debug)]
+struct WebhookAttempt {
+    status_code: Option<u16>,
+    result: Result<(), JobError>,
+}
+
+impl DeliverWebhook {
+    pub fn new(webhook_id: Uuid, event: WebhookEvent) -> Self {
+        Self {
+            webhook_id,
+            event,
+            attempts: 0,
+        }
+    }
+
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let fetched = self.fetch(job_state, db).await?;
+        self.perform_fetched(fetched, db).await
+    }
+
+    pub async fn fetch(
+        &self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<FetchedWebhookDelivery, JobError> {
+        let webhook = Webhooks::find_by_id(self.webhook_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("webhook"), self.webhook_id.to_string())
+            })?;
+
+        // events are not delivered to webhooks that were deleted after they were queued
+        if webhook.is_tombstoned() {
+            return Ok(FetchedWebhookDelivery(None));
+        }
+
+        Ok(FetchedWebhookDelivery(Some(
+            self.post(&webhook, job_state).await,
+        )))
+    }
+
+    async fn post(&self, webhook: &Webhook, job_state: &SharedJobState) -> WebhookAttempt {
+        // an event that cannot be signed or sent now never will be, so it is not retried
+        let crypter = &job_state.crypter;
+        let prepared = webhook
+            .decrypt_url(crypter)
+            .and_then(|url| Ok((url, webhook.secret(crypter)?)))
+            .map_err(|error| error.to_string())
+            .and_then(|(url, secret)| {
+                let body = serde_json::to_string(&self.event).map_err(|error| error.to_string())?;
+                Ok((url, secret, body))
+            });
+        let (url, secret, body) = match prepared {
+            Ok(prepared) => prepared,
+            Err(error) => {
+                return WebhookAttempt {
+                    status_code: None,
+                    result: Err(JobError::Permanent(error)),
+                }
+            }
+        };
+        let signature =
+            webhook::signature(&secret, OffsetDateTime::now_utc().unix_timestamp(), &body);
+
+        // the queue is the only retry layer, so each attempt is a single request
+        let client = &job_state.http_client;
+        let result = client
+            .send(
+                Method::POST,
+                client
+                    .post_url(url)
+                    .header(CONTENT_TYPE, "application/json")
+                    .header(webhook::SIGNATURE_HEADER, signature)
+                    .header(webhook::EVENT_HEADER, self.event.event_type.to_string())
+                    .header(webhook::DELIVERY_HEADER, self.event.id.to_string())
+                    .body(body),
+            )
+            .await;
+
+        match result {
+            Ok(response) => WebhookAttempt {
+                status_code: Some(response.status().as_u16()),
+                result: Ok(()),
+            },
+            Err(ClientError::HttpStatusNotSuccess(error)) => {
+                let status = error.status;
+                // the receiver will reject the event again, unless it timed out or was rate limited
+                let result = match status {
+                    Some(status)
+                        if status.is_client_error()
+                            && status != StatusCode::REQUEST_TIMEOUT
+                            && status != StatusCode::TOO_MANY_REQUESTS =>
+                    {
+                        JobError::Permanent(ClientError::HttpStatusNotSuccess(error).to_string())
+                    }
+                    _ => ClientError::HttpStatusNotSuccess(error).into(),
+                };
+                WebhookAttempt {
+                    status_code: status.map(|s| s.as_u16()),
+                    result: Err(result),
+                }
+            }
+            Err(error) => WebhookAttempt {
+                status_code: None,
+                result: Err(error.into()),
+            },
+        }
+    }
+
+    pub async fn perform_fetched(
+        &mut self,
+        FetchedWebhookDelivery(attempt): FetchedWebhookDelivery,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let Some(WebhookAttempt {
+            status_code,
+            result,
+        }) = attempt
+        else {
+            return Ok(None);
+        };
+
+        self.attempts += 1;
+        WebhookDeliveries::record(
+            self.webhook_id,
+            self.event.id,
+            self.event.event_type,
+            self.attempts,
+            status_code,
+            result.as_ref().err().map(ToString::to_string),
+            db,
+        )
+        .await?;
+
+        result?;
+        Ok(None)
+    }
+}
+
+impl From<DeliverWebhook> for Job {
+    fn from(value: DeliverWebhook) -> Self {
+        Self::V1(V1::DeliverWebhook(value))
+    }
+}
+
+impl PartialEq<Job> for DeliverWebhook {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::DeliverWebhook(j)) if j == self)
+    }
+}
+
+impl PartialEq<DeliverWebhook> for Job {
+    fn eq(&self, other: &DeliverWebhook) -> bool {
+        matches!(self, Job::V1(V1::DeliverWebhook(j)) if j == other)
+    }
+}
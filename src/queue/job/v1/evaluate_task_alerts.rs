@@ -0,0 +1,114 @@
+use crate::{
+    entity::*,
+    queue::job::{v1::V1, EnqueueJob, Job, JobError, SendAlertEmail, SharedJobState},
+};
+use sea_orm::{ConnectionTrait, EntityTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Fires or resolves alerts for each alert rule that applies to a task, after its metrics were
+/// refreshed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EvaluateTaskAlerts {
+    pub task_id: String,
+}
+
+impl EvaluateTaskAlerts {
+    pub async fn perform(
+        &mut self,
+        _job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let task = Tasks::find_by_id(&self.task_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| JobError::MissingRecord(String::from("task"), self.task_id.clone()))?;
+
+        if task.deleted_at.is_some() {
+            return Ok(None);
+        }
+
+        for alert_rule in AlertRules::for_task(&task, db).await? {
+            let baseline = TaskCounterSnapshots::baseline(&task, alert_rule.window(), db).await?;
+            let value = alert_rule.measure(&task, baseline.as_ref());
+            let exceeded = alert_rule.is_exceeded_by(value);
+            match Alerts::firing(&alert_rule, &task, db).await? {
+                None if exceeded => {
+                    // a concurrent evaluation may have fired it first, and notified for it
+                    if let Some(alert) = Alerts::fire(&alert_rule, &task, value, db).await? {
+                        notify(&alert_rule, &alert, db).await?;
+                    }
+                }
+
+                Some(alert) if exceeded => {
+                    alert.update_value(value, db).await?;
+                }
+
+                Some(alert) => {
+                    let alert = alert.resolve(value, db).await?;
+                    notify(&alert_rule, &alert, db).await?;
+                }
+
+                None => {}
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+async fn notify(
+    alert_rule: &AlertRule,
+    alert: &Alert,
+    db: &impl ConnectionTrait,
+) -> Result<(), JobError> {
+    if alert_rule.notifies_by(AlertChannel::Email) {
+        let memberships = Memberships::for_account(alert.account_id).all(db).await?;
+
+        for membership in memberships {
+            Job::from(SendAlertEmail {
+                membership_id: membership.id,
+                alert_id: alert.id,
+                resolved: !alert.is_firing(),
+                message_id: Uuid::new_v4(),
+            })
+            .insert(db)
+            .await?;
+        }
+    }
+
+    if alert_rule.notifies_by(AlertChannel::Webhook) {
+        let event_type = if alert.is_firing() {
+            WebhookEventType::AlertFired
+        } else {
+            WebhookEventType::AlertResolved
+        };
+        Webhooks::enqueue(
+            alert.account_id,
+            event_type,
+            json!({ "alert": alert, "alert_rule": alert_rule }),
+            db,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+impl From<EvaluateTaskAlerts> for Job {
+    fn from(value: EvaluateTaskAlerts) -> Self {
+        Self::V1(V1::EvaluateTaskAlerts(value))
+    }
+}
+
+impl PartialEq<Job> for EvaluateTaskAlerts {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::EvaluateTaskAlerts(j)) if j == self)
+    }
+}
+impl PartialEq<EvaluateTaskAlerts> for Job {
+    fn eq(&self, other: &EvaluateTaskAlerts) -> bool {
+        matches!(self, Job::V1(V1::EvaluateTaskAlerts(j)) if j == other)
+    }
+}
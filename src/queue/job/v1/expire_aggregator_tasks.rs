@@ -0,0 +1,241 @@
+use crate::{
+    entity::*,
+    queue::{
+        job::{v1::V1, EnqueueJob, Job, JobError, SharedJobState},
+        JobStatus,
+    },
+};
+use sea_orm::{
+    sea_query::{all, Expr, ExprTrait},
+    ActiveModelTrait, ActiveValue, ConnectionTrait, DbErr, EntityTrait, IntoActiveModel,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+const BATCH_SIZE: u64 = 10;
+
+/// Expires every unexpired task that uses a deleted aggregator, a batch at a time. Each job in the
+/// chain records the tasks expired so far, so the most recent one reflects overall progress.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExpireAggregatorTasks {
+    pub aggregator_id: Uuid,
+    #[serde(default)]
+    pub expired_task_ids: Vec<String>,
+    // tasks that were expired here but that one of their aggregators could not be told about
+    #[serde(default)]
+    pub failed_task_ids: Vec<String>,
+    #[serde(default)]
+    pub remaining_tasks: u64,
+}
+
+/// How far the most recent chain of [`ExpireAggregatorTasks`] jobs for an aggregator has
+/// progressed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TaskExpirationProgress {
+    pub aggregator_id: Uuid,
+    /// Pending until every task has been expired, or Failed if the chain gave up.
+    pub status: JobStatus,
+    pub expired_task_ids: Vec<String>,
+    pub failed_task_ids: Vec<String>,
+    pub remaining_tasks: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// The tasks whose aggregators were told about their expiration before the job's transaction
+/// began, and whether every aggregator was told.
+#[derive(Debug)]
+pub struct FetchedTaskExpirations {
+    expiration: OffsetDateTime,
+    tasks: Vec<(String, bool)>,
+}
+
+impl ExpireAggregatorTasks {
+    pub fn new(aggregator_id: Uuid) -> Self {
+        Self {
+            aggregator_id,
+            expired_task_ids: vec![],
+            failed_task_ids: vec![],
+            remaining_tasks: 0,
+        }
+    }
+
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let fetched = self.fetch(job_state, db).await?;
+        self.perform_fetched(fetched, db).await
+    }
+
+    /// Tells the aggregators about the next batch of expirations, before the job's transaction
+    /// begins.
+    pub async fn fetch(
+        &self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<FetchedTaskExpirations, JobError> {
+        let aggregator = self.aggregator(db).await?;
+        let tasks = aggregator
+            .unexpired_tasks()
+            .order_by_asc(TaskColumn::CreatedAt)
+            .limit(BATCH_SIZE)
+            .all(db)
+            .await?;
+
+        let now = OffsetDateTime::now_utc();
+        let update = UpdateTask::expiration(Some(now));
+        let mut fetched = FetchedTaskExpirations {
+            expiration: now,
+            tasks: vec![],
+        };
+        for task in tasks {
+            let mut succeeded = true;
+            for aggregator in task.aggregators(db).await? {
+                if let Err(error) = update
+                    .update_aggregator_expiration(
+                        aggregator,
+                        &task.id,
+                        &job_state.http_client,
+                        &job_state.crypter,
+                    )
+                    .await
+                {
+                    // Like a forced task deletion, an unreachable aggregator does not keep the
+                    // task alive here.
+                    tracing::warn!(
+                        task_id = task.id,
+                        ?error,
+                        "failed to expire aggregator task"
+                    );
+                    succeeded = false;
+                }
+            }
+            fetched.tasks.push((task.id, succeeded));
+        }
+
+        Ok(fetched)
+    }
+
+    pub async fn perform_fetched(
+        &mut self,
+        FetchedTaskExpirations { expiration, tasks }: FetchedTaskExpirations,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let aggregator = self.aggregator(db).await?;
+
+        for (task_id, succeeded) in tasks {
+            // the task may have been deleted or expired while its aggregators were being told
+            let Some(task) = Tasks::find_by_id(&task_id).one(db).await?.filter(|task| {
+                task.deleted_at.is_none()
+                    && task
+                        .expiration
+                        .is_none_or(|task_expiration| task_expiration > expiration)
+            }) else {
+                continue;
+            };
+
+            let mut task = task.into_active_model();
+            task.expiration = ActiveValue::Set(Some(expiration));
+            task.expiration_notified_at = ActiveValue::Set(Some(expiration));
+            task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+            let task = task.update(db).await?;
+            Webhooks::enqueue(
+                task.account_id,
+                WebhookEventType::TaskExpired,
+                json!(task),
+                db,
+            )
+            .await?;
+
+            if succeeded {
+                self.expired_task_ids.push(task_id);
+            } else {
+                self.failed_task_ids.push(task_id);
+            }
+        }
+
+        self.remaining_tasks = aggregator.unexpired_tasks().count(db).await?;
+        tracing::info!(
+            aggregator_id = %self.aggregator_id,
+            expired = self.expired_task_ids.len(),
+            failed = self.failed_task_ids.len(),
+            remaining = self.remaining_tasks,
+            "expired aggregator tasks"
+        );
+
+        if self.remaining_tasks == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(EnqueueJob::from(self.clone())))
+        }
+    }
+
+    /// Reads the progress of the most recent job for `aggregator`, counting the tasks that
+    /// remain as of now.
+    pub async fn progress(
+        aggregator: &Aggregator,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<TaskExpirationProgress>, DbErr> {
+        let latest = queue::Entity::find()
+            .filter(all![
+                Expr::cust("job->>'type'").eq("ExpireAggregatorTasks"),
+                Expr::cust("job->>'aggregator_id'").eq(aggregator.id.to_string()),
+            ])
+            .order_by_desc(queue::Column::CreatedAt)
+            .one(db)
+            .await?;
+
+        let Some(queue::Model {
+            job,
+            status,
+            updated_at,
+            ..
+        }) = latest
+        else {
+            return Ok(None);
+        };
+        let Job::V1(V1::ExpireAggregatorTasks(job)) = job.0 else {
+            return Ok(None);
+        };
+
+        Ok(Some(TaskExpirationProgress {
+            aggregator_id: aggregator.id,
+            status,
+            expired_task_ids: job.expired_task_ids,
+            failed_task_ids: job.failed_task_ids,
+            remaining_tasks: aggregator.unexpired_tasks().count(db).await?,
+            updated_at,
+        }))
+    }
+
+    async fn aggregator(&self, db: &impl ConnectionTrait) -> Result<Aggregator, JobError> {
+        Aggregators::find_by_id(self.aggregator_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("aggregator"), self.aggregator_id.to_string())
+            })
+    }
+}
+
+impl From<ExpireAggregatorTasks> for Job {
+    fn from(value: ExpireAggregatorTasks) -> Self {
+        Self::V1(V1::ExpireAggregatorTasks(value))
+    }
+}
+
+impl PartialEq<Job> for ExpireAggregatorTasks {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::ExpireAggregatorTasks(j)) if j == self)
+    }
+}
+impl PartialEq<ExpireAggregatorTasks> for Job {
+    fn eq(&self, other: &ExpireAggregatorTasks) -> bool {
+        matches!(self, Job::V1(V1::ExpireAggregatorTasks(j)) if j == other)
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    entity::*,
+    queue::job::{v1::V1, EnqueueJob, Job, JobError, SendApiTokenExpirationEmail, SharedJobState},
+};
+use sea_orm::{
+    sea_query::{all, Expr},
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+const PERIOD: Duration = Duration::hours(1);
+
+/// How long before an api token expires that account members are warned about it.
+const EXPIRATION_NOTICE: Duration = Duration::days(7);
+
+/// Warns account members about api tokens that will expire soon, and tombstones api tokens that
+/// have expired.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
+pub struct ExpireApiTokens;
+
+impl ExpireApiTokens {
+    pub async fn perform(
+        &mut self,
+        _job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        queue::Entity::delete_many()
+            .filter(all![
+                Expr::cust_with_expr("job->>'type' = $1", "ExpireApiTokens"),
+                queue::Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .exec(db)
+            .await?;
+
+        let now = OffsetDateTime::now_utc();
+        let expiring_soon = ApiTokens::find()
+            .filter(all![
+                ApiTokenColumn::DeletedAt.is_null(),
+                ApiTokenColumn::ExpirationNotifiedAt.is_null(),
+                ApiTokenColumn::ExpiresAt.gt(now),
+                ApiTokenColumn::ExpiresAt.lte(now + EXPIRATION_NOTICE),
+            ])
+            .all(db)
+            .await?;
+
+        for api_token in expiring_soon {
+            let memberships =
+                Memberships::for_account_with_role(api_token.account_id, MembershipRole::Member)
+                    .all(db)
+                    .await?;
+
+            for membership in memberships {
+                Job::from(SendApiTokenExpirationEmail {
+                    membership_id: membership.id,
+                    api_token_id: api_token.id,
+                    message_id: Uuid::new_v4(),
+                })
+                .insert(db)
+                .await?;
+            }
+
+            let mut api_token = api_token.into_active_model();
+            api_token.expiration_notified_at = ActiveValue::Set(Some(now));
+            api_token.update(db).await?;
+        }
+
+        ApiTokens::update_many()
+            .col_expr(ApiTokenColumn::DeletedAt, Expr::value(now))
+            .col_expr(ApiTokenColumn::UpdatedAt, Expr::value(now))
+            .filter(all![
+                ApiTokenColumn::DeletedAt.is_null(),
+                ApiTokenColumn::ExpiresAt.lte(now),
+            ])
+            .exec(db)
+            .await?;
+
+        Ok(Some(EnqueueJob::from(ExpireApiTokens).scheduled_in(PERIOD)))
+    }
+}
+
+impl From<ExpireApiTokens> for Job {
+    fn from(value: ExpireApiTokens) -> Self {
+        Self::V1(V1::ExpireApiTokens(value))
+    }
+}
+
+impl PartialEq<Job> for ExpireApiTokens {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::ExpireApiTokens(j)) if j == self)
+    }
+}
+impl PartialEq<ExpireApiTokens> for Job {
+    fn eq(&self, other: &ExpireApiTokens) -> bool {
+        matches!(self, Job::V1(V1::ExpireApiTokens(j)) if j == other)
+    }
+}
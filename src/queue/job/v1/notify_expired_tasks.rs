@@ -0,0 +1,85 @@
+use crate::{
+    entity::*,
+    queue::job::{v1::V1, EnqueueJob, Job, JobError, SharedJobState},
+};
+use sea_orm::{
+    sea_query::{all, any, Expr, ExprTrait},
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use time::{Duration, OffsetDateTime};
+
+const PERIOD: Duration = Duration::hours(1);
+
+/// Sends the task.expired webhook event for tasks that reached their expiration on their own,
+/// rather than by being updated or by their aggregator being deleted. Each expiration is
+/// announced once.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
+pub struct NotifyExpiredTasks;
+
+impl NotifyExpiredTasks {
+    pub async fn perform(
+        &mut self,
+        _job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        queue::Entity::delete_many()
+            .filter(all![
+                Expr::cust("job->>'type'").eq("NotifyExpiredTasks"),
+                queue::Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .exec(db)
+            .await?;
+
+        let now = OffsetDateTime::now_utc();
+        let expired = Tasks::find()
+            .filter(all![
+                TaskColumn::DeletedAt.is_null(),
+                TaskColumn::Expiration.lte(now),
+                // a task whose expiration was moved after it was announced is announced again
+                any![
+                    TaskColumn::ExpirationNotifiedAt.is_null(),
+                    Expr::col(TaskColumn::ExpirationNotifiedAt)
+                        .lt(Expr::col(TaskColumn::Expiration)),
+                ],
+            ])
+            .all(db)
+            .await?;
+
+        for task in expired {
+            let mut task = task.into_active_model();
+            task.expiration_notified_at = ActiveValue::Set(Some(now));
+            let task = task.update(db).await?;
+            Webhooks::enqueue(
+                task.account_id,
+                WebhookEventType::TaskExpired,
+                json!(task),
+                db,
+            )
+            .await?;
+        }
+
+        Ok(Some(
+            EnqueueJob::from(NotifyExpiredTasks).scheduled_in(PERIOD),
+        ))
+    }
+}
+
+impl From<NotifyExpiredTasks> for Job {
+    fn from(value: NotifyExpiredTasks) -> Self {
+        Self::V1(V1::NotifyExpiredTasks(value))
+    }
+}
+
+impl PartialEq<Job> for NotifyExpiredTasks {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::NotifyExpiredTasks(j)) if j == self)
+    }
+}
+impl PartialEq<NotifyExpiredTasks> for Job {
+    fn eq(&self, other: &NotifyExpiredTasks) -> bool {
+        matches!(self, Job::V1(V1::NotifyExpiredTasks(j)) if j == other)
+    }
+}
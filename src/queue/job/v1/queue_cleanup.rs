@@ -1,5 +1,8 @@
 use crate::{
-    entity::queue::{Column as QueueColumn, Entity as QueueEntity, JobStatus},
+    entity::{
+        queue::{Column as QueueColumn, Entity as QueueEntity, JobStatus},
+        ApiTokenUsages, WebhookDeliveries,
+    },
     queue::job::{v1::V1, EnqueueJob, Job, JobError, SharedJobState},
 };
 use sea_orm::{
@@ -37,6 +40,9 @@ impl QueueCleanup {
             .exec(db)
             .await?;
 
+        ApiTokenUsages::clean_up(db).await?;
+        WebhookDeliveries::clean_up(db).await?;
+
         Ok(Some(
             EnqueueJob::from(QueueCleanup).scheduled_in(CLEANUP_PERIOD),
         ))
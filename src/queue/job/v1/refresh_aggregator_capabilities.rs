@@ -0,0 +1,234 @@
+use crate::{
+    entity::{aggregator::Capabilities, *},
+    queue::job::{v1::V1, EnqueueJob, Job, JobError, SendCapabilityRemovedEmail, SharedJobState},
+};
+use sea_orm::{
+    sea_query::{all, any, Expr},
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+const PERIOD: Duration = Duration::hours(6);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
+pub struct RefreshAggregatorCapabilities;
+
+/// The capabilities that each reachable aggregator reported, read before the job's transaction
+/// began.
+#[derive(Debug)]
+pub struct FetchedCapabilities(Vec<FetchedAggregator>);
+
+#[derive(Debug)]
+struct FetchedAggregator {
+    aggregator: Aggregator,
+    capabilities: Capabilities,
+    secondary_bearer_token_confirmed: bool,
+}
+
+impl RefreshAggregatorCapabilities {
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let fetched = self.fetch(job_state, db).await?;
+        self.perform_fetched(fetched, db).await
+    }
+
+    pub async fn fetch(
+        &self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<FetchedCapabilities, JobError> {
+        let aggregators = Aggregators::find()
+            .filter(AggregatorColumn::DeletedAt.is_null())
+            .all(db)
+            .await?;
+
+        let mut fetched = vec![];
+        for aggregator in aggregators {
+            match fetch_capabilities(job_state, &aggregator).await {
+                Ok((capabilities, secondary_bearer_token_confirmed)) => {
+                    fetched.push(FetchedAggregator {
+                        aggregator,
+                        capabilities,
+                        secondary_bearer_token_confirmed,
+                    });
+                }
+                Err(error) => {
+                    // An unreachable aggregator should not prevent the others from refreshing,
+                    // and its previously known capabilities remain the best information we have.
+                    tracing::warn!(
+                        aggregator_id = %aggregator.id,
+                        %error,
+                        "failed to refresh aggregator capabilities"
+                    );
+                }
+            }
+        }
+
+        Ok(FetchedCapabilities(fetched))
+    }
+
+    pub async fn perform_fetched(
+        &mut self,
+        FetchedCapabilities(fetched): FetchedCapabilities,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        queue::Entity::delete_many()
+            .filter(all![
+                Expr::cust_with_expr("job->>'type' = $1", "RefreshAggregatorCapabilities"),
+                queue::Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .exec(db)
+            .await?;
+
+        for FetchedAggregator {
+            aggregator: fetched_aggregator,
+            capabilities: current,
+            secondary_bearer_token_confirmed,
+        } in fetched
+        {
+            // The aggregator may have changed or been deleted while its capabilities were
+            // being fetched.
+            let Some(mut aggregator) = Aggregators::find_by_id(fetched_aggregator.id)
+                .one(db)
+                .await?
+                .filter(|aggregator| !aggregator.is_tombstoned())
+            else {
+                continue;
+            };
+
+            if secondary_bearer_token_confirmed
+                && aggregator.encrypted_secondary_bearer_token
+                    == fetched_aggregator.encrypted_secondary_bearer_token
+            {
+                aggregator = aggregator
+                    .promote_secondary_bearer_token()
+                    .update(db)
+                    .await?;
+            }
+
+            let previous = Capabilities::from(&aggregator);
+            if previous == current {
+                continue;
+            }
+
+            let aggregator_id = aggregator.id;
+            let mut active_model = aggregator.into_active_model();
+            active_model.protocol = ActiveValue::Set(current.protocol);
+            active_model.vdafs = ActiveValue::Set(current.vdafs.clone().into());
+            active_model.query_types = ActiveValue::Set(current.query_types.clone().into());
+            active_model.features = ActiveValue::Set(current.features.clone().into());
+            active_model.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+            active_model.update(db).await?;
+
+            let change = AggregatorCapabilityChange::build(aggregator_id, previous, current)
+                .insert(db)
+                .await?;
+
+            notify_affected_accounts(&change, db).await?;
+        }
+
+        Ok(Some(
+            EnqueueJob::from(RefreshAggregatorCapabilities).scheduled_in(PERIOD),
+        ))
+    }
+}
+
+async fn fetch_capabilities(
+    job_state: &SharedJobState,
+    aggregator: &Aggregator,
+) -> Result<(Capabilities, bool), crate::Error> {
+    let client = aggregator.client(job_state.http_client.clone(), &job_state.crypter)?;
+    let config = client.get_aggregator_config().await?;
+    Ok((config.into(), client.secondary_bearer_token_confirmed()))
+}
+
+async fn notify_affected_accounts(
+    change: &AggregatorCapabilityChange,
+    db: &impl ConnectionTrait,
+) -> Result<(), JobError> {
+    let removed = change.removed();
+    if removed.is_empty() {
+        return Ok(());
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let tasks = Tasks::find()
+        .filter(all![
+            TaskColumn::DeletedAt.is_null(),
+            any![
+                TaskColumn::Expiration.is_null(),
+                TaskColumn::Expiration.gt(now)
+            ],
+            any![
+                TaskColumn::LeaderAggregatorId.eq(change.aggregator_id),
+                TaskColumn::HelperAggregatorId.eq(change.aggregator_id)
+            ],
+        ])
+        .all(db)
+        .await?;
+
+    let mut affected_tasks = BTreeMap::<Uuid, Vec<String>>::new();
+    for task in tasks {
+        let is_leader = task.leader_aggregator_id == change.aggregator_id;
+        if removed.affects_task(&task, is_leader) {
+            affected_tasks
+                .entry(task.account_id)
+                .or_default()
+                .push(task.id);
+        }
+    }
+
+    for (account_id, task_ids) in affected_tasks {
+        let memberships = Memberships::for_account(account_id).all(db).await?;
+
+        for membership in memberships {
+            Job::from(SendCapabilityRemovedEmail {
+                membership_id: membership.id,
+                capability_change_id: change.id,
+                task_ids: task_ids.clone(),
+                message_id: Uuid::new_v4(),
+            })
+            .insert(db)
+            .await?;
+        }
+
+        Webhooks::enqueue(
+            account_id,
+            WebhookEventType::AggregatorCapabilitiesRemoved,
+            json!({
+                "aggregator_id": change.aggregator_id,
+                "removed": &removed,
+                "task_ids": task_ids,
+            }),
+            db,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+impl From<RefreshAggregatorCapabilities> for Job {
+    fn from(value: RefreshAggregatorCapabilities) -> Self {
+        Self::V1(V1::RefreshAggregatorCapabilities(value))
+    }
+}
+
+impl PartialEq<Job> for RefreshAggregatorCapabilities {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::RefreshAggregatorCapabilities(j)) if j == self)
+    }
+}
+impl PartialEq<RefreshAggregatorCapabilities> for Job {
+    fn eq(&self, other: &RefreshAggregatorCapabilities) -> bool {
+        matches!(self, Job::V1(V1::RefreshAggregatorCapabilities(j)) if j == other)
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    entity::{
+        aggregator::{fetch_hpke_config_ids, HpkeConfigError},
+        *,
+    },
+    queue::job::{v1::V1, EnqueueJob, Job, JobError, SharedJobState},
+};
+use sea_orm::{
+    sea_query::{all, Expr},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+const PERIOD: Duration = Duration::hours(1);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
+pub struct RefreshAggregatorHpkeConfigs;
+
+/// The result of fetching each aggregator's hpke configs, read before the job's transaction
+/// began.
+#[derive(Debug)]
+pub struct FetchedHpkeConfigs(Vec<(Uuid, Result<Vec<u8>, HpkeConfigError>)>);
+
+impl RefreshAggregatorHpkeConfigs {
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let fetched = self.fetch(job_state, db).await?;
+        self.perform_fetched(fetched, db).await
+    }
+
+    pub async fn fetch(
+        &self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<FetchedHpkeConfigs, JobError> {
+        let aggregators = Aggregators::find()
+            .filter(AggregatorColumn::DeletedAt.is_null())
+            .all(db)
+            .await?;
+
+        let mut fetched = vec![];
+        for aggregator in aggregators {
+            let http_client =
+                match aggregator.http_client(job_state.http_client.clone(), &job_state.crypter) {
+                    Ok(http_client) => http_client,
+                    Err(error) => {
+                        tracing::warn!(
+                            aggregator_id = %aggregator.id,
+                            %error,
+                            "could not build http client for aggregator"
+                        );
+                        continue;
+                    }
+                };
+
+            let result =
+                fetch_hpke_config_ids(&http_client, &aggregator.dap_url.clone().into()).await;
+            if let Err(error) = &result {
+                tracing::warn!(
+                    aggregator_id = %aggregator.id,
+                    %error,
+                    "aggregator hpke configs are unavailable"
+                );
+            }
+
+            fetched.push((aggregator.id, result));
+        }
+
+        Ok(FetchedHpkeConfigs(fetched))
+    }
+
+    pub async fn perform_fetched(
+        &mut self,
+        FetchedHpkeConfigs(fetched): FetchedHpkeConfigs,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        queue::Entity::delete_many()
+            .filter(all![
+                Expr::cust_with_expr("job->>'type' = $1", "RefreshAggregatorHpkeConfigs"),
+                queue::Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .exec(db)
+            .await?;
+
+        for (aggregator_id, result) in fetched {
+            if let Some(aggregator) = Aggregators::find_by_id(aggregator_id).one(db).await? {
+                aggregator.hpke_configs_checked(&result).update(db).await?;
+            }
+        }
+
+        Ok(Some(
+            EnqueueJob::from(RefreshAggregatorHpkeConfigs).scheduled_in(PERIOD),
+        ))
+    }
+}
+
+impl From<RefreshAggregatorHpkeConfigs> for Job {
+    fn from(value: RefreshAggregatorHpkeConfigs) -> Self {
+        Self::V1(V1::RefreshAggregatorHpkeConfigs(value))
+    }
+}
+
+impl PartialEq<Job> for RefreshAggregatorHpkeConfigs {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::RefreshAggregatorHpkeConfigs(j)) if j == self)
+    }
+}
+impl PartialEq<RefreshAggregatorHpkeConfigs> for Job {
+    fn eq(&self, other: &RefreshAggregatorHpkeConfigs) -> bool {
+        matches!(self, Job::V1(V1::RefreshAggregatorHpkeConfigs(j)) if j == other)
+    }
+}
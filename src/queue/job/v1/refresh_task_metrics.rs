@@ -0,0 +1,141 @@
+use crate::{
+    entity::{task::FetchedTaskMetrics, *},
+    queue::job::{v1::V1, EnqueueJob, EvaluateTaskAlerts, Job, JobError, SharedJobState},
+};
+use sea_orm::{
+    sea_query::{all, any, Expr, ExprTrait},
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+const PERIOD: Duration = Duration::minutes(5);
+
+/// Refreshes the metrics of each unexpired task that an alert rule applies to and evaluates its
+/// alerts, so that alerts fire and resolve whether or not anyone is looking at the task. Tasks
+/// whose metrics were refreshed within the last period are skipped.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
+pub struct RefreshTaskMetrics;
+
+/// The metrics of each task that could be fetched, read before the job's transaction began.
+#[derive(Debug)]
+pub struct FetchedTasksMetrics(Vec<(String, FetchedTaskMetrics)>);
+
+impl RefreshTaskMetrics {
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let fetched = self.fetch(job_state, db).await?;
+        self.perform_fetched(fetched, db).await
+    }
+
+    pub async fn fetch(
+        &self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<FetchedTasksMetrics, JobError> {
+        if !job_state.feature_flags.metrics_refresh_enabled {
+            return Ok(FetchedTasksMetrics(vec![]));
+        }
+
+        let mut fetched = vec![];
+        for task in stale_tasks_with_alert_rules(db).await? {
+            match FetchedTaskMetrics::fetch(&task, &job_state.http_client, &job_state.crypter, db)
+                .await
+            {
+                Ok(metrics) => fetched.push((task.id, metrics)),
+                Err(error) => {
+                    tracing::warn!(task_id = %task.id, %error, "could not refresh task metrics")
+                }
+            }
+        }
+
+        Ok(FetchedTasksMetrics(fetched))
+    }
+
+    pub async fn perform_fetched(
+        &mut self,
+        FetchedTasksMetrics(fetched): FetchedTasksMetrics,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        queue::Entity::delete_many()
+            .filter(all![
+                Expr::cust("job->>'type'").eq("RefreshTaskMetrics"),
+                queue::Column::ScheduledAt.gt(OffsetDateTime::now_utc()),
+            ])
+            .exec(db)
+            .await?;
+
+        for (task_id, metrics) in fetched {
+            // the task may have been deleted while its metrics were being fetched
+            let Some(task) = Tasks::find_by_id(&task_id)
+                .filter(TaskColumn::DeletedAt.is_null())
+                .one(db)
+                .await?
+            else {
+                continue;
+            };
+            metrics.write(task, db).await?;
+            Job::from(EvaluateTaskAlerts { task_id }).insert(db).await?;
+        }
+
+        Ok(Some(
+            EnqueueJob::from(RefreshTaskMetrics).scheduled_in(PERIOD),
+        ))
+    }
+}
+
+async fn stale_tasks_with_alert_rules(db: &impl ConnectionTrait) -> Result<Vec<Task>, DbErr> {
+    let alert_rules = AlertRules::find()
+        .filter(AlertRuleColumn::DeletedAt.is_null())
+        .all(db)
+        .await?;
+    if alert_rules.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut account_ids = vec![];
+    let mut task_ids = vec![];
+    for alert_rule in alert_rules {
+        match alert_rule.task_id {
+            Some(task_id) => task_ids.push(task_id),
+            None => account_ids.push(alert_rule.account_id),
+        }
+    }
+
+    let now = OffsetDateTime::now_utc();
+    Tasks::find()
+        .filter(all![
+            TaskColumn::DeletedAt.is_null(),
+            any![
+                TaskColumn::Expiration.is_null(),
+                TaskColumn::Expiration.gt(now)
+            ],
+            TaskColumn::UpdatedAt.lte(now - PERIOD),
+            any![
+                TaskColumn::AccountId.is_in(account_ids),
+                TaskColumn::Id.is_in(task_ids)
+            ],
+        ])
+        .all(db)
+        .await
+}
+
+impl From<RefreshTaskMetrics> for Job {
+    fn from(value: RefreshTaskMetrics) -> Self {
+        Self::V1(V1::RefreshTaskMetrics(value))
+    }
+}
+
+impl PartialEq<Job> for RefreshTaskMetrics {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::RefreshTaskMetrics(j)) if j == self)
+    }
+}
+impl PartialEq<RefreshTaskMetrics> for Job {
+    fn eq(&self, other: &RefreshTaskMetrics) -> bool {
+        matches!(self, Job::V1(V1::RefreshTaskMetrics(j)) if j == other)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    entity::*,
+    queue::{EnqueueJob, Job, JobError, SharedJobState, V1},
+};
+use sea_orm::{ConnectionTrait, EntityTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendAlertEmail {
+    pub membership_id: Uuid,
+    pub alert_id: Uuid,
+    /// whether this announces that the alert stopped firing
+    pub resolved: bool,
+    pub message_id: Uuid,
+}
+
+impl SendAlertEmail {
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let (membership, account) = Memberships::find_by_id(self.membership_id)
+            .find_also_related(Accounts)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("membership"), self.membership_id.to_string())
+            })?;
+
+        let account = account.ok_or_else(|| {
+            JobError::MissingRecord(String::from("account"), membership.account_id.to_string())
+        })?;
+
+        let (alert, alert_rule) = Alerts::find_by_id(self.alert_id)
+            .find_also_related(AlertRules)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("alert"), self.alert_id.to_string())
+            })?;
+
+        let alert_rule = alert_rule.ok_or_else(|| {
+            JobError::MissingRecord(String::from("alert rule"), alert.alert_rule_id.to_string())
+        })?;
+
+        let task = Tasks::find_by_id(&alert.task_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| JobError::MissingRecord(String::from("task"), alert.task_id.clone()))?;
+
+        job_state
+            .postmark_client
+            .send_email_template(
+                &membership.user_email,
+                if self.resolved {
+                    "alert-resolved"
+                } else {
+                    "alert-fired"
+                },
+                &json!({
+                    "email": membership.user_email,
+                    "account_name": &account.name,
+                    "alert_rule_id": alert_rule.id,
+                    "alert_rule_name": &alert_rule.name,
+                    "counter": alert_rule.counter,
+                    "relative_to": alert_rule.relative_to,
+                    "threshold": alert_rule.threshold,
+                    "window_seconds": alert_rule.window_seconds,
+                    "value": alert.value,
+                    "task_id": &task.id,
+                    "task_name": &task.name,
+                    "fired_at": alert.fired_at.format(&Rfc3339).unwrap_or_default(),
+                    "resolved_at": alert
+                        .resolved_at
+                        .and_then(|resolved_at| resolved_at.format(&Rfc3339).ok()),
+                }),
+                Some(self.message_id.to_string()),
+            )
+            .await?;
+
+        Ok(None)
+    }
+}
+
+impl From<SendAlertEmail> for Job {
+    fn from(value: SendAlertEmail) -> Self {
+        Self::V1(V1::SendAlertEmail(value))
+    }
+}
+impl PartialEq<Job> for SendAlertEmail {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::SendAlertEmail(j)) if j == self)
+    }
+}
+
+impl PartialEq<SendAlertEmail> for Job {
+    fn eq(&self, other: &SendAlertEmail) -> bool {
+        matches!(self, Job::V1(V1::SendAlertEmail(j)) if j == other)
+    }
+}
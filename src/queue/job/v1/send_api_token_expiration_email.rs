@@ -0,0 +1,88 @@
+use crate::{
+    entity::*,
+    queue::{EnqueueJob, Job, JobError, SharedJobState, V1},
+};
+use sea_orm::{ConnectionTrait, EntityTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendApiTokenExpirationEmail {
+    pub membership_id: Uuid,
+    pub api_token_id: Uuid,
+    pub message_id: Uuid,
+}
+
+impl SendApiTokenExpirationEmail {
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let (membership, account) = Memberships::find_by_id(self.membership_id)
+            .find_also_related(Accounts)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("membership"), self.membership_id.to_string())
+            })?;
+
+        let account = account.ok_or_else(|| {
+            JobError::MissingRecord(String::from("account"), membership.account_id.to_string())
+        })?;
+
+        // viewers cannot replace the token, so they are not asked to
+        if membership.role < MembershipRole::Member {
+            return Ok(None);
+        }
+
+        let api_token = ApiTokens::find_by_id(self.api_token_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("api token"), self.api_token_id.to_string())
+            })?;
+
+        // there is nothing to warn about if the token was revoked or no longer expires
+        let Some(expires_at) = api_token.expires_at.filter(|_| !api_token.is_tombstoned()) else {
+            return Ok(None);
+        };
+
+        job_state
+            .postmark_client
+            .send_email_template(
+                &membership.user_email,
+                "api-token-expiring",
+                &json!({
+                    "email": membership.user_email,
+                    "account_name": &account.name,
+                    "api_token_id": api_token.id,
+                    "api_token_name": &api_token.name,
+                    "expires_at": expires_at.format(&Rfc3339).unwrap_or_default(),
+                }),
+                Some(self.message_id.to_string()),
+            )
+            .await?;
+
+        Ok(None)
+    }
+}
+
+impl From<SendApiTokenExpirationEmail> for Job {
+    fn from(value: SendApiTokenExpirationEmail) -> Self {
+        Self::V1(V1::SendApiTokenExpirationEmail(value))
+    }
+}
+impl PartialEq<Job> for SendApiTokenExpirationEmail {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::SendApiTokenExpirationEmail(j)) if j == self)
+    }
+}
+
+impl PartialEq<SendApiTokenExpirationEmail> for Job {
+    fn eq(&self, other: &SendApiTokenExpirationEmail) -> bool {
+        matches!(self, Job::V1(V1::SendApiTokenExpirationEmail(j)) if j == other)
+    }
+}
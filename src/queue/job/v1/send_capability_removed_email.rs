@@ -0,0 +1,87 @@
+use crate::{
+    entity::*,
+    queue::{EnqueueJob, Job, JobError, SharedJobState, V1},
+};
+use sea_orm::{ConnectionTrait, EntityTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SendCapabilityRemovedEmail {
+    pub membership_id: Uuid,
+    pub capability_change_id: Uuid,
+    pub task_ids: Vec<String>,
+    pub message_id: Uuid,
+}
+
+impl SendCapabilityRemovedEmail {
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let (membership, account) = Memberships::find_by_id(self.membership_id)
+            .find_also_related(Accounts)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("membership"), self.membership_id.to_string())
+            })?;
+
+        let account = account.ok_or_else(|| {
+            JobError::MissingRecord(String::from("account"), membership.account_id.to_string())
+        })?;
+
+        let (change, aggregator) =
+            AggregatorCapabilityChanges::find_by_id(self.capability_change_id)
+                .find_also_related(Aggregators)
+                .one(db)
+                .await?
+                .ok_or_else(|| {
+                    JobError::MissingRecord(
+                        String::from("aggregator_capability_change"),
+                        self.capability_change_id.to_string(),
+                    )
+                })?;
+
+        let aggregator = aggregator.ok_or_else(|| {
+            JobError::MissingRecord(String::from("aggregator"), change.aggregator_id.to_string())
+        })?;
+
+        job_state
+            .postmark_client
+            .send_email_template(
+                &membership.user_email,
+                "aggregator-capability-removed",
+                &json!({
+                    "email": membership.user_email,
+                    "account_name": &account.name,
+                    "aggregator_name": &aggregator.name,
+                    "removed": change.removed(),
+                    "task_ids": &self.task_ids,
+                }),
+                Some(self.message_id.to_string()),
+            )
+            .await?;
+
+        Ok(None)
+    }
+}
+
+impl From<SendCapabilityRemovedEmail> for Job {
+    fn from(value: SendCapabilityRemovedEmail) -> Self {
+        Self::V1(V1::SendCapabilityRemovedEmail(value))
+    }
+}
+impl PartialEq<Job> for SendCapabilityRemovedEmail {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::SendCapabilityRemovedEmail(j)) if j == self)
+    }
+}
+
+impl PartialEq<SendCapabilityRemovedEmail> for Job {
+    fn eq(&self, other: &SendCapabilityRemovedEmail) -> bool {
+        matches!(self, Job::V1(V1::SendCapabilityRemovedEmail(j)) if j == other)
+    }
+}
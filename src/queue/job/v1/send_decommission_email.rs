@@ -0,0 +1,127 @@
+use crate::{
+    entity::{aggregator::Decommission, *},
+    queue::{EnqueueJob, Job, JobError, SharedJobState, V1},
+};
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use time::format_description::well_known::Rfc3339;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SendDecommissionEmail {
+    pub membership_id: Uuid,
+    pub aggregator_id: Uuid,
+    pub task_ids: Vec<String>,
+    pub message_id: Uuid,
+}
+
+impl SendDecommissionEmail {
+    /// Emails the members of every account with a task that uses a decommissioned aggregator,
+    /// and tells the account's webhooks.
+    pub async fn notify_affected_accounts(
+        decommission: &Decommission,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        let aggregator_id = decommission.aggregator.id;
+        let mut affected_tasks = BTreeMap::<Uuid, Vec<String>>::new();
+        for task in &decommission.affected_tasks {
+            affected_tasks
+                .entry(task.account_id)
+                .or_default()
+                .push(task.id.clone());
+        }
+
+        for (account_id, task_ids) in affected_tasks {
+            for membership in Memberships::for_account(account_id).all(db).await? {
+                Job::from(Self {
+                    membership_id: membership.id,
+                    aggregator_id,
+                    task_ids: task_ids.clone(),
+                    message_id: Uuid::new_v4(),
+                })
+                .insert(db)
+                .await?;
+            }
+
+            Webhooks::enqueue(
+                account_id,
+                WebhookEventType::AggregatorDecommissioned,
+                json!({
+                    "aggregator": &decommission.aggregator,
+                    "task_ids": task_ids,
+                }),
+                db,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn perform(
+        &mut self,
+        job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let (membership, account) = Memberships::find_by_id(self.membership_id)
+            .find_also_related(Accounts)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("membership"), self.membership_id.to_string())
+            })?;
+
+        let account = account.ok_or_else(|| {
+            JobError::MissingRecord(String::from("account"), membership.account_id.to_string())
+        })?;
+
+        let aggregator = Aggregators::find_by_id(self.aggregator_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("aggregator"), self.aggregator_id.to_string())
+            })?;
+
+        // there is nothing to announce if the aggregator is no longer being decommissioned
+        let Some(sunset_at) = aggregator.sunset_at else {
+            return Ok(None);
+        };
+
+        job_state
+            .postmark_client
+            .send_email_template(
+                &membership.user_email,
+                "aggregator-decommissioned",
+                &json!({
+                    "email": membership.user_email,
+                    "account_name": &account.name,
+                    "aggregator_name": &aggregator.name,
+                    "sunset_at": sunset_at.format(&Rfc3339).unwrap_or_default(),
+                    "task_ids": &self.task_ids,
+                }),
+                Some(self.message_id.to_string()),
+            )
+            .await?;
+
+        Ok(None)
+    }
+}
+
+impl From<SendDecommissionEmail> for Job {
+    fn from(value: SendDecommissionEmail) -> Self {
+        Self::V1(V1::SendDecommissionEmail(value))
+    }
+}
+impl PartialEq<Job> for SendDecommissionEmail {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::SendDecommissionEmail(j)) if j == self)
+    }
+}
+
+impl PartialEq<SendDecommissionEmail> for Job {
+    fn eq(&self, other: &SendDecommissionEmail) -> bool {
+        matches!(self, Job::V1(V1::SendDecommissionEmail(j)) if j == other)
+    }
+}
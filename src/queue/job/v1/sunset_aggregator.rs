@@ -0,0 +1,102 @@
+use crate::{
+    entity::*,
+    queue::{
+        job::{v1::V1, EnqueueJob, ExpireAggregatorTasks, Job, JobError, SharedJobState},
+        JobStatus,
+    },
+};
+use sea_orm::{
+    sea_query::{all, Expr, ExprTrait},
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter,
+};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Scheduled for a decommissioned aggregator's sunset date. Tombstones the aggregator and expires
+/// any tasks that still use it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
+pub struct SunsetAggregator {
+    pub aggregator_id: Uuid,
+}
+
+impl SunsetAggregator {
+    /// Schedules the sunset of an aggregator, moving its pending sunset if it already has one
+    /// rather than scheduling another.
+    pub async fn schedule(
+        aggregator_id: Uuid,
+        sunset_at: OffsetDateTime,
+        db: &impl ConnectionTrait,
+    ) -> Result<(), DbErr> {
+        let rescheduled = queue::Entity::update_many()
+            .col_expr(queue::Column::ScheduledAt, Expr::value(sunset_at))
+            .col_expr(
+                queue::Column::UpdatedAt,
+                Expr::value(OffsetDateTime::now_utc()),
+            )
+            .filter(all![
+                queue::Column::Status.eq(JobStatus::Pending),
+                Expr::cust("job->>'type'").eq("SunsetAggregator"),
+                Expr::cust("job->>'aggregator_id'").eq(aggregator_id.to_string()),
+            ])
+            .exec(db)
+            .await?
+            .rows_affected;
+
+        if rescheduled == 0 {
+            queue::ActiveModel::from(
+                EnqueueJob::from(Self { aggregator_id }).scheduled_at(sunset_at),
+            )
+            .insert(db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn perform(
+        &mut self,
+        _job_state: &SharedJobState,
+        db: &impl ConnectionTrait,
+    ) -> Result<Option<EnqueueJob>, JobError> {
+        let aggregator = Aggregators::find_by_id(self.aggregator_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                JobError::MissingRecord(String::from("aggregator"), self.aggregator_id.to_string())
+            })?;
+
+        // a job that was already running when the sunset was moved later finds that the sunset
+        // date has not arrived yet
+        if aggregator
+            .sunset_at
+            .is_none_or(|sunset_at| sunset_at > OffsetDateTime::now_utc())
+        {
+            return Ok(None);
+        }
+
+        if !aggregator.is_tombstoned() {
+            aggregator.tombstone().update(db).await?;
+        }
+
+        Ok(Some(EnqueueJob::from(ExpireAggregatorTasks::new(
+            self.aggregator_id,
+        ))))
+    }
+}
+
+impl From<SunsetAggregator> for Job {
+    fn from(value: SunsetAggregator) -> Self {
+        Self::V1(V1::SunsetAggregator(value))
+    }
+}
+
+impl PartialEq<Job> for SunsetAggregator {
+    fn eq(&self, other: &Job) -> bool {
+        matches!(other, Job::V1(V1::SunsetAggregator(j)) if j == self)
+    }
+}
+impl PartialEq<SunsetAggregator> for Job {
+    fn eq(&self, other: &SunsetAggregator) -> bool {
+        matches!(self, Job::V1(V1::SunsetAggregator(j)) if j == other)
+    }
+}
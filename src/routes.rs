@@ -1,33 +1,41 @@
 mod accounts;
 mod admin;
 mod aggregators;
+mod alerts;
 mod api_tokens;
+mod audit_events;
 mod collector_credentials;
 mod health_check;
 mod memberships;
 mod tasks;
 mod users;
+mod webhooks;
 
 pub use health_check::health_check;
 
 pub(crate) mod axum_routes {
     use super::{
-        accounts, admin::axum_handler as admin, aggregators::axum_handler as aggregators,
-        api_tokens, collector_credentials, memberships, tasks::axum_handler as tasks, users,
+        accounts, admin::axum_handler as admin, aggregators::axum_handler as aggregators, alerts,
+        api_tokens, audit_events, collector_credentials, memberships, tasks::axum_handler as tasks,
+        users, webhooks,
     };
     use crate::handler::{custom_mime_types::ReplaceMimeTypesLayer, AxumAppState};
-    use axum::routing::{delete, get};
+    use axum::routing::{delete, get, post, put};
 
     /// Axum sub-router for `/api` routes.
     pub fn api_router(state: &AxumAppState) -> axum::Router<AxumAppState> {
         axum::Router::new()
             .route("/users/me", get(users::show))
             .route("/accounts", get(accounts::index).post(accounts::create))
-            .route("/memberships/{membership_id}", delete(memberships::delete))
+            .route(
+                "/memberships/{membership_id}",
+                delete(memberships::delete).patch(memberships::update),
+            )
             .route(
                 "/api_tokens/{api_token_id}",
                 delete(api_tokens::delete).patch(api_tokens::update),
             )
+            .route("/api_tokens/{api_token_id}/usage", get(api_tokens::usage))
             .route(
                 "/collector_credentials/{collector_credential_id}",
                 delete(collector_credentials::delete)
@@ -44,15 +52,66 @@ pub(crate) mod axum_routes {
                     .patch(aggregators::update)
                     .delete(aggregators::delete),
             )
+            .route(
+                "/aggregators/{aggregator_id}/decommission",
+                get(aggregators::show_decommission).post(aggregators::decommission),
+            )
+            .route(
+                "/aggregators/{aggregator_id}/task_expiration",
+                get(aggregators::task_expiration),
+            )
+            .route(
+                "/aggregators/{aggregator_id}/access",
+                get(aggregators::index_access),
+            )
+            .route(
+                "/aggregators/{aggregator_id}/access/{account_id}",
+                put(aggregators::grant_access).delete(aggregators::revoke_access),
+            )
+            .route(
+                "/aggregators/{aggregator_id}/secondary_bearer_token",
+                put(aggregators::stage_secondary_bearer_token)
+                    .delete(aggregators::discard_secondary_bearer_token),
+            )
+            .route(
+                "/aggregators/{aggregator_id}/secondary_bearer_token/verify",
+                post(aggregators::verify_secondary_bearer_token),
+            )
+            .route(
+                "/aggregators/{aggregator_id}/secondary_bearer_token/promote",
+                post(aggregators::promote_secondary_bearer_token),
+            )
             .route(
                 "/tasks/{task_id}",
                 get(tasks::show).patch(tasks::update).delete(tasks::delete),
             )
+            .route(
+                "/tasks/{task_id}/collection_readiness",
+                get(tasks::collection_readiness),
+            )
+            .route("/dp_estimate", post(tasks::dp_estimate))
+            .route(
+                "/alert_rules/{alert_rule_id}",
+                get(alerts::show)
+                    .patch(alerts::update)
+                    .delete(alerts::delete),
+            )
+            .route(
+                "/webhooks/{webhook_id}",
+                get(webhooks::show)
+                    .patch(webhooks::update)
+                    .delete(webhooks::delete),
+            )
+            .route(
+                "/webhooks/{webhook_id}/deliveries",
+                get(webhooks::deliveries),
+            )
             .nest(
                 "/admin",
                 axum::Router::new()
                     .route("/queue", get(admin::index))
                     .route("/queue/{job_id}", get(admin::show).delete(admin::delete))
+                    .route("/audit_events", get(audit_events::admin_index))
                     .route_layer(axum::middleware::from_fn_with_state(
                         state.clone(),
                         admin::require_admin,
@@ -70,14 +129,28 @@ pub(crate) mod axum_routes {
                         "/api_tokens",
                         get(api_tokens::index).post(api_tokens::create),
                     )
+                    .route("/audit_events", get(audit_events::index))
                     .route(
                         "/collector_credentials",
                         get(collector_credentials::index).post(collector_credentials::create),
                     )
                     .route("/tasks", get(tasks::index).post(tasks::create))
+                    .route("/metrics_summary", get(tasks::metrics_summary))
+                    .route("/webhooks", get(webhooks::index).post(webhooks::create))
+                    .route("/alert_rules", get(alerts::index).post(alerts::create))
+                    .route("/alerts", get(alerts::firing))
                     .route(
                         "/aggregators",
                         get(aggregators::index_for_account).post(aggregators::create),
+                    )
+                    .route(
+                        "/aggregator_access",
+                        get(aggregators::index_access_for_account)
+                            .post(aggregators::request_access),
+                    )
+                    .route(
+                        "/aggregator_pairs/compatibility",
+                        get(aggregators::compatibility),
                     ),
             )
             .layer(ReplaceMimeTypesLayer)
@@ -1,5 +1,8 @@
 use crate::{
-    entity::{Account, Accounts, CreateMembership, NewAccount, UpdateAccount},
+    entity::{
+        Account, Accounts, AuditAction, AuditEvents, CreateMembership, MembershipRole, NewAccount,
+        UpdateAccount,
+    },
     handler::{extract::extract_entity, extract::Json, Error},
     Db, Permissions, PermissionsActor,
 };
@@ -12,8 +15,13 @@ use httpdate::fmt_http_date;
 use sea_orm::{ActiveModelTrait, TransactionTrait};
 
 impl Permissions for Account {
+    fn allow_read(&self, actor: &PermissionsActor) -> bool {
+        actor.has_role(&self.id, MembershipRole::Viewer)
+    }
+
+    // this also governs creating tasks, aggregators and collector credentials for the account
     fn allow_write(&self, actor: &PermissionsActor) -> bool {
-        actor.is_admin() || actor.account_ids().contains(&self.id)
+        actor.has_role(&self.id, MembershipRole::Member)
     }
 }
 
@@ -55,9 +63,18 @@ pub async fn create(
 
     let transaction = db.begin().await?;
     let account = new_account.build()?.insert(&transaction).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Create,
+        None,
+        Some(&account),
+        &transaction,
+    )
+    .await?;
     if let PermissionsActor::User(user, _) = actor {
         let membership = CreateMembership {
             user_email: Some(user.email),
+            role: Some(MembershipRole::Owner),
         };
         membership.build(&account)?.insert(&transaction).await?;
     }
@@ -66,10 +83,24 @@ pub async fn create(
 }
 
 pub async fn update(
+    actor: PermissionsActor,
     account: Account,
     State(db): State<Db>,
     Json(update_account): Json<UpdateAccount>,
 ) -> Result<impl IntoResponse, Error> {
-    let account = update_account.build(account)?.update(&db).await?;
-    Ok((StatusCode::ACCEPTED, Json(account)))
+    if !actor.has_role(&account.id, MembershipRole::Admin) {
+        return Err(Error::AccessDenied);
+    }
+    let tx = db.begin().await?;
+    let updated = update_account.build(account.clone())?.update(&tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Update,
+        Some(&account),
+        Some(&updated),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok((StatusCode::ACCEPTED, Json(updated)))
 }
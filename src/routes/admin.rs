@@ -1,5 +1,8 @@
 use crate::{
-    entity::queue::{self, Column, Entity, JobStatus, Model},
+    entity::{
+        queue::{self, Column, Entity, JobStatus, Model},
+        AuditAction, AuditEvents,
+    },
     handler::extract::Json,
     Db, Error, PermissionsActor,
 };
@@ -8,7 +11,7 @@ use axum::http::{header, request::Parts, StatusCode};
 use axum::middleware::Next;
 use axum::response::IntoResponse;
 use httpdate::fmt_http_date;
-use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryOrder, QuerySelect};
+use sea_orm::{ColumnTrait, EntityTrait, ModelTrait, QueryOrder, QuerySelect, TransactionTrait};
 use serde::Deserialize;
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -80,8 +83,15 @@ pub mod axum_handler {
         ([(header::LAST_MODIFIED, last_modified)], Json(queue_job))
     }
 
-    pub async fn delete(queue_job: Model, State(db): State<Db>) -> Result<StatusCode, Error> {
-        queue_job.delete(&db).await?;
+    pub async fn delete(
+        actor: PermissionsActor,
+        queue_job: Model,
+        State(db): State<Db>,
+    ) -> Result<StatusCode, Error> {
+        let tx = db.begin().await?;
+        queue_job.clone().delete(&tx).await?;
+        AuditEvents::record(&actor, AuditAction::Delete, Some(&queue_job), None, &tx).await?;
+        tx.commit().await?;
         Ok(StatusCode::NO_CONTENT)
     }
 }
@@ -1,17 +1,61 @@
 use crate::clients::HttpClient;
 use crate::{
     config::FeatureFlags,
-    entity::{Account, Aggregator, AggregatorColumn, Aggregators, NewAggregator, UpdateAggregator},
+    entity::{
+        aggregator::{Compatibility, Decommission, DecommissionAggregator},
+        task::load_aggregator,
+        Account, Aggregator, AggregatorAccess, AggregatorAccessColumn, AggregatorAccesses,
+        AggregatorColumn, Aggregators, ApiTokenScope, AuditAction, AuditEvents, MembershipRole,
+        NewAggregator, RequestAggregatorAccess, StageBearerToken, UpdateAggregator,
+        WebhookEventType, Webhooks,
+    },
     handler::extract::{extract_entity, Json},
+    queue::{
+        ExpireAggregatorTasks, Job, SendDecommissionEmail, SunsetAggregator, TaskExpirationProgress,
+    },
     AdminPermissionsActor, Crypter, Db, Error, Permissions, PermissionsActor,
 };
-use axum::extract::{FromRef, FromRequestParts, State};
+use axum::extract::{FromRef, FromRequestParts, Query, State};
 use axum::http::{request::Parts, StatusCode};
-use axum::response::IntoResponse;
+use axum::response::{IntoResponse, Response};
 use sea_orm::{
-    sea_query::{all, any},
-    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter,
+    sea_query::all, ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, TransactionTrait,
 };
+use serde::Deserialize;
+use serde_json::json;
+use time::OffsetDateTime;
+use validator::{ValidationError, ValidationErrors};
+
+/// Notifies every account with a task that uses this aggregator, unless they were notified when
+/// it was first decommissioned, and schedules the aggregator's sunset.
+async fn notify_decommission(
+    decommission: &Decommission,
+    previous_sunset_at: Option<OffsetDateTime>,
+    sunset_at: OffsetDateTime,
+    db: &impl ConnectionTrait,
+) -> Result<(), Error> {
+    if previous_sunset_at.is_none() {
+        SendDecommissionEmail::notify_affected_accounts(decommission, db).await?;
+    }
+    SunsetAggregator::schedule(decommission.aggregator.id, sunset_at, db).await?;
+    Ok(())
+}
+
+/// Tells an account's webhooks that one of its own aggregators was deleted. Shared aggregators
+/// reach their accounts' webhooks through decommissioning instead.
+async fn notify_deleted(aggregator: &Aggregator, db: &impl ConnectionTrait) -> Result<(), Error> {
+    if let Some(account_id) = aggregator.account_id {
+        Webhooks::enqueue(
+            account_id,
+            WebhookEventType::AggregatorDeleted,
+            json!(aggregator),
+            db,
+        )
+        .await?;
+    }
+    Ok(())
+}
 
 impl<S> FromRequestParts<S> for Aggregator
 where
@@ -27,16 +71,20 @@ where
 impl Permissions for Aggregator {
     fn allow_read(&self, actor: &crate::PermissionsActor) -> bool {
         actor.is_admin()
-            || match &self.account_id {
-                Some(account_id) => actor.account_ids().contains(account_id),
-                None => true,
-            }
+            || actor.can_read_account_resources(Some(ApiTokenScope::AggregatorsWrite))
+                && match &self.account_id {
+                    Some(account_id) => actor.has_role(account_id, MembershipRole::Viewer),
+                    None => true,
+                }
     }
 
     fn allow_write(&self, actor: &crate::PermissionsActor) -> bool {
         actor.is_admin()
             || match &self.account_id {
-                Some(account_id) => actor.account_ids().contains(account_id),
+                Some(account_id) => {
+                    actor.has_role(account_id, MembershipRole::Member)
+                        && actor.has_scope(ApiTokenScope::AggregatorsWrite)
+                }
                 None => false,
             }
     }
@@ -45,44 +93,56 @@ impl Permissions for Aggregator {
 pub mod axum_handler {
     use super::*;
 
-    pub async fn show(aggregator: Aggregator) -> Json<Aggregator> {
-        Json(aggregator)
+    pub async fn show(
+        actor: PermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+    ) -> Result<Json<Aggregator>, Error> {
+        if actor.is_admin() || aggregator.is_visible_to(actor.account_ids(), &db).await? {
+            Ok(Json(aggregator))
+        } else {
+            Err(Error::NotFound)
+        }
     }
 
     pub async fn index_shared(
-        _actor: PermissionsActor,
+        actor: PermissionsActor,
         State(db): State<Db>,
     ) -> Result<Json<Vec<Aggregator>>, Error> {
-        Ok(Json(
+        let aggregators = if actor.is_admin() {
             Aggregators::find()
                 .filter(all![
                     AggregatorColumn::AccountId.is_null(),
                     AggregatorColumn::DeletedAt.is_null()
                 ])
                 .all(&db)
-                .await?,
-        ))
+                .await?
+        } else if actor.can_read_account_resources(Some(ApiTokenScope::AggregatorsWrite)) {
+            Aggregators::visible_to(actor.account_ids())
+                .filter(AggregatorColumn::AccountId.is_null())
+                .all(&db)
+                .await?
+        } else {
+            return Err(Error::AccessDenied);
+        };
+        Ok(Json(aggregators))
     }
 
     pub async fn index_for_account(
+        actor: PermissionsActor,
         account: Account,
         State(db): State<Db>,
     ) -> Result<Json<Vec<Aggregator>>, Error> {
+        if !actor.can_read_account_resources(Some(ApiTokenScope::AggregatorsWrite)) {
+            return Err(Error::AccessDenied);
+        }
         Ok(Json(
-            Aggregators::find()
-                .filter(all![
-                    any![
-                        AggregatorColumn::AccountId.eq(account.id),
-                        AggregatorColumn::AccountId.is_null()
-                    ],
-                    AggregatorColumn::DeletedAt.is_null()
-                ])
-                .all(&db)
-                .await?,
+            Aggregators::visible_to(vec![account.id]).all(&db).await?,
         ))
     }
 
     pub async fn create(
+        actor: PermissionsActor,
         account: Account,
         State(db): State<Db>,
         State(client): State<HttpClient>,
@@ -90,6 +150,10 @@ pub mod axum_handler {
         State(feature_flags): State<FeatureFlags>,
         Json(new_aggregator): Json<NewAggregator>,
     ) -> Result<impl IntoResponse, Error> {
+        if !actor.has_scope(ApiTokenScope::AggregatorsWrite) {
+            return Err(Error::AccessDenied);
+        }
+
         let aggregator = new_aggregator
             .build(
                 Some(&account),
@@ -97,35 +161,198 @@ pub mod axum_handler {
                 &crypter,
                 feature_flags.ssrf_validation_enabled,
             )
-            .await?
-            .insert(&db)
             .await?;
+        let tx = db.begin().await?;
+        let aggregator = aggregator.insert(&tx).await?;
+        AuditEvents::record(&actor, AuditAction::Create, None, Some(&aggregator), &tx).await?;
+        tx.commit().await?;
         Ok((StatusCode::CREATED, Json(aggregator)))
     }
 
     pub async fn update(
+        actor: PermissionsActor,
         aggregator: Aggregator,
         State(db): State<Db>,
         State(client): State<HttpClient>,
         State(crypter): State<Crypter>,
         Json(update_aggregator): Json<UpdateAggregator>,
     ) -> Result<Json<Aggregator>, Error> {
-        Ok(Json(
-            update_aggregator
-                .build(aggregator, client, &crypter)
-                .await?
-                .update(&db)
-                .await?,
-        ))
+        let updated = update_aggregator
+            .build(aggregator.clone(), client, &crypter)
+            .await?;
+        let tx = db.begin().await?;
+        let updated = updated.update(&tx).await?;
+        AuditEvents::record(
+            &actor,
+            AuditAction::Update,
+            Some(&aggregator),
+            Some(&updated),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(Json(updated))
     }
 
-    pub async fn delete(aggregator: Aggregator, State(db): State<Db>) -> Result<StatusCode, Error> {
-        aggregator.tombstone().update(&db).await?;
+    pub async fn stage_secondary_bearer_token(
+        actor: PermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+        State(crypter): State<Crypter>,
+        Json(stage_bearer_token): Json<StageBearerToken>,
+    ) -> Result<Json<Aggregator>, Error> {
+        let tx = db.begin().await?;
+        let updated = stage_bearer_token
+            .build(aggregator.clone(), &crypter)?
+            .update(&tx)
+            .await?;
+        AuditEvents::record(
+            &actor,
+            AuditAction::StageSecondaryBearerToken,
+            Some(&aggregator),
+            Some(&updated),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(Json(updated))
+    }
+
+    pub async fn verify_secondary_bearer_token(
+        aggregator: Aggregator,
+        State(client): State<HttpClient>,
+        State(crypter): State<Crypter>,
+    ) -> Result<Json<Aggregator>, Error> {
+        aggregator
+            .verify_secondary_bearer_token(client, &crypter)
+            .await?;
+        Ok(Json(aggregator))
+    }
+
+    pub async fn promote_secondary_bearer_token(
+        actor: PermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+        State(client): State<HttpClient>,
+        State(crypter): State<Crypter>,
+    ) -> Result<Json<Aggregator>, Error> {
+        aggregator
+            .verify_secondary_bearer_token(client, &crypter)
+            .await?;
+        let tx = db.begin().await?;
+        let updated = aggregator
+            .clone()
+            .promote_secondary_bearer_token()
+            .update(&tx)
+            .await?;
+        AuditEvents::record(
+            &actor,
+            AuditAction::PromoteSecondaryBearerToken,
+            Some(&aggregator),
+            Some(&updated),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(Json(updated))
+    }
+
+    pub async fn discard_secondary_bearer_token(
+        actor: PermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+    ) -> Result<StatusCode, Error> {
+        let mut active_model = aggregator.clone().into_active_model();
+        active_model.encrypted_secondary_bearer_token = ActiveValue::Set(None);
+        active_model.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+        let tx = db.begin().await?;
+        let updated = active_model.update(&tx).await?;
+        AuditEvents::record(
+            &actor,
+            AuditAction::DiscardSecondaryBearerToken,
+            Some(&aggregator),
+            Some(&updated),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
         Ok(StatusCode::NO_CONTENT)
     }
 
+    #[derive(Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Cascade {
+        Expire,
+    }
+
+    #[derive(Deserialize)]
+    pub struct DeleteParams {
+        cascade: Option<Cascade>,
+    }
+
+    pub async fn delete(
+        actor: PermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+        Query(params): Query<DeleteParams>,
+    ) -> Result<Response, Error> {
+        let tx = db.begin().await?;
+        // tasks created with this aggregator hold a shared lock on it, so none can appear between
+        // counting them and tombstoning the aggregator
+        let aggregator = Aggregators::lock_for_delete(aggregator.id, &tx)
+            .await?
+            .ok_or(Error::NotFound)?;
+        let unexpired_tasks = aggregator.unexpired_tasks().count(&tx).await?;
+        if unexpired_tasks != 0 && !matches!(params.cascade, Some(Cascade::Expire)) {
+            let mut error = ValidationError::new("unexpired-tasks");
+            error.add_param("count".into(), &unexpired_tasks);
+            let mut errors = ValidationErrors::new();
+            errors.add("tasks", error);
+            return Err(errors.into());
+        }
+
+        let aggregator_id = aggregator.id;
+        let deleted = aggregator.clone().tombstone().update(&tx).await?;
+        AuditEvents::record(
+            &actor,
+            AuditAction::Delete,
+            Some(&aggregator),
+            Some(&deleted),
+            &tx,
+        )
+        .await?;
+        notify_deleted(&deleted, &tx).await?;
+        let response = if unexpired_tasks == 0 {
+            StatusCode::NO_CONTENT.into_response()
+        } else {
+            let job = Job::from(ExpireAggregatorTasks::new(aggregator_id))
+                .insert(&tx)
+                .await?;
+            (StatusCode::ACCEPTED, Json(job)).into_response()
+        };
+        tx.commit().await?;
+        Ok(response)
+    }
+
+    /// Reports how far expiring the tasks of an aggregator deleted with `cascade=expire` has
+    /// progressed, to anyone who can read the account the aggregator belonged to.
+    pub async fn task_expiration(
+        actor: PermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+    ) -> Result<Json<TaskExpirationProgress>, Error> {
+        if !actor.is_admin() && aggregator.account_id.is_none() {
+            return Err(Error::NotFound);
+        }
+
+        ExpireAggregatorTasks::progress(&aggregator, &db)
+            .await?
+            .map(Json)
+            .ok_or(Error::NotFound)
+    }
+
     pub async fn admin_create(
-        _admin: AdminPermissionsActor,
+        AdminPermissionsActor(actor): AdminPermissionsActor,
         State(db): State<Db>,
         State(client): State<HttpClient>,
         State(crypter): State<Crypter>,
@@ -139,9 +366,176 @@ pub mod axum_handler {
                 &crypter,
                 feature_flags.ssrf_validation_enabled,
             )
-            .await?
-            .insert(&db)
             .await?;
+        let tx = db.begin().await?;
+        let aggregator = aggregator.insert(&tx).await?;
+        AuditEvents::record(&actor, AuditAction::Create, None, Some(&aggregator), &tx).await?;
+        tx.commit().await?;
         Ok((StatusCode::CREATED, Json(aggregator)))
     }
+
+    #[derive(Deserialize)]
+    pub struct CompatibilityParams {
+        leader: Option<String>,
+        helper: Option<String>,
+    }
+
+    pub async fn compatibility(
+        account: Account,
+        State(db): State<Db>,
+        Query(params): Query<CompatibilityParams>,
+    ) -> Result<Json<Compatibility>, Error> {
+        let leader = load_aggregator(&account, params.leader.as_deref(), &db).await?;
+        let helper = load_aggregator(&account, params.helper.as_deref(), &db).await?;
+
+        match (leader, helper) {
+            (Ok(leader), Ok(helper)) => {
+                let mut compatibility = Compatibility::between(&leader, &helper);
+                if !leader.is_usable_by(account.id, &db).await? {
+                    compatibility = compatibility.without_access_to("leader_aggregator_id");
+                }
+                if !helper.is_usable_by(account.id, &db).await? {
+                    compatibility = compatibility.without_access_to("helper_aggregator_id");
+                }
+                Ok(Json(compatibility))
+            }
+            (leader, helper) => {
+                let mut errors = ValidationErrors::new();
+                if let Err(error) = leader {
+                    errors.add("leader", error);
+                }
+                if let Err(error) = helper {
+                    errors.add("helper", error);
+                }
+                Err(errors.into())
+            }
+        }
+    }
+
+    pub async fn show_decommission(
+        _admin: AdminPermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+    ) -> Result<Json<Decommission>, Error> {
+        Ok(Json(Decommission::load(aggregator, &db).await?))
+    }
+
+    pub async fn decommission(
+        AdminPermissionsActor(actor): AdminPermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+        Json(decommission_aggregator): Json<DecommissionAggregator>,
+    ) -> Result<Json<Decommission>, Error> {
+        let tx = db.begin().await?;
+        let previous = aggregator.clone();
+        let aggregator = decommission_aggregator
+            .build(aggregator)?
+            .update(&tx)
+            .await?;
+        AuditEvents::record(
+            &actor,
+            AuditAction::Decommission,
+            Some(&previous),
+            Some(&aggregator),
+            &tx,
+        )
+        .await?;
+        // unwrap safety: build validates that sunset_at is present
+        let sunset_at = aggregator.sunset_at.unwrap();
+        let decommission = Decommission::load(aggregator, &tx).await?;
+        notify_decommission(&decommission, previous.sunset_at, sunset_at, &tx).await?;
+        tx.commit().await?;
+        Ok(Json(decommission))
+    }
+
+    pub async fn index_access(
+        _admin: AdminPermissionsActor,
+        aggregator: Aggregator,
+        State(db): State<Db>,
+    ) -> Result<Json<Vec<AggregatorAccess>>, Error> {
+        Ok(Json(
+            AggregatorAccesses::find()
+                .filter(AggregatorAccessColumn::AggregatorId.eq(aggregator.id))
+                .all(&db)
+                .await?,
+        ))
+    }
+
+    pub async fn grant_access(
+        AdminPermissionsActor(actor): AdminPermissionsActor,
+        aggregator: Aggregator,
+        account: Account,
+        State(db): State<Db>,
+    ) -> Result<Json<AggregatorAccess>, Error> {
+        let tx = db.begin().await?;
+        let previous = AggregatorAccesses::for_aggregator_and_account(aggregator.id, account.id)
+            .one(&tx)
+            .await?;
+        let access = AggregatorAccesses::grant(&aggregator, &account, &tx).await?;
+        AuditEvents::record(
+            &actor,
+            AuditAction::GrantAccess,
+            previous.as_ref(),
+            Some(&access),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(Json(access))
+    }
+
+    pub async fn revoke_access(
+        AdminPermissionsActor(actor): AdminPermissionsActor,
+        aggregator: Aggregator,
+        account: Account,
+        State(db): State<Db>,
+    ) -> Result<StatusCode, Error> {
+        let tx = db.begin().await?;
+        let revoked = AggregatorAccesses::for_aggregator_and_account(aggregator.id, account.id)
+            .all(&tx)
+            .await?;
+        AggregatorAccesses::delete_many()
+            .filter(all![
+                AggregatorAccessColumn::AggregatorId.eq(aggregator.id),
+                AggregatorAccessColumn::AccountId.eq(account.id)
+            ])
+            .exec(&tx)
+            .await?;
+        for access in &revoked {
+            AuditEvents::record(&actor, AuditAction::RevokeAccess, Some(access), None, &tx).await?;
+        }
+        tx.commit().await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    pub async fn index_access_for_account(
+        account: Account,
+        State(db): State<Db>,
+    ) -> Result<Json<Vec<AggregatorAccess>>, Error> {
+        Ok(Json(
+            AggregatorAccesses::find()
+                .filter(AggregatorAccessColumn::AccountId.eq(account.id))
+                .all(&db)
+                .await?,
+        ))
+    }
+
+    pub async fn request_access(
+        actor: PermissionsActor,
+        account: Account,
+        State(db): State<Db>,
+        Json(request): Json<RequestAggregatorAccess>,
+    ) -> Result<impl IntoResponse, Error> {
+        let aggregator = request.aggregator(&db).await?;
+        let tx = db.begin().await?;
+        let (access, requested) = AggregatorAccesses::request(&aggregator, &account, &tx).await?;
+        if !requested {
+            tx.commit().await?;
+            return Ok((StatusCode::OK, Json(access)));
+        }
+
+        AuditEvents::record(&actor, AuditAction::RequestAccess, None, Some(&access), &tx).await?;
+        tx.commit().await?;
+        Ok((StatusCode::CREATED, Json(access)))
+    }
 }
@@ -0,0 +1,150 @@
+use crate::{
+    entity::{
+        Account, Alert, AlertRule, AlertRuleColumn, AlertRules, Alerts, ApiTokenScope, AuditAction,
+        AuditEvents, MembershipRole, NewAlertRule, UpdateAlertRule,
+    },
+    handler::{extract::extract_entity, extract::Json},
+    Db, Error, Permissions, PermissionsActor,
+};
+use axum::{
+    extract::{FromRef, FromRequestParts, State},
+    http::{request::Parts, StatusCode},
+    response::IntoResponse,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+
+impl<S> FromRequestParts<S> for AlertRule
+where
+    Db: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Error> {
+        let alert_rule = extract_entity::<AlertRules, S>(parts, state, "alert_rule_id").await?;
+        if alert_rule.is_tombstoned() {
+            Err(Error::NotFound)
+        } else {
+            Ok(alert_rule)
+        }
+    }
+}
+
+// rules for every task in the account are only visible to actors that can see every task
+fn can_access(actor: &PermissionsActor, task_id: Option<&str>) -> bool {
+    match task_id {
+        Some(task_id) => actor.can_access_task(task_id),
+        None => actor.can_access_all_tasks(),
+    }
+}
+
+impl Permissions for AlertRule {
+    fn allow_read(&self, actor: &PermissionsActor) -> bool {
+        actor.has_role(&self.account_id, MembershipRole::Viewer)
+            && actor.can_read(Some(ApiTokenScope::TasksWrite))
+            && can_access(actor, self.task_id.as_deref())
+    }
+
+    fn allow_write(&self, actor: &PermissionsActor) -> bool {
+        actor.has_role(&self.account_id, MembershipRole::Member)
+            && actor.has_scope(ApiTokenScope::TasksWrite)
+            && can_access(actor, self.task_id.as_deref())
+    }
+}
+
+pub async fn index(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+) -> Result<Json<Vec<AlertRule>>, Error> {
+    if !actor.can_read(Some(ApiTokenScope::TasksWrite)) {
+        return Err(Error::AccessDenied);
+    }
+    let mut alert_rules = AlertRules::find()
+        .filter(AlertRuleColumn::AccountId.eq(account.id))
+        .filter(AlertRuleColumn::DeletedAt.is_null())
+        .order_by_desc(AlertRuleColumn::CreatedAt)
+        .all(&db)
+        .await?;
+    alert_rules.retain(|alert_rule| can_access(&actor, alert_rule.task_id.as_deref()));
+    Ok(Json(alert_rules))
+}
+
+pub async fn create(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+    Json(new_alert_rule): Json<NewAlertRule>,
+) -> Result<impl IntoResponse, Error> {
+    if !actor.has_scope(ApiTokenScope::TasksWrite)
+        || !can_access(&actor, new_alert_rule.task_id.as_deref())
+    {
+        return Err(Error::AccessDenied);
+    }
+
+    let tx = db.begin().await?;
+    let alert_rule = new_alert_rule
+        .build(&account, &tx)
+        .await?
+        .insert(&tx)
+        .await?;
+    AuditEvents::record(&actor, AuditAction::Create, None, Some(&alert_rule), &tx).await?;
+    tx.commit().await?;
+    Ok((StatusCode::CREATED, Json(alert_rule)))
+}
+
+pub async fn show(alert_rule: AlertRule) -> Json<AlertRule> {
+    Json(alert_rule)
+}
+
+pub async fn update(
+    actor: PermissionsActor,
+    alert_rule: AlertRule,
+    State(db): State<Db>,
+    Json(update): Json<UpdateAlertRule>,
+) -> Result<Json<AlertRule>, Error> {
+    let tx = db.begin().await?;
+    let updated = update.build(alert_rule.clone())?.update(&tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Update,
+        Some(&alert_rule),
+        Some(&updated),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(Json(updated))
+}
+
+pub async fn delete(
+    actor: PermissionsActor,
+    alert_rule: AlertRule,
+    State(db): State<Db>,
+) -> Result<StatusCode, Error> {
+    let tx = db.begin().await?;
+    let deleted = alert_rule.clone().tombstone().update(&tx).await?;
+    Alerts::resolve_for_alert_rule(deleted.id, &tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Delete,
+        Some(&alert_rule),
+        Some(&deleted),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Alerts that are currently firing in the account.
+pub async fn firing(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+) -> Result<Json<Vec<Alert>>, Error> {
+    let mut alerts = Alerts::firing_in_account(account.id).all(&db).await?;
+    alerts.retain(|alert| actor.can_access_task(&alert.task_id));
+    Ok(Json(alerts))
+}
@@ -1,14 +1,20 @@
 use crate::{
-    entity::{Account, ApiToken, ApiTokenColumn, ApiTokens, UpdateApiToken},
-    handler::{extract::extract_entity, extract::Json},
+    entity::{
+        Account, ApiToken, ApiTokenColumn, ApiTokenUsage, ApiTokenUsages, ApiTokens, AuditAction,
+        AuditEvents, MembershipRole, NewApiToken, UpdateApiToken,
+    },
+    handler::{extract::extract_entity, extract::Json, ApiTokenUsageBuffer},
     Db, Error, Permissions, PermissionsActor,
 };
 use axum::{
     extract::{FromRef, FromRequestParts, State},
     http::{request::Parts, StatusCode},
     response::IntoResponse,
+    Extension,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ModelTrait, QueryFilter, QueryOrder, TransactionTrait,
 };
-use sea_orm::{ActiveModelTrait, ColumnTrait, ModelTrait, QueryFilter, QueryOrder};
 
 impl<S> FromRequestParts<S> for ApiToken
 where
@@ -23,11 +29,19 @@ where
 
 impl Permissions for ApiToken {
     fn allow_write(&self, actor: &PermissionsActor) -> bool {
-        actor.is_admin() || actor.account_ids().contains(&self.account_id)
+        actor.has_role(&self.account_id, MembershipRole::Admin)
     }
 }
 
-pub async fn index(account: Account, State(db): State<Db>) -> Result<Json<Vec<ApiToken>>, Error> {
+pub async fn index(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+) -> Result<Json<Vec<ApiToken>>, Error> {
+    if !actor.has_role(&account.id, MembershipRole::Admin) {
+        return Err(Error::AccessDenied);
+    }
+
     account
         .find_related(ApiTokens)
         .filter(ApiTokenColumn::DeletedAt.is_null())
@@ -38,23 +52,79 @@ pub async fn index(account: Account, State(db): State<Db>) -> Result<Json<Vec<Ap
         .map_err(Error::from)
 }
 
-pub async fn create(account: Account, State(db): State<Db>) -> Result<impl IntoResponse, Error> {
-    let (api_token, token) = ApiToken::build(&account);
-    let mut api_token = api_token.insert(&db).await?;
+pub async fn create(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+    new_api_token: Option<Json<NewApiToken>>,
+) -> Result<impl IntoResponse, Error> {
+    let new_api_token = new_api_token.map(|Json(n)| n).unwrap_or_default();
+    // unscoped api tokens act as account owners, so only owners can create them
+    let role = if new_api_token.is_scoped() {
+        MembershipRole::Admin
+    } else {
+        MembershipRole::Owner
+    };
+    if !actor.has_role(&account.id, role) {
+        return Err(Error::AccessDenied);
+    }
+
+    let tx = db.begin().await?;
+    let (api_token, token) = new_api_token.build(&account, &tx).await?;
+    let mut api_token = api_token.insert(&tx).await?;
+    AuditEvents::record(&actor, AuditAction::Create, None, Some(&api_token), &tx).await?;
     api_token.token = Some(token);
+    tx.commit().await?;
     Ok((StatusCode::CREATED, Json(api_token)))
 }
 
-pub async fn delete(api_token: ApiToken, State(db): State<Db>) -> Result<StatusCode, Error> {
-    api_token.tombstone().update(&db).await?;
+pub async fn delete(
+    actor: PermissionsActor,
+    api_token: ApiToken,
+    State(db): State<Db>,
+) -> Result<StatusCode, Error> {
+    let tx = db.begin().await?;
+    let deleted = api_token.clone().tombstone().update(&tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Delete,
+        Some(&api_token),
+        Some(&deleted),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
 pub async fn update(
+    actor: PermissionsActor,
     api_token: ApiToken,
     State(db): State<Db>,
     Json(update): Json<UpdateApiToken>,
 ) -> Result<impl IntoResponse, Error> {
-    let token = update.build(api_token)?.update(&db).await?;
+    let tx = db.begin().await?;
+    let token = update.build(api_token.clone())?.update(&tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Update,
+        Some(&api_token),
+        Some(&token),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
     Ok((StatusCode::OK, Json(token)))
 }
+
+pub async fn usage(
+    api_token: ApiToken,
+    State(db): State<Db>,
+    Extension(buffer): Extension<ApiTokenUsageBuffer>,
+) -> Result<Json<Vec<ApiTokenUsage>>, Error> {
+    // include usage that has not been written yet, such as this request
+    ApiTokenUsages::for_api_token_with_pending(api_token.id, buffer.pending_for(api_token.id), &db)
+        .await
+        .map(Json)
+        .map_err(Error::from)
+}
@@ -0,0 +1,29 @@
+use crate::{
+    entity::{Account, AuditEvent, AuditEventFilter, MembershipRole},
+    handler::extract::Json,
+    Db, Error, PermissionsActor,
+};
+use axum::extract::{Query, State};
+
+pub async fn index(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+    Query(mut filter): Query<AuditEventFilter>,
+) -> Result<Json<Vec<AuditEvent>>, Error> {
+    if !actor.has_role(&account.id, MembershipRole::Admin) {
+        return Err(Error::AccessDenied);
+    }
+
+    filter.account_id = Some(account.id);
+    Ok(Json(filter.query().all(&db).await?))
+}
+
+/// Events for every account, including changes to shared aggregators and queue jobs, which do
+/// not belong to any account. Only reachable by admins.
+pub async fn admin_index(
+    State(db): State<Db>,
+    Query(filter): Query<AuditEventFilter>,
+) -> Result<Json<Vec<AuditEvent>>, Error> {
+    Ok(Json(filter.query().all(&db).await?))
+}
@@ -1,7 +1,8 @@
 use crate::{
     entity::{
-        Account, CollectorCredential, CollectorCredentialColumn, CollectorCredentials,
-        NewCollectorCredential, UpdateCollectorCredential,
+        Account, ApiTokenScope, AuditAction, AuditEvents, CollectorCredential,
+        CollectorCredentialColumn, CollectorCredentials, MembershipRole, NewCollectorCredential,
+        UpdateCollectorCredential,
     },
     handler::{extract::extract_entity, extract::Json},
     Db, Error, Permissions, PermissionsActor,
@@ -12,7 +13,7 @@ use axum::{
     response::IntoResponse,
 };
 use httpdate::fmt_http_date;
-use sea_orm::{ActiveModelTrait, ColumnTrait, ModelTrait, QueryFilter};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ModelTrait, QueryFilter, TransactionTrait};
 
 impl<S> FromRequestParts<S> for CollectorCredential
 where
@@ -26,15 +27,25 @@ where
 }
 
 impl Permissions for CollectorCredential {
+    fn allow_read(&self, actor: &PermissionsActor) -> bool {
+        actor.has_role(&self.account_id, MembershipRole::Viewer)
+            && actor.can_read_account_resources(Some(ApiTokenScope::CollectorCredentialsWrite))
+    }
+
     fn allow_write(&self, actor: &PermissionsActor) -> bool {
-        actor.is_admin() || actor.account_ids().contains(&self.account_id)
+        actor.has_role(&self.account_id, MembershipRole::Member)
+            && actor.has_scope(ApiTokenScope::CollectorCredentialsWrite)
     }
 }
 
 pub async fn index(
+    actor: PermissionsActor,
     account: Account,
     State(db): State<Db>,
 ) -> Result<Json<Vec<CollectorCredential>>, Error> {
+    if !actor.can_read_account_resources(Some(ApiTokenScope::CollectorCredentialsWrite)) {
+        return Err(Error::AccessDenied);
+    }
     account
         .find_related(CollectorCredentials)
         .filter(CollectorCredentialColumn::DeletedAt.is_null())
@@ -53,29 +64,69 @@ pub async fn show(collector_credential: CollectorCredential) -> impl IntoRespons
 }
 
 pub async fn create(
+    actor: PermissionsActor,
     account: Account,
     State(db): State<Db>,
     Json(collector_credential): Json<NewCollectorCredential>,
 ) -> Result<impl IntoResponse, Error> {
+    if !actor.has_scope(ApiTokenScope::CollectorCredentialsWrite) {
+        return Err(Error::AccessDenied);
+    }
+
     let (collector_credential, token) = collector_credential.build(&account)?;
-    let mut collector_credential = collector_credential.insert(&db).await?;
+    let tx = db.begin().await?;
+    let mut collector_credential = collector_credential.insert(&tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Create,
+        None,
+        Some(&collector_credential),
+        &tx,
+    )
+    .await?;
     collector_credential.token = Some(token);
+    tx.commit().await?;
     Ok((StatusCode::CREATED, Json(collector_credential)))
 }
 
 pub async fn delete(
+    actor: PermissionsActor,
     collector_credential: CollectorCredential,
     State(db): State<Db>,
 ) -> Result<StatusCode, Error> {
-    collector_credential.tombstone().update(&db).await?;
+    let tx = db.begin().await?;
+    let deleted = collector_credential.clone().tombstone().update(&tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Delete,
+        Some(&collector_credential),
+        Some(&deleted),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
 pub async fn update(
+    actor: PermissionsActor,
     collector_credential: CollectorCredential,
     State(db): State<Db>,
     Json(update): Json<UpdateCollectorCredential>,
 ) -> Result<impl IntoResponse, Error> {
-    let token = update.build(collector_credential)?.update(&db).await?;
+    let tx = db.begin().await?;
+    let token = update
+        .build(collector_credential.clone())?
+        .update(&tx)
+        .await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Update,
+        Some(&collector_credential),
+        Some(&token),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
     Ok((StatusCode::OK, Json(token)))
 }
@@ -1,5 +1,8 @@
 use crate::{
-    entity::{Account, CreateMembership, Membership, MembershipColumn, Memberships},
+    entity::{
+        Account, AuditAction, AuditEvents, CreateMembership, Membership, MembershipColumn,
+        MembershipRole, Memberships, UpdateMembership, WebhookEventType, Webhooks,
+    },
     handler::extract::Json,
     queue::Job,
     Db, Error, PermissionsActor,
@@ -13,10 +16,18 @@ use sea_orm::{
     sea_query::all, ActiveModelTrait, ColumnTrait, EntityTrait, ModelTrait, QueryFilter,
     TransactionTrait,
 };
+use serde_json::json;
 use std::collections::HashMap;
 use uuid::Uuid;
 
-pub async fn index(account: Account, State(db): State<Db>) -> Result<Json<Vec<Membership>>, Error> {
+pub async fn index(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+) -> Result<Json<Vec<Membership>>, Error> {
+    if !actor.can_read_account_resources(None) {
+        return Err(Error::AccessDenied);
+    }
     account
         .find_related(Memberships)
         .all(&db)
@@ -26,11 +37,20 @@ pub async fn index(account: Account, State(db): State<Db>) -> Result<Json<Vec<Me
 }
 
 pub async fn create(
+    actor: PermissionsActor,
     account: Account,
     State(db): State<Db>,
     Json(membership): Json<CreateMembership>,
 ) -> Result<impl IntoResponse, Error> {
     let membership = membership.build(&account)?;
+    // only owners can make other users owners
+    if !actor.has_role(
+        &account.id,
+        (*membership.role.as_ref()).max(MembershipRole::Admin),
+    ) {
+        return Err(Error::AccessDenied);
+    }
+
     if let Some(membership) = Memberships::find()
         .filter(all![
             MembershipColumn::AccountId.eq(*membership.account_id.as_ref()),
@@ -51,6 +71,14 @@ pub async fn create(
         .is_none();
 
     let membership = membership.insert(&tx).await?;
+    AuditEvents::record(&actor, AuditAction::Create, None, Some(&membership), &tx).await?;
+    Webhooks::enqueue(
+        membership.account_id,
+        WebhookEventType::MembershipCreated,
+        json!(membership),
+        &tx,
+    )
+    .await?;
 
     if first_membership_for_this_email && !cfg!(feature = "integration-testing") {
         Job::new_invitation_flow(&membership).insert(&tx).await?;
@@ -61,27 +89,93 @@ pub async fn create(
     Ok((StatusCode::CREATED, Json(membership)))
 }
 
-pub async fn delete(
-    Path(params): Path<HashMap<String, String>>,
-    actor: PermissionsActor,
-    State(db): State<Db>,
-) -> Result<StatusCode, Error> {
+async fn load_membership(
+    params: &HashMap<String, String>,
+    actor: &PermissionsActor,
+    db: &Db,
+) -> Result<Membership, Error> {
     let membership_id = params
         .get("membership_id")
         .and_then(|s| s.parse::<Uuid>().ok())
         .ok_or(Error::NotFound)?;
 
     let membership = Memberships::find_by_id(membership_id)
-        .one(&db)
+        .one(db)
         .await?
         .ok_or(Error::NotFound)?;
 
-    if matches!(&actor, PermissionsActor::User(user, _) if membership.user_email == user.email) {
+    if matches!(actor, PermissionsActor::User(user, _) if membership.user_email == user.email) {
         Err(Error::AccessDenied)
-    } else if actor.is_admin() || actor.account_ids().contains(&membership.account_id) {
-        membership.delete(&db).await?;
-        Ok(StatusCode::NO_CONTENT)
+    } else if actor.has_role(&membership.account_id, MembershipRole::Viewer) {
+        Ok(membership)
     } else {
         Err(Error::NotFound)
     }
 }
+
+pub async fn update(
+    Path(params): Path<HashMap<String, String>>,
+    actor: PermissionsActor,
+    State(db): State<Db>,
+    Json(update_membership): Json<UpdateMembership>,
+) -> Result<Json<Membership>, Error> {
+    let previous = load_membership(&params, &actor, &db).await?;
+    let previous_role = previous.role;
+    let membership = update_membership.build(previous.clone())?;
+    // only owners can make other users owners, or change an owner's role
+    if !actor.has_role(
+        membership.account_id.as_ref(),
+        (*membership.role.as_ref())
+            .max(previous_role)
+            .max(MembershipRole::Admin),
+    ) {
+        return Err(Error::AccessDenied);
+    }
+
+    let tx = db.begin().await?;
+    let membership = membership.update(&tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Update,
+        Some(&previous),
+        Some(&membership),
+        &tx,
+    )
+    .await?;
+    Webhooks::enqueue(
+        membership.account_id,
+        WebhookEventType::MembershipUpdated,
+        json!(membership),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(Json(membership))
+}
+
+pub async fn delete(
+    Path(params): Path<HashMap<String, String>>,
+    actor: PermissionsActor,
+    State(db): State<Db>,
+) -> Result<StatusCode, Error> {
+    let membership = load_membership(&params, &actor, &db).await?;
+    if actor.has_role(
+        &membership.account_id,
+        membership.role.max(MembershipRole::Admin),
+    ) {
+        let tx = db.begin().await?;
+        membership.clone().delete(&tx).await?;
+        AuditEvents::record(&actor, AuditAction::Delete, Some(&membership), None, &tx).await?;
+        Webhooks::enqueue(
+            membership.account_id,
+            WebhookEventType::MembershipDeleted,
+            json!(membership),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(Error::AccessDenied)
+    }
+}
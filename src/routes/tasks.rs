@@ -1,9 +1,15 @@
 use crate::clients::HttpClient;
 use crate::{
-    clients::aggregator_client::{api_types::TaskAggregationJobMetrics, TaskUploadMetrics},
     config::FeatureFlags,
-    entity::{Account, NewTask, Task, TaskColumn, Tasks, UpdateTask},
+    entity::{
+        task::{
+            CollectionReadiness, DpEstimate, DpEstimateParams, FetchedTaskMetrics, MetricsSummary,
+        },
+        Account, Aggregators, AlertRules, Alerts, ApiTokenScope, AuditAction, AuditEvents,
+        MembershipRole, NewTask, Task, TaskColumn, Tasks, UpdateTask, WebhookEventType, Webhooks,
+    },
     handler::extract::Json,
+    queue::{EvaluateTaskAlerts, Job},
     Crypter, Db, Error, Permissions, PermissionsActor,
 };
 use axum::extract::{FromRef, FromRequestParts, Path, Query, State};
@@ -12,18 +18,28 @@ use axum::response::IntoResponse;
 use httpdate::fmt_http_date;
 use sea_orm::{
     ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, IntoActiveModel, ModelTrait,
-    QueryFilter,
+    QueryFilter, TransactionTrait,
 };
 use serde::Deserialize;
+use serde_json::json;
 use std::collections::HashMap;
 use std::time::Duration;
 use time::OffsetDateTime;
 use tokio::join;
 use tracing::warn;
+use validator::{ValidationError, ValidationErrors};
 
 impl Permissions for Task {
+    fn allow_read(&self, actor: &PermissionsActor) -> bool {
+        actor.has_role(&self.account_id, MembershipRole::Viewer)
+            && actor.can_read(Some(ApiTokenScope::TasksWrite))
+            && actor.can_access_task(&self.id)
+    }
+
     fn allow_write(&self, actor: &PermissionsActor) -> bool {
-        actor.is_admin() || actor.account_ids().contains(&self.account_id)
+        actor.has_role(&self.account_id, MembershipRole::Member)
+            && actor.has_scope(ApiTokenScope::TasksWrite)
+            && actor.can_access_task(&self.id)
     }
 }
 
@@ -63,57 +79,106 @@ async fn refresh_metrics_if_needed(
     if OffsetDateTime::now_utc() - task.updated_at <= Duration::from_secs(5 * 60) {
         return Ok(task);
     }
-    let aggregator = task.leader_aggregator(&db).await?;
-    let metrics = if aggregator.features.upload_metrics_enabled() {
-        aggregator
-            .client(client.clone(), crypter)?
-            .get_task_upload_metrics(&task.id)
-            .await?
-    } else {
-        TaskUploadMetrics::default()
-    };
-    let task = task.update_task_upload_metrics(metrics, db.clone()).await?;
-
-    let metrics = if aggregator.features.aggregation_job_metrics_enabled() {
-        aggregator
-            .client(client, crypter)?
-            .get_task_aggregation_job_metrics(&task.id)
-            .await?
-    } else {
-        TaskAggregationJobMetrics::default()
-    };
-    task.update_task_aggregation_job_metrics(metrics, db)
-        .await
-        .map_err(Into::into)
+    let metrics = FetchedTaskMetrics::fetch(&task, &client, crypter, &db).await?;
+    let tx = db.begin().await?;
+    let task = metrics.write(task, &tx).await?;
+    if !AlertRules::for_task(&task, &tx).await?.is_empty() {
+        Job::from(EvaluateTaskAlerts {
+            task_id: task.id.clone(),
+        })
+        .insert(&tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(task)
 }
 
 pub mod axum_handler {
     use super::*;
 
-    pub async fn index(account: Account, State(db): State<Db>) -> Result<Json<Vec<Task>>, Error> {
+    pub async fn index(
+        actor: PermissionsActor,
+        account: Account,
+        State(db): State<Db>,
+    ) -> Result<Json<Vec<Task>>, Error> {
+        if !actor.can_read(Some(ApiTokenScope::TasksWrite)) {
+            return Err(Error::AccessDenied);
+        }
+        let mut tasks = account
+            .find_related(Tasks)
+            .filter(TaskColumn::DeletedAt.is_null())
+            .all(&db)
+            .await?;
+        tasks.retain(|task| actor.can_access_task(&task.id));
+        Ok(Json(tasks))
+    }
+
+    pub async fn metrics_summary(
+        actor: PermissionsActor,
+        account: Account,
+        State(db): State<Db>,
+    ) -> Result<Json<MetricsSummary>, Error> {
+        if !actor.can_read(Some(ApiTokenScope::TasksWrite)) {
+            return Err(Error::AccessDenied);
+        }
         Ok(Json(
-            account
-                .find_related(Tasks)
-                .filter(TaskColumn::DeletedAt.is_null())
-                .all(&db)
-                .await?,
+            MetricsSummary::for_account(&account, actor.task_ids(), &db).await?,
         ))
     }
 
+    pub async fn dp_estimate(
+        _actor: PermissionsActor,
+        Json(params): Json<DpEstimateParams>,
+    ) -> Result<Json<DpEstimate>, Error> {
+        Ok(Json(params.estimate()?))
+    }
+
     pub async fn create(
+        actor: PermissionsActor,
         account: Account,
         State(db): State<Db>,
         State(client): State<HttpClient>,
         State(crypter): State<Crypter>,
         Json(mut new_task): Json<NewTask>,
     ) -> Result<impl IntoResponse, Error> {
+        // tokens limited to specific tasks cannot create more
+        if !actor.has_scope(ApiTokenScope::TasksWrite) || !actor.can_access_all_tasks() {
+            return Err(Error::AccessDenied);
+        }
+
         let task = new_task
             .normalize_and_validate(account, &db)
             .await?
             .provision(client, &crypter)
-            .await?
-            .insert(&db)
             .await?;
+        let tx = db.begin().await?;
+        let task = task.insert(&tx).await?;
+        // an aggregator deleted since the task was validated would leave it on a tombstone
+        let mut errors = ValidationErrors::new();
+        for (field, aggregator_id) in [
+            ("leader_aggregator_id", task.leader_aggregator_id),
+            ("helper_aggregator_id", task.helper_aggregator_id),
+        ] {
+            if Aggregators::lock_for_task(aggregator_id, &tx)
+                .await?
+                .is_none()
+            {
+                errors.add(field, ValidationError::new("required"));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors.into());
+        }
+        AuditEvents::record(&actor, AuditAction::Create, None, Some(&task), &tx).await?;
+        Webhooks::enqueue(
+            task.account_id,
+            WebhookEventType::TaskCreated,
+            json!(task),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
         Ok((StatusCode::CREATED, Json(task)))
     }
 
@@ -133,20 +198,56 @@ pub mod axum_handler {
         Ok(([(header::LAST_MODIFIED, last_modified)], Json(task)))
     }
 
+    pub async fn collection_readiness(
+        task: Task,
+        State(db): State<Db>,
+        State(client): State<HttpClient>,
+        State(crypter): State<Crypter>,
+        State(feature_flags): State<FeatureFlags>,
+    ) -> Result<Json<CollectionReadiness>, Error> {
+        let task = if feature_flags.metrics_refresh_enabled {
+            refresh_metrics_if_needed(task, db.clone(), client, &crypter).await?
+        } else {
+            task
+        };
+        Ok(Json(CollectionReadiness::estimate(&task, &db).await?))
+    }
+
     pub async fn update(
+        actor: PermissionsActor,
         task: Task,
         State(db): State<Db>,
         State(client): State<HttpClient>,
         State(crypter): State<Crypter>,
         Json(update): Json<UpdateTask>,
     ) -> Result<Json<Task>, Error> {
-        Ok(Json(
-            update
-                .update(&client, &db, &crypter, task)
-                .await?
-                .update(&db)
-                .await?,
-        ))
+        let updated = update.update(&client, &db, &crypter, task.clone()).await?;
+        let tx = db.begin().await?;
+        let mut updated = updated.update(&tx).await?;
+        let now = OffsetDateTime::now_utc();
+        let is_expired = |task: &Task| task.expiration.is_some_and(|expiration| expiration <= now);
+        if is_expired(&updated) && !is_expired(&task) {
+            let mut am = updated.into_active_model();
+            am.expiration_notified_at = ActiveValue::Set(Some(now));
+            updated = am.update(&tx).await?;
+            Webhooks::enqueue(
+                updated.account_id,
+                WebhookEventType::TaskExpired,
+                json!(updated),
+                &tx,
+            )
+            .await?;
+        }
+        AuditEvents::record(
+            &actor,
+            AuditAction::Update,
+            Some(&task),
+            Some(&updated),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(Json(updated))
     }
 
     #[derive(Deserialize)]
@@ -156,6 +257,7 @@ pub mod axum_handler {
     }
 
     pub async fn delete(
+        actor: PermissionsActor,
         task: Task,
         State(db): State<Db>,
         State(client): State<HttpClient>,
@@ -202,7 +304,25 @@ pub mod axum_handler {
 
         am.updated_at = ActiveValue::Set(now);
         am.deleted_at = ActiveValue::Set(Some(now));
-        am.update(&db).await?;
+        let tx = db.begin().await?;
+        let deleted = am.update(&tx).await?;
+        Alerts::resolve_for_task(&deleted.id, &tx).await?;
+        AuditEvents::record(
+            &actor,
+            AuditAction::Delete,
+            Some(&task),
+            Some(&deleted),
+            &tx,
+        )
+        .await?;
+        Webhooks::enqueue(
+            deleted.account_id,
+            WebhookEventType::TaskDeleted,
+            json!(deleted),
+            &tx,
+        )
+        .await?;
+        tx.commit().await?;
         Ok(StatusCode::NO_CONTENT)
     }
 }
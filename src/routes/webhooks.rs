@@ -0,0 +1,156 @@
+use crate::{
+    config::FeatureFlags,
+    entity::{
+        Account, AuditAction, AuditEvents, MembershipRole, NewWebhook, UpdateWebhook, Webhook,
+        WebhookColumn, WebhookDeliveries, WebhookDelivery, Webhooks,
+    },
+    handler::{extract::extract_entity, extract::Json},
+    Crypter, Db, Error, Permissions, PermissionsActor,
+};
+use axum::{
+    extract::{FromRef, FromRequestParts, State},
+    http::{request::Parts, StatusCode},
+    response::IntoResponse,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+
+impl<S> FromRequestParts<S> for Webhook
+where
+    Db: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Error> {
+        let webhook = extract_entity::<Webhooks, S>(parts, state, "webhook_id").await?;
+        if webhook.is_tombstoned() {
+            Err(Error::NotFound)
+        } else {
+            Ok(webhook)
+        }
+    }
+}
+
+// webhook urls can carry credentials, so only account admins can see them
+impl Permissions for Webhook {
+    fn allow_write(&self, actor: &PermissionsActor) -> bool {
+        actor.has_role(&self.account_id, MembershipRole::Admin)
+    }
+}
+
+pub async fn index(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+    State(crypter): State<Crypter>,
+) -> Result<Json<Vec<Webhook>>, Error> {
+    if !actor.has_role(&account.id, MembershipRole::Admin) {
+        return Err(Error::AccessDenied);
+    }
+
+    Webhooks::find()
+        .filter(WebhookColumn::AccountId.eq(account.id))
+        .filter(WebhookColumn::DeletedAt.is_null())
+        .order_by_desc(WebhookColumn::CreatedAt)
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(|webhook| webhook.with_url(&crypter))
+        .collect::<Result<_, _>>()
+        .map(Json)
+}
+
+pub async fn create(
+    actor: PermissionsActor,
+    account: Account,
+    State(db): State<Db>,
+    State(crypter): State<Crypter>,
+    State(feature_flags): State<FeatureFlags>,
+    Json(new_webhook): Json<NewWebhook>,
+) -> Result<impl IntoResponse, Error> {
+    if !actor.has_role(&account.id, MembershipRole::Admin) {
+        return Err(Error::AccessDenied);
+    }
+
+    let (webhook, secret) = new_webhook
+        .build(&account, &crypter, feature_flags.ssrf_validation_enabled)
+        .await?;
+    let tx = db.begin().await?;
+    let webhook = webhook.insert(&tx).await?;
+    // recorded before the url is decrypted, so that the audit log does not hold it
+    AuditEvents::record(&actor, AuditAction::Create, None, Some(&webhook), &tx).await?;
+    tx.commit().await?;
+    let webhook = webhook.with_url(&crypter)?;
+    Ok((
+        StatusCode::CREATED,
+        Json(Webhook {
+            secret: Some(secret),
+            ..webhook
+        }),
+    ))
+}
+
+pub async fn show(
+    webhook: Webhook,
+    State(crypter): State<Crypter>,
+) -> Result<Json<Webhook>, Error> {
+    Ok(Json(webhook.with_url(&crypter)?))
+}
+
+pub async fn update(
+    actor: PermissionsActor,
+    webhook: Webhook,
+    State(db): State<Db>,
+    State(crypter): State<Crypter>,
+    State(feature_flags): State<FeatureFlags>,
+    Json(update): Json<UpdateWebhook>,
+) -> Result<Json<Webhook>, Error> {
+    let previous = webhook.clone();
+    let updated = update
+        .build(webhook, &crypter, feature_flags.ssrf_validation_enabled)
+        .await?;
+    let tx = db.begin().await?;
+    let updated = updated.update(&tx).await?;
+    // recorded before the url is decrypted, so that the audit log does not hold it
+    AuditEvents::record(
+        &actor,
+        AuditAction::Update,
+        Some(&previous),
+        Some(&updated),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(Json(updated.with_url(&crypter)?))
+}
+
+pub async fn delete(
+    actor: PermissionsActor,
+    webhook: Webhook,
+    State(db): State<Db>,
+) -> Result<StatusCode, Error> {
+    let tx = db.begin().await?;
+    let deleted = webhook.clone().tombstone().update(&tx).await?;
+    AuditEvents::record(
+        &actor,
+        AuditAction::Delete,
+        Some(&webhook),
+        Some(&deleted),
+        &tx,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn deliveries(
+    webhook: Webhook,
+    State(db): State<Db>,
+) -> Result<Json<Vec<WebhookDelivery>>, Error> {
+    WebhookDeliveries::for_webhook(webhook.id)
+        .all(&db)
+        .await
+        .map(Json)
+        .map_err(Error::from)
+}
@@ -130,7 +130,8 @@ pub async fn client_logs_middleware(
     let response_body = if response_bytes.is_empty() {
         None
     } else {
-        Some(String::from_utf8(response_bytes.to_vec()).expect("could not decode body as UTF-8"))
+        // binary dap messages are logged lossily; only json responses are ever inspected
+        Some(String::from_utf8_lossy(&response_bytes).into_owned())
     };
 
     logs.logged_conns.write().unwrap().push(LoggedConn {
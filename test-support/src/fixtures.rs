@@ -48,7 +48,16 @@ pub async fn admin_account(app: &DivviupApi) -> Account {
 }
 
 pub async fn membership(app: &DivviupApi, account: &Account, user: &User) -> Membership {
-    Membership::build(user.email.clone(), account)
+    membership_with_role(app, account, user, MembershipRole::Owner).await
+}
+
+pub async fn membership_with_role(
+    app: &DivviupApi,
+    account: &Account,
+    user: &User,
+    role: MembershipRole,
+) -> Membership {
+    Membership::build(user.email.clone(), account, role)
         .unwrap()
         .insert(app.db())
         .await
@@ -58,7 +67,7 @@ pub async fn membership(app: &DivviupApi, account: &Account, user: &User) -> Mem
 pub async fn build_membership(app: &DivviupApi) -> Membership {
     let account = account(app).await;
     let email = format!("test-{}@example.test", random_name());
-    Membership::build(email, &account)
+    Membership::build(email, &account, MembershipRole::Owner)
         .unwrap()
         .insert(app.db())
         .await
@@ -81,6 +90,17 @@ pub async fn member(app: &DivviupApi) -> (User, Account, Membership) {
     (user, account, membership)
 }
 
+pub async fn member_with_role(
+    app: &DivviupApi,
+    role: MembershipRole,
+) -> (User, Account, Membership) {
+    let user = user();
+    let account = account(app).await;
+    let membership = membership_with_role(app, &account, &user, role).await;
+
+    (user, account, membership)
+}
+
 pub async fn collector_credential(app: &DivviupApi, account: &Account) -> CollectorCredential {
     let (token, token_hash) = CollectorCredential::new_token();
     CollectorCredential {
@@ -109,7 +129,7 @@ pub async fn task(app: &DivviupApi, account: &Account) -> Task {
         id: random::<TaskId>().to_string(),
         account_id: account.id,
         name: random_name(),
-        vdaf: task::vdaf::Vdaf::Count.into(),
+        vdaf: task::vdaf::Vdaf::Count(Default::default()).into(),
         min_batch_size: 100,
         max_batch_size: Some(200),
         batch_time_window_size_seconds: None,
@@ -122,6 +142,7 @@ pub async fn task(app: &DivviupApi, account: &Account) -> Task {
         expiration: Some(
             OffsetDateTime::now_utc() + divviup_api::entity::task::DEFAULT_EXPIRATION_DURATION,
         ),
+        expiration_notified_at: None,
         leader_aggregator_id: leader_aggregator.id,
         helper_aggregator_id: helper_aggregator.id,
         collector_credential_id: collector_credential.id,
@@ -143,6 +164,10 @@ pub async fn task(app: &DivviupApi, account: &Account) -> Task {
         aggregation_job_counter_helper_task_expired: 0,
         aggregation_job_counter_helper_invalid_message: 0,
         aggregation_job_counter_helper_report_too_early: 0,
+        collection_job_counter_pending: 0,
+        collection_job_counter_abandoned: 0,
+        collection_job_counter_deleted: 0,
+        helper_metrics: None,
     }
     .into_active_model()
     .insert(app.db())
@@ -156,6 +181,7 @@ pub fn new_aggregator() -> NewAggregator {
         api_url: Some(format!("https://api.{}.divviup.org/", random_name())),
         bearer_token: Some(random_name()),
         is_first_party: None,
+        ..Default::default()
     }
 }
 
@@ -191,6 +217,14 @@ pub async fn aggregator(app: &DivviupApi, account: Option<&Account>) -> Aggregat
         vdafs: Default::default(),
         protocol: Protocol::Dap09,
         features: Features::from(Feature::TokenHash).into(),
+        encrypted_secondary_bearer_token: None,
+        encrypted_client_identity: None,
+        ca_certificate: None,
+        hpke_config_ids: None,
+        hpke_config_error: None,
+        hpke_configs_checked_at: None,
+        sunset_at: None,
+        visibility: Visibility::Public,
     }
     .into_active_model()
     .insert(app.db())
@@ -207,6 +241,22 @@ pub async fn api_token(app: &DivviupApi, account: &Account) -> (ApiToken, Header
     )
 }
 
+pub async fn scoped_api_token(
+    app: &DivviupApi,
+    account: &Account,
+    scopes: &[ApiTokenScope],
+    task_ids: Option<Vec<String>>,
+) -> (ApiToken, HeaderValue) {
+    let (mut api_token, token) = ApiToken::build(account);
+    api_token.scopes = ActiveValue::Set(Some(scopes.to_vec().into()));
+    api_token.task_ids = ActiveValue::Set(task_ids.map(Into::into));
+    let api_token = api_token.insert(app.db()).await.unwrap();
+    (
+        api_token,
+        HeaderValue::try_from(format!("Bearer {token}")).unwrap(),
+    )
+}
+
 pub async fn admin_token(app: &DivviupApi) -> HeaderValue {
     let account = admin_account(app).await;
     let (_, header) = api_token(app, &account).await;
@@ -218,3 +268,39 @@ pub async fn make_account_admin(app: &DivviupApi, account: Account) -> Account {
     account.admin = ActiveValue::Set(true);
     account.update(app.db()).await.unwrap()
 }
+
+pub async fn webhook(app: &DivviupApi, account: &Account) -> (Webhook, String) {
+    let (webhook, secret) = NewWebhook {
+        name: Some(random_name()),
+        url: Some(format!("https://{}.example/hooks", random_name())),
+        event_types: None,
+    }
+    .build(account, app.crypter(), false)
+    .await
+    .unwrap();
+    let webhook = webhook
+        .insert(app.db())
+        .await
+        .unwrap()
+        .with_url(app.crypter())
+        .unwrap();
+    (webhook, secret)
+}
+
+pub async fn alert_rule(app: &DivviupApi, account: &Account, task: Option<&Task>) -> AlertRule {
+    NewAlertRule {
+        name: Some(random_name()),
+        task_id: task.map(|task| task.id.clone()),
+        counter: Some(TaskCounter::ReportCounterDecryptFailure),
+        relative_to: Some(TaskCounter::ReportCounterSuccess),
+        threshold: Some(0.05),
+        window_seconds: None,
+        channels: None,
+    }
+    .build(account, app.db())
+    .await
+    .unwrap()
+    .insert(app.db())
+    .await
+    .unwrap()
+}
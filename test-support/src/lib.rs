@@ -11,19 +11,20 @@
 
 use axum::{
     body::Body,
-    extract::Request,
+    extract::{ConnectInfo, Request},
     http::header::{self, HeaderName, HeaderValue},
     middleware::from_fn_with_state,
     response::Response,
     serve, Router,
 };
 use divviup_api::{
-    clients::{aggregator_client::api_types, HttpClient},
-    handler::BuiltApp,
+    clients::{aggregator_client::api_types, HttpClient, RequestPolicy},
+    handler::{ApiTokenUsageBuffer, BuiltApp},
     Config, Crypter, Db,
 };
 use http_body_util::BodyExt;
 use reqwest::Client;
+use sea_orm::sea_query::{ConditionalStatement, Index};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     error::Error,
@@ -96,6 +97,9 @@ async fn set_up_schema_for<T: EntityTrait>(schema: &Schema, db: &Db, t: T) {
     db.execute(&schema.create_table_from_entity(t))
         .await
         .unwrap();
+    for index in schema.create_index_from_entity(t) {
+        db.execute(&index).await.unwrap();
+    }
 }
 
 pub async fn set_up_schema(db: &Db) {
@@ -108,6 +112,28 @@ pub async fn set_up_schema(db: &Db) {
     set_up_schema_for(&schema, db, Aggregators).await;
     set_up_schema_for(&schema, db, ApiTokens).await;
     set_up_schema_for(&schema, db, CollectorCredentials).await;
+    set_up_schema_for(&schema, db, AggregatorCapabilityChanges).await;
+    set_up_schema_for(&schema, db, AggregatorAccesses).await;
+    set_up_schema_for(&schema, db, ApiTokenUsages).await;
+    set_up_schema_for(&schema, db, AuditEvents).await;
+    set_up_schema_for(&schema, db, Webhooks).await;
+    set_up_schema_for(&schema, db, WebhookDeliveries).await;
+    set_up_schema_for(&schema, db, AlertRules).await;
+    set_up_schema_for(&schema, db, Alerts).await;
+    // partial indexes cannot be declared on entities, so this one mirrors its migration
+    db.execute(
+        &Index::create()
+            .name("index-alert-alert-rule-id-task-id-firing")
+            .table(Alerts)
+            .col(AlertColumn::AlertRuleId)
+            .col(AlertColumn::TaskId)
+            .unique()
+            .and_where(AlertColumn::ResolvedAt.is_null())
+            .to_owned(),
+    )
+    .await
+    .unwrap();
+    set_up_schema_for(&schema, db, TaskCounterSnapshots).await;
 }
 
 pub async fn config(mock_router: Router) -> Config {
@@ -123,12 +149,14 @@ pub async fn config(mock_router: Router) -> Config {
             .expect("mock server error");
     });
 
-    let reqwest_client = Client::builder()
-        .no_proxy()
-        .build()
-        .expect("failed to build reqwest client");
-    let http_client =
-        HttpClient::new(reqwest_client).with_proxy_base(mock_base.parse::<url::Url>().unwrap());
+    // Retries are disabled so that tests observe exactly the requests they make. Tests that
+    // exercise retries opt back in with `HttpClient::with_request_policy`.
+    let http_client = HttpClient::from_builder(|| Client::builder().no_proxy())
+        .with_proxy_base(mock_base.parse::<url::Url>().unwrap())
+        .with_request_policy(RequestPolicy {
+            max_retries: 0,
+            ..RequestPolicy::default()
+        });
 
     Config {
         session_secrets: repeat_with(|| fastrand::u8(..))
@@ -165,6 +193,7 @@ pub struct DivviupApi {
     router: Router,
     db: Db,
     config: Arc<Config>,
+    api_token_usage: ApiTokenUsageBuffer,
 }
 
 impl DivviupApi {
@@ -176,6 +205,10 @@ impl DivviupApi {
         &self.db
     }
 
+    pub fn api_token_usage(&self) -> &ApiTokenUsageBuffer {
+        &self.api_token_usage
+    }
+
     pub fn config(&self) -> &Config {
         &self.config
     }
@@ -201,10 +234,19 @@ pub async fn build_test_app() -> (DivviupApi, ClientLogs) {
     install_test_trace_subscriber();
     let api_mocks = ApiMocks::new();
     let client_logs = api_mocks.client_logs();
-    let BuiltApp { router, db, config } =
-        divviup_api::build_app(config(api_mocks.into_router()).await).await;
+    let BuiltApp {
+        router,
+        db,
+        config,
+        api_token_usage,
+    } = divviup_api::build_app(config(api_mocks.into_router()).await).await;
     set_up_schema(&db).await;
-    let app = DivviupApi { router, db, config };
+    let app = DivviupApi {
+        router,
+        db,
+        config,
+        api_token_usage,
+    };
     (app, client_logs)
 }
 
@@ -218,10 +260,19 @@ pub async fn build_test_app_with_mock(mock: Router) -> (DivviupApi, ClientLogs)
         client_logs.clone(),
         client_logs::client_logs_middleware,
     ));
-    let BuiltApp { router, db, config } =
-        divviup_api::build_app(config(mock_with_logs).await).await;
+    let BuiltApp {
+        router,
+        db,
+        config,
+        api_token_usage,
+    } = divviup_api::build_app(config(mock_with_logs).await).await;
     set_up_schema(&db).await;
-    let app = DivviupApi { router, db, config };
+    let app = DivviupApi {
+        router,
+        db,
+        config,
+        api_token_usage,
+    };
     (app, client_logs)
 }
 
@@ -270,6 +321,7 @@ pub struct TestRequest {
     headers: HeaderMap,
     body: Vec<u8>,
     user: Option<User>,
+    peer_address: Option<SocketAddr>,
 }
 
 pub fn get(path: impl Into<String>) -> TestRequest {
@@ -300,6 +352,7 @@ impl TestRequest {
             headers: HeaderMap::new(),
             body: Vec::new(),
             user: None,
+            peer_address: None,
         }
     }
 
@@ -352,6 +405,12 @@ impl TestRequest {
         self
     }
 
+    /// Sends the request as if from this address, as the server does for every connection.
+    pub fn with_peer_address(mut self, peer_address: SocketAddr) -> Self {
+        self.peer_address = Some(peer_address);
+        self
+    }
+
     /// In Part 10, refactor all callers from `.with_state(user)` to `.with_user(&user)`.
     pub fn with_state(self, user: User) -> Self {
         self.with_user(&user)
@@ -376,6 +435,9 @@ impl TestRequest {
         if let Some(user) = self.user {
             request.extensions_mut().insert(user);
         }
+        if let Some(peer_address) = self.peer_address {
+            request.extensions_mut().insert(ConnectInfo(peer_address));
+        }
         let response = app
             .router
             .clone()
@@ -564,6 +626,9 @@ impl_reload!(Task, Tasks);
 impl_reload!(Aggregator, Aggregators);
 impl_reload!(ApiToken, ApiTokens);
 impl_reload!(CollectorCredential, CollectorCredentials);
+impl_reload!(Webhook, Webhooks);
+impl_reload!(AlertRule, AlertRules);
+impl_reload!(Alert, Alerts);
 
 #[track_caller]
 pub fn assert_same_json_representation<Actual, Expected>(actual: &Actual, expected: &Expected)
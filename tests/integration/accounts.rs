@@ -217,7 +217,7 @@ mod create {
 
         let accounts = Accounts::find().all(app.db()).await?;
 
-        assert_eq!(accounts, [account.clone()]);
+        assert_eq!(accounts, std::slice::from_ref(&account));
 
         let memberships = Memberships::find().all(app.db()).await?;
         assert_eq!(memberships.len(), 1);
@@ -305,6 +305,22 @@ mod update {
         Ok(())
     }
 
+    #[test(harness = set_up)]
+    async fn without_admin_role(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+
+        let resp = patch(format!("/api/accounts/{}", account.id))
+            .with_api_headers()
+            .with_request_json(json!({ "name": "new name" }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+
+        assert_response!(resp, 403);
+        assert_eq!(account.reload(app.db()).await?.unwrap().name, account.name);
+        Ok(())
+    }
+
     #[test(harness = set_up)]
     async fn not_as_a_member(app: DivviupApi) -> TestResult {
         let (user, ..) = fixtures::member(&app).await;
@@ -199,3 +199,325 @@ mod prefixes {
         Ok(())
     }
 }
+
+mod request_policy {
+    use axum::{
+        extract::Request,
+        http::StatusCode,
+        middleware::{from_fn, Next},
+        response::IntoResponse,
+        Router,
+    };
+    use divviup_api::clients::{ClientError, RequestPolicy};
+    use std::{
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use super::{assert_eq, test, *};
+
+    fn policy() -> RequestPolicy {
+        RequestPolicy {
+            timeout: Duration::from_millis(250),
+            max_retries: 2,
+            retry_base_delay: Duration::from_millis(1),
+            circuit_failure_threshold: 3,
+            circuit_open_duration: Duration::from_millis(300),
+        }
+    }
+
+    /// The standard aggregator mock, except that the first `failures` requests are answered
+    /// with a 503 and every request is delayed by `latency`.
+    fn unreliable_mock(failures: Arc<AtomicU32>, latency: Duration) -> Router {
+        aggregator_api::mock().layer(from_fn(move |request: Request, next: Next| {
+            let failures = failures.clone();
+            async move {
+                tokio::time::sleep(latency).await;
+                let failing = failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok();
+                if failing {
+                    StatusCode::SERVICE_UNAVAILABLE.into_response()
+                } else {
+                    next.run(request).await
+                }
+            }
+        }))
+    }
+
+    fn client(app: &DivviupApi, aggregator: Aggregator) -> AggregatorClient {
+        aggregator
+            .client(
+                app.config().client.clone().with_request_policy(policy()),
+                app.crypter(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn retries_transient_get_failures() -> TestResult {
+        block_on(async {
+            let failures = Arc::new(AtomicU32::new(2));
+            let (app, client_logs) =
+                build_test_app_with_mock(unreliable_mock(failures, Duration::ZERO)).await;
+            let aggregator = fixtures::aggregator(&app, None).await;
+            let client = client(&app, aggregator);
+
+            client.get_task_upload_metrics("fake-task-id").await?;
+            let statuses = client_logs
+                .logs()
+                .iter()
+                .map(|log| log.response_status)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                statuses,
+                [
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    StatusCode::OK
+                ]
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() -> TestResult {
+        block_on(async {
+            let failures = Arc::new(AtomicU32::new(10));
+            let (app, client_logs) =
+                build_test_app_with_mock(unreliable_mock(failures, Duration::ZERO)).await;
+            let aggregator = fixtures::aggregator(&app, None).await;
+            let client = client(&app, aggregator);
+
+            let error = client
+                .get_task_upload_metrics("fake-task-id")
+                .await
+                .unwrap_err();
+            assert!(error.is_transient());
+            assert_eq!(client_logs.logs().len(), 3);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn does_not_retry_non_idempotent_requests() -> TestResult {
+        block_on(async {
+            let failures = Arc::new(AtomicU32::new(1));
+            let (app, client_logs) =
+                build_test_app_with_mock(unreliable_mock(failures, Duration::ZERO)).await;
+            let aggregator = fixtures::aggregator(&app, None).await;
+            let http_client = app.config().client.clone().with_request_policy(policy());
+            let url = Url::from(aggregator.api_url.clone()).join("tasks")?;
+
+            let result = http_client
+                .send_with_policy(&url, Method::POST, || http_client.post(url.as_str()))
+                .await;
+            assert!(result.unwrap_err().is_transient());
+            assert_eq!(client_logs.logs().len(), 1);
+            assert_eq!(client_logs.last().method, Method::POST);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn times_out_slow_requests() -> TestResult {
+        block_on(async {
+            let failures = Arc::new(AtomicU32::new(0));
+            let (app, _) =
+                build_test_app_with_mock(unreliable_mock(failures, Duration::from_secs(2))).await;
+            let aggregator = fixtures::aggregator(&app, None).await;
+            let client = aggregator.client(
+                app.config()
+                    .client
+                    .clone()
+                    .with_request_policy(RequestPolicy {
+                        max_retries: 0,
+                        ..policy()
+                    }),
+                app.crypter(),
+            )?;
+
+            let error = client
+                .get_task_upload_metrics("fake-task-id")
+                .await
+                .unwrap_err();
+            assert!(matches!(error, ClientError::Http(ref e) if e.is_timeout()));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn circuit_opens_and_closes() -> TestResult {
+        block_on(async {
+            let failures = Arc::new(AtomicU32::new(3));
+            let (app, client_logs) =
+                build_test_app_with_mock(unreliable_mock(failures, Duration::ZERO)).await;
+            let aggregator = fixtures::aggregator(&app, None).await;
+            let api_url = Url::from(aggregator.api_url.clone());
+            let client = client(&app, aggregator);
+
+            // three failed attempts reach the failure threshold
+            assert!(client
+                .get_task_upload_metrics("fake-task-id")
+                .await
+                .is_err());
+            assert_eq!(client_logs.logs().len(), 3);
+            assert!(app.config().client.circuit_breakers().is_open(&api_url));
+
+            // while open, requests fail without contacting the aggregator
+            let error = client
+                .get_task_upload_metrics("fake-task-id")
+                .await
+                .unwrap_err();
+            assert!(matches!(error, ClientError::CircuitOpen { .. }));
+            assert_eq!(client_logs.logs().len(), 3);
+
+            tokio::time::sleep(policy().circuit_open_duration).await;
+            client.get_task_upload_metrics("fake-task-id").await?;
+            assert_eq!(client_logs.logs().len(), 4);
+            assert!(!app.config().client.circuit_breakers().is_open(&api_url));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn open_circuit_is_service_unavailable() -> TestResult {
+        block_on(async {
+            let failures = Arc::new(AtomicU32::new(3));
+            let (app, client_logs) =
+                build_test_app_with_mock(unreliable_mock(failures, Duration::ZERO)).await;
+            let (user, account, ..) = fixtures::member(&app).await;
+            let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+            let aggregator_id = aggregator.id;
+            let client = client(&app, aggregator);
+            assert!(client
+                .get_task_upload_metrics("fake-task-id")
+                .await
+                .is_err());
+
+            let response = patch(format!("/api/aggregators/{aggregator_id}"))
+                .with_api_headers()
+                .with_request_json(json!({ "bearer_token": fixtures::random_name() }))
+                .with_state(user)
+                .run_async(&app)
+                .await;
+            assert_status!(response, 503);
+            assert!(response.header_str(headers::RETRY_AFTER).is_some());
+            assert_eq!(client_logs.logs().len(), 3);
+            Ok(())
+        })
+    }
+}
+
+mod ssrf_protection {
+    use axum::middleware::from_fn_with_state;
+    use divviup_api::clients::{ClientError, HttpClient, PublicAddressResolver};
+    use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+    use std::net::{Ipv4Addr, SocketAddr};
+    use test_support::client_logs::client_logs_middleware;
+    use tokio::net::TcpListener;
+
+    use super::{assert_eq, test, *};
+
+    /// Resolves every name to loopback, as a rebinding attacker's dns server would after
+    /// the aggregator url had been validated.
+    struct RebindingResolver;
+
+    impl Resolve for RebindingResolver {
+        fn resolve(&self, _: Name) -> Resolving {
+            let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+            Box::pin(async move { Ok(Box::new(std::iter::once(addr)) as Addrs) })
+        }
+    }
+
+    /// Serves the aggregator api mock on loopback without the test proxy.
+    async fn serve_mock() -> (u16, ClientLogs) {
+        let client_logs = ClientLogs::default();
+        let mock = aggregator_api::mock().layer(from_fn_with_state(
+            client_logs.clone(),
+            client_logs_middleware,
+        ));
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, mock).await });
+        (port, client_logs)
+    }
+
+    fn http_client() -> HttpClient {
+        HttpClient::from_builder(|| reqwest::Client::builder().no_proxy())
+    }
+
+    #[test(harness = set_up)]
+    async fn refuses_hostnames_resolving_to_private_addresses(_app: DivviupApi) -> TestResult {
+        let (port, client_logs) = serve_mock().await;
+        let client =
+            http_client().with_ssrf_protection(PublicAddressResolver::new(RebindingResolver));
+
+        let error = AggregatorClient::get_config(
+            client,
+            format!("http://aggregator.example:{port}/").parse()?,
+            "token",
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, ClientError::NonPublicAddress(_)));
+        assert!(!error.is_transient());
+        assert!(client_logs.logs().is_empty());
+
+        // the same server is reachable when protection is disabled
+        AggregatorClient::get_config(
+            http_client(),
+            format!("http://localhost:{port}/").parse()?,
+            "token",
+        )
+        .await?;
+        assert_eq!(client_logs.logs().len(), 1);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn refuses_private_ip_literals(_app: DivviupApi) -> TestResult {
+        let (port, client_logs) = serve_mock().await;
+        let client =
+            http_client().with_ssrf_protection(PublicAddressResolver::new(RebindingResolver));
+
+        let error = AggregatorClient::get_config(
+            client,
+            format!("http://127.0.0.1:{port}/").parse()?,
+            "token",
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, ClientError::NonPublicAddress(_)));
+        assert!(client_logs.logs().is_empty());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn applies_to_aggregator_clients(app: DivviupApi) -> TestResult {
+        let (port, client_logs) = serve_mock().await;
+        let api_url: Url = format!("http://aggregator.example:{port}/").parse()?;
+        let mut aggregator = fixtures::aggregator(&app, None).await.into_active_model();
+        aggregator.api_url = ActiveValue::Set(api_url.clone().into());
+        aggregator.encrypted_bearer_token = ActiveValue::Set(
+            app.crypter()
+                .encrypt(api_url.as_str().as_bytes(), b"token")?,
+        );
+        let aggregator = aggregator.update(app.db()).await?;
+
+        let client = aggregator.client(
+            http_client().with_ssrf_protection(PublicAddressResolver::new(RebindingResolver)),
+            app.crypter(),
+        )?;
+        assert!(matches!(
+            client.get_task_ids().await,
+            Err(ClientError::NonPublicAddress(_))
+        ));
+        assert!(client_logs.logs().is_empty());
+        Ok(())
+    }
+}
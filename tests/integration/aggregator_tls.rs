@@ -0,0 +1,210 @@
+use divviup_api::{api_mocks::aggregator_api, clients::HttpClient};
+use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, IsCa, KeyPair};
+use rustls::{
+    pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore, ServerConfig,
+};
+use std::{
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use test_support::{assert_eq, test, *};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+
+/// Accepts tls connections, discarding any that fail the handshake.
+struct TlsListener {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    handshakes: Arc<AtomicUsize>,
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            let Ok((stream, addr)) = self.listener.accept().await else {
+                continue;
+            };
+            if let Ok(stream) = self.acceptor.accept(stream).await {
+                self.handshakes.fetch_add(1, Ordering::Relaxed);
+                return (stream, addr);
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+struct Pki {
+    ca_certificate: String,
+    client_identity: String,
+    api_url: Url,
+    handshakes: Arc<AtomicUsize>,
+}
+
+/// Serves the aggregator api mock over tls with a server certificate issued by a private ca,
+/// requiring clients to present a certificate issued by the same ca.
+async fn serve_mutual_tls() -> Pki {
+    // See tls_smoke_test for why the crypto provider is installed explicitly.
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let ca = CertifiedIssuer::self_signed(ca_params, KeyPair::generate().unwrap()).unwrap();
+
+    let server_key = KeyPair::generate().unwrap();
+    let server_certificate = CertificateParams::new(["localhost".to_string()])
+        .unwrap()
+        .signed_by(&server_key, &ca)
+        .unwrap();
+
+    let client_key = KeyPair::generate().unwrap();
+    let client_certificate = CertificateParams::new(["client.example".to_string()])
+        .unwrap()
+        .signed_by(&client_key, &ca)
+        .unwrap();
+
+    let mut roots = RootCertStore::empty();
+    roots.add(ca.der().clone()).unwrap();
+    let mut server_config = ServerConfig::builder()
+        .with_client_cert_verifier(
+            WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .unwrap(),
+        )
+        .with_single_cert(
+            vec![server_certificate.der().clone()],
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(server_key.serialize_der())),
+        )
+        .unwrap();
+    server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+    let listener = TcpListener::bind("localhost:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handshakes = Arc::new(AtomicUsize::new(0));
+    let listener = TlsListener {
+        listener,
+        acceptor: TlsAcceptor::from(Arc::new(server_config)),
+        handshakes: handshakes.clone(),
+    };
+    tokio::spawn(async move { axum::serve(listener, aggregator_api::mock()).await });
+
+    Pki {
+        ca_certificate: ca.pem(),
+        client_identity: format!(
+            "{}\n{}",
+            client_certificate.pem(),
+            client_key.serialize_pem()
+        ),
+        api_url: format!("https://localhost:{port}/").parse().unwrap(),
+        handshakes,
+    }
+}
+
+async fn aggregator_at(
+    app: &DivviupApi,
+    api_url: &Url,
+    client_identity: Option<&str>,
+    ca_certificate: Option<&str>,
+) -> Aggregator {
+    let crypter = app.crypter();
+    let mut aggregator = fixtures::aggregator(app, None).await.into_active_model();
+    aggregator.api_url = ActiveValue::Set(api_url.clone().into());
+    aggregator.encrypted_bearer_token = ActiveValue::Set(
+        crypter
+            .encrypt(api_url.as_str().as_bytes(), b"token")
+            .unwrap(),
+    );
+    aggregator.encrypted_client_identity = ActiveValue::Set(client_identity.map(|identity| {
+        crypter
+            .encrypt(api_url.as_str().as_bytes(), identity.as_bytes())
+            .unwrap()
+    }));
+    aggregator.ca_certificate = ActiveValue::Set(ca_certificate.map(String::from));
+    aggregator.update(app.db()).await.unwrap()
+}
+
+/// Unlike the application's test client, this does not proxy requests to the mock server.
+fn http_client() -> HttpClient {
+    HttpClient::from_builder(|| {
+        reqwest::Client::builder()
+            .use_rustls_tls()
+            .no_proxy()
+            .http1_only()
+    })
+}
+
+#[test(harness = set_up)]
+async fn mutual_tls(app: DivviupApi) -> TestResult {
+    let pki = serve_mutual_tls().await;
+    let aggregator = aggregator_at(
+        &app,
+        &pki.api_url,
+        Some(&pki.client_identity),
+        Some(&pki.ca_certificate),
+    )
+    .await;
+
+    let client = aggregator.client(http_client(), app.crypter())?;
+    client.get_aggregator_config().await?;
+    assert!(client.get_task_upload_metrics("fake-task-id").await.is_ok());
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn connections_are_reused(app: DivviupApi) -> TestResult {
+    let pki = serve_mutual_tls().await;
+    let aggregator = aggregator_at(
+        &app,
+        &pki.api_url,
+        Some(&pki.client_identity),
+        Some(&pki.ca_certificate),
+    )
+    .await;
+
+    let http_client = http_client();
+    for _ in 0..2 {
+        aggregator
+            .client(http_client.clone(), app.crypter())?
+            .get_aggregator_config()
+            .await?;
+    }
+    assert_eq!(pki.handshakes.load(Ordering::Relaxed), 1);
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn missing_client_identity(app: DivviupApi) -> TestResult {
+    let pki = serve_mutual_tls().await;
+    let aggregator = aggregator_at(&app, &pki.api_url, None, Some(&pki.ca_certificate)).await;
+
+    assert!(aggregator
+        .client(http_client(), app.crypter())?
+        .get_aggregator_config()
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn untrusted_server_certificate(app: DivviupApi) -> TestResult {
+    let pki = serve_mutual_tls().await;
+    let aggregator = aggregator_at(&app, &pki.api_url, Some(&pki.client_identity), None).await;
+
+    assert!(aggregator
+        .client(http_client(), app.crypter())?
+        .get_aggregator_config()
+        .await
+        .is_err());
+    Ok(())
+}
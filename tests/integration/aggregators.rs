@@ -303,7 +303,13 @@ mod create {
         assert_response!(resp, 201);
         let aggregator: Aggregator = resp.response_json();
 
-        let aggregator_config: AggregatorApiConfig = client_logs.last().response_json();
+        let logs = client_logs.logs();
+        assert_eq!(logs.len(), 2);
+        let aggregator_config: AggregatorApiConfig = logs[0].response_json();
+        assert_eq!(
+            logs[1].url,
+            aggregator_config.dap_url.join("hpke_config").unwrap()
+        );
 
         assert_eq!(aggregator.account_id.unwrap(), account.id);
         assert_eq!(aggregator.dap_url, aggregator_config.dap_url);
@@ -323,6 +329,58 @@ mod create {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn unsupported_hpke_configs() -> TestResult {
+        use axum::{routing::get, Json, Router};
+        use divviup_api::clients::aggregator_client::api_types::{
+            Encode, HpkeAeadId, HpkeConfig, HpkeConfigId, HpkeConfigList, HpkeKdfId, HpkeKemId,
+            HpkePublicKey,
+        };
+
+        let mock = Router::new()
+            .route(
+                "/",
+                get(|| async {
+                    Json(AggregatorApiConfig {
+                        dap_url: "https://dap.example".parse().unwrap(),
+                        role: Role::Either,
+                        vdafs: Default::default(),
+                        query_types: Default::default(),
+                        protocol: Protocol::Dap09,
+                        features: Default::default(),
+                    })
+                }),
+            )
+            .route(
+                "/hpke_config",
+                get(|| async {
+                    HpkeConfigList::new(vec![HpkeConfig::new(
+                        HpkeConfigId::from(1),
+                        HpkeKemId::X448HkdfSha512,
+                        HpkeKdfId::HkdfSha512,
+                        HpkeAeadId::Aes256Gcm,
+                        HpkePublicKey::from(vec![0; 56]),
+                    )])
+                    .get_encoded()
+                    .unwrap()
+                }),
+            );
+        let (app, _) = build_test_app_with_mock(mock).await;
+        let (user, account, ..) = fixtures::member(&app).await;
+
+        let resp = post(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(fixtures::new_aggregator())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let error: Value = resp.response_json();
+        assert_eq!(error["dap_url"][0]["code"], "hpke-config-unsupported");
+        assert_eq!(Aggregators::find().count(app.db()).await?, 0);
+        Ok(())
+    }
+
     #[test(harness = set_up)]
     async fn is_first_party_is_ignored(app: DivviupApi) -> TestResult {
         let (user, account, ..) = fixtures::member(&app).await;
@@ -757,6 +815,23 @@ mod update {
         Ok(())
     }
 
+    #[test(harness = with_client_logs)]
+    async fn as_viewer(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Viewer).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+
+        let resp = patch(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({ "bearer_token": fixtures::random_name() }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert!(client_logs.is_empty());
+        assert_eq!(aggregator.reload(app.db()).await?.unwrap(), aggregator);
+        Ok(())
+    }
+
     #[test(harness = with_client_logs)]
     async fn bad_bearer_token(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
         let (user, account, ..) = fixtures::member(&app).await;
@@ -1107,6 +1182,11 @@ mod update {
 
 mod delete {
     use super::{assert_eq, test, *};
+    use divviup_api::{
+        entity::queue::Entity,
+        queue::{ExpireAggregatorTasks, Job},
+    };
+    use time::Duration;
     use uuid::Uuid;
 
     #[test(harness = set_up)]
@@ -1167,6 +1247,68 @@ mod delete {
         Ok(())
     }
 
+    #[test(harness = set_up)]
+    async fn with_unexpired_tasks(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.leader_aggregator(app.db()).await?;
+        let resp = delete(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert_eq!(errors["tasks"][0]["code"], "unexpired-tasks");
+        assert_eq!(errors["tasks"][0]["params"]["count"], 1);
+        assert!(!aggregator.reload(app.db()).await?.unwrap().is_tombstoned());
+        assert_eq!(Entity::find().count(app.db()).await?, 0);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn with_expired_tasks(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.leader_aggregator(app.db()).await?;
+        let mut task = task.into_active_model();
+        task.expiration = ActiveValue::Set(Some(OffsetDateTime::now_utc() - Duration::days(1)));
+        task.update(app.db()).await?;
+
+        let resp = delete(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_status!(resp, 204);
+        assert!(aggregator.reload(app.db()).await?.unwrap().is_tombstoned());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn cascade_expire(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.leader_aggregator(app.db()).await?;
+        let resp = delete(format!("/api/aggregators/{}?cascade=expire", aggregator.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 202);
+        let job: Value = resp.response_json();
+        assert_eq!(job["job"]["type"], "ExpireAggregatorTasks");
+        assert_eq!(job["job"]["aggregator_id"], aggregator.id.to_string());
+        assert!(aggregator.reload(app.db()).await?.unwrap().is_tombstoned());
+
+        let queue_job = Entity::find().one(app.db()).await?.unwrap();
+        assert_eq!(
+            *queue_job.job,
+            Job::from(ExpireAggregatorTasks::new(aggregator.id))
+        );
+        Ok(())
+    }
+
     #[test(harness = set_up)]
     async fn non_member(app: DivviupApi) -> TestResult {
         let account = fixtures::account(&app).await;
@@ -1275,6 +1417,126 @@ mod delete {
     }
 }
 
+mod task_expiration {
+    use super::{assert_eq, test, *};
+    use divviup_api::queue::{ExpireAggregatorTasks, Job, Queue};
+
+    async fn delete_with_tasks(app: &DivviupApi, user: User, aggregator: &Aggregator) {
+        let resp = delete(format!("/api/aggregators/{}?cascade=expire", aggregator.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(app)
+            .await;
+        assert_response!(resp, 202);
+    }
+
+    #[test(harness = set_up)]
+    async fn as_member(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.leader_aggregator(app.db()).await?;
+        delete_with_tasks(&app, user.clone(), &aggregator).await;
+
+        let resp = get(format!(
+            "/api/aggregators/{}/task_expiration",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user.clone())
+        .run_async(&app)
+        .await;
+        assert_ok!(resp);
+        let progress: Value = resp.response_json();
+        assert_eq!(progress["status"], "Pending");
+        assert_eq!(progress["remaining_tasks"], 1);
+        assert_eq!(progress["expired_task_ids"], json!([]));
+
+        Queue::from(&app).perform_one_queue_job().await?.unwrap();
+
+        let resp = get(format!(
+            "/api/aggregators/{}/task_expiration",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_ok!(resp);
+        let progress: Value = resp.response_json();
+        assert_eq!(progress["status"], "Success");
+        assert_eq!(progress["remaining_tasks"], 0);
+        assert_eq!(progress["expired_task_ids"], json!([task.id]));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn not_deleted(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let resp = get(format!(
+            "/api/aggregators/{}/task_expiration",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_response!(resp, 404);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn non_member(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.leader_aggregator(app.db()).await?;
+        delete_with_tasks(&app, user, &aggregator).await;
+
+        let (other_user, ..) = fixtures::member(&app).await;
+        let resp = get(format!(
+            "/api/aggregators/{}/task_expiration",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(other_user)
+        .run_async(&app)
+        .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn shared(app: DivviupApi) -> TestResult {
+        let (admin, ..) = fixtures::admin(&app).await;
+        let aggregator = fixtures::aggregator(&app, None).await;
+        Job::from(ExpireAggregatorTasks::new(aggregator.id))
+            .insert(app.db())
+            .await?;
+
+        let (user, ..) = fixtures::member(&app).await;
+        let resp = get(format!(
+            "/api/aggregators/{}/task_expiration",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_response!(resp, 404);
+
+        let resp = get(format!(
+            "/api/aggregators/{}/task_expiration",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(admin)
+        .run_async(&app)
+        .await;
+        assert_ok!(resp);
+        Ok(())
+    }
+}
+
 mod shared_create {
     use super::{assert_eq, test, *};
 
@@ -1291,7 +1553,13 @@ mod shared_create {
 
         assert_response!(resp, 201);
         let aggregator: Aggregator = resp.response_json();
-        let aggregator_config: AggregatorApiConfig = client_logs.last().response_json();
+        let logs = client_logs.logs();
+        assert_eq!(logs.len(), 2);
+        let aggregator_config: AggregatorApiConfig = logs[0].response_json();
+        assert_eq!(
+            logs[1].url,
+            aggregator_config.dap_url.join("hpke_config").unwrap()
+        );
 
         assert!(aggregator.account_id.is_none());
         assert_eq!(aggregator.dap_url, aggregator_config.dap_url);
@@ -1410,3 +1678,1031 @@ mod shared_create {
         Ok(())
     }
 }
+
+mod secondary_bearer_token {
+    use divviup_api::api_mocks::aggregator_api::BAD_BEARER_TOKEN;
+
+    use super::{assert_eq, test, *};
+
+    async fn with_tokens(
+        app: &DivviupApi,
+        aggregator: Aggregator,
+        primary: &str,
+        secondary: Option<&str>,
+    ) -> Aggregator {
+        let aad = aggregator.api_url.as_ref().as_bytes().to_vec();
+        let mut aggregator = aggregator.into_active_model();
+        aggregator.encrypted_bearer_token =
+            ActiveValue::Set(app.crypter().encrypt(&aad, primary.as_bytes()).unwrap());
+        aggregator.encrypted_secondary_bearer_token = ActiveValue::Set(
+            secondary.map(|secondary| app.crypter().encrypt(&aad, secondary.as_bytes()).unwrap()),
+        );
+        aggregator.update(app.db()).await.unwrap()
+    }
+
+    #[test(harness = set_up)]
+    async fn stage(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let original_bearer_token = aggregator.bearer_token(app.crypter())?;
+        let new_bearer_token = fixtures::random_name();
+
+        let resp = put(format!(
+            "/api/aggregators/{}/secondary_bearer_token",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_request_json(json!({ "bearer_token": &new_bearer_token }))
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_ok!(resp);
+        let response: Value = resp.response_json();
+        assert_eq!(response["has_secondary_bearer_token"], json!(true));
+
+        let reloaded = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(reloaded.bearer_token(app.crypter())?, original_bearer_token);
+        assert_eq!(
+            reloaded.secondary_bearer_token(app.crypter())?,
+            Some(new_bearer_token)
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn stage_invalid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+
+        let resp = put(format!(
+            "/api/aggregators/{}/secondary_bearer_token",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_request_json(json!({ "bearer_token": "" }))
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert!(errors.get("bearer_token").is_some());
+        assert!(aggregator
+            .reload(app.db())
+            .await?
+            .unwrap()
+            .encrypted_secondary_bearer_token
+            .is_none());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn stage_shared_as_member(app: DivviupApi) -> TestResult {
+        let (user, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, None).await;
+
+        let resp = put(format!(
+            "/api/aggregators/{}/secondary_bearer_token",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_request_json(json!({ "bearer_token": fixtures::random_name() }))
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn verify(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let aggregator = with_tokens(
+            &app,
+            aggregator,
+            &fixtures::random_name(),
+            Some(BAD_BEARER_TOKEN),
+        )
+        .await;
+
+        let resp = post(format!(
+            "/api/aggregators/{}/secondary_bearer_token/verify",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user.clone())
+        .run_async(&app)
+        .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert!(errors.get("bearer_token").is_some());
+
+        let aggregator = with_tokens(
+            &app,
+            aggregator,
+            &fixtures::random_name(),
+            Some(&fixtures::random_name()),
+        )
+        .await;
+        let resp = post(format!(
+            "/api/aggregators/{}/secondary_bearer_token/verify",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_ok!(resp);
+        assert_eq!(aggregator.reload(app.db()).await?.unwrap(), aggregator);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn promote(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let new_bearer_token = fixtures::random_name();
+        let aggregator = with_tokens(
+            &app,
+            aggregator,
+            &fixtures::random_name(),
+            Some(&new_bearer_token),
+        )
+        .await;
+
+        let resp = post(format!(
+            "/api/aggregators/{}/secondary_bearer_token/promote",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_ok!(resp);
+        let response: Value = resp.response_json();
+        assert_eq!(response["has_secondary_bearer_token"], json!(false));
+
+        let reloaded = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(reloaded.bearer_token(app.crypter())?, new_bearer_token);
+        assert!(reloaded.encrypted_secondary_bearer_token.is_none());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn promote_without_secondary(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+
+        let resp = post(format!(
+            "/api/aggregators/{}/secondary_bearer_token/promote",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_response!(resp, 400);
+        assert_eq!(aggregator.reload(app.db()).await?.unwrap(), aggregator);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn discard(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let original_bearer_token = aggregator.bearer_token(app.crypter())?;
+        let aggregator = with_tokens(
+            &app,
+            aggregator,
+            &original_bearer_token,
+            Some(&fixtures::random_name()),
+        )
+        .await;
+
+        let resp = delete(format!(
+            "/api/aggregators/{}/secondary_bearer_token",
+            aggregator.id
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_status!(resp, 204);
+
+        let reloaded = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(reloaded.bearer_token(app.crypter())?, original_bearer_token);
+        assert!(reloaded.encrypted_secondary_bearer_token.is_none());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn replacing_primary_discards_secondary(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let aggregator = with_tokens(
+            &app,
+            aggregator,
+            &fixtures::random_name(),
+            Some(&fixtures::random_name()),
+        )
+        .await;
+        let new_bearer_token = fixtures::random_name();
+
+        let resp = patch(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({ "bearer_token": &new_bearer_token }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let response: Value = resp.response_json();
+        assert_eq!(response["has_secondary_bearer_token"], json!(false));
+
+        let reloaded = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(reloaded.bearer_token(app.crypter())?, new_bearer_token);
+        assert!(reloaded.encrypted_secondary_bearer_token.is_none());
+        Ok(())
+    }
+
+    #[test(harness = with_client_logs)]
+    async fn fallback_and_automatic_promotion(
+        app: DivviupApi,
+        client_logs: ClientLogs,
+    ) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let new_bearer_token = fixtures::random_name();
+        let aggregator =
+            with_tokens(&app, aggregator, BAD_BEARER_TOKEN, Some(&new_bearer_token)).await;
+
+        let resp = patch(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({}))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let statuses = client_logs
+            .logs()
+            .iter()
+            .map(|log| log.response_status)
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, [StatusCode::UNAUTHORIZED, StatusCode::OK]);
+
+        let reloaded = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(reloaded.bearer_token(app.crypter())?, new_bearer_token);
+        assert!(reloaded.encrypted_secondary_bearer_token.is_none());
+        Ok(())
+    }
+}
+
+mod tls_settings {
+    use rcgen::generate_simple_self_signed;
+
+    use super::{assert_eq, test, *};
+
+    fn certificate_and_key() -> (String, String) {
+        let certified_key = generate_simple_self_signed(["client.example".into()]).unwrap();
+        (
+            certified_key.cert.pem(),
+            certified_key.signing_key.serialize_pem(),
+        )
+    }
+
+    #[test(harness = set_up)]
+    async fn create(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (client_certificate, client_key) = certificate_and_key();
+        let (ca_certificate, _) = certificate_and_key();
+
+        let mut new_aggregator = fixtures::new_aggregator();
+        new_aggregator.client_certificate = Some(client_certificate.clone());
+        new_aggregator.client_key = Some(client_key.clone());
+        new_aggregator.ca_certificate = Some(ca_certificate.clone());
+        let resp = post(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(new_aggregator)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let json: Value = resp.response_json();
+        assert_eq!(json["has_client_identity"], true);
+        assert_eq!(json["ca_certificate"], ca_certificate);
+
+        let aggregator: Aggregator = resp.response_json();
+        let aggregator = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(
+            String::from_utf8(aggregator.client_identity(app.crypter())?.unwrap())?,
+            format!("{client_certificate}\n{client_key}")
+        );
+        assert_eq!(aggregator.ca_certificate, Some(ca_certificate));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn create_with_certificate_but_no_key(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (client_certificate, _) = certificate_and_key();
+
+        let mut new_aggregator = fixtures::new_aggregator();
+        new_aggregator.client_certificate = Some(client_certificate);
+        let resp = post(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(new_aggregator)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let error: Value = resp.response_json();
+        assert!(error.get("client_key").is_some());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn create_with_invalid_pem(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (_, client_key) = certificate_and_key();
+
+        let mut new_aggregator = fixtures::new_aggregator();
+        new_aggregator.client_certificate = Some("not a certificate".into());
+        new_aggregator.client_key = Some(client_key);
+        new_aggregator.ca_certificate = Some("not a certificate".into());
+        let resp = post(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(new_aggregator)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let error: Value = resp.response_json();
+        assert!(error.get("ca_certificate").is_some());
+
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (_, client_key) = certificate_and_key();
+        let mut new_aggregator = fixtures::new_aggregator();
+        new_aggregator.client_certificate = Some("not a certificate".into());
+        new_aggregator.client_key = Some(client_key);
+        let resp = post(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(new_aggregator)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let error: Value = resp.response_json();
+        assert!(error.get("client_certificate").is_some());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn update(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let (client_certificate, client_key) = certificate_and_key();
+
+        let resp = patch(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({
+                "client_certificate": &client_certificate,
+                "client_key": &client_key,
+            }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let json: Value = resp.response_json();
+        assert_eq!(json["has_client_identity"], true);
+
+        let reloaded = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(
+            String::from_utf8(reloaded.client_identity(app.crypter())?.unwrap())?,
+            format!("{client_certificate}\n{client_key}")
+        );
+        assert_eq!(reloaded.ca_certificate, None);
+        assert_eq!(
+            reloaded.encrypted_bearer_token,
+            aggregator.encrypted_bearer_token
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn remove(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (client_certificate, client_key) = certificate_and_key();
+        let (ca_certificate, _) = certificate_and_key();
+
+        let mut new_aggregator = fixtures::new_aggregator();
+        new_aggregator.client_certificate = Some(client_certificate);
+        new_aggregator.client_key = Some(client_key);
+        new_aggregator.ca_certificate = Some(ca_certificate);
+        let resp = post(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_request_json(new_aggregator)
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let aggregator: Aggregator = resp.response_json();
+
+        let resp = patch(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({ "client_certificate": null }))
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let error: Value = resp.response_json();
+        assert!(error.get("client_key").is_some());
+
+        let resp = patch(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({
+                "client_certificate": null,
+                "client_key": null,
+                "ca_certificate": null,
+            }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let json: Value = resp.response_json();
+        assert_eq!(json["has_client_identity"], false);
+
+        let reloaded = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(reloaded.encrypted_client_identity, None);
+        assert_eq!(reloaded.ca_certificate, None);
+        Ok(())
+    }
+}
+
+mod compatibility {
+    use super::{assert_eq, test, *};
+    use divviup_api::entity::aggregator::{Feature, Features};
+
+    fn url(account: &Account, leader: &Aggregator, helper: &Aggregator) -> String {
+        format!(
+            "/api/accounts/{}/aggregator_pairs/compatibility?leader={}&helper={}",
+            account.id, leader.id, helper.id
+        )
+    }
+
+    #[test(harness = set_up)]
+    async fn compatible(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (leader, helper) = fixtures::aggregator_pair(&app, &account).await;
+        let mut leader = leader.into_active_model();
+        leader.features = ActiveValue::Set(
+            Features::from_iter([
+                Feature::TokenHash,
+                Feature::PureDpDiscreteLaplace,
+                Feature::TimeBucketedFixedSize,
+            ])
+            .into(),
+        );
+        let leader = leader.update(app.db()).await?;
+        let mut helper = helper.into_active_model();
+        helper.features = ActiveValue::Set(Features::from(Feature::PureDpDiscreteLaplace).into());
+        helper.vdafs = ActiveValue::Set(
+            aggregator::VdafNameSet::from_iter(["Prio3Count", "Prio3Histogram"]).into(),
+        );
+        let helper = helper.update(app.db()).await?;
+
+        let resp = get(url(&account, &leader, &helper))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let compatibility: Value = resp.response_json();
+        assert_eq!(
+            compatibility,
+            json!({
+                "compatible": true,
+                "errors": {},
+                "protocol": "DAP-09",
+                "vdafs": ["Prio3Count", "Prio3Histogram"],
+                "query_types": ["TimeInterval", "FixedSize"],
+                "features": ["PureDpDiscreteLaplace"],
+                "time_bucketed_fixed_size": true,
+                "creatable_vdafs": [
+                    {
+                        "type": "count",
+                        "dp_strategies": ["NoDifferentialPrivacy", "PureDpDiscreteLaplace"]
+                    },
+                    {
+                        "type": "histogram",
+                        "dp_strategies": ["NoDifferentialPrivacy", "PureDpDiscreteLaplace"]
+                    },
+                    { "type": "count_vec", "dp_strategies": ["NoDifferentialPrivacy"] },
+                ]
+            })
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn incompatible(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let leader = fixtures::aggregator(&app, Some(&account)).await;
+        let helper = fixtures::aggregator(&app, Some(&account)).await;
+        let mut helper = helper.into_active_model();
+        helper.role = ActiveValue::Set(Role::Leader);
+        let helper = helper.update(app.db()).await?;
+
+        let resp = get(url(&account, &leader, &helper))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let compatibility: Value = resp.response_json();
+        assert_eq!(compatibility["compatible"], false);
+        assert_eq!(compatibility["creatable_vdafs"], json!([]));
+        let errors = &compatibility["errors"];
+        assert_eq!(errors["leader_aggregator_id"][0]["code"], "no-first-party");
+        assert_eq!(errors["helper_aggregator_id"][0]["code"], "no-first-party");
+        assert_eq!(errors["helper_aggregator_id"][1]["code"], "role");
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn inaccessible_aggregator(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let other_account = fixtures::account(&app).await;
+        let leader = fixtures::aggregator(&app, Some(&other_account)).await;
+        let helper = fixtures::aggregator(&app, None).await;
+
+        let resp = get(url(&account, &leader, &helper))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert_eq!(errors["leader"][0]["code"], "required");
+        assert_eq!(errors.get("helper"), None);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn not_member(app: DivviupApi) -> TestResult {
+        let user = fixtures::user();
+        let account = fixtures::account(&app).await;
+        let (leader, helper) = fixtures::aggregator_pair(&app, &account).await;
+
+        let resp = get(url(&account, &leader, &helper))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn malformed_ids(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+
+        let resp = get(format!(
+            "/api/accounts/{}/aggregator_pairs/compatibility?leader=not-a-uuid&helper={}",
+            account.id,
+            uuid::Uuid::new_v4()
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert_eq!(errors["leader"][0]["code"], "invalid-uuid");
+        assert_eq!(errors["helper"][0]["code"], "required");
+        Ok(())
+    }
+}
+
+mod decommission {
+    use super::{assert_eq, test, *};
+    use divviup_api::{
+        entity::queue::Entity,
+        queue::{Job, SendDecommissionEmail, SunsetAggregator, V1},
+    };
+    use time::{format_description::well_known::Rfc3339, Duration};
+
+    #[test(harness = set_up)]
+    async fn as_admin(app: DivviupApi) -> TestResult {
+        let (admin, ..) = fixtures::admin(&app).await;
+        let (_, account, membership) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.helper_aggregator(app.db()).await?;
+        let _unaffected_task = fixtures::task(&app, &account).await;
+        let sunset_at = OffsetDateTime::now_utc() + Duration::days(30);
+
+        let resp = post(format!("/api/aggregators/{}/decommission", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({ "sunset_at": sunset_at.format(&Rfc3339)? }))
+            .with_state(admin)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let decommission: Value = resp.response_json();
+        assert_eq!(decommission["affected_tasks"].as_array().unwrap().len(), 1);
+        assert_eq!(decommission["affected_tasks"][0]["id"], task.id);
+
+        let aggregator = aggregator.reload(app.db()).await?.unwrap();
+        assert_eq!(aggregator.sunset_at, Some(sunset_at));
+        assert!(!aggregator.is_tombstoned());
+
+        let jobs = Entity::find().all(app.db()).await?;
+        assert_eq!(jobs.len(), 2);
+        let sunset_job = jobs
+            .iter()
+            .find(|job| {
+                *job.job
+                    == SunsetAggregator {
+                        aggregator_id: aggregator.id,
+                    }
+            })
+            .unwrap();
+        assert_eq!(sunset_job.scheduled_at, Some(sunset_at));
+        assert!(jobs.iter().any(|job| matches!(
+            &*job.job,
+            Job::V1(V1::SendDecommissionEmail(SendDecommissionEmail {
+                membership_id,
+                aggregator_id,
+                task_ids,
+                ..
+            })) if *membership_id == membership.id
+                && *aggregator_id == aggregator.id
+                && *task_ids == [task.id.clone()]
+        )));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn again(app: DivviupApi) -> TestResult {
+        let (admin, ..) = fixtures::admin(&app).await;
+        let (_, account, _) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.helper_aggregator(app.db()).await?;
+
+        for days in [30, 60] {
+            let sunset_at = OffsetDateTime::now_utc() + Duration::days(days);
+            let resp = post(format!("/api/aggregators/{}/decommission", aggregator.id))
+                .with_api_headers()
+                .with_request_json(json!({ "sunset_at": sunset_at.format(&Rfc3339)? }))
+                .with_state(admin.clone())
+                .run_async(&app)
+                .await;
+            assert_ok!(resp);
+
+            // accounts are only notified once, and the pending sunset is moved
+            let jobs = Entity::find().all(app.db()).await?;
+            assert_eq!(jobs.len(), 2);
+            let sunset_job = jobs
+                .iter()
+                .find(|job| {
+                    *job.job
+                        == SunsetAggregator {
+                            aggregator_id: aggregator.id,
+                        }
+                })
+                .unwrap();
+            assert_eq!(sunset_job.scheduled_at, Some(sunset_at));
+        }
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn show(app: DivviupApi) -> TestResult {
+        let (admin, ..) = fixtures::admin(&app).await;
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.helper_aggregator(app.db()).await?;
+
+        let resp = get(format!("/api/aggregators/{}/decommission", aggregator.id))
+            .with_api_headers()
+            .with_state(admin)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let decommission: Value = resp.response_json();
+        assert_eq!(decommission["aggregator"]["sunset_at"], Value::Null);
+        assert_eq!(decommission["affected_tasks"][0]["id"], task.id);
+        assert_eq!(Entity::find().count(app.db()).await?, 0);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn invalid(app: DivviupApi) -> TestResult {
+        let (admin, account, ..) = fixtures::admin(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let resp = post(format!("/api/aggregators/{}/decommission", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({
+                "sunset_at": (OffsetDateTime::now_utc() - Duration::days(1)).format(&Rfc3339)?
+            }))
+            .with_state(admin)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert_eq!(errors["sunset_at"][0]["code"], "past");
+        assert_eq!(errors["account_id"][0]["code"], "not-shared");
+        assert_eq!(aggregator.reload(app.db()).await?.unwrap().sunset_at, None);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_nonadmin(app: DivviupApi) -> TestResult {
+        let (user, ..) = fixtures::member(&app).await;
+        let aggregator = fixtures::aggregator(&app, None).await;
+        let resp = post(format!("/api/aggregators/{}/decommission", aggregator.id))
+            .with_api_headers()
+            .with_request_json(json!({
+                "sunset_at": (OffsetDateTime::now_utc() + Duration::days(1)).format(&Rfc3339)?
+            }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_not_found!(resp);
+        assert_eq!(aggregator.reload(app.db()).await?.unwrap().sunset_at, None);
+        Ok(())
+    }
+}
+
+mod visibility {
+    use super::{assert_eq, test, *};
+    use divviup_api::entity::{AggregatorAccesses, Visibility};
+
+    async fn shared_aggregator(app: &DivviupApi, visibility: Visibility) -> Aggregator {
+        let mut aggregator = fixtures::aggregator(app, None).await.into_active_model();
+        aggregator.visibility = ActiveValue::Set(visibility);
+        aggregator.update(app.db()).await.unwrap()
+    }
+
+    async fn listed(app: &DivviupApi, user: &User, account: &Account) -> Vec<Aggregator> {
+        let resp = get(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(app)
+            .await;
+        assert_ok!(resp);
+        resp.response_json()
+    }
+
+    #[test(harness = set_up)]
+    async fn allowlist(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (admin, ..) = fixtures::admin(&app).await;
+        let public = fixtures::aggregator(&app, None).await;
+        let allowlisted = shared_aggregator(&app, Visibility::Allowlist).await;
+
+        assert_same_json_representation(&listed(&app, &user, &account).await, &vec![public]);
+
+        let resp = get("/api/aggregators")
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(resp.response_json::<Vec<Aggregator>>().len(), 1);
+
+        let resp = get(format!("/api/aggregators/{}", allowlisted.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_not_found!(resp);
+
+        let resp = put(format!(
+            "/api/aggregators/{}/access/{}",
+            allowlisted.id, account.id
+        ))
+        .with_api_headers()
+        .with_state(admin)
+        .run_async(&app)
+        .await;
+        assert_ok!(resp);
+        let access: Value = resp.response_json();
+        assert_eq!(access["account_id"], account.id.to_string());
+        assert!(access["granted_at"].is_string());
+
+        assert!(listed(&app, &user, &account)
+            .await
+            .iter()
+            .any(|aggregator| aggregator.id == allowlisted.id));
+
+        let resp = get(format!("/api/aggregators/{}", allowlisted.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn request_access(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (admin, ..) = fixtures::admin(&app).await;
+        let leader = fixtures::aggregator(&app, Some(&account)).await;
+        let helper = shared_aggregator(&app, Visibility::RequestAccess).await;
+        assert_same_json_representation(
+            &listed(&app, &user, &account).await,
+            &vec![leader.clone(), helper.clone()],
+        );
+
+        let compatibility_url = format!(
+            "/api/accounts/{}/aggregator_pairs/compatibility?leader={}&helper={}",
+            account.id, leader.id, helper.id
+        );
+        let resp = get(&compatibility_url)
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let compatibility: Value = resp.response_json();
+        assert_eq!(compatibility["compatible"], false);
+        assert_eq!(
+            compatibility["errors"]["helper_aggregator_id"][0]["code"],
+            "access-required"
+        );
+
+        let resp = post(format!("/api/accounts/{}/aggregator_access", account.id))
+            .with_api_headers()
+            .with_request_json(json!({ "aggregator_id": helper.id }))
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, StatusCode::CREATED);
+        let access: Value = resp.response_json();
+        assert_eq!(access["granted_at"], Value::Null);
+
+        let resp = post(format!("/api/accounts/{}/aggregator_access", account.id))
+            .with_api_headers()
+            .with_request_json(json!({ "aggregator_id": helper.id }))
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(resp.response_json::<Value>()["id"], access["id"]);
+
+        let resp = get(format!("/api/aggregators/{}/access", helper.id))
+            .with_api_headers()
+            .with_state(admin.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(resp.response_json::<Value>(), json!([access]));
+
+        let resp = put(format!(
+            "/api/aggregators/{}/access/{}",
+            helper.id, account.id
+        ))
+        .with_api_headers()
+        .with_state(admin)
+        .run_async(&app)
+        .await;
+        assert_ok!(resp);
+        assert_eq!(AggregatorAccesses::find().count(app.db()).await?, 1);
+
+        let resp = get(format!("/api/accounts/{}/aggregator_access", account.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let accesses: Value = resp.response_json();
+        assert!(accesses[0]["granted_at"].is_string());
+
+        let resp = get(&compatibility_url)
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(resp.response_json::<Value>()["compatible"], true);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn request_access_invalid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let public = fixtures::aggregator(&app, None).await;
+        let allowlisted = shared_aggregator(&app, Visibility::Allowlist).await;
+
+        for (aggregator_id, code) in [
+            (json!(public.id), "public"),
+            (json!(allowlisted.id), "required"),
+            (Value::Null, "required"),
+        ] {
+            let resp = post(format!("/api/accounts/{}/aggregator_access", account.id))
+                .with_api_headers()
+                .with_request_json(json!({ "aggregator_id": aggregator_id }))
+                .with_state(user.clone())
+                .run_async(&app)
+                .await;
+            assert_response!(resp, StatusCode::BAD_REQUEST);
+            let errors: Value = resp.response_json();
+            assert_eq!(errors["aggregator_id"][0]["code"], code);
+        }
+        assert_eq!(AggregatorAccesses::find().count(app.db()).await?, 0);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn revoke(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (admin, ..) = fixtures::admin(&app).await;
+        let allowlisted = shared_aggregator(&app, Visibility::Allowlist).await;
+        AggregatorAccesses::grant(&allowlisted, &account, app.db()).await?;
+        assert_eq!(listed(&app, &user, &account).await.len(), 1);
+
+        let resp = delete(format!(
+            "/api/aggregators/{}/access/{}",
+            allowlisted.id, account.id
+        ))
+        .with_api_headers()
+        .with_state(admin)
+        .run_async(&app)
+        .await;
+        assert_status!(resp, StatusCode::NO_CONTENT);
+        assert!(listed(&app, &user, &account).await.is_empty());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn grant_as_nonadmin(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let allowlisted = shared_aggregator(&app, Visibility::Allowlist).await;
+
+        let resp = put(format!(
+            "/api/aggregators/{}/access/{}",
+            allowlisted.id, account.id
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await;
+        assert_not_found!(resp);
+        assert_eq!(AggregatorAccesses::find().count(app.db()).await?, 0);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn grant_account_aggregator(app: DivviupApi) -> TestResult {
+        let (admin, account, ..) = fixtures::admin(&app).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+
+        let resp = put(format!(
+            "/api/aggregators/{}/access/{}",
+            aggregator.id, account.id
+        ))
+        .with_api_headers()
+        .with_state(admin)
+        .run_async(&app)
+        .await;
+        assert_response!(resp, StatusCode::BAD_REQUEST);
+        let errors: Value = resp.response_json();
+        assert_eq!(errors["aggregator_id"][0]["code"], "not-shared");
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn update_visibility(app: DivviupApi) -> TestResult {
+        let (admin, account, ..) = fixtures::admin(&app).await;
+        let shared = fixtures::aggregator(&app, None).await;
+        let owned = fixtures::aggregator(&app, Some(&account)).await;
+
+        let resp = patch(format!("/api/aggregators/{}", shared.id))
+            .with_api_headers()
+            .with_request_json(json!({ "visibility": "request_access" }))
+            .with_state(admin.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(
+            resp.response_json::<Value>()["visibility"],
+            "request_access"
+        );
+        assert_eq!(
+            shared.reload(app.db()).await?.unwrap().visibility,
+            Visibility::RequestAccess
+        );
+
+        let resp = patch(format!("/api/aggregators/{}", owned.id))
+            .with_api_headers()
+            .with_request_json(json!({ "visibility": "allowlist" }))
+            .with_state(admin)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, StatusCode::BAD_REQUEST);
+        let errors: Value = resp.response_json();
+        assert_eq!(errors["visibility"][0]["code"], "not-shared");
+        Ok(())
+    }
+}
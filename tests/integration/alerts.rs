@@ -0,0 +1,707 @@
+use divviup_api::{
+    entity::{
+        aggregator::{Feature, Features},
+        AlertChannel, Alerts, TaskCounter, TaskCounterSnapshot, TaskCounterSnapshots,
+    },
+    queue::{EvaluateTaskAlerts, RefreshTaskMetrics, SendAlertEmail, V1},
+};
+use std::collections::BTreeMap;
+use test_support::{assert_eq, test, *};
+use time::Duration;
+use uuid::Uuid;
+
+async fn queued_jobs(app: &DivviupApi) -> Vec<Job> {
+    entity::queue::Entity::find()
+        .all(app.db())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|queue_job| queue_job.job.0)
+        .collect()
+}
+
+async fn set_counters(
+    app: &DivviupApi,
+    task: Task,
+    decrypt_failure: i64,
+    success: i64,
+) -> Result<Task, DbErr> {
+    let mut task = task.into_active_model();
+    task.report_counter_decrypt_failure = ActiveValue::Set(decrypt_failure);
+    task.report_counter_success = ActiveValue::Set(success);
+    task.update(app.db()).await
+}
+
+async fn snapshot(
+    app: &DivviupApi,
+    task: &Task,
+    age: Duration,
+) -> Result<TaskCounterSnapshot, DbErr> {
+    TaskCounterSnapshot {
+        id: Uuid::new_v4(),
+        task_id: task.id.clone(),
+        counters: BTreeMap::from([
+            (
+                TaskCounter::ReportCounterDecryptFailure,
+                task.report_counter_decrypt_failure,
+            ),
+            (
+                TaskCounter::ReportCounterSuccess,
+                task.report_counter_success,
+            ),
+        ])
+        .into(),
+        captured_at: OffsetDateTime::now_utc() - age,
+    }
+    .into_active_model()
+    .insert(app.db())
+    .await
+}
+
+async fn old_task(app: &DivviupApi, account: &Account) -> Result<Task, DbErr> {
+    let mut task = fixtures::task(app, account).await.into_active_model();
+    task.created_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::days(1));
+    task.update(app.db()).await
+}
+
+mod index {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_member(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let account_rule = fixtures::alert_rule(&app, &account, None).await;
+        let task_rule = fixtures::alert_rule(&app, &account, Some(&task)).await;
+        let deleted = fixtures::alert_rule(&app, &account, None).await;
+        deleted.tombstone().update(app.db()).await?;
+        let other_account = fixtures::account(&app).await;
+        fixtures::alert_rule(&app, &other_account, None).await;
+
+        let resp = get(format!("/api/accounts/{}/alert_rules", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let alert_rules: Vec<AlertRule> = resp.response_json();
+        assert_eq!(alert_rules, vec![task_rule, account_rule]);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_token_limited_to_a_task(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let other_task = fixtures::task(&app, &account).await;
+        fixtures::alert_rule(&app, &account, None).await;
+        let task_rule = fixtures::alert_rule(&app, &account, Some(&task)).await;
+        fixtures::alert_rule(&app, &account, Some(&other_task)).await;
+        let (_, token) = fixtures::scoped_api_token(
+            &app,
+            &account,
+            &[ApiTokenScope::Read],
+            Some(vec![task.id.clone()]),
+        )
+        .await;
+
+        let resp = get(format!("/api/accounts/{}/alert_rules", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let alert_rules: Vec<AlertRule> = resp.response_json();
+        assert_eq!(alert_rules, vec![task_rule]);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn not_member(app: DivviupApi) -> TestResult {
+        let user = fixtures::user();
+        let account = fixtures::account(&app).await;
+        let resp = get(format!("/api/accounts/{}/alert_rules", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
+mod create {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn valid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+        let task = fixtures::task(&app, &account).await;
+        let resp = post(format!("/api/accounts/{}/alert_rules", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "name": "decrypt failures",
+                "task_id": task.id,
+                "counter": "report_counter_decrypt_failure",
+                "relative_to": "report_counter_success",
+                "threshold": 0.05
+            }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let alert_rule: AlertRule = resp.response_json();
+        assert_eq!(alert_rule.name.as_deref(), Some("decrypt failures"));
+        assert_eq!(alert_rule.task_id, Some(task.id));
+        assert_eq!(alert_rule.counter, TaskCounter::ReportCounterDecryptFailure);
+        assert_eq!(
+            alert_rule.relative_to,
+            Some(TaskCounter::ReportCounterSuccess)
+        );
+        assert_eq!(alert_rule.threshold, 0.05);
+        assert_eq!(alert_rule.window_seconds, 3600);
+        assert_eq!(*alert_rule.channels, vec![AlertChannel::Email]);
+        assert_eq!(
+            alert_rule.reload(app.db()).await?.unwrap().account_id,
+            account.id
+        );
+
+        let audit_event = AuditEvents::find()
+            .filter(AuditEventColumn::TargetId.eq(alert_rule.id.to_string()))
+            .one(app.db())
+            .await?
+            .unwrap();
+        assert_eq!(audit_event.target_type, AuditTargetType::AlertRule);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn invalid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let resp = post(format!("/api/accounts/{}/alert_rules", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "threshold": -1,
+                "window_seconds": 60,
+                "channels": []
+            }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert!(errors.get("counter").is_some());
+        assert!(errors.get("threshold").is_some());
+        assert!(errors.get("window_seconds").is_some());
+        assert!(errors.get("channels").is_some());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn task_in_other_account(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let other_account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &other_account).await;
+        let resp = post(format!("/api/accounts/{}/alert_rules", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "task_id": task.id,
+                "counter": "report_counter_decrypt_failure",
+                "threshold": 10
+            }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert!(errors.get("task_id").is_some());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_viewer(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Viewer).await;
+        let resp = post(format!("/api/accounts/{}/alert_rules", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "counter": "report_counter_decrypt_failure",
+                "threshold": 10
+            }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
+mod show {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_viewer(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Viewer).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+        let resp = get(format!("/api/alert_rules/{}", alert_rule.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(resp.response_json::<AlertRule>(), alert_rule);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn other_account(app: DivviupApi) -> TestResult {
+        let (user, ..) = fixtures::member(&app).await;
+        let other_account = fixtures::account(&app).await;
+        let alert_rule = fixtures::alert_rule(&app, &other_account, None).await;
+        let resp = get(format!("/api/alert_rules/{}", alert_rule.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
+mod update {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn valid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+        let resp = patch(format!("/api/alert_rules/{}", alert_rule.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "absolute": true,
+                "threshold": 100,
+                "channels": ["webhook", "email"]
+            }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let updated: AlertRule = resp.response_json();
+        assert_eq!(updated.relative_to, None);
+        assert_eq!(updated.threshold, 100.0);
+        assert_eq!(
+            *updated.channels,
+            vec![AlertChannel::Email, AlertChannel::Webhook]
+        );
+        assert_eq!(updated.counter, alert_rule.counter);
+        assert_eq!(updated.reload(app.db()).await?.unwrap(), updated);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_viewer(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Viewer).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+        let resp = patch(format!("/api/alert_rules/{}", alert_rule.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({ "threshold": 100 }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
+mod delete {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_member(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+        let resp = delete(format!("/api/alert_rules/{}", alert_rule.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 204);
+        assert!(alert_rule.reload(app.db()).await?.unwrap().is_tombstoned());
+
+        let resp = get(format!("/api/alert_rules/{}", alert_rule.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_not_found!(resp);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn resolves_firing_alerts(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+        let alert = Alerts::fire(&alert_rule, &task, 0.5, app.db())
+            .await?
+            .unwrap();
+
+        let resp = delete(format!("/api/alert_rules/{}", alert_rule.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 204);
+        assert!(!alert.reload(app.db()).await?.unwrap().is_firing());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn task_resolves_firing_alerts(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+        let alert = Alerts::fire(&alert_rule, &task, 0.5, app.db())
+            .await?
+            .unwrap();
+
+        let resp = delete(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 204);
+        assert!(!alert.reload(app.db()).await?.unwrap().is_firing());
+        assert!(alert_rule
+            .reload(app.db())
+            .await?
+            .unwrap()
+            .deleted_at
+            .is_none());
+        Ok(())
+    }
+}
+
+mod firing {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_viewer(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Viewer).await;
+        let task = fixtures::task(&app, &account).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+        Alerts::fire(&alert_rule, &task, 0.5, app.db())
+            .await?
+            .unwrap()
+            .resolve(0.0, app.db())
+            .await?;
+        let firing = Alerts::fire(&alert_rule, &task, 0.5, app.db())
+            .await?
+            .unwrap();
+
+        let resp = get(format!("/api/accounts/{}/alerts", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(resp.response_json::<Vec<Alert>>(), vec![firing]);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn excludes_deleted_rules_and_tasks(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let deleted_task = fixtures::task(&app, &account).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, None).await;
+        let deleted_rule = fixtures::alert_rule(&app, &account, None).await;
+        let firing = Alerts::fire(&alert_rule, &task, 0.5, app.db())
+            .await?
+            .unwrap();
+        Alerts::fire(&deleted_rule, &task, 0.5, app.db()).await?;
+        Alerts::fire(&alert_rule, &deleted_task, 0.5, app.db()).await?;
+        deleted_rule.tombstone().update(app.db()).await?;
+        let mut deleted_task = deleted_task.into_active_model();
+        deleted_task.deleted_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
+        deleted_task.update(app.db()).await?;
+
+        let resp = get(format!("/api/accounts/{}/alerts", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(resp.response_json::<Vec<Alert>>(), vec![firing]);
+        Ok(())
+    }
+}
+
+mod evaluate {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn fires_and_resolves(app: DivviupApi) -> TestResult {
+        let (_, account, membership) = fixtures::member(&app).await;
+        let task = old_task(&app, &account).await?;
+        let alert_rule = fixtures::alert_rule(&app, &account, Some(&task)).await;
+        let task = set_counters(&app, task, 0, 1000).await?;
+        snapshot(&app, &task, Duration::minutes(90)).await?;
+
+        // 100 decrypt failures for 1000 successes in the last hour is more than 5%
+        let task = set_counters(&app, task, 100, 2000).await?;
+        let mut job = EvaluateTaskAlerts {
+            task_id: task.id.clone(),
+        };
+        assert!(job.perform(&app.config().into(), app.db()).await?.is_none());
+
+        let alert = Alerts::firing(&alert_rule, &task, app.db()).await?.unwrap();
+        assert_eq!(alert.value, 0.1);
+        let jobs = queued_jobs(&app).await;
+        assert_eq!(jobs.len(), 1);
+        let Job::V1(V1::SendAlertEmail(email_job)) = &jobs[0] else {
+            panic!("expected an alert email, found {:?}", jobs[0]);
+        };
+        assert_eq!(email_job.membership_id, membership.id);
+        assert_eq!(email_job.alert_id, alert.id);
+        assert!(!email_job.resolved);
+
+        // evaluating again while the rule is still exceeded does not notify again
+        job.perform(&app.config().into(), app.db()).await?;
+        assert_eq!(queued_jobs(&app).await.len(), 1);
+
+        snapshot(&app, &task, Duration::minutes(70)).await?;
+        let task = set_counters(&app, task, 110, 3000).await?;
+        job.perform(&app.config().into(), app.db()).await?;
+
+        assert!(Alerts::firing(&alert_rule, &task, app.db())
+            .await?
+            .is_none());
+        let alert = alert.reload(app.db()).await?.unwrap();
+        assert!(!alert.is_firing());
+        assert_eq!(alert.value, 0.01);
+        let jobs = queued_jobs(&app).await;
+        assert_eq!(jobs.len(), 2);
+        assert!(matches!(
+            &jobs[1],
+            Job::V1(V1::SendAlertEmail(email_job)) if email_job.resolved
+        ));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn new_task_is_measured_from_zero(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let task = fixtures::task(&app, &account).await;
+        let alert_rule = NewAlertRule {
+            counter: Some(TaskCounter::ReportCounterDecryptFailure),
+            threshold: Some(5.0),
+            channels: Some(vec![AlertChannel::Webhook]),
+            ..Default::default()
+        }
+        .build(&account, app.db())
+        .await?
+        .insert(app.db())
+        .await?;
+        let task = set_counters(&app, task, 10, 0).await?;
+
+        EvaluateTaskAlerts {
+            task_id: task.id.clone(),
+        }
+        .perform(&app.config().into(), app.db())
+        .await?;
+
+        let alert = Alerts::firing(&alert_rule, &task, app.db()).await?.unwrap();
+        assert_eq!(alert.value, 10.0);
+        let jobs = queued_jobs(&app).await;
+        assert_eq!(jobs.len(), 1);
+        let Job::V1(V1::DeliverWebhook(delivery)) = &jobs[0] else {
+            panic!("expected a webhook delivery, found {:?}", jobs[0]);
+        };
+        assert_eq!(delivery.webhook_id, webhook.id);
+        assert_eq!(delivery.event.event_type, WebhookEventType::AlertFired);
+        assert_eq!(delivery.event.data["alert"]["id"], json!(alert.id));
+        assert_eq!(
+            delivery.event.data["alert_rule"]["id"],
+            json!(alert_rule.id)
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn only_rules_for_the_task(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let other_task = fixtures::task(&app, &account).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, Some(&other_task)).await;
+        let task = set_counters(&app, task, 10, 0).await?;
+
+        EvaluateTaskAlerts {
+            task_id: task.id.clone(),
+        }
+        .perform(&app.config().into(), app.db())
+        .await?;
+
+        assert!(Alerts::firing(&alert_rule, &task, app.db())
+            .await?
+            .is_none());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn fires_once_until_resolved(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let alert_rule = fixtures::alert_rule(&app, &account, Some(&task)).await;
+
+        let alert = Alerts::fire(&alert_rule, &task, 0.5, app.db())
+            .await?
+            .unwrap();
+        // an evaluation that raced the first one does not fire a second alert
+        assert!(Alerts::fire(&alert_rule, &task, 0.6, app.db())
+            .await?
+            .is_none());
+        assert_eq!(
+            Alerts::firing(&alert_rule, &task, app.db()).await?,
+            Some(alert.clone())
+        );
+
+        alert.resolve(0.0, app.db()).await?;
+        let refired = Alerts::fire(&alert_rule, &task, 0.7, app.db())
+            .await?
+            .unwrap();
+        assert_eq!(
+            Alerts::firing(&alert_rule, &task, app.db()).await?,
+            Some(refired)
+        );
+        Ok(())
+    }
+
+    #[test(harness = with_client_logs)]
+    async fn after_metrics_refresh(app: DivviupApi, _client_logs: ClientLogs) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let mut task = task.into_active_model();
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::minutes(10));
+        let task = task.update(app.db()).await?;
+        let mut leader = task.leader_aggregator(app.db()).await?.into_active_model();
+        leader.features = ActiveValue::Set(Features::from_iter([Feature::UploadMetrics]).into());
+        leader.update(app.db()).await?;
+
+        // without any alert rules there is nothing to evaluate, but the counters are still
+        // snapshotted
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert!(queued_jobs(&app).await.is_empty());
+        assert_eq!(
+            TaskCounterSnapshots::find()
+                .filter(TaskCounterSnapshotColumn::TaskId.eq(&task.id))
+                .count(app.db())
+                .await?,
+            1
+        );
+
+        fixtures::alert_rule(&app, &account, None).await;
+        let mut task = task.into_active_model();
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::minutes(10));
+        let task = task.update(app.db()).await?;
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(
+            queued_jobs(&app).await,
+            vec![Job::from(EvaluateTaskAlerts {
+                task_id: task.id.clone()
+            })]
+        );
+        Ok(())
+    }
+}
+
+#[test(harness = set_up)]
+async fn scheduled_metrics_refresh(app: DivviupApi) -> TestResult {
+    let account = fixtures::account(&app).await;
+    let stale_at = OffsetDateTime::now_utc() - Duration::minutes(10);
+    let mut tasks = vec![];
+    for _ in 0..4 {
+        let mut task = fixtures::task(&app, &account).await.into_active_model();
+        task.updated_at = ActiveValue::Set(stale_at);
+        tasks.push(task.update(app.db()).await?);
+    }
+    let [stale, fresh, expired, deleted] = &tasks[..] else {
+        unreachable!()
+    };
+    let mut fresh = fresh.clone().into_active_model();
+    fresh.updated_at = ActiveValue::Set(OffsetDateTime::now_utc());
+    fresh.update(app.db()).await?;
+    let mut expired = expired.clone().into_active_model();
+    expired.expiration = ActiveValue::Set(Some(stale_at));
+    expired.update(app.db()).await?;
+    let mut deleted = deleted.clone().into_active_model();
+    deleted.deleted_at = ActiveValue::Set(Some(stale_at));
+    deleted.update(app.db()).await?;
+    // tasks in accounts without alert rules are not refreshed
+    let other_account = fixtures::account(&app).await;
+    let mut untracked = fixtures::task(&app, &other_account)
+        .await
+        .into_active_model();
+    untracked.updated_at = ActiveValue::Set(stale_at);
+    untracked.update(app.db()).await?;
+    fixtures::alert_rule(&app, &account, None).await;
+
+    let next = RefreshTaskMetrics
+        .perform(&app.config().into(), app.db())
+        .await?
+        .unwrap();
+    assert_eq!(next.job, RefreshTaskMetrics);
+    assert_eq!(
+        queued_jobs(&app).await,
+        vec![Job::from(EvaluateTaskAlerts {
+            task_id: stale.id.clone()
+        })]
+    );
+    assert_eq!(TaskCounterSnapshots::find().all(app.db()).await?.len(), 1);
+    assert!(stale.clone().reload(app.db()).await?.unwrap().updated_at > stale_at);
+    Ok(())
+}
+
+#[test(harness = with_client_logs)]
+async fn send_alert_email(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+    let (_, account, membership) = fixtures::member(&app).await;
+    let task = fixtures::task(&app, &account).await;
+    let alert_rule = fixtures::alert_rule(&app, &account, Some(&task)).await;
+    let alert = Alerts::fire(&alert_rule, &task, 0.5, app.db())
+        .await?
+        .unwrap();
+
+    let mut job = SendAlertEmail {
+        membership_id: membership.id,
+        alert_id: alert.id,
+        resolved: false,
+        message_id: Uuid::new_v4(),
+    };
+    assert!(job.perform(&app.config().into(), app.db()).await?.is_none());
+
+    let email = client_logs.last();
+    assert_eq!(
+        email.url,
+        app.config().postmark_url.join("/email/withTemplate")?
+    );
+    let body: Value = email.request_json();
+    assert_eq!(body["TemplateAlias"], "alert-fired");
+    assert_eq!(body["To"], membership.user_email);
+    assert_eq!(body["TemplateModel"]["task_id"], json!(task.id));
+    assert_eq!(
+        body["TemplateModel"]["counter"],
+        "report_counter_decrypt_failure"
+    );
+    assert_eq!(body["TemplateModel"]["value"], 0.5);
+    Ok(())
+}
@@ -167,6 +167,42 @@ mod create {
         Ok(())
     }
 
+    #[test(harness = set_up)]
+    async fn without_admin_role(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert_eq!(ApiTokens::find().count(app.db()).await?, 0);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn unscoped_with_admin_role(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Admin).await;
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_request_json(json!({ "scopes": ["tasks:write"] }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        assert_eq!(ApiTokens::find().count(app.db()).await?, 1);
+        Ok(())
+    }
+
     #[test(harness = set_up)]
     async fn not_member(app: DivviupApi) -> TestResult {
         let user = fixtures::user();
@@ -293,6 +329,604 @@ mod create {
         assert_eq!(count_before, count_after);
         Ok(())
     }
+
+    #[test(harness = set_up)]
+    async fn with_scopes(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "scopes": ["tasks:write", "read", "read"],
+                "task_ids": [task.id]
+            }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let response: Value = resp.response_json();
+        assert_eq!(response["scopes"], json!(["read", "tasks:write"]));
+        assert_eq!(response["task_ids"], json!([task.id]));
+
+        let api_token: ApiToken = serde_json::from_value(response)?;
+        let api_token = api_token.reload(app.db()).await?.unwrap();
+        assert!(api_token.is_scoped());
+        assert!(api_token.has_scope(ApiTokenScope::TasksWrite));
+        assert!(!api_token.has_scope(ApiTokenScope::AggregatorsWrite));
+        assert!(api_token.allows_task(&task.id));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn invalid_scopes(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let other_account = fixtures::account(&app).await;
+        let other_task = fixtures::task(&app, &other_account).await;
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({ "task_ids": [other_task.id] }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert!(errors.get("scopes").is_some());
+        assert!(errors.get("task_ids").is_some());
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({ "scopes": [] }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert!(errors.get("scopes").is_some());
+
+        assert_eq!(ApiTokens::find().count(app.db()).await?, 0);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn with_name(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({ "name": "ci deploys" }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let api_token: ApiToken = resp.response_json();
+        assert_eq!(api_token.name.as_deref(), Some("ci deploys"));
+        assert_eq!(
+            api_token.reload(app.db()).await?.unwrap().name.as_deref(),
+            Some("ci deploys")
+        );
+        assert!(!api_token.is_scoped());
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({ "name": "" }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let api_token: ApiToken = resp.response_json();
+        assert_eq!(api_token.name, None);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn with_expiration(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let expires_at = (OffsetDateTime::now_utc() + time::Duration::days(90))
+            .replace_nanosecond(0)
+            .unwrap();
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({
+                "expires_at": expires_at.format(&time::format_description::well_known::Rfc3339)?
+            }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let api_token: ApiToken = resp.response_json();
+        assert_eq!(api_token.expires_at, Some(expires_at));
+        assert_eq!(
+            api_token.reload(app.db()).await?.unwrap().expires_at,
+            Some(expires_at)
+        );
+
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({ "expires_at": "2020-01-01T00:00:00Z" }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert!(errors.get("expires_at").is_some());
+        assert_eq!(ApiTokens::find().count(app.db()).await?, 1);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn scoped_token(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (_, token) = fixtures::scoped_api_token(
+            &app,
+            &account,
+            &[ApiTokenScope::TasksWrite, ApiTokenScope::AggregatorsWrite],
+            None,
+        )
+        .await;
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert_eq!(ApiTokens::find().count(app.db()).await?, 1);
+        Ok(())
+    }
+}
+
+mod expiration {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn expired_token_is_rejected(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (api_token, token) = fixtures::api_token(&app, &account).await;
+
+        let mut active_model = api_token.into_active_model();
+        active_model.expires_at =
+            ActiveValue::Set(Some(OffsetDateTime::now_utc() + time::Duration::hours(1)));
+        let api_token = active_model.update(app.db()).await?;
+
+        let resp = get(format!("/api/accounts/{}", account.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let mut active_model = api_token.into_active_model();
+        active_model.expires_at =
+            ActiveValue::Set(Some(OffsetDateTime::now_utc() - time::Duration::hours(1)));
+        let api_token = active_model.update(app.db()).await?;
+        assert!(api_token.is_expired());
+
+        let resp = get(format!("/api/accounts/{}", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
+mod scopes {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn read_only(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let (_, token) =
+            fixtures::scoped_api_token(&app, &account, &[ApiTokenScope::Read], None).await;
+
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let resp = patch(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .with_request_json(json!({ "name": "new name" }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+
+        let resp = delete(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert!(task.reload(app.db()).await?.unwrap().deleted_at.is_none());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn tasks_write(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let (_, token) =
+            fixtures::scoped_api_token(&app, &account, &[ApiTokenScope::TasksWrite], None).await;
+
+        let resp = patch(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .with_request_json(json!({ "name": "new name" }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert_eq!(task.reload(app.db()).await?.unwrap().name, "new name");
+
+        let resp = patch(format!("/api/aggregators/{}", aggregator.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .with_request_json(json!({ "name": "new name" }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+
+        let resp = post(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .with_request_json(fixtures::new_aggregator())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+
+        let resp = patch(format!("/api/accounts/{}", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .with_request_json(json!({ "name": "new name" }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn limited_to_tasks(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let other_task = fixtures::task(&app, &account).await;
+        let (_, token) = fixtures::scoped_api_token(
+            &app,
+            &account,
+            &[ApiTokenScope::TasksWrite],
+            Some(vec![task.id.clone()]),
+        )
+        .await;
+
+        let resp = get(format!("/api/accounts/{}/tasks", account.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let tasks: Vec<Task> = resp.response_json();
+        assert_eq!(
+            tasks.into_iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![task.id.clone()]
+        );
+
+        let resp = get(format!("/api/tasks/{}", other_task.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+
+        let resp = patch(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .with_request_json(json!({ "name": "new name" }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let resp = post(format!("/api/accounts/{}/tasks", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .with_request_json(json!({}))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn limited_to_tasks_cannot_read_account_resources(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let collector_credential = fixtures::collector_credential(&app, &account).await;
+        let aggregator = fixtures::aggregator(&app, Some(&account)).await;
+        let (_, token) = fixtures::scoped_api_token(
+            &app,
+            &account,
+            &[ApiTokenScope::Read],
+            Some(vec![task.id.clone()]),
+        )
+        .await;
+
+        for path in [
+            format!("/api/accounts/{}/collector_credentials", account.id),
+            format!("/api/collector_credentials/{}", collector_credential.id),
+            format!("/api/accounts/{}/aggregators", account.id),
+            format!("/api/aggregators/{}", aggregator.id),
+            String::from("/api/aggregators"),
+            format!("/api/accounts/{}/memberships", account.id),
+        ] {
+            let resp = get(&path)
+                .with_api_headers()
+                .with_auth_header(token.clone())
+                .run_async(&app)
+                .await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{path}");
+        }
+
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn without_read_scope(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let collector_credential = fixtures::collector_credential(&app, &account).await;
+        let (_, token) =
+            fixtures::scoped_api_token(&app, &account, &[ApiTokenScope::AggregatorsWrite], None)
+                .await;
+
+        for path in [
+            format!("/api/accounts/{}/tasks", account.id),
+            format!("/api/tasks/{}", task.id),
+            format!("/api/accounts/{}/collector_credentials", account.id),
+            format!("/api/collector_credentials/{}", collector_credential.id),
+            format!("/api/accounts/{}/memberships", account.id),
+        ] {
+            let resp = get(&path)
+                .with_api_headers()
+                .with_auth_header(token.clone())
+                .run_async(&app)
+                .await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{path}");
+        }
+
+        // the aggregators it can write are still readable
+        let resp = get(format!("/api/accounts/{}/aggregators", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn admin_account_scoped_token_is_not_admin(app: DivviupApi) -> TestResult {
+        let admin_account = fixtures::admin_account(&app).await;
+        let account = fixtures::account(&app).await;
+        let (_, token) =
+            fixtures::scoped_api_token(&app, &admin_account, &[ApiTokenScope::Read], None).await;
+
+        let resp = get(format!("/api/accounts/{}", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
+mod usage {
+    use super::{assert_eq, test, *};
+    use divviup_api::{
+        entity::api_token_usage::{hour_of, PendingUsage, RETENTION},
+        queue::QueueCleanup,
+    };
+    use time::Duration;
+
+    #[test(harness = set_up)]
+    async fn records_requests(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (api_token, token) = fixtures::api_token(&app, &account).await;
+
+        for source_ip in ["192.0.2.1", "192.0.2.1", "198.51.100.7"] {
+            let resp = get(format!("/api/accounts/{}", account.id))
+                .with_api_headers()
+                .with_auth_header(token.clone())
+                .with_request_header("x-forwarded-for", format!("10.0.0.1, {source_ip}"))
+                .run_async(&app)
+                .await;
+            assert_ok!(resp);
+        }
+
+        // nothing is written until the buffer is flushed
+        assert!(ApiTokenUsages::find().all(app.db()).await?.is_empty());
+        assert!(api_token
+            .reload(app.db())
+            .await?
+            .unwrap()
+            .last_used_at
+            .is_none());
+
+        let resp = get(format!("/api/api_tokens/{}/usage", api_token.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let usage: Vec<ApiTokenUsage> = resp.response_json();
+        assert_eq!(usage.len(), 1);
+        let usage = &usage[0];
+        assert_eq!(usage.api_token_id, api_token.id);
+        assert_eq!(usage.request_count, 4);
+        assert_eq!(usage.routes.get("GET /api/accounts/{account_id}"), Some(&3));
+        assert_eq!(
+            usage.routes.get("GET /api/api_tokens/{api_token_id}/usage"),
+            Some(&1)
+        );
+        assert_eq!(usage.source_ips.get("192.0.2.1"), Some(&2));
+        assert_eq!(usage.source_ips.get("198.51.100.7"), Some(&1));
+        assert!(!usage.source_ips.contains_key("10.0.0.1"));
+
+        // reading usage does not write it
+        assert!(ApiTokenUsages::find().all(app.db()).await?.is_empty());
+
+        app.api_token_usage().flush(app.db()).await;
+        let written = ApiTokenUsages::find().all(app.db()).await?;
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].id, usage.id);
+        assert_eq!(written[0].request_count, 4);
+        let last_used_at = api_token.reload(app.db()).await?.unwrap().last_used_at;
+        assert_eq!(last_used_at, Some(usage.last_used_at));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn records_peer_address_without_forwarded_for(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (api_token, token) = fixtures::api_token(&app, &account).await;
+
+        let resp = get(format!("/api/accounts/{}", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .with_peer_address("203.0.113.9:52100".parse()?)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        app.api_token_usage().flush(app.db()).await;
+        let written = ApiTokenUsages::find().all(app.db()).await?;
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].api_token_id, api_token.id);
+        assert_eq!(written[0].source_ips.get("203.0.113.9"), Some(&1));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn expired_usage_is_cleaned_up(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (api_token, _) = fixtures::api_token(&app, &account).await;
+        let now = OffsetDateTime::now_utc();
+        let current = hour_of(now);
+        let expired = hour_of(now - RETENTION - Duration::hours(1));
+        for hour in [current, expired] {
+            ApiTokenUsages::add(api_token.id, hour, PendingUsage::new(hour), app.db()).await?;
+        }
+
+        QueueCleanup.perform(&app.config().into(), app.db()).await?;
+        let remaining = ApiTokenUsages::find().all(app.db()).await?;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].hour, current);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn flushes_accumulate(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (api_token, token) = fixtures::api_token(&app, &account).await;
+
+        for _ in 0..2 {
+            let resp = get(format!("/api/accounts/{}", account.id))
+                .with_api_headers()
+                .with_auth_header(token.clone())
+                .run_async(&app)
+                .await;
+            assert_ok!(resp);
+            app.api_token_usage().flush(app.db()).await;
+        }
+
+        let usage = ApiTokenUsages::find().all(app.db()).await?;
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].api_token_id, api_token.id);
+        assert_eq!(usage[0].request_count, 2);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn includes_pending_usage_with_written(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (api_token, token) = fixtures::api_token(&app, &account).await;
+
+        let resp = get(format!("/api/accounts/{}", account.id))
+            .with_api_headers()
+            .with_auth_header(token.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        app.api_token_usage().flush(app.db()).await;
+
+        let resp = get(format!("/api/api_tokens/{}/usage", api_token.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let usage: Vec<ApiTokenUsage> = resp.response_json();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].request_count, 2);
+        assert_eq!(
+            ApiTokenUsages::find().all(app.db()).await?[0].request_count,
+            1
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_member(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (api_token, _) = fixtures::api_token(&app, &account).await;
+        let resp = get(format!("/api/api_tokens/{}/usage", api_token.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let usage: Vec<ApiTokenUsage> = resp.response_json();
+        assert!(usage.is_empty());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn without_admin_role(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+        let (api_token, _) = fixtures::api_token(&app, &account).await;
+        let resp = get(format!("/api/api_tokens/{}/usage", api_token.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn non_member(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (user, ..) = fixtures::member(&app).await;
+        let (api_token, _) = fixtures::api_token(&app, &account).await;
+        let resp = get(format!("/api/api_tokens/{}/usage", api_token.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
 }
 
 mod delete {
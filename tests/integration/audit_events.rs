@@ -0,0 +1,312 @@
+use test_support::{assert_eq, test, *};
+
+mod recording {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn task_update_by_user(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let new_name = fixtures::random_name();
+        let resp = patch(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_request_json(json!({ "name": &new_name }))
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let events = AuditEvents::find().all(app.db()).await?;
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.account_id, Some(account.id));
+        assert_eq!(event.actor_email.as_deref(), Some(user.email.as_str()));
+        assert_eq!(event.actor_api_token_id, None);
+        assert_eq!(event.action, AuditAction::Update);
+        assert_eq!(event.target_type, AuditTargetType::Task);
+        assert_eq!(event.target_id, task.id);
+        assert_eq!(
+            event.changes.get("name"),
+            Some(&AuditChange {
+                before: json!(task.name),
+                after: json!(new_name)
+            })
+        );
+        assert!(!event.changes.contains_key("id"));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn api_token_create_by_token(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (actor_token, header) = fixtures::api_token(&app, &account).await;
+        let resp = post(format!("/api/accounts/{}/api_tokens", account.id))
+            .with_api_headers()
+            .with_auth_header(header)
+            .run_async(&app)
+            .await;
+        assert_status!(resp, 201);
+        let created: ApiToken = resp.response_json();
+
+        let event = AuditEvents::find()
+            .filter(AuditEventColumn::TargetId.eq(created.id.to_string()))
+            .one(app.db())
+            .await?
+            .unwrap();
+        assert_eq!(event.actor_email, None);
+        assert_eq!(event.actor_api_token_id, Some(actor_token.id));
+        assert_eq!(event.action, AuditAction::Create);
+        assert_eq!(event.target_type, AuditTargetType::ApiToken);
+        assert_eq!(event.changes["id"].before, Value::Null);
+        assert_eq!(event.changes["id"].after, json!(created.id));
+        // secrets are never recorded
+        assert!(!event.changes.contains_key("token"));
+        assert!(!event.changes.contains_key("token_hash"));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn membership_delete(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let other = fixtures::membership(&app, &account, &fixtures::user()).await;
+        let resp = delete(format!("/api/memberships/{}", other.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_status!(resp, 204);
+
+        let events = AuditEvents::find().all(app.db()).await?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, AuditAction::Delete);
+        assert_eq!(events[0].target_type, AuditTargetType::Membership);
+        assert_eq!(events[0].target_id, other.id.to_string());
+        assert_eq!(
+            events[0].changes.get("user_email"),
+            Some(&AuditChange {
+                before: json!(other.user_email),
+                after: Value::Null
+            })
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn rejected_change_is_not_recorded(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Viewer).await;
+        let task = fixtures::task(&app, &account).await;
+        let resp = delete(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert!(AuditEvents::find().all(app.db()).await?.is_empty());
+        Ok(())
+    }
+}
+
+mod index {
+    use super::{assert_eq, test, *};
+
+    async fn rename_task(app: &DivviupApi, user: &User, task: &Task) {
+        let resp = patch(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_request_json(json!({ "name": fixtures::random_name() }))
+            .with_state(user.clone())
+            .run_async(app)
+            .await;
+        assert_ok!(resp);
+    }
+
+    #[test(harness = set_up)]
+    async fn as_owner(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        rename_task(&app, &user, &task).await;
+        rename_task(&app, &user, &task).await;
+
+        let (other_user, other_account, ..) = fixtures::member(&app).await;
+        let other_task = fixtures::task(&app, &other_account).await;
+        rename_task(&app, &other_user, &other_task).await;
+
+        let resp = get(format!("/api/accounts/{}/audit_events", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let events: Vec<AuditEvent> = resp.response_json();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event.target_id == task.id));
+        assert!(events[0].created_at >= events[1].created_at);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn filtering(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let other_task = fixtures::task(&app, &account).await;
+        rename_task(&app, &user, &task).await;
+        rename_task(&app, &user, &other_task).await;
+        let resp = delete(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_status!(resp, 204);
+
+        let events: Vec<AuditEvent> = get(format!(
+            "/api/accounts/{}/audit_events?action=delete",
+            account.id
+        ))
+        .with_api_headers()
+        .with_state(user.clone())
+        .run_async(&app)
+        .await
+        .response_json();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].target_id, task.id);
+
+        let events: Vec<AuditEvent> = get(format!(
+            "/api/accounts/{}/audit_events?target_type=task&target_id={}",
+            account.id, other_task.id
+        ))
+        .with_api_headers()
+        .with_state(user.clone())
+        .run_async(&app)
+        .await
+        .response_json();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, AuditAction::Update);
+
+        let events: Vec<AuditEvent> = get(format!(
+            "/api/accounts/{}/audit_events?actor={}&limit=2",
+            account.id, user.email
+        ))
+        .with_api_headers()
+        .with_state(user.clone())
+        .run_async(&app)
+        .await
+        .response_json();
+        assert_eq!(events.len(), 2);
+
+        let events: Vec<AuditEvent> = get(format!(
+            "/api/accounts/{}/audit_events?actor={}",
+            account.id,
+            fixtures::random_email()
+        ))
+        .with_api_headers()
+        .with_state(user)
+        .run_async(&app)
+        .await
+        .response_json();
+        assert!(events.is_empty());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn without_admin_role(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+        let resp = get(format!("/api/accounts/{}/audit_events", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn not_member(app: DivviupApi) -> TestResult {
+        let (user, ..) = fixtures::member(&app).await;
+        let account = fixtures::account(&app).await;
+        let resp = get(format!("/api/accounts/{}/audit_events", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn admin_not_member(app: DivviupApi) -> TestResult {
+        let (admin, ..) = fixtures::admin(&app).await;
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        rename_task(&app, &user, &task).await;
+
+        let resp = get(format!("/api/accounts/{}/audit_events", account.id))
+            .with_api_headers()
+            .with_state(admin)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let events: Vec<AuditEvent> = resp.response_json();
+        assert_eq!(events.len(), 1);
+        Ok(())
+    }
+}
+
+mod admin_index {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_an_admin(app: DivviupApi) -> TestResult {
+        let (admin, ..) = fixtures::admin(&app).await;
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let resp = patch(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_request_json(json!({ "name": fixtures::random_name() }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let resp = post("/api/aggregators")
+            .with_api_headers()
+            .with_request_json(fixtures::new_aggregator())
+            .with_state(admin.clone())
+            .run_async(&app)
+            .await;
+        assert_status!(resp, 201);
+
+        let events: Vec<AuditEvent> = get("/api/admin/audit_events")
+            .with_api_headers()
+            .with_state(admin.clone())
+            .run_async(&app)
+            .await
+            .response_json();
+        assert_eq!(events.len(), 2);
+        // shared aggregators do not belong to any account
+        assert_eq!(events[0].target_type, AuditTargetType::Aggregator);
+        assert_eq!(events[0].account_id, None);
+        assert_eq!(events[0].actor_email.as_deref(), Some(admin.email.as_str()));
+
+        let events: Vec<AuditEvent> =
+            get(format!("/api/admin/audit_events?account_id={}", account.id))
+                .with_api_headers()
+                .with_state(admin)
+                .run_async(&app)
+                .await
+                .response_json();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].target_id, task.id);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_a_non_admin(app: DivviupApi) -> TestResult {
+        let (user, ..) = fixtures::member(&app).await;
+        let resp = get("/api/admin/audit_events")
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_status!(resp, 404);
+        Ok(())
+    }
+}
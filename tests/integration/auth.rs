@@ -10,6 +10,7 @@ async fn first_use_of_a_token_updates_last_used_at(app: DivviupApi) -> TestResul
         .with_auth_header(token)
         .run_async(&app)
         .await;
+    app.api_token_usage().flush(app.db()).await;
     assert!(api_token
         .reload(app.db())
         .await?
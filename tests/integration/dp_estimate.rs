@@ -0,0 +1,230 @@
+use divviup_api::entity::task::DpEstimate;
+use test_support::{assert_eq, test, *};
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-3,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test(harness = set_up)]
+async fn histogram(app: DivviupApi) -> TestResult {
+    let (user, ..) = fixtures::member(&app).await;
+    let resp = post("/api/dp_estimate")
+        .with_api_headers()
+        .with_state(user)
+        .with_request_json(json!({
+            "vdaf": {
+                "type": "histogram",
+                "length": 4,
+                "dp_strategy": {
+                    "dp_strategy": "PureDpDiscreteLaplace",
+                    "budget": {"epsilon": [[1], [1]]}
+                }
+            },
+            "min_batch_size": 100
+        }))
+        .run_async(&app)
+        .await;
+    assert_ok!(resp);
+    let estimate: DpEstimate = resp.response_json();
+    assert_eq!(estimate.epsilon, Some(1.0));
+    assert_eq!(estimate.sensitivity, 2);
+    assert_close(estimate.noise_scale, 2.0);
+    assert_close(estimate.standard_deviation, 3.958);
+    assert_close(estimate.relative_error_at_min_batch_size, 0.03958);
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn sum_vec(app: DivviupApi) -> TestResult {
+    let (user, ..) = fixtures::member(&app).await;
+    let resp = post("/api/dp_estimate")
+        .with_api_headers()
+        .with_state(user)
+        .with_request_json(json!({
+            "vdaf": {
+                "type": "sum_vec",
+                "bits": 2,
+                "length": 3,
+                "dp_strategy": {
+                    "dp_strategy": "PureDpDiscreteLaplace",
+                    "budget": {"epsilon": [[1], [2]]}
+                }
+            },
+            "min_batch_size": 1000
+        }))
+        .run_async(&app)
+        .await;
+    assert_ok!(resp);
+    let estimate: DpEstimate = resp.response_json();
+    assert_eq!(estimate.epsilon, Some(0.5));
+    assert_eq!(estimate.sensitivity, 9);
+    assert_close(estimate.noise_scale, 18.0);
+    assert_close(estimate.standard_deviation, 35.995);
+    assert_close(estimate.relative_error_at_min_batch_size, 0.011998);
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn count_and_sum(app: DivviupApi) -> TestResult {
+    let (user, ..) = fixtures::member(&app).await;
+    let dp_strategy = json!({
+        "dp_strategy": "PureDpDiscreteLaplace",
+        "budget": {"epsilon": [[1], [1]]}
+    });
+    for (vdaf, sensitivity, noise_scale, standard_deviation, relative_error) in [
+        (
+            json!({"type": "count", "dp_strategy": dp_strategy}),
+            1,
+            1.0,
+            1.919,
+            0.01919,
+        ),
+        (
+            json!({"type": "sum", "bits": 2, "dp_strategy": dp_strategy}),
+            3,
+            3.0,
+            5.972,
+            0.01991,
+        ),
+    ] {
+        let resp = post("/api/dp_estimate")
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({"vdaf": vdaf, "min_batch_size": 100}))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let estimate: DpEstimate = resp.response_json();
+        assert_eq!(estimate.sensitivity, sensitivity, "{vdaf}");
+        assert_close(estimate.noise_scale, noise_scale);
+        assert_close(estimate.standard_deviation, standard_deviation);
+        assert_close(estimate.relative_error_at_min_batch_size, relative_error);
+    }
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn readable_epsilon(app: DivviupApi) -> TestResult {
+    let (user, ..) = fixtures::member(&app).await;
+    for epsilon in [
+        json!("0.5"),
+        json!(0.5),
+        json!("1/2"),
+        json!({"numerator": 1, "denominator": "2"}),
+    ] {
+        let resp = post("/api/dp_estimate")
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({
+                "vdaf": {
+                    "type": "histogram",
+                    "length": 4,
+                    "dp_strategy": {
+                        "dp_strategy": "PureDpDiscreteLaplace",
+                        "budget": {"epsilon": epsilon}
+                    }
+                },
+                "min_batch_size": 100
+            }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let estimate: DpEstimate = resp.response_json();
+        assert_eq!(estimate.epsilon, Some(0.5), "{epsilon}");
+    }
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn no_differential_privacy(app: DivviupApi) -> TestResult {
+    let (user, ..) = fixtures::member(&app).await;
+    let resp = post("/api/dp_estimate")
+        .with_api_headers()
+        .with_state(user)
+        .with_request_json(json!({
+            "vdaf": {"type": "histogram", "buckets": ["a", "b"]},
+            "min_batch_size": 100
+        }))
+        .run_async(&app)
+        .await;
+    assert_ok!(resp);
+    let estimate: DpEstimate = resp.response_json();
+    assert_eq!(estimate.epsilon, None);
+    assert_eq!(estimate.standard_deviation, 0.0);
+    assert_eq!(estimate.relative_error_at_min_batch_size, 0.0);
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn invalid(app: DivviupApi) -> TestResult {
+    let (user, ..) = fixtures::member(&app).await;
+    let scenarios = [
+        (
+            json!({
+                "vdaf": {
+                    "type": "histogram",
+                    "length": 4,
+                    "dp_strategy": {"dp_strategy": "PureDpDiscreteLaplace"}
+                },
+                "min_batch_size": 100
+            }),
+            ("/vdaf/dp_strategy/0/code", "missing_epsilon"),
+        ),
+        (
+            json!({
+                "vdaf": {
+                    "type": "histogram",
+                    "length": 4,
+                    "dp_strategy": {
+                        "dp_strategy": "PureDpDiscreteLaplace",
+                        "budget": {"epsilon": [[0], [1]]}
+                    }
+                },
+                "min_batch_size": 100
+            }),
+            ("/vdaf/0/code", "invalid_epsilon"),
+        ),
+        (
+            json!({"vdaf": {"type": "count_vec", "length": 4}, "min_batch_size": 100}),
+            ("/vdaf/0/code", "dp_not_supported"),
+        ),
+        (
+            json!({"vdaf": {"type": "histogram", "length": 4}, "min_batch_size": 10}),
+            ("/min_batch_size/0/code", "range"),
+        ),
+    ];
+
+    for (request, (pointer, code)) in scenarios {
+        let resp = post("/api/dp_estimate")
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(request.clone())
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert_eq!(
+            errors.pointer(pointer),
+            Some(&json!(code)),
+            "{request} {errors}"
+        );
+    }
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn not_logged_in(app: DivviupApi) -> TestResult {
+    let resp = post("/api/dp_estimate")
+        .with_api_headers()
+        .with_request_json(json!({
+            "vdaf": {"type": "histogram", "length": 4},
+            "min_batch_size": 100
+        }))
+        .run_async(&app)
+        .await;
+    assert_response!(resp, 403);
+    Ok(())
+}
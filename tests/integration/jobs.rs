@@ -1,8 +1,22 @@
+use axum::{routing::get, Json, Router};
 use divviup_api::{
-    entity::queue::Entity,
-    queue::{CreateUser, JobStatus, ResetPassword, SendInvitationEmail, V1},
+    clients::aggregator_client::api_types::{AggregatorApiConfig, TaskId},
+    entity::{
+        aggregator::{Feature, Features, VdafName},
+        queue::Entity,
+    },
+    queue::{
+        CreateUser, ExpireAggregatorTasks, ExpireApiTokens, JobStatus,
+        RefreshAggregatorCapabilities, RefreshAggregatorHpkeConfigs, ResetPassword,
+        SendApiTokenExpirationEmail, SendDecommissionEmail, SendInvitationEmail, SunsetAggregator,
+        V1,
+    },
 };
+use rand::random;
+use sea_orm::TransactionTrait;
+use std::collections::HashSet;
 use test_support::{assert_eq, test, *};
+use time::Duration;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
@@ -121,6 +135,89 @@ async fn all_together(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
     Ok(())
 }
 
+#[test(harness = with_client_logs)]
+async fn refresh_aggregator_capabilities_unchanged(
+    app: DivviupApi,
+    client_logs: ClientLogs,
+) -> TestResult {
+    let aggregator = fixtures::aggregator(&app, None).await;
+    let next = RefreshAggregatorCapabilities
+        .perform(&app.config().into(), app.db())
+        .await?
+        .unwrap();
+    assert_eq!(next.job, RefreshAggregatorCapabilities);
+    assert!(next.scheduled.unwrap() > OffsetDateTime::now_utc());
+
+    assert!(!client_logs
+        .matching_url(aggregator.api_url.clone().into())
+        .is_empty());
+    assert_eq!(aggregator.reload(app.db()).await?.unwrap(), aggregator);
+    assert_eq!(
+        AggregatorCapabilityChanges::find().count(app.db()).await?,
+        0
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn refresh_aggregator_capabilities_removed() -> TestResult {
+    let mock = Router::new().route(
+        "/",
+        get(|| async {
+            Json(AggregatorApiConfig {
+                dap_url: "https://dap.example".parse().unwrap(),
+                role: Role::Either,
+                vdafs: [VdafName::Prio3Sum, VdafName::Prio3Histogram]
+                    .into_iter()
+                    .collect(),
+                query_types: Default::default(),
+                protocol: Protocol::Dap09,
+                features: Features::from_iter([Feature::TokenHash, Feature::AggregationJobMetrics]),
+            })
+        }),
+    );
+    let (app, _) = build_test_app_with_mock(mock).await;
+    let (_, account, membership) = fixtures::member(&app).await;
+    let task = fixtures::task(&app, &account).await;
+    let leader = task.leader_aggregator(app.db()).await?;
+
+    RefreshAggregatorCapabilities
+        .perform(&app.config().into(), app.db())
+        .await?;
+
+    let leader = leader.reload(app.db()).await?.unwrap();
+    assert!(!leader.vdafs.contains(&VdafName::Prio3Count));
+    assert!(leader.features.aggregation_job_metrics_enabled());
+
+    let changes = AggregatorCapabilityChanges::find()
+        .filter(AggregatorCapabilityChangeColumn::AggregatorId.eq(leader.id))
+        .all(app.db())
+        .await?;
+    let [change] = &changes[..] else {
+        panic!("expected exactly one capability change");
+    };
+    let removed = change.removed();
+    assert!(removed.vdafs.contains(&VdafName::Prio3Count));
+    assert!(removed.features.is_empty());
+
+    let email_jobs = Entity::find()
+        .all(app.db())
+        .await?
+        .into_iter()
+        .filter_map(|queue_job| match queue_job.job.0 {
+            Job::V1(V1::SendCapabilityRemovedEmail(job)) => Some(job),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(email_jobs
+        .iter()
+        .any(|job| job.capability_change_id == change.id
+            && job.membership_id == membership.id
+            && job.task_ids == [task.id.clone()]));
+
+    Ok(())
+}
+
 #[test]
 fn json_representations() {
     let membership_id = Uuid::new_v4();
@@ -167,3 +264,305 @@ fn json_representations() {
         })
     );
 }
+
+#[test(harness = with_client_logs)]
+async fn refresh_aggregator_hpke_configs(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+    let aggregator = fixtures::aggregator(&app, None).await;
+    let next = RefreshAggregatorHpkeConfigs
+        .perform(&app.config().into(), app.db())
+        .await?
+        .unwrap();
+    assert_eq!(next.job, RefreshAggregatorHpkeConfigs);
+    assert!(next.scheduled.unwrap() > OffsetDateTime::now_utc());
+
+    let hpke_config_url = Url::from(aggregator.dap_url.clone()).join("hpke_config")?;
+    assert_eq!(client_logs.matching_url(hpke_config_url).len(), 1);
+    let aggregator = aggregator.reload(app.db()).await?.unwrap();
+    assert_eq!(aggregator.hpke_config_ids.unwrap().len(), 2);
+    assert!(aggregator.hpke_config_error.is_none());
+    assert!(aggregator.hpke_configs_checked_at.is_some());
+    Ok(())
+}
+
+#[test(harness = with_client_logs)]
+async fn refresh_aggregator_hpke_configs_from_queue(
+    app: DivviupApi,
+    client_logs: ClientLogs,
+) -> TestResult {
+    let aggregator = fixtures::aggregator(&app, None).await;
+    let queue_job = Job::from(RefreshAggregatorHpkeConfigs)
+        .insert(app.db())
+        .await?;
+
+    let queue = Queue::new(app.db(), app.config(), CancellationToken::new());
+    let performed = queue.perform_one_queue_job().await?.unwrap();
+    assert_eq!(performed.id, queue_job.id);
+    assert_eq!(performed.status, JobStatus::Success);
+    assert!(performed.child_id.is_some());
+
+    let hpke_config_url = Url::from(aggregator.dap_url.clone()).join("hpke_config")?;
+    assert_eq!(client_logs.matching_url(hpke_config_url).len(), 1);
+    let aggregator = aggregator.reload(app.db()).await?.unwrap();
+    assert_eq!(aggregator.hpke_config_ids.unwrap().len(), 2);
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn claimed_job_is_not_fetched_again(app: DivviupApi) -> TestResult {
+    let queue_job = Job::from(RefreshAggregatorHpkeConfigs)
+        .insert(app.db())
+        .await?;
+
+    let tx = app.db().begin().await?;
+    let next = Entity::next(&tx).await?.unwrap();
+    assert_eq!(next.id, queue_job.id);
+    let claimed = Entity::claim(next, &tx).await?;
+    tx.commit().await?;
+    assert!(claimed.scheduled_at.unwrap() > OffsetDateTime::now_utc());
+
+    // another worker does not pick up the claimed job
+    let queue = Queue::new(app.db(), app.config(), CancellationToken::new());
+    assert!(queue.perform_one_queue_job().await?.is_none());
+
+    let tx = app.db().begin().await?;
+    assert!(Entity::relock(&claimed, &tx).await?.is_some());
+    tx.commit().await?;
+
+    // a job rescheduled since it was claimed cannot be relocked
+    let mut rescheduled = claimed.clone().into_active_model();
+    rescheduled.scheduled_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
+    rescheduled.update(app.db()).await?;
+    let tx = app.db().begin().await?;
+    assert!(Entity::relock(&claimed, &tx).await?.is_none());
+    tx.commit().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn refresh_aggregator_hpke_configs_invalid() -> TestResult {
+    let mock = Router::new().route("/hpke_config", get(|| async { "not an hpke config list" }));
+    let (app, _) = build_test_app_with_mock(mock).await;
+    let mut aggregator = fixtures::aggregator(&app, None).await.into_active_model();
+    aggregator.hpke_config_ids = ActiveValue::Set(Some(vec![7].into()));
+    let aggregator = aggregator.update(app.db()).await?;
+
+    RefreshAggregatorHpkeConfigs
+        .perform(&app.config().into(), app.db())
+        .await?;
+
+    let aggregator = aggregator.reload(app.db()).await?.unwrap();
+    assert!(aggregator
+        .hpke_config_error
+        .unwrap()
+        .starts_with("could not parse hpke configs"));
+    // the last known config ids are kept
+    assert_eq!(aggregator.hpke_config_ids.unwrap(), vec![7]);
+    assert!(aggregator.hpke_configs_checked_at.is_some());
+    Ok(())
+}
+
+#[test(harness = with_client_logs)]
+async fn expire_aggregator_tasks(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+    let account = fixtures::account(&app).await;
+    let task = fixtures::task(&app, &account).await;
+    let other_task = Task {
+        id: random::<TaskId>().to_string(),
+        ..task.clone()
+    }
+    .into_active_model()
+    .insert(app.db())
+    .await?;
+    let unrelated_task = fixtures::task(&app, &account).await;
+    let leader = task.leader_aggregator(app.db()).await?;
+
+    let mut job = ExpireAggregatorTasks::new(leader.id);
+    let next = job.perform(&app.config().into(), app.db()).await?;
+    assert!(next.is_none());
+    assert_eq!(job.remaining_tasks, 0);
+    assert!(job.failed_task_ids.is_empty());
+    assert_eq!(
+        job.expired_task_ids.iter().collect::<HashSet<_>>(),
+        HashSet::from([&task.id, &other_task.id])
+    );
+
+    for task in [task, other_task] {
+        let task = task.reload(app.db()).await?.unwrap();
+        assert!(task.expiration.unwrap() <= OffsetDateTime::now_utc());
+    }
+    let unrelated_task = unrelated_task.reload(app.db()).await?.unwrap();
+    assert!(unrelated_task.expiration.unwrap() > OffsetDateTime::now_utc());
+
+    // each task is expired on both its leader and its helper
+    assert_eq!(
+        client_logs
+            .logs()
+            .iter()
+            .filter(|log| log.method == Method::PATCH)
+            .count(),
+        4
+    );
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn sunset_aggregator(app: DivviupApi) -> TestResult {
+    let account = fixtures::account(&app).await;
+    let task = fixtures::task(&app, &account).await;
+    let aggregator = task.helper_aggregator(app.db()).await?;
+    let mut job = SunsetAggregator {
+        aggregator_id: aggregator.id,
+    };
+
+    // the sunset date was moved later after this job was scheduled
+    let mut active_model = aggregator.clone().into_active_model();
+    active_model.sunset_at = ActiveValue::Set(Some(OffsetDateTime::now_utc() + Duration::days(1)));
+    let aggregator = active_model.update(app.db()).await?;
+    assert!(job.perform(&app.config().into(), app.db()).await?.is_none());
+    assert!(!aggregator.reload(app.db()).await?.unwrap().is_tombstoned());
+
+    let mut active_model = aggregator.clone().into_active_model();
+    active_model.sunset_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
+    let aggregator = active_model.update(app.db()).await?;
+    let next = job.perform(&app.config().into(), app.db()).await?.unwrap();
+    assert_eq!(next.job, ExpireAggregatorTasks::new(aggregator.id));
+    assert!(aggregator.reload(app.db()).await?.unwrap().is_tombstoned());
+    Ok(())
+}
+
+#[test(harness = with_client_logs)]
+async fn send_decommission_email(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+    let (_, account, membership) = fixtures::member(&app).await;
+    let task = fixtures::task(&app, &account).await;
+    let mut aggregator = task.helper_aggregator(app.db()).await?.into_active_model();
+    aggregator.sunset_at = ActiveValue::Set(Some(OffsetDateTime::now_utc() + Duration::days(30)));
+    let aggregator = aggregator.update(app.db()).await?;
+
+    let mut job = SendDecommissionEmail {
+        membership_id: membership.id,
+        aggregator_id: aggregator.id,
+        task_ids: vec![task.id.clone()],
+        message_id: Uuid::new_v4(),
+    };
+    assert!(job.perform(&app.config().into(), app.db()).await?.is_none());
+
+    let email = client_logs.last();
+    assert_eq!(
+        email.url,
+        app.config().postmark_url.join("/email/withTemplate")?
+    );
+    let body: Value = email.request_json();
+    assert_eq!(body["TemplateAlias"], "aggregator-decommissioned");
+    assert_eq!(body["To"], membership.user_email);
+    assert_eq!(body["TemplateModel"]["task_ids"], json!([task.id]));
+    Ok(())
+}
+
+#[test(harness = set_up)]
+async fn expire_api_tokens(app: DivviupApi) -> TestResult {
+    let (_, account, membership) = fixtures::member(&app).await;
+    fixtures::membership_with_role(&app, &account, &fixtures::user(), MembershipRole::Viewer).await;
+    let (api_token, _) = fixtures::api_token(&app, &account).await;
+    let mut expiring_soon = api_token.into_active_model();
+    expiring_soon.expires_at =
+        ActiveValue::Set(Some(OffsetDateTime::now_utc() + Duration::days(1)));
+    let expiring_soon = expiring_soon.update(app.db()).await?;
+
+    let (api_token, _) = fixtures::api_token(&app, &account).await;
+    let mut expiring_later = api_token.into_active_model();
+    expiring_later.expires_at =
+        ActiveValue::Set(Some(OffsetDateTime::now_utc() + Duration::days(30)));
+    let expiring_later = expiring_later.update(app.db()).await?;
+
+    let (api_token, _) = fixtures::api_token(&app, &account).await;
+    let mut expired = api_token.into_active_model();
+    expired.expires_at = ActiveValue::Set(Some(OffsetDateTime::now_utc() - Duration::minutes(1)));
+    let expired = expired.update(app.db()).await?;
+
+    let (never_expiring, _) = fixtures::api_token(&app, &account).await;
+
+    let mut job = ExpireApiTokens;
+    let next = job.perform(&app.config().into(), app.db()).await?.unwrap();
+    assert_eq!(next.job, ExpireApiTokens);
+
+    let jobs = Entity::find().all(app.db()).await?;
+    assert_eq!(jobs.len(), 1);
+    let Job::V1(V1::SendApiTokenExpirationEmail(email_job)) = &*jobs[0].job else {
+        panic!(
+            "expected an api token expiration email, found {:?}",
+            jobs[0].job
+        );
+    };
+    assert_eq!(email_job.membership_id, membership.id);
+    assert_eq!(email_job.api_token_id, expiring_soon.id);
+
+    let expiring_soon = expiring_soon.reload(app.db()).await?.unwrap();
+    assert!(expiring_soon.expiration_notified_at.is_some());
+    assert!(!expiring_soon.is_tombstoned());
+    assert!(!expiring_later
+        .reload(app.db())
+        .await?
+        .unwrap()
+        .is_tombstoned());
+    assert!(expired.reload(app.db()).await?.unwrap().is_tombstoned());
+    assert!(!never_expiring
+        .reload(app.db())
+        .await?
+        .unwrap()
+        .is_tombstoned());
+
+    // members are only warned once about each token
+    job.perform(&app.config().into(), app.db()).await?;
+    assert_eq!(Entity::find().count(app.db()).await?, 1);
+    Ok(())
+}
+
+#[test(harness = with_client_logs)]
+async fn send_api_token_expiration_email(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+    let (_, account, membership) = fixtures::member(&app).await;
+    let (api_token, _) = fixtures::api_token(&app, &account).await;
+    let mut api_token = api_token.into_active_model();
+    api_token.expires_at = ActiveValue::Set(Some(OffsetDateTime::now_utc() + Duration::days(7)));
+    let api_token = api_token.update(app.db()).await?;
+
+    let mut job = SendApiTokenExpirationEmail {
+        membership_id: membership.id,
+        api_token_id: api_token.id,
+        message_id: Uuid::new_v4(),
+    };
+    assert!(job.perform(&app.config().into(), app.db()).await?.is_none());
+
+    let email = client_logs.last();
+    assert_eq!(
+        email.url,
+        app.config().postmark_url.join("/email/withTemplate")?
+    );
+    let body: Value = email.request_json();
+    assert_eq!(body["TemplateAlias"], "api-token-expiring");
+    assert_eq!(body["To"], membership.user_email);
+    assert_eq!(
+        body["TemplateModel"]["api_token_id"],
+        json!(api_token.id.to_string())
+    );
+
+    // viewers are not warned
+    let viewer =
+        fixtures::membership_with_role(&app, &account, &fixtures::user(), MembershipRole::Viewer)
+            .await;
+    let logs_before = client_logs.logs().len();
+    let mut viewer_job = SendApiTokenExpirationEmail {
+        membership_id: viewer.id,
+        ..job
+    };
+    assert!(viewer_job
+        .perform(&app.config().into(), app.db())
+        .await?
+        .is_none());
+    assert_eq!(client_logs.logs().len(), logs_before);
+
+    // revoked tokens are not announced
+    api_token.tombstone().update(app.db()).await?;
+    let logs_before = client_logs.logs().len();
+    assert!(job.perform(&app.config().into(), app.db()).await?.is_none());
+    assert_eq!(client_logs.logs().len(), logs_before);
+    Ok(())
+}
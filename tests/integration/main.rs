@@ -1,12 +1,16 @@
 mod accounts;
 mod admin_queue;
 mod aggregator_client;
+mod aggregator_tls;
 mod aggregators;
+mod alerts;
 mod api_tokens;
 mod assets;
+mod audit_events;
 mod auth;
 mod collector_credentials;
 mod crypter;
+mod dp_estimate;
 mod health_check;
 mod jobs;
 mod memberships;
@@ -15,3 +19,4 @@ mod tasks;
 mod tls_smoke_test;
 mod users;
 mod vdaf;
+mod webhooks;
@@ -20,6 +20,7 @@ mod create {
         let membership: Membership = resp.response_json();
         assert_eq!(membership.user_email, "someone.else@example.com");
         assert_eq!(membership.account_id, account.id);
+        assert_eq!(membership.role, MembershipRole::Member);
         let membership_id = membership.id;
 
         assert!(membership.reload(app.db()).await?.is_some());
@@ -32,6 +33,56 @@ mod create {
         Ok(())
     }
 
+    #[test(harness = set_up)]
+    async fn with_role(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Admin).await;
+        for (role, status) in [("admin", 201), ("viewer", 201), ("owner", 403)] {
+            let resp = post(format!("/api/accounts/{}/memberships", account.id))
+                .with_api_headers()
+                .with_request_json(json!({
+                    "user_email": format!("{role}@example.com"),
+                    "role": role
+                }))
+                .with_state(user.clone())
+                .run_async(&app)
+                .await;
+            assert_response!(resp, status);
+        }
+
+        let roles = Memberships::find()
+            .filter(MembershipColumn::AccountId.eq(account.id))
+            .all(app.db())
+            .await?
+            .into_iter()
+            .map(|membership| membership.role)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            roles,
+            [
+                MembershipRole::Admin,
+                MembershipRole::Admin,
+                MembershipRole::Viewer
+            ]
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn without_admin_role(app: DivviupApi) -> TestResult {
+        for role in [MembershipRole::Member, MembershipRole::Viewer] {
+            let (user, account, ..) = fixtures::member_with_role(&app, role).await;
+            let resp = post(format!("/api/accounts/{}/memberships", account.id))
+                .with_api_headers()
+                .with_request_json(json!({ "user_email": "someone.else@example.com" }))
+                .with_state(user)
+                .run_async(&app)
+                .await;
+            assert_response!(resp, 403);
+        }
+        assert_eq!(Memberships::find().count(app.db()).await?, 2);
+        Ok(())
+    }
+
     #[test(harness = set_up)]
     async fn duplicate(app: DivviupApi) -> TestResult {
         let (user, account, ..) = fixtures::member(&app).await;
@@ -86,6 +137,40 @@ mod create {
         Ok(())
     }
 
+    #[test(harness = set_up)]
+    async fn owner_as_admin(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Admin).await;
+        let owner = fixtures::membership(&app, &account, &fixtures::user()).await;
+        let resp = delete(format!("/api/memberships/{}", owner.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert!(owner.reload(app.db()).await?.is_some());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_member_role(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+        let other_membership = fixtures::membership_with_role(
+            &app,
+            &account,
+            &fixtures::user(),
+            MembershipRole::Viewer,
+        )
+        .await;
+        let resp = delete(format!("/api/memberships/{}", other_membership.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert!(other_membership.reload(app.db()).await?.is_some());
+        Ok(())
+    }
+
     #[test(harness = set_up)]
     async fn not_member(app: DivviupApi) -> TestResult {
         let (user, ..) = fixtures::member(&app).await;
@@ -439,3 +524,119 @@ mod delete {
         Ok(())
     }
 }
+
+mod update {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_owner(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let other_membership = fixtures::membership_with_role(
+            &app,
+            &account,
+            &fixtures::user(),
+            MembershipRole::Viewer,
+        )
+        .await;
+        let resp = patch(format!("/api/memberships/{}", other_membership.id))
+            .with_api_headers()
+            .with_request_json(json!({ "role": "owner" }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let membership: Membership = resp.response_json();
+        assert_eq!(membership.role, MembershipRole::Owner);
+        assert_eq!(
+            other_membership.reload(app.db()).await?.unwrap().role,
+            MembershipRole::Owner
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_admin_role(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Admin).await;
+        let viewer = fixtures::membership_with_role(
+            &app,
+            &account,
+            &fixtures::user(),
+            MembershipRole::Viewer,
+        )
+        .await;
+        let owner = fixtures::membership(&app, &account, &fixtures::user()).await;
+
+        let resp = patch(format!("/api/memberships/{}", viewer.id))
+            .with_api_headers()
+            .with_request_json(json!({ "role": "member" }))
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        for (membership, role) in [(&viewer, "owner"), (&owner, "viewer")] {
+            let resp = patch(format!("/api/memberships/{}", membership.id))
+                .with_api_headers()
+                .with_request_json(json!({ "role": role }))
+                .with_state(user.clone())
+                .run_async(&app)
+                .await;
+            assert_response!(resp, 403);
+        }
+
+        assert_eq!(
+            viewer.reload(app.db()).await?.unwrap().role,
+            MembershipRole::Member
+        );
+        assert_eq!(
+            owner.reload(app.db()).await?.unwrap().role,
+            MembershipRole::Owner
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn own_membership(app: DivviupApi) -> TestResult {
+        let (user, _, membership) = fixtures::member(&app).await;
+        let resp = patch(format!("/api/memberships/{}", membership.id))
+            .with_api_headers()
+            .with_request_json(json!({ "role": "viewer" }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert_eq!(
+            membership.reload(app.db()).await?.unwrap().role,
+            MembershipRole::Owner
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn invalid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let other_membership = fixtures::membership(&app, &account, &fixtures::user()).await;
+        let resp = patch(format!("/api/memberships/{}", other_membership.id))
+            .with_api_headers()
+            .with_request_json(json!({}))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn not_member(app: DivviupApi) -> TestResult {
+        let (user, ..) = fixtures::member(&app).await;
+        let other_membership = fixtures::build_membership(&app).await;
+        let resp = patch(format!("/api/memberships/{}", other_membership.id))
+            .with_api_headers()
+            .with_request_json(json!({ "role": "viewer" }))
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_not_found!(resp);
+        Ok(())
+    }
+}
@@ -1,13 +1,26 @@
-use divviup_api::entity::aggregator::{Feature, Features};
+use divviup_api::{
+    entity::aggregator::{Feature, Features},
+    Error,
+};
 use test_support::{assert_eq, test, *};
+use validator::ValidationErrors;
+
+async fn validation_errors(
+    app: &DivviupApi,
+    account: Account,
+    new_task: &mut NewTask,
+) -> ValidationErrors {
+    match new_task.normalize_and_validate(account, app.db()).await {
+        Err(Error::Validation(errors)) => errors,
+        other => panic!("expected validation errors, got {other:?}"),
+    }
+}
 
 pub async fn assert_errors(app: &DivviupApi, new_task: &mut NewTask, field: &str, codes: &[&str]) {
     let account = fixtures::account(app).await;
     assert_eq!(
-        new_task
-            .normalize_and_validate(account, app.db())
+        validation_errors(app, account, new_task)
             .await
-            .unwrap_err()
             .field_errors()
             .get(field)
             .map(|c| c.iter().map(|error| &error.code).collect::<Vec<_>>())
@@ -18,10 +31,7 @@ pub async fn assert_errors(app: &DivviupApi, new_task: &mut NewTask, field: &str
 
 pub async fn assert_no_errors(app: &DivviupApi, new_task: &mut NewTask, field: &str) {
     let account = fixtures::account(app).await;
-    let errors = new_task
-        .normalize_and_validate(account, app.db())
-        .await
-        .unwrap_err();
+    let errors = validation_errors(app, account, new_task).await;
     let errors = errors
         .field_errors()
         .get(field)
@@ -36,10 +46,7 @@ pub async fn assert_errors_nested(
     new_task: &mut NewTask,
     expected_errors: Value,
 ) {
-    let errors = new_task
-        .normalize_and_validate(account, app.db())
-        .await
-        .unwrap_err();
+    let errors = validation_errors(app, account, new_task).await;
     let serialized = serde_json::to_value(errors).unwrap();
     assert_eq!(serialized, expected_errors);
 }
@@ -204,6 +211,7 @@ async fn pure_dp_discrete_laplace(app: DivviupApi) -> TestResult {
                             "dp_strategy": "NoDifferentialPrivacy",
                             "budget": {
                                 "epsilon": [[1], [1]],
+                                "epsilon_ratio": {"numerator": "1", "denominator": "1"},
                             },
                         },
                     },
@@ -244,6 +252,7 @@ async fn pure_dp_discrete_laplace(app: DivviupApi) -> TestResult {
                             "dp_strategy": "PureDpDiscreteLaplace",
                             "budget": {
                                 "epsilon": null,
+                                "epsilon_ratio": null,
                             },
                         },
                     },
@@ -380,6 +389,55 @@ async fn pure_dp_discrete_laplace(app: DivviupApi) -> TestResult {
     Ok(())
 }
 
+#[test(harness = set_up)]
+async fn pure_dp_discrete_laplace_count_and_sum(app: DivviupApi) -> TestResult {
+    let mut leader = fixtures::aggregator(&app, None).await.into_active_model();
+    leader.role = ActiveValue::Set(Role::Leader);
+    leader.features =
+        ActiveValue::Set(Features::from_iter([Feature::PureDpDiscreteLaplace]).into());
+    let leader = leader.update(app.db()).await?;
+
+    let mut helper = fixtures::aggregator(&app, None).await.into_active_model();
+    helper.role = ActiveValue::Set(Role::Helper);
+    let helper = helper.update(app.db()).await?;
+
+    let dp_strategy = task::vdaf::DpStrategy {
+        dp_strategy: task::vdaf::DpStrategyKind::PureDpDiscreteLaplace,
+        budget: task::vdaf::DpBudget {
+            epsilon: Some(Vec::from([Vec::from([1]), Vec::from([1])])),
+        },
+    };
+    let vdafs = [
+        task::vdaf::Vdaf::Count(task::vdaf::Count {
+            dp_strategy: dp_strategy.clone(),
+        }),
+        task::vdaf::Vdaf::Sum(task::vdaf::Sum {
+            bits: Some(8),
+            dp_strategy,
+        }),
+    ];
+
+    for vdaf in vdafs {
+        let mut new_task = NewTask {
+            leader_aggregator_id: Some(leader.id.to_string()),
+            helper_aggregator_id: Some(helper.id.to_string()),
+            time_precision_seconds: Some(300),
+            vdaf: Some(vdaf),
+            ..Default::default()
+        };
+        assert_no_errors(&app, &mut new_task, "leader_aggregator_id").await;
+        assert_errors(
+            &app,
+            &mut new_task,
+            "helper_aggregator_id",
+            &["pure-dp-discrete-laplace-unsupported"],
+        )
+        .await;
+    }
+
+    Ok(())
+}
+
 #[test(harness = set_up)]
 async fn aggregator_roles(app: DivviupApi) -> TestResult {
     let mut leader = fixtures::aggregator(&app, None).await.into_active_model();
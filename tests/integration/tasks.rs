@@ -159,9 +159,228 @@ mod index {
     }
 }
 
+mod metrics_summary {
+    use super::{assert_eq, test, *};
+    use divviup_api::entity::{
+        aggregator::{Feature, Features},
+        task::{MetricsSummary, TaskCounter},
+        ApiTokenScope,
+    };
+    use time::Duration;
+
+    async fn task_with_counters(
+        app: &DivviupApi,
+        account: &Account,
+        report_counter_success: i64,
+        report_counter_decode_failure: i64,
+        aggregation_job_counter_helper_report_replayed: i64,
+    ) -> Task {
+        let mut task = fixtures::task(app, account).await.into_active_model();
+        task.report_counter_success = ActiveValue::Set(report_counter_success);
+        task.report_counter_decode_failure = ActiveValue::Set(report_counter_decode_failure);
+        task.aggregation_job_counter_success = ActiveValue::Set(report_counter_success);
+        task.aggregation_job_counter_helper_report_replayed =
+            ActiveValue::Set(aggregation_job_counter_helper_report_replayed);
+        task.update(app.db()).await.unwrap()
+    }
+
+    #[test(harness = set_up)]
+    async fn saturated_counters(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        task_with_counters(&app, &account, i64::MAX, i64::MAX, 0).await;
+        task_with_counters(&app, &account, i64::MAX, 0, 0).await;
+
+        let resp = get(format!("/api/accounts/{}/metrics_summary", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let summary: MetricsSummary = resp.response_json();
+        assert_eq!(summary.totals[&TaskCounter::ReportCounterSuccess], i64::MAX);
+        assert_eq!(summary.tasks[0].upload_error_ratio, 0.5);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn totals_and_breakdown(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let first = task_with_counters(&app, &account, 90, 10, 0).await;
+        let second = task_with_counters(&app, &account, 50, 0, 50).await;
+        let third = task_with_counters(&app, &account, 100, 0, 0).await;
+
+        let deleted = task_with_counters(&app, &account, 1000, 1000, 1000).await;
+        let mut deleted = deleted.into_active_model();
+        deleted.deleted_at = ActiveValue::Set(Some(OffsetDateTime::now_utc()));
+        deleted.update(app.db()).await?;
+        let other_account = fixtures::account(&app).await;
+        task_with_counters(&app, &other_account, 1000, 1000, 1000).await;
+
+        let resp = get(format!("/api/accounts/{}/metrics_summary", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let summary: MetricsSummary = resp.response_json();
+
+        assert_eq!(summary.task_count, 3);
+        assert_eq!(summary.totals[&TaskCounter::ReportCounterSuccess], 240);
+        assert_eq!(summary.totals[&TaskCounter::ReportCounterDecodeFailure], 10);
+        assert_eq!(
+            summary.totals[&TaskCounter::AggregationJobCounterSuccess],
+            240
+        );
+        assert_eq!(
+            summary.totals[&TaskCounter::AggregationJobCounterHelperReportReplayed],
+            50
+        );
+        assert_eq!(summary.totals[&TaskCounter::ReportCounterExpired], 0);
+
+        assert_eq!(
+            summary
+                .tasks
+                .iter()
+                .map(|task| &task.task_id)
+                .collect::<Vec<_>>(),
+            [&first.id, &second.id, &third.id]
+        );
+        assert_eq!(summary.tasks[0].counters, first.counters());
+        assert_eq!(summary.tasks[0].upload_error_ratio, 0.1);
+        assert_eq!(summary.tasks[0].aggregation_error_ratio, 0.0);
+        assert_eq!(summary.tasks[1].upload_error_ratio, 0.0);
+        assert_eq!(summary.tasks[1].aggregation_error_ratio, 0.5);
+
+        assert_eq!(
+            summary
+                .highest_error_ratios
+                .iter()
+                .map(|task| (&task.task_id, task.error_ratio))
+                .collect::<Vec<_>>(),
+            [(&second.id, 0.5), (&first.id, 0.1)]
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn no_tasks(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let resp = get(format!("/api/accounts/{}/metrics_summary", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let summary: MetricsSummary = resp.response_json();
+        assert_eq!(summary.task_count, 0);
+        assert!(summary.totals.values().all(|total| *total == 0));
+        assert!(summary.tasks.is_empty());
+        assert!(summary.highest_error_ratios.is_empty());
+        assert!(summary.expiring_soon.is_empty());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn expiring_soon(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let now = OffsetDateTime::now_utc();
+        let mut tasks = vec![];
+        for expiration in [
+            Some(now + Duration::days(3)),
+            Some(now + Duration::days(30)),
+            Some(now + Duration::days(1)),
+            Some(now - Duration::days(1)),
+            None,
+        ] {
+            let mut task = fixtures::task(&app, &account).await.into_active_model();
+            task.expiration = ActiveValue::Set(expiration);
+            tasks.push(task.update(app.db()).await?);
+        }
+
+        let resp = get(format!("/api/accounts/{}/metrics_summary", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let summary: MetricsSummary = resp.response_json();
+        assert_eq!(
+            summary
+                .expiring_soon
+                .iter()
+                .map(|task| &task.task_id)
+                .collect::<Vec<_>>(),
+            [&tasks[2].id, &tasks[0].id]
+        );
+        Ok(())
+    }
+
+    #[test(harness = with_client_logs)]
+    async fn does_not_refresh_metrics(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let mut task = task.into_active_model();
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::minutes(10));
+        let task = task.update(app.db()).await?;
+        let mut leader = task.leader_aggregator(app.db()).await?.into_active_model();
+        leader.features = ActiveValue::Set(Features::from_iter([Feature::UploadMetrics]).into());
+        leader.update(app.db()).await?;
+
+        let resp = get(format!("/api/accounts/{}/metrics_summary", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        assert!(client_logs.logs().is_empty());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn token_limited_to_tasks(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let task = task_with_counters(&app, &account, 10, 0, 0).await;
+        task_with_counters(&app, &account, 20, 0, 0).await;
+        let (_, token) = fixtures::scoped_api_token(
+            &app,
+            &account,
+            &[ApiTokenScope::Read],
+            Some(vec![task.id.clone()]),
+        )
+        .await;
+
+        let resp = get(format!("/api/accounts/{}/metrics_summary", account.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let summary: MetricsSummary = resp.response_json();
+        assert_eq!(summary.task_count, 1);
+        assert_eq!(summary.totals[&TaskCounter::ReportCounterSuccess], 10);
+        assert_eq!(summary.tasks.len(), 1);
+        assert_eq!(summary.tasks[0].task_id, task.id);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn not_member(app: DivviupApi) -> TestResult {
+        let user = fixtures::user();
+        let account = fixtures::account(&app).await;
+        let resp = get(format!("/api/accounts/{}/metrics_summary", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
 mod create {
     use super::{assert_eq, test, *};
-    use divviup_api::entity::{aggregator::Features, task::vdaf::Vdaf};
+    use divviup_api::entity::{aggregator::Features, task::vdaf::Vdaf, Visibility};
+    use time::Duration;
 
     fn valid_task_json(
         collector_credential: &CollectorCredential,
@@ -216,7 +435,7 @@ mod create {
 
         assert_eq!(task.leader_aggregator_id, leader.id);
         assert_eq!(task.helper_aggregator_id, helper.id);
-        assert_eq!(task.vdaf, Vdaf::Count);
+        assert_eq!(task.vdaf, Vdaf::Count(Default::default()));
         assert_eq!(task.min_batch_size, 500);
         assert_eq!(task.time_precision_seconds, 60);
         assert!(task.reload(app.db()).await?.is_some());
@@ -259,7 +478,7 @@ mod create {
 
         assert_eq!(task.leader_aggregator_id, leader.id);
         assert_eq!(task.helper_aggregator_id, helper.id);
-        assert_eq!(task.vdaf, Vdaf::Count);
+        assert_eq!(task.vdaf, Vdaf::Count(Default::default()));
         assert_eq!(task.min_batch_size, 500);
         assert_eq!(task.time_precision_seconds, 60);
         assert!(task.reload(app.db()).await?.is_some());
@@ -315,6 +534,64 @@ mod create {
         Ok(())
     }
 
+    #[test(harness = with_client_logs)]
+    async fn attempting_to_provision_against_a_decommissioned_helper(
+        app: DivviupApi,
+        client_logs: ClientLogs,
+    ) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (leader, helper) = fixtures::aggregator_pair(&app, &account).await;
+        let collector_credential = fixtures::collector_credential(&app, &account).await;
+        let mut helper = helper.into_active_model();
+        helper.sunset_at = ActiveValue::Set(Some(OffsetDateTime::now_utc() + Duration::days(30)));
+        let helper = helper.update(app.db()).await?;
+
+        let resp = post(format!("/api/accounts/{}/tasks", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(valid_task_json(&collector_credential, &leader, &helper))
+            .run_async(&app)
+            .await;
+
+        assert_response!(resp, StatusCode::BAD_REQUEST);
+        let error: Value = resp.response_json();
+        assert_eq!(error["helper_aggregator_id"][0]["code"], "decommissioned");
+        assert!(client_logs.is_empty());
+        Ok(())
+    }
+
+    #[test(harness = with_client_logs)]
+    async fn attempting_to_provision_against_a_helper_without_access(
+        app: DivviupApi,
+        client_logs: ClientLogs,
+    ) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (leader, helper) = fixtures::aggregator_pair(&app, &account).await;
+        let collector_credential = fixtures::collector_credential(&app, &account).await;
+
+        for (visibility, code) in [
+            (Visibility::Allowlist, "required"),
+            (Visibility::RequestAccess, "access-required"),
+        ] {
+            let mut helper = helper.clone().into_active_model();
+            helper.visibility = ActiveValue::Set(visibility);
+            let helper = helper.update(app.db()).await?;
+
+            let resp = post(format!("/api/accounts/{}/tasks", account.id))
+                .with_api_headers()
+                .with_state(user.clone())
+                .with_request_json(valid_task_json(&collector_credential, &leader, &helper))
+                .run_async(&app)
+                .await;
+
+            assert_response!(resp, StatusCode::BAD_REQUEST);
+            let error: Value = resp.response_json();
+            assert_eq!(error["helper_aggregator_id"][0]["code"], code);
+        }
+        assert!(client_logs.is_empty());
+        Ok(())
+    }
+
     #[test(harness = set_up)]
     async fn invalid(app: DivviupApi) -> TestResult {
         let (user, account, ..) = fixtures::member(&app).await;
@@ -341,6 +618,32 @@ mod create {
         Ok(())
     }
 
+    #[test(harness = set_up)]
+    async fn malformed_aggregator_ids(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+
+        let resp = post(format!("/api/accounts/{}/tasks", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "name": "my task name",
+                "leader_aggregator_id": "not-a-uuid",
+                "helper_aggregator_id": uuid::Uuid::new_v4(),
+                "vdaf": { "type": "count" },
+                "min_batch_size": 100,
+                "time_precision_seconds": 60,
+            }))
+            .run_async(&app)
+            .await;
+
+        assert_response!(resp, StatusCode::BAD_REQUEST);
+        let error: Value = resp.response_json();
+        assert_eq!(error["leader_aggregator_id"][0]["code"], "invalid-uuid");
+        assert_eq!(error["helper_aggregator_id"][0]["code"], "required");
+
+        Ok(())
+    }
+
     #[test(harness = set_up)]
     async fn not_member(app: DivviupApi) -> TestResult {
         let user = fixtures::user();
@@ -466,7 +769,13 @@ mod create {
 
 mod show {
     use super::{assert_eq, test, *};
-    use divviup_api::entity::aggregator::{Feature, Features};
+    use divviup_api::{
+        api_mocks::aggregator_api::BAD_BEARER_TOKEN,
+        clients::aggregator_client::api_types::{
+            TaskAggregationJobMetrics, TaskCollectionJobMetrics,
+        },
+        entity::aggregator::{Feature, Features},
+    };
     use time::Duration;
 
     #[test(harness = set_up)]
@@ -531,6 +840,177 @@ mod show {
         Ok(())
     }
 
+    #[test(harness = set_up)]
+    async fn metrics_refresh_promotes_secondary_bearer_token(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let mut task = task.into_active_model();
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::minutes(10));
+        let task = task.update(app.db()).await?;
+
+        let leader = task.leader_aggregator(app.db()).await?;
+        let aad = leader.api_url.as_ref().as_bytes().to_vec();
+        let new_bearer_token = fixtures::random_name();
+        let mut leader = leader.into_active_model();
+        leader.features = ActiveValue::Set(Features::from_iter([Feature::UploadMetrics]).into());
+        leader.encrypted_bearer_token = ActiveValue::Set(
+            app.crypter()
+                .encrypt(&aad, BAD_BEARER_TOKEN.as_bytes())
+                .unwrap(),
+        );
+        leader.encrypted_secondary_bearer_token = ActiveValue::Set(Some(
+            app.crypter()
+                .encrypt(&aad, new_bearer_token.as_bytes())
+                .unwrap(),
+        ));
+        let leader = leader.update(app.db()).await?;
+
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let leader = leader.reload(app.db()).await?.unwrap();
+        assert_eq!(leader.bearer_token(app.crypter())?, new_bearer_token);
+        assert!(leader.encrypted_secondary_bearer_token.is_none());
+        Ok(())
+    }
+
+    #[test(harness = with_client_logs)]
+    async fn collection_job_metrics(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let mut task = task.into_active_model();
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::minutes(10));
+        let task = task.update(app.db()).await?;
+
+        let mut leader = task.leader_aggregator(app.db()).await?.into_active_model();
+        leader.features =
+            ActiveValue::Set(Features::from_iter([Feature::CollectionJobMetrics]).into());
+        leader.update(app.db()).await?;
+
+        let leader = task.leader_aggregator(app.db()).await?;
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let aggregator_api_request = client_logs.last();
+        assert_eq!(
+            aggregator_api_request.url,
+            leader
+                .api_url
+                .join(&format!("tasks/{}/metrics/collections", task.id))
+                .unwrap()
+        );
+        let metrics: TaskCollectionJobMetrics = aggregator_api_request.response_json();
+        let response_task: Task = resp.response_json();
+        assert_eq!(metrics, response_task);
+        assert_eq!(
+            response_task.aggregate_collection_count,
+            metrics.finished as i64
+        );
+        assert_eq!(response_task.report_count, metrics.report_count as i64);
+        assert_eq!(
+            task.reload(app.db())
+                .await?
+                .unwrap()
+                .aggregate_collection_count,
+            response_task.aggregate_collection_count
+        );
+        Ok(())
+    }
+
+    #[test(harness = with_client_logs)]
+    async fn helper_metrics(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let mut task = task.into_active_model();
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::minutes(10));
+        let task = task.update(app.db()).await?;
+
+        let features =
+            Features::from_iter([Feature::UploadMetrics, Feature::AggregationJobMetrics]);
+        for aggregator in task.aggregators(app.db()).await? {
+            let mut aggregator = aggregator.into_active_model();
+            aggregator.features = ActiveValue::Set(features.clone().into());
+            aggregator.update(app.db()).await?;
+        }
+
+        let helper = task.helper_aggregator(app.db()).await?;
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let response_task: Task = resp.response_json();
+
+        let helper_requests = client_logs
+            .logs()
+            .into_iter()
+            .filter(|log| log.url.as_str().starts_with(helper.api_url.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(helper_requests.len(), 2);
+        let uploads: TaskUploadMetrics = helper_requests[0].response_json();
+        let aggregation_jobs: TaskAggregationJobMetrics = helper_requests[1].response_json();
+
+        let helper_metrics = response_task.helper_metrics.as_deref().unwrap();
+        assert_eq!(helper_metrics.uploads, Some(uploads));
+        assert_eq!(helper_metrics.aggregation_jobs, Some(aggregation_jobs));
+        assert_eq!(
+            task.reload(app.db()).await?.unwrap().helper_metrics,
+            response_task.helper_metrics
+        );
+
+        // the leader's metrics are still reported separately
+        let leader = task.leader_aggregator(app.db()).await?;
+        let leader_uploads: TaskUploadMetrics = client_logs
+            .logs()
+            .into_iter()
+            .find(|log| log.url.as_str().starts_with(leader.api_url.as_str()))
+            .unwrap()
+            .response_json();
+        assert_eq!(leader_uploads, response_task);
+        Ok(())
+    }
+
+    #[test(harness = with_client_logs)]
+    async fn helper_metrics_failure(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let mut task = task.into_active_model();
+        task.updated_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::minutes(10));
+        let task = task.update(app.db()).await?;
+
+        let helper = task.helper_aggregator(app.db()).await?;
+        let mut helper = helper.into_active_model();
+        helper.features = ActiveValue::Set(Features::from_iter([Feature::UploadMetrics]).into());
+        let helper = helper.update(app.db()).await?;
+        let encrypted_bearer_token = app.crypter().encrypt(
+            helper.api_url.as_ref().as_bytes(),
+            BAD_BEARER_TOKEN.as_bytes(),
+        )?;
+        let mut helper = helper.into_active_model();
+        helper.encrypted_bearer_token = ActiveValue::Set(encrypted_bearer_token);
+        helper.update(app.db()).await?;
+
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let response_task: Task = resp.response_json();
+        assert_eq!(response_task.helper_metrics, None);
+        assert_eq!(client_logs.last().response_status, StatusCode::UNAUTHORIZED);
+        Ok(())
+    }
+
     #[test(harness = with_client_logs)]
     async fn no_metrics_refresh_without_aggregator_features(
         app: DivviupApi,
@@ -645,6 +1125,194 @@ mod show {
     }
 }
 
+mod collection_readiness {
+    use super::{assert_eq, test, *};
+    use divviup_api::entity::{
+        aggregator::QueryTypeName, task::CollectionReadiness, TaskCounterSnapshots,
+    };
+    use time::Duration;
+
+    async fn task_with_reports(
+        app: &DivviupApi,
+        account: &Account,
+        max_batch_size: Option<i64>,
+        report_counter_success: i64,
+    ) -> Task {
+        let task = fixtures::task(app, account).await;
+        let mut task = task.into_active_model();
+        task.created_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::hours(10));
+        task.min_batch_size = ActiveValue::Set(100);
+        task.max_batch_size = ActiveValue::Set(max_batch_size);
+        task.time_precision_seconds = ActiveValue::Set(3600);
+        task.report_counter_success = ActiveValue::Set(report_counter_success);
+        task.report_counter_interval_collected = ActiveValue::Set(3);
+        task.update(app.db()).await.unwrap()
+    }
+
+    async fn readiness(app: &DivviupApi, user: User, task: &Task) -> CollectionReadiness {
+        let resp = get(format!("/api/tasks/{}/collection_readiness", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(app)
+            .await;
+        assert_ok!(resp);
+        resp.response_json()
+    }
+
+    #[test(harness = set_up)]
+    async fn time_interval(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = task_with_reports(&app, &account, None, 1100).await;
+        let now = OffsetDateTime::now_utc();
+        let readiness = readiness(&app, user, &task).await;
+
+        assert_eq!(readiness.query_type, QueryTypeName::TimeInterval);
+        assert_eq!(readiness.report_count, 1100);
+        assert_eq!(readiness.late_report_count, 3);
+        assert_eq!(readiness.rate_measured_since, task.created_at);
+        assert!((readiness.reports_per_hour - 110.0).abs() < 1.0);
+        assert_eq!(readiness.ready_batches, None);
+
+        // one time precision holds min_batch_size reports at the current rate
+        assert_eq!(readiness.batch_interval_seconds, Some(3600));
+        let ready_intervals = readiness.ready_intervals.unwrap();
+        assert!((9..=10).contains(&ready_intervals.len()));
+        for interval in &ready_intervals {
+            assert_eq!(interval.duration_seconds, 3600);
+            assert_eq!(interval.start.unix_timestamp() % 3600, 0);
+            assert!(interval.start >= task.created_at);
+            assert!(interval.start + Duration::hours(1) <= now);
+        }
+        assert!(ready_intervals
+            .windows(2)
+            .all(|pair| pair[0].start > pair[1].start));
+
+        let next_ready_at = readiness.next_ready_at.unwrap();
+        assert_eq!(next_ready_at.unix_timestamp() % 3600, 0);
+        assert!(next_ready_at > now && next_ready_at <= now + Duration::hours(1));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn time_interval_longer_than_precision(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = task_with_reports(&app, &account, None, 300).await;
+        let readiness = readiness(&app, user, &task).await;
+
+        // 30 reports an hour take four hours to reach 100
+        assert_eq!(readiness.batch_interval_seconds, Some(4 * 3600));
+        let ready_intervals = readiness.ready_intervals.unwrap();
+        assert!((1..=2).contains(&ready_intervals.len()));
+        assert!(ready_intervals
+            .iter()
+            .all(|interval| interval.start.unix_timestamp() % (4 * 3600) == 0));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn fixed_size(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = task_with_reports(&app, &account, Some(200), 250).await;
+        let now = OffsetDateTime::now_utc();
+        let readiness = readiness(&app, user, &task).await;
+
+        assert_eq!(readiness.query_type, QueryTypeName::FixedSize);
+        assert_eq!(readiness.ready_batches, Some(2));
+        assert_eq!(readiness.ready_intervals, None);
+        assert_eq!(readiness.batch_interval_seconds, None);
+
+        // the remaining 50 reports arrive in about two hours at 25 reports an hour
+        let wait = readiness.next_ready_at.unwrap() - now;
+        assert!((wait - Duration::hours(2)).abs() < Duration::minutes(1));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn no_reports(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = task_with_reports(&app, &account, None, 0).await;
+        let readiness = readiness(&app, user, &task).await;
+        assert_eq!(readiness.reports_per_hour, 0.0);
+        assert_eq!(readiness.ready_intervals, Some(vec![]));
+        assert_eq!(readiness.batch_interval_seconds, None);
+        assert_eq!(readiness.next_ready_at, None);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn expires_before_ready(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = task_with_reports(&app, &account, Some(200), 250).await;
+        let mut task = task.into_active_model();
+        task.expiration = ActiveValue::Set(Some(OffsetDateTime::now_utc() + Duration::hours(1)));
+        let task = task.update(app.db()).await?;
+        let readiness = readiness(&app, user, &task).await;
+        assert_eq!(readiness.ready_batches, Some(2));
+        assert_eq!(readiness.next_ready_at, None);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn rate_from_counter_snapshot(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let task = task_with_reports(&app, &account, Some(200), 1000).await;
+        let mut task = task.into_active_model();
+        task.created_at = ActiveValue::Set(OffsetDateTime::now_utc() - Duration::days(5));
+        let task = task.update(app.db()).await?;
+
+        let captured_at = OffsetDateTime::now_utc() - Duration::hours(25);
+        let mut earlier = task.clone().into_active_model();
+        earlier.report_counter_success = ActiveValue::Set(750);
+        let snapshot =
+            TaskCounterSnapshots::record(&earlier.update(app.db()).await?, app.db()).await?;
+        let mut snapshot = snapshot.into_active_model();
+        snapshot.captured_at = ActiveValue::Set(captured_at);
+        snapshot.update(app.db()).await?;
+        let task = task
+            .into_active_model()
+            .reset_all()
+            .update(app.db())
+            .await?;
+
+        let readiness = readiness(&app, user, &task).await;
+        assert_eq!(
+            readiness.rate_measured_since.unix_timestamp(),
+            captured_at.unix_timestamp()
+        );
+        assert!((readiness.reports_per_hour - 10.0).abs() < 0.1);
+        assert_eq!(readiness.ready_batches, Some(10));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn not_member(app: DivviupApi) -> TestResult {
+        let user = fixtures::user();
+        let account = fixtures::account(&app).await;
+        let task = fixtures::task(&app, &account).await;
+        let resp = get(format!("/api/tasks/{}/collection_readiness", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn member_token(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (_, token) = fixtures::api_token(&app, &account).await;
+        let task = fixtures::task(&app, &account).await;
+        let resp = get(format!("/api/tasks/{}/collection_readiness", task.id))
+            .with_api_headers()
+            .with_auth_header(token)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        Ok(())
+    }
+}
+
 mod update {
     use time::format_description::well_known::Rfc3339;
 
@@ -932,6 +1600,29 @@ mod delete {
         assert!(helper_task.task_expiration.unwrap() <= expected);
     }
 
+    #[test(harness = with_client_logs)]
+    async fn as_viewer(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Viewer).await;
+        let task = fixtures::task(&app, &account).await;
+
+        let resp = get(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let resp = delete(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert!(task.reload(app.db()).await?.unwrap().deleted_at.is_none());
+        assert!(client_logs.logs().iter().all(|log| log.method != "DELETE"));
+        Ok(())
+    }
+
     #[test(harness = with_client_logs)]
     async fn success(app: DivviupApi, client_logs: ClientLogs) -> TestResult {
         let (user, account, ..) = fixtures::member(&app).await;
@@ -1,6 +1,10 @@
-use divviup_api::entity::task::vdaf::{BucketLength, CategoricalBuckets, Histogram, Vdaf};
+use divviup_api::{
+    clients::aggregator_client::api_types::AggregatorVdaf,
+    entity::task::vdaf::{BucketLength, CategoricalBuckets, Histogram, Vdaf},
+};
 use task::vdaf::DpStrategy;
 use test_support::{assert_eq, test, *};
+use validator::Validate;
 #[test]
 pub fn histogram_representations() {
     let scenarios = [
@@ -91,6 +95,60 @@ fn sumvec_representations() {
     }
 }
 
+#[test]
+fn count_and_sum_representations() {
+    let scenarios = [
+        (json!({"type": "count"}), json!("Prio3Count")),
+        (
+            json!({"type": "count", "dp_strategy": {"dp_strategy": "NoDifferentialPrivacy"}}),
+            json!("Prio3Count"),
+        ),
+        (
+            json!({"type": "count", "dp_strategy": {"dp_strategy": "PureDpDiscreteLaplace", "budget": {"epsilon": [[1], [1]]}}}),
+            json!({"Prio3Count": {"dp_strategy": {"dp_strategy": "PureDpDiscreteLaplace", "budget": {"epsilon": [[1], [1]]}}}}),
+        ),
+        (
+            json!({"type": "sum", "bits": 8}),
+            json!({"Prio3Sum": {"bits": 8}}),
+        ),
+        (
+            json!({"type": "sum", "bits": 8, "dp_strategy": {"dp_strategy": "PureDpDiscreteLaplace", "budget": {"epsilon": "1/2"}}}),
+            json!({"Prio3Sum": {"bits": 8, "dp_strategy": {"dp_strategy": "PureDpDiscreteLaplace", "budget": {"epsilon": [[1], [2]]}}}}),
+        ),
+    ];
+
+    for (input, output) in scenarios {
+        let vdaf: Vdaf = serde_json::from_value(input.clone()).unwrap();
+        let representation = vdaf.representation_for_protocol(&Protocol::Dap09).unwrap();
+        assert_eq!(
+            serde_json::to_value(&representation).unwrap(),
+            output,
+            "{input}"
+        );
+        // aggregators' responses are read back into the same vdaf
+        assert_eq!(
+            Vdaf::from(serde_json::from_value::<AggregatorVdaf>(output).unwrap()),
+            vdaf,
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn count_and_sum_invalid_dp_strategy_returns_error() {
+    for input in [
+        json!({"type": "count", "dp_strategy": {"dp_strategy": "PureDpDiscreteLaplace"}}),
+        json!({"type": "sum", "bits": 8, "dp_strategy": {"dp_strategy": "NoDifferentialPrivacy", "budget": {"epsilon": [[1], [1]]}}}),
+    ] {
+        let vdaf: Vdaf = serde_json::from_value(input.clone()).unwrap();
+        assert!(vdaf.validate().is_err(), "{input}");
+        assert!(
+            vdaf.representation_for_protocol(&Protocol::Dap09).is_err(),
+            "{input}"
+        );
+    }
+}
+
 #[test]
 fn histogram_representation_dap_09_no_chunk_length_1() {
     let result = Vdaf::Histogram(Histogram::Categorical(CategoricalBuckets {
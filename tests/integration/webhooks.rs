@@ -0,0 +1,715 @@
+use axum::{http::StatusCode as MockStatus, routing, Router};
+use divviup_api::{
+    entity::{
+        webhook::{self, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER},
+        webhook_delivery::RETENTION,
+    },
+    queue::{DeliverWebhook, JobStatus, NotifyExpiredTasks, QueueCleanup, V1},
+};
+use test_support::{assert_eq, assert_ne, test, *};
+use time::{format_description::well_known::Rfc3339, Duration};
+
+async fn queued_deliveries(app: &DivviupApi) -> Vec<DeliverWebhook> {
+    entity::queue::Entity::find()
+        .all(app.db())
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|queue_job| match queue_job.job.0 {
+            Job::V1(V1::DeliverWebhook(job)) => Some(job),
+            _ => None,
+        })
+        .collect()
+}
+
+mod index {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_admin(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook1, _) = fixtures::webhook(&app, &account).await;
+        let (webhook2, _) = fixtures::webhook(&app, &account).await;
+        let (deleted, _) = fixtures::webhook(&app, &account).await;
+        deleted.tombstone().update(app.db()).await?;
+        let other_account = fixtures::account(&app).await;
+        fixtures::webhook(&app, &other_account).await;
+
+        let resp = get(format!("/api/accounts/{}/webhooks", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let webhooks: Vec<Webhook> = resp.response_json();
+        assert_eq!(
+            webhooks.iter().map(|w| w.id).collect::<Vec<_>>(),
+            vec![webhook2.id, webhook1.id]
+        );
+        assert_eq!(webhooks[1].url, webhook1.url);
+        assert!(webhooks.iter().all(|w| w.secret.is_none()));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_member(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+        fixtures::webhook(&app, &account).await;
+
+        let resp = get(format!("/api/accounts/{}/webhooks", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
+mod create {
+    use super::{assert_eq, assert_ne, test, *};
+
+    #[test(harness = set_up)]
+    async fn valid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let resp = post(format!("/api/accounts/{}/webhooks", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "name": "alerts",
+                "url": "https://hooks.example/divviup",
+                "event_types": ["task.deleted", "task.created", "task.created"]
+            }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let webhook: Webhook = resp.response_json();
+        assert_eq!(webhook.name.as_deref(), Some("alerts"));
+        assert_eq!(webhook.url, "https://hooks.example/divviup");
+        assert_eq!(
+            webhook.event_types.as_deref().unwrap(),
+            &[WebhookEventType::TaskCreated, WebhookEventType::TaskDeleted]
+        );
+        let secret = webhook.secret.unwrap();
+        assert!(secret.starts_with("whsec_"));
+
+        // the url and secret are only stored encrypted
+        let stored = Webhooks::find_by_id(webhook.id)
+            .one(app.db())
+            .await?
+            .unwrap();
+        assert_ne!(stored.encrypted_url, b"https://hooks.example/divviup");
+        assert_eq!(stored.secret(app.crypter())?, secret);
+        assert_eq!(
+            stored.decrypt_url(app.crypter())?.as_str(),
+            "https://hooks.example/divviup"
+        );
+
+        let audit_event = AuditEvents::find()
+            .filter(AuditEventColumn::TargetId.eq(webhook.id.to_string()))
+            .one(app.db())
+            .await?
+            .unwrap();
+        assert_eq!(audit_event.target_type, AuditTargetType::Webhook);
+        assert!(!audit_event.changes.contains_key("secret"));
+        assert!(!audit_event.changes.contains_key("url"));
+        assert!(!serde_json::to_string(&audit_event.changes)?.contains("hooks.example"));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn invalid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let resp = post(format!("/api/accounts/{}/webhooks", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({ "event_types": [] }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 400);
+        let errors: Value = resp.response_json();
+        assert!(errors.get("url").is_some());
+        assert!(errors.get("event_types").is_some());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_member(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+        let resp = post(format!("/api/accounts/{}/webhooks", account.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({ "url": "https://hooks.example/" }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        assert_eq!(Webhooks::find().count(app.db()).await?, 0);
+        Ok(())
+    }
+}
+
+mod show {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_admin(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let resp = get(format!("/api/webhooks/{}", webhook.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let shown: Webhook = resp.response_json();
+        assert_eq!(shown.url, webhook.url);
+        assert!(shown.secret.is_none());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn other_account(app: DivviupApi) -> TestResult {
+        let (user, ..) = fixtures::member(&app).await;
+        let other_account = fixtures::account(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &other_account).await;
+        let resp = get(format!("/api/webhooks/{}", webhook.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn deleted(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        webhook.clone().tombstone().update(app.db()).await?;
+        let resp = get(format!("/api/webhooks/{}", webhook.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_not_found!(resp);
+        Ok(())
+    }
+}
+
+mod update {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn valid(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook, secret) = fixtures::webhook(&app, &account).await;
+        let resp = patch(format!("/api/webhooks/{}", webhook.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({
+                "url": "https://other.example/hook",
+                "event_types": ["membership.created"]
+            }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let updated: Webhook = resp.response_json();
+        assert_eq!(updated.url, "https://other.example/hook");
+        assert_eq!(updated.name, webhook.name);
+        assert!(updated.subscribes_to(WebhookEventType::MembershipCreated));
+        assert!(!updated.subscribes_to(WebhookEventType::TaskCreated));
+
+        // the secret is unchanged
+        let stored = updated.reload(app.db()).await?.unwrap();
+        assert_eq!(stored.secret(app.crypter())?, secret);
+
+        let resp = patch(format!("/api/webhooks/{}", webhook.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({ "all_event_types": true }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let updated: Webhook = resp.response_json();
+        assert!(updated.event_types.is_none());
+
+        let audit_events = AuditEvents::find()
+            .filter(AuditEventColumn::TargetId.eq(webhook.id.to_string()))
+            .all(app.db())
+            .await?;
+        assert!(!audit_events.is_empty());
+        for audit_event in audit_events {
+            assert!(!audit_event.changes.contains_key("url"));
+            assert!(!serde_json::to_string(&audit_event.changes)?.contains("other.example"));
+        }
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn as_member(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member_with_role(&app, MembershipRole::Member).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let resp = patch(format!("/api/webhooks/{}", webhook.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({ "name": "renamed" }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 403);
+        Ok(())
+    }
+}
+
+mod delete {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn as_admin(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let resp = delete(format!("/api/webhooks/{}", webhook.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 204);
+        assert!(webhook.reload(app.db()).await?.unwrap().is_tombstoned());
+
+        // deleted webhooks no longer receive events
+        Webhooks::enqueue(
+            account.id,
+            WebhookEventType::TaskCreated,
+            json!({}),
+            app.db(),
+        )
+        .await?;
+        assert!(queued_deliveries(&app).await.is_empty());
+        Ok(())
+    }
+}
+
+mod events {
+    use super::{assert_eq, test, *};
+
+    #[test(harness = set_up)]
+    async fn task_lifecycle(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let task = fixtures::task(&app, &account).await;
+
+        let resp = patch(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({
+                "expiration": OffsetDateTime::now_utc().format(&Rfc3339)?
+            }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let resp = delete(format!("/api/tasks/{}", task.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 204);
+
+        let deliveries = queued_deliveries(&app).await;
+        assert_eq!(
+            deliveries
+                .iter()
+                .map(|job| job.event.event_type)
+                .collect::<Vec<_>>(),
+            vec![WebhookEventType::TaskExpired, WebhookEventType::TaskDeleted]
+        );
+        assert!(deliveries.iter().all(|job| job.webhook_id == webhook.id
+            && job.event.account_id == account.id
+            && job.event.data["id"] == task.id));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn natural_expiration(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+
+        let expired = fixtures::task(&app, &account).await;
+        let mut active_model = expired.clone().into_active_model();
+        active_model.expiration =
+            ActiveValue::Set(Some(OffsetDateTime::now_utc() - Duration::hours(1)));
+        active_model.update(app.db()).await?;
+
+        let unexpired = fixtures::task(&app, &account).await;
+
+        let expired_by_update = fixtures::task(&app, &account).await;
+        let resp = patch(format!("/api/tasks/{}", expired_by_update.id))
+            .with_api_headers()
+            .with_state(user)
+            .with_request_json(json!({
+                "expiration": OffsetDateTime::now_utc().format(&Rfc3339)?
+            }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        for _ in 0..2 {
+            let next = NotifyExpiredTasks
+                .perform(&app.config().into(), app.db())
+                .await?
+                .unwrap();
+            assert_eq!(next.job, NotifyExpiredTasks);
+        }
+
+        let deliveries = queued_deliveries(&app).await;
+        assert_eq!(deliveries.len(), 2);
+        assert!(deliveries.iter().all(|job| job.webhook_id == webhook.id
+            && job.event.event_type == WebhookEventType::TaskExpired));
+        let mut task_ids = deliveries
+            .iter()
+            .map(|job| job.event.data["id"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        task_ids.sort();
+        let mut expected = vec![expired.id.as_str(), expired_by_update.id.as_str()];
+        expected.sort();
+        assert_eq!(task_ids, expected);
+        assert!(!task_ids.contains(&unexpired.id.as_str()));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn membership_changes(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let mut active_model = webhook.into_active_model();
+        active_model.event_types = ActiveValue::Set(Some(
+            vec![
+                WebhookEventType::MembershipCreated,
+                WebhookEventType::MembershipDeleted,
+            ]
+            .into(),
+        ));
+        active_model.update(app.db()).await?;
+
+        let email = fixtures::random_email();
+        let resp = post(format!("/api/accounts/{}/memberships", account.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({ "user_email": email }))
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 201);
+        let membership: Membership = resp.response_json();
+
+        let resp = patch(format!("/api/memberships/{}", membership.id))
+            .with_api_headers()
+            .with_state(user.clone())
+            .with_request_json(json!({ "role": "viewer" }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let resp = delete(format!("/api/memberships/{}", membership.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_response!(resp, 204);
+
+        // the webhook does not subscribe to membership.updated
+        let deliveries = queued_deliveries(&app).await;
+        assert_eq!(
+            deliveries
+                .iter()
+                .map(|job| job.event.event_type)
+                .collect::<Vec<_>>(),
+            vec![
+                WebhookEventType::MembershipCreated,
+                WebhookEventType::MembershipDeleted
+            ]
+        );
+        assert!(deliveries
+            .iter()
+            .all(|job| job.event.data["user_email"] == email));
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn aggregator_decommissioned(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let task = fixtures::task(&app, &account).await;
+        let aggregator = task.helper_aggregator(app.db()).await?;
+
+        let resp = post(format!("/api/aggregators/{}/decommission", aggregator.id))
+            .with_api_headers()
+            .with_auth_header(fixtures::admin_token(&app).await)
+            .with_request_json(json!({
+                "sunset_at": (OffsetDateTime::now_utc() + Duration::days(30)).format(&Rfc3339)?
+            }))
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+
+        let [delivery] = &queued_deliveries(&app).await[..] else {
+            panic!("expected exactly one webhook delivery");
+        };
+        assert_eq!(delivery.webhook_id, webhook.id);
+        assert_eq!(
+            delivery.event.event_type,
+            WebhookEventType::AggregatorDecommissioned
+        );
+        assert_eq!(
+            delivery.event.data["aggregator"]["id"],
+            json!(aggregator.id)
+        );
+        assert_eq!(delivery.event.data["task_ids"], json!([task.id]));
+        Ok(())
+    }
+}
+
+mod delivery {
+    use super::{assert_eq, assert_ne, test, *};
+
+    fn mock(status: MockStatus) -> Router {
+        Router::new().route("/hooks", routing::post(move || async move { status }))
+    }
+
+    #[tokio::test]
+    async fn signed() -> TestResult {
+        let (app, client_logs) = build_test_app_with_mock(mock(MockStatus::OK)).await;
+        let account = fixtures::account(&app).await;
+        let (webhook, secret) = fixtures::webhook(&app, &account).await;
+        let task = fixtures::task(&app, &account).await;
+        Webhooks::enqueue(
+            account.id,
+            WebhookEventType::TaskCreated,
+            json!(task),
+            app.db(),
+        )
+        .await?;
+
+        let queue_job = Queue::from(&app).perform_one_queue_job().await?.unwrap();
+        assert_eq!(queue_job.status, JobStatus::Success);
+
+        let request = client_logs.last();
+        assert_eq!(request.url.as_str(), webhook.url);
+        assert_eq!(request.method, Method::POST);
+        let body = request.request_body.clone().unwrap();
+        let event: WebhookEvent = serde_json::from_str(&body)?;
+        assert_eq!(event.event_type, WebhookEventType::TaskCreated);
+        assert_eq!(event.data["id"], task.id);
+        assert_eq!(request.request_headers[EVENT_HEADER], "task.created");
+        assert_eq!(
+            request.request_headers[DELIVERY_HEADER],
+            event.id.to_string()
+        );
+
+        let signature_header = request.request_headers[SIGNATURE_HEADER].to_str()?;
+        let timestamp: i64 = signature_header
+            .strip_prefix("t=")
+            .and_then(|rest| rest.split(',').next())
+            .unwrap()
+            .parse()?;
+        assert_eq!(
+            signature_header,
+            webhook::signature(&secret, timestamp, &body)
+        );
+        // a different secret produces a different signature
+        assert_ne!(
+            signature_header,
+            webhook::signature("whsec_other", timestamp, &body)
+        );
+
+        let deliveries = WebhookDeliveries::for_webhook(webhook.id)
+            .all(app.db())
+            .await?;
+        let [delivery] = &deliveries[..] else {
+            panic!("expected exactly one delivery");
+        };
+        assert_eq!(delivery.event_id, event.id);
+        assert_eq!(delivery.attempt, 1);
+        assert_eq!(delivery.status_code, Some(200));
+        assert_eq!(delivery.error, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retried() -> TestResult {
+        let (app, client_logs) =
+            build_test_app_with_mock(mock(MockStatus::SERVICE_UNAVAILABLE)).await;
+        let account = fixtures::account(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        Webhooks::enqueue(
+            account.id,
+            WebhookEventType::TaskCreated,
+            json!({}),
+            app.db(),
+        )
+        .await?;
+
+        let queue_job = Queue::from(&app).perform_one_queue_job().await?.unwrap();
+        assert_eq!(queue_job.status, JobStatus::Pending);
+        assert_eq!(queue_job.failure_count, 1);
+        assert!(queue_job.scheduled_at.unwrap() > OffsetDateTime::now_utc());
+        // the queue retries the delivery, so the attempt itself is a single request
+        assert_eq!(client_logs.len(), 1);
+        let Job::V1(V1::DeliverWebhook(job)) = &queue_job.job.0 else {
+            panic!("expected a webhook delivery");
+        };
+        assert_eq!(job.attempts, 1);
+
+        let deliveries = WebhookDeliveries::for_webhook(webhook.id)
+            .all(app.db())
+            .await?;
+        let [delivery] = &deliveries[..] else {
+            panic!("expected exactly one delivery");
+        };
+        assert_eq!(delivery.attempt, 1);
+        assert_eq!(delivery.status_code, Some(503));
+        assert!(delivery.error.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rate_limited() -> TestResult {
+        let (app, _) = build_test_app_with_mock(mock(MockStatus::TOO_MANY_REQUESTS)).await;
+        let account = fixtures::account(&app).await;
+        fixtures::webhook(&app, &account).await;
+        Webhooks::enqueue(
+            account.id,
+            WebhookEventType::TaskCreated,
+            json!({}),
+            app.db(),
+        )
+        .await?;
+
+        let queue_job = Queue::from(&app).perform_one_queue_job().await?.unwrap();
+        assert_eq!(queue_job.status, JobStatus::Pending);
+        assert_eq!(queue_job.failure_count, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejected() -> TestResult {
+        let (app, _) = build_test_app_with_mock(mock(MockStatus::BAD_REQUEST)).await;
+        let account = fixtures::account(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        Webhooks::enqueue(
+            account.id,
+            WebhookEventType::TaskCreated,
+            json!({}),
+            app.db(),
+        )
+        .await?;
+
+        let queue_job = Queue::from(&app).perform_one_queue_job().await?.unwrap();
+        assert_eq!(queue_job.status, JobStatus::Failed);
+        assert_eq!(queue_job.scheduled_at, None);
+
+        let deliveries = WebhookDeliveries::for_webhook(webhook.id)
+            .all(app.db())
+            .await?;
+        let [delivery] = &deliveries[..] else {
+            panic!("expected exactly one delivery");
+        };
+        assert_eq!(delivery.attempt, 1);
+        assert_eq!(delivery.status_code, Some(400));
+        assert!(delivery.error.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn undecryptable() -> TestResult {
+        let (app, client_logs) = build_test_app_with_mock(mock(MockStatus::OK)).await;
+        let account = fixtures::account(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let mut active_model = webhook.clone().into_active_model();
+        active_model.encrypted_url = ActiveValue::Set(b"not encrypted".to_vec());
+        active_model.update(app.db()).await?;
+        Webhooks::enqueue(
+            account.id,
+            WebhookEventType::TaskCreated,
+            json!({}),
+            app.db(),
+        )
+        .await?;
+
+        let queue_job = Queue::from(&app).perform_one_queue_job().await?.unwrap();
+        assert_eq!(queue_job.status, JobStatus::Failed);
+        assert!(client_logs.is_empty());
+
+        let deliveries = WebhookDeliveries::for_webhook(webhook.id)
+            .all(app.db())
+            .await?;
+        let [delivery] = &deliveries[..] else {
+            panic!("expected exactly one delivery");
+        };
+        assert_eq!(delivery.status_code, None);
+        assert!(delivery.error.is_some());
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn log(app: DivviupApi) -> TestResult {
+        let (user, account, ..) = fixtures::member(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let event_id = uuid::Uuid::new_v4();
+        for attempt in 1..=2 {
+            WebhookDeliveries::record(
+                webhook.id,
+                event_id,
+                WebhookEventType::TaskCreated,
+                attempt,
+                Some(500),
+                Some("failed".into()),
+                app.db(),
+            )
+            .await?;
+        }
+
+        let resp = get(format!("/api/webhooks/{}/deliveries", webhook.id))
+            .with_api_headers()
+            .with_state(user)
+            .run_async(&app)
+            .await;
+        assert_ok!(resp);
+        let deliveries: Vec<WebhookDelivery> = resp.response_json();
+        assert_eq!(
+            deliveries.iter().map(|d| d.attempt).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        Ok(())
+    }
+
+    #[test(harness = set_up)]
+    async fn expired_deliveries_are_cleaned_up(app: DivviupApi) -> TestResult {
+        let account = fixtures::account(&app).await;
+        let (webhook, _) = fixtures::webhook(&app, &account).await;
+        let mut deliveries = vec![];
+        for attempt in 1..=2 {
+            deliveries.push(
+                WebhookDeliveries::record(
+                    webhook.id,
+                    uuid::Uuid::new_v4(),
+                    WebhookEventType::TaskCreated,
+                    attempt,
+                    Some(200),
+                    None,
+                    app.db(),
+                )
+                .await?,
+            );
+        }
+        let mut expired = deliveries[0].clone().into_active_model();
+        expired.created_at =
+            ActiveValue::Set(OffsetDateTime::now_utc() - RETENTION - Duration::hours(1));
+        expired.update(app.db()).await?;
+
+        QueueCleanup.perform(&app.config().into(), app.db()).await?;
+        let remaining = WebhookDeliveries::for_webhook(webhook.id)
+            .all(app.db())
+            .await?;
+        assert_eq!(remaining, vec![deliveries[1].clone()]);
+        Ok(())
+    }
+}